@@ -0,0 +1,45 @@
+//! Measures how fast actors are created and started
+//!
+//! Run with `cargo bench --bench actor_creation`.
+extern crate riker;
+use riker::actors::*;
+
+use std::time::Instant;
+
+const ACTORS: usize = 20_000;
+
+#[derive(Default)]
+struct Idle;
+
+impl Actor for Idle {
+    type Msg = ();
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {}
+}
+
+fn main() {
+    let mut cfg = riker::load_config();
+    cfg.set("debug", false).unwrap();
+    cfg.set("log.level", "error").unwrap();
+    let sys = ActorSystem::with_config("bench", cfg).unwrap();
+
+    let start = Instant::now();
+    let actors: Vec<_> = (0..ACTORS)
+        .map(|i| sys.actor_of::<Idle>(&format!("idle-{}", i)).unwrap())
+        .collect();
+    let created = start.elapsed();
+
+    for actor in &actors {
+        futures::executor::block_on(actor.started()).unwrap();
+    }
+    let started = start.elapsed();
+
+    println!(
+        "{} actors created in {:?} ({:.0}/sec), started in {:?} ({:.0}/sec)",
+        ACTORS,
+        created,
+        ACTORS as f64 / created.as_secs_f64(),
+        started,
+        ACTORS as f64 / started.as_secs_f64()
+    );
+}
@@ -0,0 +1,112 @@
+//! Compares publishing a large payload to many subscribers as a
+//! cloned `Vec<u8>` and as an `ArcMessage`
+//!
+//! Run with `cargo bench --bench arc_message`.
+extern crate riker;
+use riker::actors::*;
+
+use std::time::{Duration, Instant};
+
+const PAYLOAD: usize = 4 * 1024 * 1024;
+const SUBSCRIBERS: usize = 64;
+const PUBLISHES: usize = 20;
+
+// Reports the payload length of each message received
+struct Sub<T: Message + Len> {
+    chan: ChannelRef<T>,
+    done: ActorRef<usize>,
+}
+
+impl<T: Message + Len> ActorFactoryArgs<(ChannelRef<T>, ActorRef<usize>)> for Sub<T> {
+    fn create_args((chan, done): (ChannelRef<T>, ActorRef<usize>)) -> Self {
+        Sub { chan, done }
+    }
+}
+
+trait Len {
+    fn len(&self) -> usize;
+}
+
+impl Len for Vec<u8> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+impl Len for ArcMessage<Vec<u8>> {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+}
+
+impl<T: Message + Len> Actor for Sub<T> {
+    type Msg = T;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.chan
+            .tell(Subscribe::new("payload", Box::new(ctx.myself())), None);
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        self.done.tell(msg.len(), None);
+    }
+}
+
+fn run<T: Message + Len>(sys: &ActorSystem, name: &str, msg: T) -> Duration {
+    let chan: ChannelRef<T> = channel(name, sys).unwrap();
+    let done = Inbox::<usize>::new(sys).unwrap();
+    let subs: Vec<_> = (0..SUBSCRIBERS)
+        .map(|i| {
+            sys.actor_of_args::<Sub<T>, _>(
+                &format!("{}-{}", name, i),
+                (chan.clone(), done.sender_ref()),
+            )
+            .unwrap()
+        })
+        .collect();
+    std::thread::sleep(Duration::from_millis(200));
+
+    let start = Instant::now();
+    for _ in 0..PUBLISHES {
+        chan.tell(
+            Publish {
+                msg: msg.clone(),
+                topic: "payload".into(),
+            },
+            None,
+        );
+    }
+    for _ in 0..SUBSCRIBERS * PUBLISHES {
+        let len = done
+            .receive(Duration::from_secs(60))
+            .expect("all messages received");
+        assert_eq!(len, PAYLOAD);
+    }
+    let elapsed = start.elapsed();
+
+    for sub in &subs {
+        sys.stop(sub);
+    }
+    sys.stop(&chan);
+    elapsed
+}
+
+fn report(name: &str, elapsed: Duration) {
+    println!(
+        "{:<12} {} publishes of {} bytes to {} subscribers in {:?}",
+        name, PUBLISHES, PAYLOAD, SUBSCRIBERS, elapsed
+    );
+}
+
+fn main() {
+    let mut cfg = riker::load_config();
+    cfg.set("debug", false).unwrap();
+    cfg.set("log.level", "error").unwrap();
+    let sys = ActorSystem::with_config("bench", cfg).unwrap();
+
+    let elapsed = run(&sys, "cloned", vec![0u8; PAYLOAD]);
+    report("Vec<u8>", elapsed);
+
+    let elapsed = run(&sys, "shared", ArcMessage::new(vec![0u8; PAYLOAD]));
+    report("ArcMessage", elapsed);
+}
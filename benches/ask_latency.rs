@@ -0,0 +1,57 @@
+//! Measures the round trip latency of a request and its reply
+//!
+//! Run with `cargo bench --bench ask_latency`.
+extern crate riker;
+use riker::actors::*;
+
+use std::time::{Duration, Instant};
+
+const ASKS: usize = 20_000;
+
+// Replies to every request with the request
+#[derive(Default)]
+struct Echo;
+
+impl Actor for Echo {
+    type Msg = u64;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        if let Some(sender) = sender {
+            let _ = sender.try_tell(msg, None);
+        }
+    }
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    sorted[((sorted.len() - 1) as f64 * p) as usize]
+}
+
+fn main() {
+    let mut cfg = riker::load_config();
+    cfg.set("debug", false).unwrap();
+    cfg.set("log.level", "error").unwrap();
+    let sys = ActorSystem::with_config("bench", cfg).unwrap();
+
+    let echo = sys.actor_of::<Echo>("echo").unwrap();
+    let replies = Inbox::<u64>::new(&sys).unwrap();
+    let reply_to: BasicActorRef = replies.sender_ref().into();
+
+    let mut latencies = Vec::with_capacity(ASKS);
+    for i in 0..ASKS as u64 {
+        let start = Instant::now();
+        echo.tell(i, Some(reply_to.clone()));
+        replies
+            .receive(Duration::from_secs(5))
+            .expect("reply received");
+        latencies.push(start.elapsed());
+    }
+    latencies.sort();
+
+    println!(
+        "{} asks: p50 {:?}, p99 {:?}, max {:?}",
+        ASKS,
+        percentile(&latencies, 0.5),
+        percentile(&latencies, 0.99),
+        latencies[ASKS - 1]
+    );
+}
@@ -0,0 +1,92 @@
+//! Compares the throughput of `recv` and `recv_batch`
+//!
+//! Run with `cargo bench --bench batch_recv`.
+extern crate riker;
+use riker::actors::*;
+
+use std::time::{Duration, Instant};
+
+const MSGS: u64 = 1_000_000;
+
+struct Summer {
+    batched: bool,
+    total: u64,
+    count: u64,
+    done: ActorRef<u64>,
+}
+
+impl ActorFactoryArgs<(bool, ActorRef<u64>)> for Summer {
+    fn create_args((batched, done): (bool, ActorRef<u64>)) -> Self {
+        Summer {
+            batched,
+            total: 0,
+            count: 0,
+            done,
+        }
+    }
+}
+
+impl Summer {
+    fn add(&mut self, msg: u64) {
+        self.total += msg;
+        self.count += 1;
+        if self.count == MSGS {
+            self.done.tell(self.total, None);
+        }
+    }
+}
+
+impl Actor for Summer {
+    type Msg = u64;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        self.add(msg);
+    }
+
+    fn receives_batches(&self) -> bool {
+        self.batched
+    }
+
+    fn recv_batch(&mut self, _ctx: &Context<Self::Msg>, msgs: Vec<Self::Msg>) {
+        for msg in msgs {
+            self.add(msg);
+        }
+    }
+}
+
+fn run(sys: &ActorSystem, batched: bool) -> Duration {
+    let done = Inbox::<u64>::new(sys).unwrap();
+    let name = if batched { "batched" } else { "unbatched" };
+    let summer = sys
+        .actor_of_args::<Summer, _>(name, (batched, done.sender_ref()))
+        .unwrap();
+
+    let start = Instant::now();
+    for i in 0..MSGS {
+        summer.tell(i, None);
+    }
+    done.receive(Duration::from_secs(60))
+        .expect("all messages processed");
+    let elapsed = start.elapsed();
+
+    sys.stop(&summer);
+    elapsed
+}
+
+fn main() {
+    let mut cfg = riker::load_config();
+    cfg.set("debug", false).unwrap();
+    cfg.set("log.level", "error").unwrap();
+    let sys = ActorSystem::with_config("bench", cfg).unwrap();
+
+    for &batched in &[false, true] {
+        let elapsed = run(&sys, batched);
+        println!(
+            "{:<10} {} msgs in {:?} ({:.0} msgs/sec)",
+            if batched { "recv_batch" } else { "recv" },
+            MSGS,
+            elapsed,
+            MSGS as f64 / elapsed.as_secs_f64()
+        );
+    }
+}
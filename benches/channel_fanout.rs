@@ -0,0 +1,118 @@
+//! Measures publishing small messages to a channel with many subscribers,
+//! one by one and in `PublishBatch`es
+//!
+//! Run with `cargo bench --bench channel_fanout`.
+extern crate riker;
+use riker::actors::*;
+
+use std::time::{Duration, Instant};
+
+const PUBLISHES: u64 = 10_000;
+const BATCH: u64 = 100;
+
+// Reports once it has received every published message
+struct Sub {
+    chan: ChannelRef<u64>,
+    received: u64,
+    done: ActorRef<()>,
+}
+
+impl ActorFactoryArgs<(ChannelRef<u64>, ActorRef<()>)> for Sub {
+    fn create_args((chan, done): (ChannelRef<u64>, ActorRef<()>)) -> Self {
+        Sub {
+            chan,
+            received: 0,
+            done,
+        }
+    }
+}
+
+impl Actor for Sub {
+    type Msg = u64;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let sub = Box::new(ctx.myself());
+        self.chan.tell(Subscribe::new("bench", sub), None);
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {
+        self.received += 1;
+        if self.received == PUBLISHES {
+            self.done.tell((), None);
+        }
+    }
+}
+
+fn run(sys: &ActorSystem, subscribers: usize, batched: bool) -> Duration {
+    let name = format!("fanout-{}-{}", subscribers, batched);
+    let chan: ChannelRef<u64> = channel(&name, sys).unwrap();
+    let done = Inbox::<()>::new(sys).unwrap();
+
+    let subs: Vec<_> = (0..subscribers)
+        .map(|i| {
+            sys.actor_of_args::<Sub, _>(
+                &format!("sub-{}-{}", name, i),
+                (chan.clone(), done.sender_ref()),
+            )
+            .unwrap()
+        })
+        .collect();
+    // subscriptions are sent in pre_start, ahead of the publishes
+    for sub in &subs {
+        futures::executor::block_on(sub.started()).unwrap();
+    }
+
+    let start = Instant::now();
+    if batched {
+        for i in 0..PUBLISHES / BATCH {
+            chan.publish_batch("bench", (i * BATCH..(i + 1) * BATCH).collect());
+        }
+    } else {
+        for i in 0..PUBLISHES {
+            chan.tell(
+                Publish {
+                    msg: i,
+                    topic: "bench".into(),
+                },
+                None,
+            );
+        }
+    }
+    for _ in 0..subscribers {
+        done.receive(Duration::from_secs(60))
+            .expect("all messages received");
+    }
+    let elapsed = start.elapsed();
+
+    for sub in &subs {
+        sys.stop(sub);
+    }
+    elapsed
+}
+
+fn main() {
+    let mut cfg = riker::load_config();
+    cfg.set("debug", false).unwrap();
+    cfg.set("log.level", "error").unwrap();
+    let sys = ActorSystem::with_config("bench", cfg).unwrap();
+
+    for &(subscribers, batched) in &[
+        (1, false),
+        (1, true),
+        (16, false),
+        (16, true),
+        (128, false),
+        (128, true),
+    ] {
+        let elapsed = run(&sys, subscribers, batched);
+        let delivered = PUBLISHES * subscribers as u64;
+        println!(
+            "{:<3} subscribers {:<10} {} msgs delivered in {:?} ({:.0} msgs/sec)",
+            subscribers,
+            if batched { "batched" } else { "one by one" },
+            delivered,
+            elapsed,
+            delivered as f64 / elapsed.as_secs_f64()
+        );
+    }
+}
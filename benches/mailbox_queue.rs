@@ -0,0 +1,83 @@
+//! Compares the `channel` and `lockfree` mailbox queues with many producers
+//!
+//! Run with `cargo bench --bench mailbox_queue`.
+extern crate riker;
+use riker::actors::*;
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+const PRODUCERS: u64 = 8;
+const MSGS_PER_PRODUCER: u64 = 250_000;
+
+struct Counter {
+    count: u64,
+    done: ActorRef<u64>,
+}
+
+impl ActorFactoryArgs<ActorRef<u64>> for Counter {
+    fn create_args(done: ActorRef<u64>) -> Self {
+        Counter { count: 0, done }
+    }
+}
+
+impl Actor for Counter {
+    type Msg = u64;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {
+        self.count += 1;
+        if self.count == PRODUCERS * MSGS_PER_PRODUCER {
+            self.done.tell(self.count, None);
+        }
+    }
+}
+
+fn run(queue: &str) -> Duration {
+    let mut cfg = riker::load_config();
+    cfg.set("debug", false).unwrap();
+    cfg.set("log.level", "error").unwrap();
+    cfg.set("mailbox.queue", queue).unwrap();
+    let sys = ActorSystem::with_config("bench", cfg).unwrap();
+
+    let done = Inbox::<u64>::new(&sys).unwrap();
+    let counter = sys
+        .actor_of_args::<Counter, _>("counter", done.sender_ref())
+        .unwrap();
+
+    let start = Instant::now();
+    let producers: Vec<_> = (0..PRODUCERS)
+        .map(|_| {
+            let counter = counter.clone();
+            thread::spawn(move || {
+                for i in 0..MSGS_PER_PRODUCER {
+                    counter.tell(i, None);
+                }
+            })
+        })
+        .collect();
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    done.receive(Duration::from_secs(60))
+        .expect("all messages processed");
+
+    start.elapsed()
+}
+
+fn main() {
+    let msgs = PRODUCERS * MSGS_PER_PRODUCER;
+
+    for queue in &["channel", "lockfree"] {
+        let elapsed = run(queue);
+        println!(
+            "{:<8} {} producers, {} msgs in {:?} ({:.0} msgs/sec)",
+            queue,
+            PRODUCERS,
+            msgs,
+            elapsed,
+            msgs as f64 / elapsed.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! Measures how late scheduled messages are received
+//!
+//! Run with `cargo bench --bench scheduler_jitter`.
+extern crate riker;
+use riker::actors::*;
+
+use std::time::{Duration, Instant};
+
+const SCHEDULES: usize = 200;
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    sorted[((sorted.len() - 1) as f64 * p) as usize]
+}
+
+fn main() {
+    let mut cfg = riker::load_config();
+    cfg.set("debug", false).unwrap();
+    cfg.set("log.level", "error").unwrap();
+    let sys = ActorSystem::with_config("bench", cfg).unwrap();
+    let inbox = Inbox::<usize>::new(&sys).unwrap();
+
+    // delays of 5ms to 1s, received in the order they're due
+    let start = Instant::now();
+    let due: Vec<Duration> = (0..SCHEDULES)
+        .map(|i| Duration::from_millis(5 + i as u64 * 5))
+        .collect();
+    for (i, delay) in due.iter().enumerate() {
+        sys.schedule_once(*delay, inbox.sender_ref(), None, i);
+    }
+
+    let mut late = Vec::with_capacity(SCHEDULES);
+    for _ in 0..SCHEDULES {
+        let i = inbox
+            .receive(Duration::from_secs(5))
+            .expect("scheduled message received");
+        late.push(start.elapsed().saturating_sub(due[i]));
+    }
+    late.sort();
+
+    println!(
+        "{} schedules late by p50 {:?}, p99 {:?}, max {:?}",
+        SCHEDULES,
+        percentile(&late, 0.5),
+        percentile(&late, 0.99),
+        late[SCHEDULES - 1]
+    );
+}
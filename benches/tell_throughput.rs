@@ -0,0 +1,31 @@
+//! Measures `tell` throughput from producer threads to consumer actors
+//!
+//! Run with `cargo bench --bench tell_throughput`.
+extern crate riker;
+use riker::actors::*;
+use riker::bench::Topology;
+
+const MSGS_PER_PRODUCER: u64 = 250_000;
+
+fn main() {
+    let mut cfg = riker::load_config();
+    cfg.set("debug", false).unwrap();
+    cfg.set("log.level", "error").unwrap();
+    let sys = ActorSystem::with_config("bench", cfg).unwrap();
+
+    for &(producers, consumers) in &[(1, 1), (4, 1), (4, 4), (8, 16)] {
+        let report = Topology::new(producers, consumers)
+            .msgs(MSGS_PER_PRODUCER)
+            .run(&sys)
+            .unwrap()
+            .expect("all messages processed");
+        println!(
+            "{} producers -> {:<2} consumers {} msgs in {:?} ({:.0} msgs/sec)",
+            producers,
+            consumers,
+            report.msgs,
+            report.elapsed,
+            report.throughput()
+        );
+    }
+}
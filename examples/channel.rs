@@ -26,7 +26,7 @@ impl Actor for GpsActor {
 
         println!("{}: pre_start subscribe to {:?}", ctx.myself.name(), topic);
         let sub = Box::new(ctx.myself());
-        self.chan.tell(Subscribe { actor: sub, topic }, None);
+        self.chan.tell(Subscribe::new(topic, sub), None);
     }
 
     fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
@@ -61,7 +61,7 @@ impl Actor for NavigationActor {
 
         println!("{}: pre_start subscribe to {:?}", ctx.myself.name(), topic);
         let sub = Box::new(ctx.myself());
-        self.chan.tell(Subscribe { actor: sub, topic }, None);
+        self.chan.tell(Subscribe::new(topic, sub), None);
     }
 
     fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
@@ -46,7 +46,7 @@ impl Actor for DeadLetterActor {
 
         ctx.system
             .dead_letters()
-            .tell(Subscribe { actor: sub, topic }, None);
+            .tell(Subscribe::new(topic, sub), None);
     }
 
     fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
@@ -44,13 +44,7 @@ impl Actor for SystemActor {
         );
         let sub = Box::new(ctx.myself());
 
-        ctx.system.sys_events().tell(
-            Subscribe {
-                actor: sub,
-                topic: "*".into(),
-            },
-            None,
-        );
+        ctx.system.sys_events().tell(Subscribe::new("*", sub), None);
     }
 
     fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
@@ -79,6 +73,19 @@ impl Receive<SystemEvent> for SystemActor {
             SystemEvent::ActorTerminated(terminated) => {
                 println!("path: {}", terminated.actor.path());
             }
+            SystemEvent::ActorFailed(failed) => {
+                println!("path: {}", failed.actor.path());
+            }
+            SystemEvent::OverloadChanged(overload) => {
+                println!("overloaded: {}", overload.overloaded);
+            }
+            SystemEvent::HealthChanged(changed) => {
+                println!(
+                    "path: {}, health: {:?}",
+                    changed.actor.path(),
+                    changed.health
+                );
+            }
         }
     }
 }
@@ -0,0 +1,217 @@
+extern crate riker;
+use riker::actors::*;
+
+use std::time::Duration;
+
+// A supervised pipeline: an ingest router spreads records over a pool of
+// workers, which pass their results to a writer. The writer batches the
+// results and writes them to a (simulated) store, retrying failed writes.
+//
+// The pipeline is configured with `pipeline.workers`, `pipeline.batch_size`
+// and `pipeline.flush_millis`, for example in config/app.toml.
+
+#[derive(Clone, Debug)]
+pub struct Record(u64);
+
+#[derive(Clone, Debug)]
+pub struct Processed(u64);
+
+#[derive(Clone, Debug)]
+pub struct Flush;
+
+#[derive(Clone, Debug)]
+pub struct Retry(Vec<u64>);
+
+struct PipelineConfig {
+    workers: usize,
+    batch_size: usize,
+    flush_interval: Duration,
+}
+
+impl PipelineConfig {
+    fn from_sys(sys: &ActorSystem) -> Self {
+        let cfg = sys.config();
+        PipelineConfig {
+            workers: cfg.get_int("pipeline.workers").unwrap_or(4) as usize,
+            batch_size: cfg.get_int("pipeline.batch_size").unwrap_or(10) as usize,
+            flush_interval: Duration::from_millis(
+                cfg.get_int("pipeline.flush_millis").unwrap_or(100) as u64,
+            ),
+        }
+    }
+}
+
+// Ingest router, forwarding records to its workers in turn.
+// Failed workers are restarted, the record being processed is lost.
+struct Ingest {
+    writer: ActorRef<WriterMsg>,
+    workers: Vec<ActorRef<Record>>,
+    next: usize,
+}
+
+impl ActorFactoryArgs<ActorRef<WriterMsg>> for Ingest {
+    fn create_args(writer: ActorRef<WriterMsg>) -> Self {
+        Ingest {
+            writer,
+            workers: Vec::new(),
+            next: 0,
+        }
+    }
+}
+
+impl Actor for Ingest {
+    type Msg = Record;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let cfg = PipelineConfig::from_sys(&ctx.system);
+
+        for i in 0..cfg.workers {
+            let worker = ctx
+                .actor_of_args::<Worker, _>(&format!("worker-{}", i), self.writer.clone())
+                .unwrap();
+            self.workers.push(worker);
+        }
+    }
+
+    fn supervisor_strategy(&self) -> Strategy {
+        Strategy::Restart
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.workers[self.next].tell(msg, sender);
+        self.next = (self.next + 1) % self.workers.len();
+    }
+}
+
+struct Worker {
+    writer: ActorRef<WriterMsg>,
+}
+
+impl ActorFactoryArgs<ActorRef<WriterMsg>> for Worker {
+    fn create_args(writer: ActorRef<WriterMsg>) -> Self {
+        Worker { writer }
+    }
+}
+
+impl Actor for Worker {
+    type Msg = Record;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        // a malformed record fails the worker
+        if msg.0.is_multiple_of(25) {
+            panic!("{}: malformed record {}", ctx.myself.name(), msg.0);
+        }
+
+        self.writer.tell(Processed(msg.0 * msg.0), None);
+    }
+}
+
+// Batching writer, writing when the batch is full or on each flush interval
+#[actor(Processed, Flush, Retry)]
+struct Writer {
+    batch_size: usize,
+    flush_interval: Duration,
+    batch: Vec<u64>,
+    writes: usize,
+    written: usize,
+}
+
+impl ActorFactoryArgs<(usize, Duration)> for Writer {
+    fn create_args((batch_size, flush_interval): (usize, Duration)) -> Self {
+        Writer {
+            batch_size,
+            flush_interval,
+            batch: Vec::new(),
+            writes: 0,
+            written: 0,
+        }
+    }
+}
+
+impl Writer {
+    fn write(&mut self, ctx: &Context<WriterMsg>, batch: Vec<u64>) {
+        if batch.is_empty() {
+            return;
+        }
+
+        // every third write to the store fails and is retried
+        self.writes += 1;
+        if self.writes.is_multiple_of(3) {
+            println!("write of {} results failed, retrying", batch.len());
+            ctx.schedule_once(Duration::from_millis(50), ctx.myself(), None, Retry(batch));
+            return;
+        }
+
+        self.written += batch.len();
+        println!(
+            "wrote {} results, {} written in total",
+            batch.len(),
+            self.written
+        );
+    }
+}
+
+impl Actor for Writer {
+    type Msg = WriterMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        ctx.schedule(
+            self.flush_interval,
+            self.flush_interval,
+            ctx.myself(),
+            None,
+            Flush,
+        );
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<Processed> for Writer {
+    type Msg = WriterMsg;
+
+    fn receive(&mut self, ctx: &Context<Self::Msg>, msg: Processed, _sender: Sender) {
+        self.batch.push(msg.0);
+
+        if self.batch.len() >= self.batch_size {
+            let batch = std::mem::take(&mut self.batch);
+            self.write(ctx, batch);
+        }
+    }
+}
+
+impl Receive<Flush> for Writer {
+    type Msg = WriterMsg;
+
+    fn receive(&mut self, ctx: &Context<Self::Msg>, _msg: Flush, _sender: Sender) {
+        let batch = std::mem::take(&mut self.batch);
+        self.write(ctx, batch);
+    }
+}
+
+impl Receive<Retry> for Writer {
+    type Msg = WriterMsg;
+
+    fn receive(&mut self, ctx: &Context<Self::Msg>, msg: Retry, _sender: Sender) {
+        self.write(ctx, msg.0);
+    }
+}
+
+fn main() {
+    let sys = ActorSystem::new().unwrap();
+    let cfg = PipelineConfig::from_sys(&sys);
+
+    let writer = sys
+        .actor_of_args::<Writer, _>("writer", (cfg.batch_size, cfg.flush_interval))
+        .unwrap();
+    let ingest = sys.actor_of_args::<Ingest, _>("ingest", writer).unwrap();
+
+    for i in 1..=100 {
+        ingest.tell(Record(i), None);
+    }
+
+    std::thread::sleep(Duration::from_millis(1000));
+    sys.print_tree();
+}
@@ -0,0 +1,113 @@
+#![crate_name = "riker_core"]
+#![no_std]
+#![deny(clippy::all)]
+
+//! The `no_std` core of the Riker actor framework.
+//!
+//! The message bound, shared messages, the lock-free mailbox queue and the
+//! supervision strategies only need `alloc`, so they can be reused by
+//! runtimes on embedded and RTOS targets. The `riker` crate re-exports
+//! them and adds the threaded runtime: actor systems, kernels, timers
+//! and the `Envelope` carrying the sender's actor reference.
+
+extern crate alloc;
+
+mod queue;
+
+use alloc::sync::Arc;
+use core::{fmt, fmt::Debug, ops::Deref};
+
+pub use crate::queue::{mpsc_queue, Consumer, Producer};
+
+pub trait Message: Debug + Clone + Send + 'static {}
+impl<T: Debug + Clone + Send + 'static> Message for T {}
+
+/// A message sharing one allocation between its clones
+///
+/// Messages are cloned for each recipient when published to a channel or
+/// sent to a selection. Wrapping a large payload in `ArcMessage` makes each
+/// clone a reference count increment, so the payload is never copied.
+/// `T` doesn't need to be `Clone`.
+///
+/// # Examples
+///
+/// ```
+/// # use riker_core::ArcMessage;
+///
+/// #[derive(Debug)]
+/// struct Frame(Vec<u8>);
+///
+/// let frame = ArcMessage::new(Frame(vec![0; 4 * 1024 * 1024]));
+/// let copy = frame.clone();
+/// assert!(ArcMessage::ptr_eq(&frame, &copy));
+/// assert_eq!(copy.0.len(), 4 * 1024 * 1024);
+/// ```
+pub struct ArcMessage<T>(Arc<T>);
+
+impl<T> ArcMessage<T> {
+    pub fn new(msg: T) -> Self {
+        ArcMessage(Arc::new(msg))
+    }
+
+    /// Returns `true` if both messages share the same allocation
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.0, &other.0)
+    }
+
+    /// Returns the payload if this is its only reference, otherwise the message
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        Arc::try_unwrap(this.0).map_err(ArcMessage)
+    }
+}
+
+impl<T> Clone for ArcMessage<T> {
+    fn clone(&self) -> Self {
+        ArcMessage(self.0.clone())
+    }
+}
+
+impl<T> Deref for ArcMessage<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> From<T> for ArcMessage<T> {
+    fn from(msg: T) -> Self {
+        ArcMessage::new(msg)
+    }
+}
+
+impl<T> From<Arc<T>> for ArcMessage<T> {
+    fn from(msg: Arc<T>) -> Self {
+        ArcMessage(msg)
+    }
+}
+
+impl<T: Debug> Debug for ArcMessage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Supervision strategy
+///
+/// Returned in `Actor.supervision_strategy`
+pub enum Strategy {
+    /// Stop the child actor
+    Stop,
+
+    /// Attempt to restart the child actor (one for one)
+    Restart,
+
+    /// Escalate the failure to a parent
+    Escalate,
+
+    /// Restart the failed child actor and all of its siblings
+    OneForAll,
+
+    /// Restart the failed child actor and the siblings created after it
+    RestForOne,
+}
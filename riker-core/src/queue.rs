@@ -0,0 +1,164 @@
+use alloc::{boxed::Box, sync::Arc};
+use core::{
+    cell::UnsafeCell,
+    hint, ptr,
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
+
+// Set in `MpscQueue::state` once the queue is closed, the other bits
+// count the producers that are linking a node
+const CLOSED: usize = 1 << (usize::BITS - 1);
+
+struct Node<T> {
+    next: AtomicPtr<Node<T>>,
+    value: Option<T>,
+}
+
+impl<T> Node<T> {
+    fn new(value: Option<T>) -> *mut Node<T> {
+        Box::into_raw(Box::new(Node {
+            next: AtomicPtr::new(ptr::null_mut()),
+            value,
+        }))
+    }
+}
+
+/// Create a lock-free multi-producer, single-consumer queue
+///
+/// Producers push with a single atomic swap, so they never contend on a
+/// lock. Based on Dmitry Vyukov's non-intrusive MPSC queue: a push is
+/// visible to the consumer once `push` has returned.
+///
+/// A push either fails, returning the value, or happens before the consumer
+/// closes the queue, so the consumer can take every value that was pushed.
+pub fn mpsc_queue<T>() -> (Producer<T>, Consumer<T>) {
+    let queue = Arc::new(MpscQueue::new());
+    let producer = Producer {
+        queue: queue.clone(),
+    };
+    (producer, Consumer { queue })
+}
+
+/// The sending half of an `mpsc_queue`
+pub struct Producer<T> {
+    queue: Arc<MpscQueue<T>>,
+}
+
+impl<T> Producer<T> {
+    /// Push a value, returning it if the consumer has closed the queue
+    pub fn push(&self, value: T) -> Result<(), T> {
+        self.queue.push(value)
+    }
+}
+
+impl<T> Clone for Producer<T> {
+    fn clone(&self) -> Self {
+        Producer {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+/// The receiving half of an `mpsc_queue`
+///
+/// Dropping the consumer closes the queue and drops the values left in it.
+pub struct Consumer<T> {
+    queue: Arc<MpscQueue<T>>,
+}
+
+impl<T> Consumer<T> {
+    /// Pop the oldest value
+    pub fn pop(&mut self) -> Option<T> {
+        // there is one consumer and popping borrows it mutably
+        unsafe { self.queue.pop() }
+    }
+
+    /// Stop accepting values
+    ///
+    /// Once `close` returns every push has either failed or is in the queue,
+    /// so the values pushed before the queue was closed can still be popped.
+    pub fn close(&mut self) {
+        self.queue.close();
+    }
+}
+
+impl<T> Drop for Consumer<T> {
+    fn drop(&mut self) {
+        self.close();
+        while self.pop().is_some() {}
+    }
+}
+
+struct MpscQueue<T> {
+    // most recently pushed node, shared by producers
+    head: AtomicPtr<Node<T>>,
+    // stub node preceding the next value, owned by the consumer
+    tail: UnsafeCell<*mut Node<T>>,
+    state: AtomicUsize,
+}
+
+// The values are only moved between threads, and only the one `Consumer`
+// touches `tail`
+unsafe impl<T: Send> Send for MpscQueue<T> {}
+unsafe impl<T: Send> Sync for MpscQueue<T> {}
+
+impl<T> MpscQueue<T> {
+    fn new() -> Self {
+        let stub = Node::new(None);
+
+        MpscQueue {
+            head: AtomicPtr::new(stub),
+            tail: UnsafeCell::new(stub),
+            state: AtomicUsize::new(0),
+        }
+    }
+
+    fn push(&self, value: T) -> Result<(), T> {
+        // register as a producer, `close` waits for the node to be linked
+        if self.state.fetch_add(1, Ordering::AcqRel) & CLOSED != 0 {
+            self.state.fetch_sub(1, Ordering::Release);
+            return Err(value);
+        }
+
+        let node = Node::new(Some(value));
+        let prev = self.head.swap(node, Ordering::AcqRel);
+        unsafe { (*prev).next.store(node, Ordering::Release) };
+
+        self.state.fetch_sub(1, Ordering::Release);
+        Ok(())
+    }
+
+    // Only one thread may pop at a time
+    unsafe fn pop(&self) -> Option<T> {
+        let tail = *self.tail.get();
+        let next = (*tail).next.load(Ordering::Acquire);
+
+        if next.is_null() {
+            return None;
+        }
+
+        // `next` becomes the new stub once its value is taken
+        *self.tail.get() = next;
+        drop(Box::from_raw(tail));
+        (*next).value.take()
+    }
+
+    fn close(&self) {
+        self.state.fetch_or(CLOSED, Ordering::AcqRel);
+
+        // the producers that registered before the queue was closed
+        // are linking their nodes, which takes a few instructions
+        while self.state.load(Ordering::Acquire) != CLOSED {
+            hint::spin_loop();
+        }
+    }
+}
+
+impl<T> Drop for MpscQueue<T> {
+    fn drop(&mut self) {
+        unsafe {
+            while self.pop().is_some() {}
+            drop(Box::from_raw(*self.tail.get()));
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use std::thread;
+
+use riker_core::mpsc_queue;
+
+#[test]
+fn queue_pop_in_push_order() {
+    let (producer, mut consumer) = mpsc_queue();
+
+    let producers: Vec<_> = (0..4)
+        .map(|p| {
+            let producer = producer.clone();
+            thread::spawn(move || {
+                for i in 0..100 {
+                    producer.push((p, i)).unwrap();
+                }
+            })
+        })
+        .collect();
+    for producer in producers {
+        producer.join().unwrap();
+    }
+
+    // each producer's values are popped in the order they were pushed
+    let mut next = [0; 4];
+    while let Some((p, i)) = consumer.pop() {
+        assert_eq!(i, next[p]);
+        next[p] += 1;
+    }
+    assert_eq!(next, [100; 4]);
+}
+
+#[test]
+fn queue_closed() {
+    let (producer, mut consumer) = mpsc_queue();
+    producer.push(1).unwrap();
+    consumer.close();
+
+    assert_eq!(producer.push(2), Err(2));
+    assert_eq!(consumer.pop(), Some(1));
+}
+
+#[test]
+fn queue_closed_while_pushing() {
+    for _ in 0..20 {
+        let (producer, mut consumer) = mpsc_queue();
+        let pushed = Arc::new(AtomicUsize::new(0));
+
+        // producers push until the queue is closed
+        let producers: Vec<_> = (0..8)
+            .map(|_| {
+                let (producer, pushed) = (producer.clone(), pushed.clone());
+                thread::spawn(move || {
+                    while producer.push(()).is_ok() {
+                        pushed.fetch_add(1, Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect();
+
+        let mut popped = 0;
+        while popped < 10_000 {
+            if consumer.pop().is_some() {
+                popped += 1;
+            }
+        }
+
+        // every value pushed before the close can still be popped
+        consumer.close();
+        while consumer.pop().is_some() {
+            popped += 1;
+        }
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        assert_eq!(popped, pushed.load(Ordering::Relaxed));
+    }
+}
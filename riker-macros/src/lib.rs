@@ -2,26 +2,54 @@ extern crate proc_macro;
 
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
+use std::collections::HashSet;
+
 use syn::parse::{Parse, ParseStream, Result};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::{Colon2, Comma};
-use syn::{DeriveInput, Generics, PathSegment, TypePath};
+use syn::{Attribute, DeriveInput, Error, Generics, PathSegment, Token, TypePath};
 
 struct MsgTypes {
     types: Vec<MsgVariant>,
 }
 
 struct MsgVariant {
+    attrs: Vec<Attribute>,
     name: Ident,
     mtype: TypePath,
 }
 
+impl MsgVariant {
+    // `#[cfg]` attributes also apply to the generated impls and match arms
+    fn cfg_attrs(&self) -> impl Iterator<Item = &Attribute> {
+        self.attrs.iter().filter(|a| a.path.is_ident("cfg"))
+    }
+}
+
+impl Parse for MsgVariant {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let mtype: TypePath = input.parse()?;
+
+        // an explicit variant name, `a::Ping as APing`
+        let name = if input.peek(Token![as]) {
+            input.parse::<Token![as]>()?;
+            input.parse()?
+        } else {
+            get_name(&mtype.path.segments)
+        };
+
+        Ok(MsgVariant { attrs, name, mtype })
+    }
+}
+
 impl MsgTypes {
     fn enum_stream(&self, name: &Ident) -> TokenStream {
         let vars = self.types.iter().map(|t| {
-            let MsgVariant { name, mtype } = t;
+            let MsgVariant { attrs, name, mtype } = t;
             quote! {
+                #(#attrs)*
                 #name(#mtype),
             }
         });
@@ -37,16 +65,23 @@ impl MsgTypes {
 
 impl Parse for MsgTypes {
     fn parse(input: ParseStream) -> Result<Self> {
-        let vars = Punctuated::<TypePath, Comma>::parse_terminated(input)?;
+        let vars = Punctuated::<MsgVariant, Comma>::parse_terminated(input)?;
+
+        let mut names = HashSet::new();
+        for var in vars.iter() {
+            if !names.insert(var.name.to_string()) {
+                return Err(Error::new(
+                    var.mtype.span(),
+                    format!(
+                        "duplicate message variant `{}`, use `Type as Name` to name the variant",
+                        var.name
+                    ),
+                ));
+            }
+        }
 
         Ok(MsgTypes {
-            types: vars
-                .into_iter()
-                .map(|t| MsgVariant {
-                    name: get_name(&t.path.segments),
-                    mtype: t,
-                })
-                .collect::<Vec<_>>(),
+            types: vars.into_iter().collect::<Vec<_>>(),
         })
     }
 }
@@ -100,10 +135,7 @@ pub fn actor(
 }
 
 fn intos(name: &Ident, types: &MsgTypes) -> TokenStream {
-    let intos = types
-        .types
-        .iter()
-        .map(|t| impl_into(&name, &t.name, &t.mtype));
+    let intos = types.types.iter().map(|t| impl_into(name, t));
     quote! {
         #(#intos)*
     }
@@ -115,7 +147,9 @@ fn receive(aname: &Ident, gen: &Generics, name: &Ident, types: &MsgTypes) -> Tok
     let vars = types.types.iter().map(|t| {
         let vname = &t.name;
         let tname = &t.mtype;
+        let cfgs = t.cfg_attrs();
         quote! {
+            #(#cfgs)*
             #name::#vname(msg) => <#aname #ty_generics as Receive<#tname>>::receive(self, ctx, msg, sender),
         }
     });
@@ -135,11 +169,15 @@ fn receive(aname: &Ident, gen: &Generics, name: &Ident, types: &MsgTypes) -> Tok
     }
 }
 
-fn impl_into(name: &Ident, vname: &Ident, ty: &TypePath) -> TokenStream {
+fn impl_into(name: &Ident, t: &MsgVariant) -> TokenStream {
+    let vname = &t.name;
+    let ty = &t.mtype;
+    let cfgs = t.cfg_attrs();
     quote! {
-        impl Into<#name> for #ty {
-            fn into(self) -> #name {
-                #name::#vname(self)
+        #(#cfgs)*
+        impl From<#ty> for #name {
+            fn from(msg: #ty) -> #name {
+                #name::#vname(msg)
             }
         }
     }
@@ -50,6 +50,7 @@ fn run_derived_actor() {
 }
 
 #[actor(String)]
+#[allow(dead_code)]
 #[derive(Clone, Default)]
 struct GenericActor<A: Send + 'static, B>
 where
@@ -222,3 +223,116 @@ fn run_path_message_actor() {
         std::thread::sleep(std::time::Duration::from_millis(50));
     }
 }
+
+#[actor(
+    /// Messages that are printed
+    String,
+    #[cfg(any())]
+    NotCompiled,
+    #[cfg(all())]
+    u32,
+)]
+#[derive(Clone, Default)]
+struct CfgMsgActor;
+
+impl Actor for CfgMsgActor {
+    type Msg = CfgMsgActorMsg;
+
+    fn supervisor_strategy(&self) -> Strategy {
+        Strategy::Stop
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+        ctx.stop(&ctx.myself);
+    }
+}
+
+impl Receive<String> for CfgMsgActor {
+    type Msg = CfgMsgActorMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: String, _sender: Option<BasicActorRef>) {
+        println!("{}", msg);
+    }
+}
+
+impl Receive<u32> for CfgMsgActor {
+    type Msg = CfgMsgActorMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, _msg: u32, _sender: Option<BasicActorRef>) {
+        println!("u32");
+    }
+}
+
+#[test]
+fn run_cfg_message_actor() {
+    let sys = ActorSystem::new().unwrap();
+
+    let act = sys.actor_of::<CfgMsgActor>("act").unwrap();
+
+    act.tell(CfgMsgActorMsg::from(1u32), None);
+
+    // wait until all direct children of the user root are terminated
+    while sys.user_root().has_children() {
+        // in order to lower cpu usage, sleep here
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+}
+
+mod ping_a {
+    #[derive(Clone, Debug)]
+    pub struct Ping;
+}
+
+mod ping_b {
+    #[derive(Clone, Debug)]
+    pub struct Ping;
+}
+
+#[actor(ping_a::Ping as APing, ping_b::Ping as BPing)]
+#[derive(Clone, Default)]
+struct RenamedMsgActor;
+
+impl Actor for RenamedMsgActor {
+    type Msg = RenamedMsgActorMsg;
+
+    fn supervisor_strategy(&self) -> Strategy {
+        Strategy::Stop
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+        ctx.stop(&ctx.myself);
+    }
+}
+
+impl Receive<ping_a::Ping> for RenamedMsgActor {
+    type Msg = RenamedMsgActorMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, _msg: ping_a::Ping, _sender: Sender) {
+        println!("a::Ping");
+    }
+}
+
+impl Receive<ping_b::Ping> for RenamedMsgActor {
+    type Msg = RenamedMsgActorMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, _msg: ping_b::Ping, _sender: Sender) {
+        println!("b::Ping");
+    }
+}
+
+#[test]
+fn run_renamed_message_actor() {
+    let sys = ActorSystem::new().unwrap();
+
+    let act = sys.actor_of::<RenamedMsgActor>("act").unwrap();
+
+    act.tell(RenamedMsgActorMsg::BPing(ping_b::Ping), None);
+
+    // wait until all direct children of the user root are terminated
+    while sys.user_root().has_children() {
+        // in order to lower cpu usage, sleep here
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+}
@@ -2,12 +2,17 @@
 pub(crate) mod actor_cell;
 pub(crate) mod actor_ref;
 pub(crate) mod channel;
+pub(crate) mod extensions;
+pub(crate) mod inbox;
+pub(crate) mod interceptor;
 pub(crate) mod macros;
 pub(crate) mod props;
+pub(crate) mod reply;
 pub(crate) mod selection;
+pub(crate) mod stream;
 pub(crate) mod uri;
 
-use std::fmt;
+use std::{any::Any, error::Error, fmt};
 
 use crate::validate::InvalidName;
 
@@ -15,20 +20,36 @@ use crate::validate::InvalidName;
 pub use self::{
     actor_cell::Context,
     actor_ref::{
-        ActorRef, ActorRefFactory, ActorReference, BasicActorRef, BoxedTell, Sender, Tell,
+        ActorRef, ActorRefFactory, ActorReference, BasicActorRef, BoxedTell, Sender, Started, Tell,
         TmpActorRefFactory,
     },
     channel::{
-        channel, All, Channel, ChannelMsg, ChannelRef, DLChannelMsg, DeadLetter, EventsChannel,
-        Publish, Subscribe, SysTopic, Topic, Unsubscribe, UnsubscribeAll,
+        channel, retained_channel, All, Channel, ChannelMsg, ChannelRef, DLChannelMsg, DeadLetter,
+        DeadLetterPayload, DeadLetterReason, EventsChannel, Publish, PublishBatch,
+        PublishWithReply, Published, Subscribe, SubscribeWithResponse, SubscribedResponse,
+        SysTopic, Topic, Unsubscribe, UnsubscribeAll,
     },
+    extensions::Extensions,
+    inbox::{Inbox, InboxReceive},
+    interceptor::{Interceptor, Next},
     macros::actor,
-    props::{ActorArgs, ActorFactory, ActorFactoryArgs, ActorProducer, BoxActorProd, Props},
-    selection::{ActorSelection, ActorSelectionFactory},
+    props::{
+        ActorArgs, ActorFactory, ActorFactoryArgs, ActorProducer, BoxActorProd, MsgPriority,
+        MsgProcessLimit, Props, WithDispatcher, WithInitMsg, WithInterceptor, WithMsgProcessLimit,
+        WithPassivation, WithPriority,
+    },
+    reply::{Ask, AskError, ReplyTo, Respondable},
+    selection::{ActorSelection, ActorSelectionFactory, CachedSelection},
+    stream::{ActorSink, StreamActor, StreamMsg},
     uri::{ActorPath, ActorUri},
 };
 
-use crate::{system::SystemMsg, Message};
+pub use riker_core::Strategy;
+
+use crate::{
+    system::{FailureInfo, Health, SystemMsg},
+    Message,
+};
 
 #[allow(unused)]
 pub type MsgResult<T> = Result<(), MsgError<T>>;
@@ -58,6 +79,53 @@ impl<T> fmt::Debug for MsgError<T> {
     }
 }
 
+/// Error returned by `ActorRef::try_send` when a message can't be sent
+///
+/// The message is returned to the caller and is not sent to dead letters.
+#[derive(Clone)]
+pub enum SendError<T> {
+    /// The actor was terminated or its mailbox was closed
+    Terminated(T),
+
+    /// The message was shed by the governor because the system is overloaded
+    Overloaded(T),
+}
+
+impl<T> SendError<T> {
+    /// Returns the message that couldn't be sent
+    pub fn into_inner(self) -> T {
+        match self {
+            SendError::Terminated(msg) | SendError::Overloaded(msg) => msg,
+        }
+    }
+
+    pub(crate) fn map<U>(self, f: impl FnOnce(T) -> U) -> SendError<U> {
+        match self {
+            SendError::Terminated(msg) => SendError::Terminated(f(msg)),
+            SendError::Overloaded(msg) => SendError::Overloaded(f(msg)),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SendError::Terminated(_) => {
+                f.write_str("The actor does not exist. It may have been terminated")
+            }
+            SendError::Overloaded(_) => {
+                f.write_str("The message was shed because the system is overloaded")
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_string())
+    }
+}
+
 /// Error type when an `try_tell` fails on `Option<ActorRef<Msg>>`
 pub struct TryMsgError<T> {
     pub msg: T,
@@ -82,12 +150,14 @@ impl<T> fmt::Debug for TryMsgError<T> {
 }
 
 /// Error type when an actor fails to start during `actor_of`.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum CreateError {
     Panicked,
     System,
     InvalidName(String),
     AlreadyExists(ActorPath),
+    UnknownDispatcher(String),
+    ParentNotFound(String),
 }
 
 impl fmt::Display for CreateError {
@@ -105,10 +175,20 @@ impl fmt::Display for CreateError {
                 "Failed to create actor. Cause: An actor at the same path already exists ({})",
                 path
             )),
+            Self::UnknownDispatcher(ref name) => f.write_str(&format!(
+                "Failed to create actor. Cause: No dispatcher is configured with the name ({})",
+                name
+            )),
+            Self::ParentNotFound(ref path) => f.write_str(&format!(
+                "Failed to create actor. Cause: No parent actor exists at the path ({})",
+                path
+            )),
         }
     }
 }
 
+impl Error for CreateError {}
+
 impl From<InvalidName> for CreateError {
     fn from(err: InvalidName) -> CreateError {
         CreateError::InvalidName(err.name)
@@ -153,11 +233,50 @@ pub trait Actor: Send + 'static {
     /// Invoked after an actor has been stopped.
     fn post_stop(&mut self) {}
 
+    /// Invoked on the current instance before an actor is restarted.
+    ///
+    /// `reason` is the failure that caused the restart. With `OneForAll`
+    /// and `RestForOne` its `actor` can be a failed sibling. It is `None`
+    /// if the restart was not caused by a failure.
+    ///
+    /// Resources held by the instance can be released here.
+    /// The instance is dropped once `pre_restart` returns.
+    fn pre_restart(&mut self, ctx: &Context<Self::Msg>, reason: Option<FailureInfo>) {}
+
+    /// Invoked on the new instance after a restarted actor has started,
+    /// following `pre_start`.
+    ///
+    /// State can be replayed here, for example.
+    fn post_restart(&mut self, ctx: &Context<Self::Msg>) {}
+
+    /// Invoked on the current instance before an actor is restarted,
+    /// ahead of `pre_restart`.
+    ///
+    /// State returned here is given to the new instance's `import_state`,
+    /// so that it can keep state that the failure didn't corrupt, such as
+    /// a connection pool, instead of starting cold.
+    fn export_state(&mut self) -> Option<StateBlob> {
+        None
+    }
+
+    /// Invoked on the new instance of a restarted actor with the state
+    /// returned by `export_state`, before `pre_start`.
+    fn import_state(&mut self, state: StateBlob) {}
+
     /// Return a supervisor strategy that will be used when handling failed child actors.
     fn supervisor_strategy(&self) -> Strategy {
         Strategy::Restart
     }
 
+    /// Return the supervisor strategy for a specific child failure.
+    ///
+    /// Defaults to `supervisor_strategy`. Override to choose a strategy
+    /// based on why the child failed, e.g. stopping it on fatal errors
+    /// and restarting it on transient ones.
+    fn decide(&self, info: &FailureInfo) -> Strategy {
+        self.supervisor_strategy()
+    }
+
     /// Invoked when an actor receives a system message
     ///
     /// It is guaranteed that only one message in the actor's mailbox is processed
@@ -169,6 +288,32 @@ pub trait Actor: Send + 'static {
     /// It is guaranteed that only one message in the actor's mailbox is processed
     /// at any one time, including `recv` and `sys_recv`.
     fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender);
+
+    /// Invoked by the system to check the actor's health, see `ActorSystem::watch_health`
+    ///
+    /// Keep it quick, it's called between messages. The default is `Healthy`.
+    fn health(&self) -> Health {
+        Health::Healthy
+    }
+
+    /// Return `true` to receive messages in batches with `recv_batch`
+    /// instead of one at a time with `recv`.
+    fn receives_batches(&self) -> bool {
+        false
+    }
+
+    /// Invoked with the messages waiting in the actor's mailbox, at most
+    /// `mailbox.msg_process_limit` at a time unless the actor sets its own
+    /// limit with `WithMsgProcessLimit`, if `receives_batches` is `true`
+    ///
+    /// Batching avoids the per-message overhead of `recv` for actors that
+    /// aggregate high volume streams. Senders aren't kept, and system
+    /// messages are processed between batches rather than between messages.
+    fn recv_batch(&mut self, ctx: &Context<Self::Msg>, msgs: Vec<Self::Msg>) {
+        for msg in msgs {
+            self.recv(ctx, msg, None);
+        }
+    }
 }
 
 impl<A: Actor + ?Sized> Actor for Box<A> {
@@ -186,6 +331,22 @@ impl<A: Actor + ?Sized> Actor for Box<A> {
         (**self).post_stop()
     }
 
+    fn pre_restart(&mut self, ctx: &Context<Self::Msg>, reason: Option<FailureInfo>) {
+        (**self).pre_restart(ctx, reason)
+    }
+
+    fn post_restart(&mut self, ctx: &Context<Self::Msg>) {
+        (**self).post_restart(ctx)
+    }
+
+    fn export_state(&mut self) -> Option<StateBlob> {
+        (**self).export_state()
+    }
+
+    fn import_state(&mut self, state: StateBlob) {
+        (**self).import_state(state)
+    }
+
     fn sys_recv(
         &mut self,
         ctx: &Context<Self::Msg>,
@@ -199,9 +360,46 @@ impl<A: Actor + ?Sized> Actor for Box<A> {
         (**self).supervisor_strategy()
     }
 
+    fn decide(&self, info: &FailureInfo) -> Strategy {
+        (**self).decide(info)
+    }
+
     fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
         (**self).recv(ctx, msg, sender)
     }
+
+    fn health(&self) -> Health {
+        (**self).health()
+    }
+
+    fn receives_batches(&self) -> bool {
+        (**self).receives_batches()
+    }
+
+    fn recv_batch(&mut self, ctx: &Context<Self::Msg>, msgs: Vec<Self::Msg>) {
+        (**self).recv_batch(ctx, msgs)
+    }
+}
+
+/// State handed from an actor's failed instance to its new instance,
+/// see `Actor::export_state`
+pub struct StateBlob(Box<dyn Any + Send>);
+
+impl StateBlob {
+    pub fn new<T: Any + Send>(state: T) -> Self {
+        StateBlob(Box::new(state))
+    }
+
+    /// Returns the state if it is a `T`, otherwise the blob
+    pub fn downcast<T: Any + Send>(self) -> Result<T, StateBlob> {
+        self.0.downcast().map(|state| *state).map_err(StateBlob)
+    }
+}
+
+impl fmt::Debug for StateBlob {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("StateBlob")
+    }
 }
 
 /// Receive and handle a specific message type
@@ -274,17 +472,3 @@ pub trait Receive<Msg: Message> {
 
 /// The actor trait object
 pub type BoxActor<Msg> = Box<dyn Actor<Msg = Msg> + Send>;
-
-/// Supervision strategy
-///
-/// Returned in `Actor.supervision_strategy`
-pub enum Strategy {
-    /// Stop the child actor
-    Stop,
-
-    /// Attempt to restart the child actor
-    Restart,
-
-    /// Escalate the failure to a parent
-    Escalate,
-}
@@ -1,27 +1,39 @@
 use std::{
+    any::Any,
+    cell::RefCell,
     fmt,
     ops::Deref,
+    panic::AssertUnwindSafe,
     sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
-        Arc,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
     },
     time::{Duration, Instant},
 };
 
 use chrono::prelude::*;
 use dashmap::DashMap;
-use futures::{future::RemoteHandle, task::SpawnError, Future};
+use futures::{
+    channel::oneshot,
+    future::{AbortHandle, Abortable, RemoteHandle},
+    task::{SpawnError, SpawnExt, Waker},
+    Future, FutureExt,
+};
 use uuid::Uuid;
 
 use crate::{
-    actor::{props::ActorFactory, *},
+    actor::{props::ActorFactory, reply::Asker, selection::from_user_root, *},
     kernel::{
         kernel_ref::{dispatch, dispatch_any, KernelRef},
-        mailbox::{AnyEnqueueError, AnySender, MailboxSender},
+        mailbox::{AnyEnqueueError, AnySender, MailboxSchedule, MailboxSender},
     },
+    metrics,
     system::{
-        timer::{Job, OnceJob, RepeatJob, ScheduleId, Timer},
-        ActorSystem, Run, SystemCmd, SystemMsg,
+        clock::delay_until,
+        governor::{self, Admission},
+        timer::{Job, OnceJob, RepeatJob, ScheduleId, SysJob, Timer},
+        ActorInfo, ActorSystem, FailureInfo, ReceiveTimeout, Run, SystemCmd, SystemMsg, TaskFailed,
+        TaskFailure,
     },
     validate::InvalidPath,
     AnyMessage, Envelope, Message,
@@ -37,14 +49,50 @@ struct ActorCellInner {
     uri: ActorUri,
     parent: Option<BasicActorRef>,
     children: Children,
-    is_remote: bool,
     is_terminating: Arc<AtomicBool>,
     is_restarting: Arc<AtomicBool>,
-    status: Arc<AtomicUsize>,
+    restarts: Arc<AtomicUsize>,
+    started: Instant,
+    processed: Arc<AtomicU64>,
+    restart_reason: Arc<Mutex<Option<FailureInfo>>>,
+    post_restart_due: Arc<AtomicBool>,
+    poison_at: Arc<AtomicU64>,
     kernel: Option<KernelRef>,
     system: ActorSystem,
     mailbox: Arc<dyn AnySender>,
     sys_mailbox: MailboxSender<SystemMsg>,
+    tasks: Arc<DashMap<u64, AbortHandle>>,
+    next_task: Arc<AtomicU64>,
+    notifications: Arc<DashMap<ScheduleId, Option<Instant>>>,
+    has_schedules: Arc<AtomicBool>,
+    receive_timeout: Arc<Mutex<Option<ReceiveTimeoutState>>>,
+    has_receive_timeout: Arc<AtomicBool>,
+    passivation: Arc<Mutex<Option<ReceiveTimeoutState>>>,
+    has_passivation: Arc<AtomicBool>,
+    passivated: Arc<AtomicBool>,
+    sinks: Arc<Mutex<Vec<Waker>>>,
+    has_sinks: Arc<AtomicBool>,
+    extensions: Extensions,
+    start: Arc<StartWatch>,
+}
+
+// Completes the `Started` futures once the actor has started, or failed to
+#[derive(Default)]
+struct StartWatch {
+    state: Mutex<StartState>,
+}
+
+#[derive(Default)]
+struct StartState {
+    result: Option<Result<(), CreateError>>,
+    waiters: Vec<oneshot::Sender<Result<(), CreateError>>>,
+}
+
+// An idle window, of the receive timeout or of passivation
+struct ReceiveTimeoutState {
+    id: ScheduleId,
+    timeout: Duration,
+    last_recv: Instant,
 }
 
 impl ActorCell {
@@ -61,14 +109,31 @@ impl ActorCell {
                 uri,
                 parent,
                 children: Children::new(),
-                is_remote: false,
                 is_terminating: Arc::new(AtomicBool::new(false)),
                 is_restarting: Arc::new(AtomicBool::new(false)),
-                status: Arc::new(AtomicUsize::new(0)),
+                restarts: Arc::new(AtomicUsize::new(0)),
+                started: system.clock().now(),
+                processed: Arc::new(AtomicU64::new(0)),
+                restart_reason: Arc::new(Mutex::new(None)),
+                post_restart_due: Arc::new(AtomicBool::new(false)),
+                poison_at: Arc::new(AtomicU64::new(u64::MAX)),
                 kernel: None,
                 system: system.clone(),
                 mailbox,
                 sys_mailbox,
+                tasks: Arc::new(DashMap::new()),
+                next_task: Arc::new(AtomicU64::new(0)),
+                notifications: Arc::new(DashMap::new()),
+                has_schedules: Arc::new(AtomicBool::new(false)),
+                receive_timeout: Arc::new(Mutex::new(None)),
+                has_receive_timeout: Arc::new(AtomicBool::new(false)),
+                passivation: Arc::new(Mutex::new(None)),
+                has_passivation: Arc::new(AtomicBool::new(false)),
+                passivated: Arc::new(AtomicBool::new(false)),
+                sinks: Arc::new(Mutex::new(Vec::new())),
+                has_sinks: Arc::new(AtomicBool::new(false)),
+                extensions: Extensions::default(),
+                start: Arc::new(StartWatch::default()),
             }),
         }
     }
@@ -96,6 +161,10 @@ impl ActorCell {
         &self.inner.uri
     }
 
+    pub(crate) fn system(&self) -> &ActorSystem {
+        &self.inner.system
+    }
+
     pub(crate) fn parent(&self) -> BasicActorRef {
         self.inner.parent.as_ref().unwrap().clone()
     }
@@ -108,6 +177,10 @@ impl ActorCell {
         Box::new(self.inner.children.iter())
     }
 
+    pub(crate) fn children_sorted<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
+        Box::new(self.inner.children.sorted())
+    }
+
     pub(crate) fn user_root(&self) -> BasicActorRef {
         self.inner.system.user_root().clone()
     }
@@ -125,21 +198,108 @@ impl ActorCell {
         msg: &mut AnyMessage,
         sender: crate::actor::Sender,
     ) -> Result<(), AnyEnqueueError> {
+        if let Some(res) = self.govern_any(msg, &sender) {
+            return res;
+        }
+
+        let sys = &self.inner.system;
         let mb = &self.inner.mailbox;
         let k = self.kernel();
 
-        dispatch_any(msg, sender, mb, k, &self.inner.system)
+        dispatch_any(msg, sender, mb, k, sys).map(|_| self.any_enqueued())
+    }
+
+    /// Enqueue several messages, scheduling the mailbox at most once
+    pub(crate) fn send_any_batch(&self, msgs: Vec<(&mut AnyMessage, crate::actor::Sender)>) {
+        let mb = &self.inner.mailbox;
+        let mut enqueued = false;
+
+        for (msg, sender) in msgs {
+            if self.govern_any(msg, &sender).is_some() {
+                continue;
+            }
+
+            if mb.try_any_enqueue(msg, sender).is_ok() {
+                self.any_enqueued();
+                enqueued = true;
+            }
+        }
+
+        if enqueued && !mb.is_sched() {
+            mb.set_sched(true);
+            self.kernel().schedule(&self.inner.system);
+        }
+    }
+
+    /// Number of user messages waiting in the mailbox
+    pub(crate) fn queued(&self) -> usize {
+        self.inner.mailbox.queued()
+    }
+
+    // Returns the result of sending `msg` if the governor shed or delayed it
+    fn govern_any(
+        &self,
+        msg: &mut AnyMessage,
+        sender: &crate::actor::Sender,
+    ) -> Option<Result<(), AnyEnqueueError>> {
+        let sys = &self.inner.system;
+        let governor = sys.governor()?;
+
+        match governor.admit(sys, &self.uri().path, sender) {
+            Admission::Accept => None,
+            Admission::Shed => {
+                metrics::dead_letter(&self.uri().path);
+
+                // the message type isn't known here so it can't be formatted
+                let dl = DeadLetter {
+                    msg: "AnyMessage".to_string(),
+                    sender: sender.clone(),
+                    recipient: self.myself(),
+                    reason: DeadLetterReason::Overloaded,
+                    payload: Some(DeadLetterPayload::from_any(msg)),
+                };
+
+                governor::publish_shed(sys, dl);
+                Some(Err(AnyEnqueueError))
+            }
+            Admission::Delay(delay) => {
+                self.set_has_schedules();
+                let job = OnceJob {
+                    id: Uuid::new_v4(),
+                    send_at: sys.clock().now() + delay,
+                    receiver: self.myself(),
+                    sender: sender.clone(),
+                    msg: msg.clone(),
+                };
+
+                Some(sys.timer.send(Job::Once(job)).map_err(|_| AnyEnqueueError))
+            }
+        }
+    }
+
+    fn any_enqueued(&self) {
+        metrics::msg_enqueued(&self.uri().path);
+        if let Some(governor) = self.inner.system.governor() {
+            governor.enqueued();
+        }
     }
 
     pub(crate) fn send_sys_msg(&self, msg: Envelope<SystemMsg>) -> MsgResult<Envelope<SystemMsg>> {
         let mb = &self.inner.sys_mailbox;
 
+        // The poison pill's place in the user queue is fixed when it's sent,
+        // after the messages enqueued so far. The earliest pill wins.
+        if let SystemMsg::Command(SystemCmd::PoisonPill) = msg.msg {
+            let at = self.inner.mailbox.enqueued();
+            self.inner.poison_at.fetch_min(at, Ordering::Relaxed);
+        }
+
         let k = self.kernel();
         dispatch(msg, mb, k, &self.inner.system)
     }
 
     pub(crate) fn is_child(&self, actor: &BasicActorRef) -> bool {
-        self.inner.children.iter().any(|child| child == *actor)
+        self.inner.children.contains(actor)
     }
 
     pub(crate) fn stop(&self, actor: &BasicActorRef) {
@@ -157,7 +317,33 @@ impl ActorCell {
     pub fn receive_cmd<A: Actor>(&self, cmd: SystemCmd, actor: &mut Option<A>) {
         match cmd {
             SystemCmd::Stop => self.terminate(actor),
+            SystemCmd::PoisonPill => self.poison(actor),
             SystemCmd::Restart => self.restart(),
+            // handled by the mailbox
+            SystemCmd::Suspend | SystemCmd::Resume => {}
+        }
+    }
+
+    fn poison<A: Actor>(&self, actor: &mut Option<A>) {
+        // The poison pill is processed after the user messages that
+        // were queued when it was sent. If they're done, stop right away.
+        if self.poison_pill_due() {
+            self.terminate(actor);
+        }
+    }
+
+    /// Returns `true` once the user messages queued ahead of a poison pill
+    /// have been processed and the actor should terminate.
+    pub(crate) fn poison_pill_due(&self) -> bool {
+        self.ahead_of_poison_pill() == Some(0)
+    }
+
+    /// The number of user messages still to be taken ahead of a poison pill,
+    /// if one has been sent
+    pub(crate) fn ahead_of_poison_pill(&self) -> Option<u64> {
+        match self.inner.poison_at.load(Ordering::Relaxed) {
+            u64::MAX => None,
+            at => Some(at.saturating_sub(self.inner.mailbox.dequeued())),
         }
     }
 
@@ -170,6 +356,7 @@ impl ActorCell {
 
         if !self.has_children() {
             self.kernel().terminate(&self.inner.system);
+            self.release();
             post_stop(actor);
         } else {
             for child in self.inner.children.iter() {
@@ -178,6 +365,268 @@ impl ActorCell {
         }
     }
 
+    /// Spawn a future on the system executor that is aborted when this actor stops
+    pub(crate) fn spawn_task<Fut>(&self, future: Fut) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let id = self.inner.next_task.fetch_add(1, Ordering::Relaxed);
+        let (handle, registration) = AbortHandle::new_pair();
+        self.inner.tasks.insert(id, handle);
+
+        let tasks = self.inner.tasks.clone();
+        let f = Abortable::new(future, registration).map(move |_| {
+            tasks.remove(&id);
+        });
+
+        self.inner.system.exec.spawn(f).inspect_err(|_| {
+            self.inner.tasks.remove(&id);
+        })
+    }
+
+    // Abort the spawned futures of a stopped actor. Its timer jobs, e.g.
+    // the receive timeout, are cancelled at once when the kernel terminates
+    // it, so they aren't cancelled one by one here.
+    fn release(&self) {
+        self.abort_futures();
+        self.inner
+            .has_receive_timeout
+            .store(false, Ordering::Relaxed);
+        self.inner.receive_timeout.lock().unwrap().take();
+        self.inner.has_passivation.store(false, Ordering::Relaxed);
+        self.inner.passivation.lock().unwrap().take();
+        self.inner.notifications.clear();
+        self.wake_sinks();
+    }
+
+    fn abort_tasks(&self) {
+        self.abort_futures();
+        self.cancel_receive_timeout();
+        self.cancel_notifications();
+    }
+
+    fn abort_futures(&self) {
+        for task in self.inner.tasks.iter() {
+            task.value().abort();
+        }
+        self.inner.tasks.clear();
+    }
+
+    /// Cancel a schedule to this actor when it stops, see `Context::notify_later`
+    ///
+    /// `due` is when a once schedule is sent, `None` if it repeats. Once
+    /// schedules that were sent are dropped as others are added.
+    pub(crate) fn add_notification(&self, id: ScheduleId, due: Option<Instant>) {
+        let now = self.inner.system.clock().now();
+        self.inner
+            .notifications
+            .retain(|_, due| due.is_none_or(|due| due > now));
+        self.inner.notifications.insert(id, due);
+    }
+
+    pub(crate) fn remove_notification(&self, id: ScheduleId) {
+        self.inner.notifications.remove(&id);
+    }
+
+    /// Marks the actor as the receiver of a timer job
+    pub(crate) fn set_has_schedules(&self) {
+        self.inner.has_schedules.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether a timer job was ever sent for the actor, so that
+    /// the timer is only asked to cancel its jobs if it may have some
+    pub(crate) fn has_schedules(&self) -> bool {
+        self.inner.has_schedules.load(Ordering::Relaxed)
+    }
+
+    fn cancel_notifications(&self) {
+        for notification in self.inner.notifications.iter() {
+            let _ = self
+                .inner
+                .system
+                .timer
+                .send(Job::Cancel(*notification.key()));
+        }
+        self.inner.notifications.clear();
+    }
+
+    /// Whether fewer than `max` user messages are waiting, or the actor is terminating
+    ///
+    /// If not, `waker` is woken when the actor next processes a message.
+    pub(crate) fn poll_queued(&self, max: usize, waker: &Waker) -> bool {
+        let ready = || self.queued() < max || self.inner.is_terminating.load(Ordering::Relaxed);
+        if ready() {
+            return true;
+        }
+
+        self.inner.sinks.lock().unwrap().push(waker.clone());
+        self.inner.has_sinks.store(true, Ordering::SeqCst);
+
+        // a message may have been processed before the waker was registered
+        ready()
+    }
+
+    /// Wake the sinks waiting for room in the mailbox after messages are dequeued
+    pub(crate) fn wake_sinks(&self) {
+        if self.inner.has_sinks.swap(false, Ordering::SeqCst) {
+            for waker in self.inner.sinks.lock().unwrap().drain(..) {
+                waker.wake();
+            }
+        }
+    }
+
+    pub(crate) fn set_receive_timeout(&self, timeout: Duration) {
+        let mut state = self.inner.receive_timeout.lock().unwrap();
+        if let Some(old) = state.take() {
+            let _ = self.inner.system.timer.send(Job::Cancel(old.id));
+        }
+
+        let id = Uuid::new_v4();
+        let now = self.inner.system.clock().now();
+        *state = Some(ReceiveTimeoutState {
+            id,
+            timeout,
+            last_recv: now,
+        });
+        self.inner
+            .has_receive_timeout
+            .store(true, Ordering::Relaxed);
+        self.schedule_receive_timeout(id, now + timeout);
+    }
+
+    pub(crate) fn cancel_receive_timeout(&self) {
+        self.inner
+            .has_receive_timeout
+            .store(false, Ordering::Relaxed);
+        if let Some(old) = self.inner.receive_timeout.lock().unwrap().take() {
+            let _ = self.inner.system.timer.send(Job::Cancel(old.id));
+        }
+    }
+
+    /// Count `count` processed user messages and restart the receive timeout window
+    pub(crate) fn received(&self, count: usize) {
+        self.inner
+            .processed
+            .fetch_add(count as u64, Ordering::Relaxed);
+
+        if self.inner.has_receive_timeout.load(Ordering::Relaxed) {
+            if let Some(state) = self.inner.receive_timeout.lock().unwrap().as_mut() {
+                state.last_recv = self.inner.system.clock().now();
+            }
+        }
+        if self.inner.has_passivation.load(Ordering::Relaxed) {
+            if let Some(state) = self.inner.passivation.lock().unwrap().as_mut() {
+                state.last_recv = self.inner.system.clock().now();
+            }
+        }
+    }
+
+    /// Passivate the actor once it hasn't received a message for `idle`, see `WithPassivation`
+    pub(crate) fn set_passivation(&self, idle: Duration) {
+        let id = Uuid::new_v4();
+        let now = self.inner.system.clock().now();
+        *self.inner.passivation.lock().unwrap() = Some(ReceiveTimeoutState {
+            id,
+            timeout: idle,
+            last_recv: now,
+        });
+        self.inner.has_passivation.store(true, Ordering::Relaxed);
+        self.schedule_receive_timeout(id, now + idle);
+    }
+
+    /// Whether the actor has been idle for the passivation window
+    ///
+    /// Returns `None` if `timeout` isn't a passivation check. If the window
+    /// hasn't passed the next check is scheduled, otherwise either
+    /// `passivate` or `restart_passivation` should follow.
+    pub(crate) fn passivation_due(&self, timeout: &ReceiveTimeout) -> Option<bool> {
+        let state = self.inner.passivation.lock().unwrap();
+        match state.as_ref() {
+            Some(state) if state.id == timeout.id => {
+                let due_at = state.last_recv + state.timeout;
+                if self.inner.system.clock().now() >= due_at {
+                    Some(true)
+                } else {
+                    self.schedule_receive_timeout(state.id, due_at);
+                    Some(false)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Start a new passivation window from now
+    pub(crate) fn restart_passivation(&self) {
+        if let Some(state) = self.inner.passivation.lock().unwrap().as_mut() {
+            let now = self.inner.system.clock().now();
+            state.last_recv = now;
+            self.schedule_receive_timeout(state.id, now + state.timeout);
+        }
+    }
+
+    /// Stop the idle actor instance, it's recreated on the next message
+    ///
+    /// Unlike `terminate` the actor keeps its path, mailbox and extensions.
+    pub(crate) fn passivate<A: Actor>(&self, actor: &mut Option<A>) {
+        self.abort_tasks();
+        post_stop(actor);
+        *actor = None;
+        self.inner.passivated.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the actor was passivated, starting a new passivation window if so
+    pub(crate) fn reactivate(&self) -> bool {
+        let passivated = self.inner.passivated.swap(false, Ordering::Relaxed);
+        if passivated {
+            self.restart_passivation();
+        }
+        passivated
+    }
+
+    pub(crate) fn info(&self) -> ActorInfo {
+        ActorInfo {
+            path: self.uri().path.clone(),
+            uptime: self.inner.system.clock().now() - self.inner.started,
+            msg_processed: self.inner.processed.load(Ordering::Relaxed),
+            mailbox_len: self.queued(),
+            restarts: self.restart_count(),
+        }
+    }
+
+    /// Whether a `ReceiveTimeout` is due, rescheduling the next check
+    ///
+    /// Timeouts from a cancelled or replaced window are never due.
+    pub(crate) fn receive_timeout_due(&self, timeout: &ReceiveTimeout) -> bool {
+        let mut state = self.inner.receive_timeout.lock().unwrap();
+        match state.as_mut() {
+            Some(state) if state.id == timeout.id => {
+                let now = self.inner.system.clock().now();
+                let due_at = state.last_recv + state.timeout;
+
+                if now >= due_at {
+                    state.last_recv = now;
+                    self.schedule_receive_timeout(state.id, now + state.timeout);
+                    true
+                } else {
+                    self.schedule_receive_timeout(state.id, due_at);
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn schedule_receive_timeout(&self, id: ScheduleId, send_at: Instant) {
+        self.set_has_schedules();
+        let job = SysJob {
+            id,
+            send_at,
+            receiver: self.myself(),
+            msg: ReceiveTimeout { id }.into(),
+        };
+        let _ = self.inner.system.timer.send(Job::Sys(job));
+    }
+
     pub fn restart(&self) {
         if !self.has_children() {
             self.kernel().restart(&self.inner.system);
@@ -190,13 +639,14 @@ impl ActorCell {
     }
 
     pub fn death_watch<A: Actor>(&self, terminated: &BasicActorRef, actor: &mut Option<A>) {
-        if self.is_child(&terminated) {
+        if self.is_child(terminated) {
             self.remove_child(terminated);
 
             if !self.has_children() {
                 // No children exist. Stop this actor's kernel.
                 if self.inner.is_terminating.load(Ordering::Relaxed) {
                     self.kernel().terminate(&self.inner.system);
+                    self.release();
                     post_stop(actor);
                 }
 
@@ -209,24 +659,94 @@ impl ActorCell {
         }
     }
 
-    pub fn handle_failure(&self, failed: BasicActorRef, strategy: Strategy) {
+    /// Number of times this actor has been restarted
+    pub(crate) fn restart_count(&self) -> usize {
+        self.inner.restarts.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn started(&self) -> oneshot::Receiver<Result<(), CreateError>> {
+        let (tx, rx) = oneshot::channel();
+        let mut state = self.inner.start.state.lock().unwrap();
+
+        match state.result {
+            Some(ref result) => {
+                let _ = tx.send(result.clone());
+            }
+            None => state.waiters.push(tx),
+        }
+
+        rx
+    }
+
+    // Only the first start counts, later ones are restarts or reactivations
+    pub(crate) fn set_started(&self, result: Result<(), CreateError>) {
+        let mut state = self.inner.start.state.lock().unwrap();
+        if state.result.is_some() {
+            return;
+        }
+
+        for tx in state.waiters.drain(..) {
+            let _ = tx.send(result.clone());
+        }
+        state.result = Some(result);
+    }
+
+    pub(crate) fn restarted(&self) {
+        self.inner.restarts.fetch_add(1, Ordering::Relaxed);
+        self.inner.post_restart_due.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` once after each restart, when `post_restart` is due
+    pub(crate) fn post_restart_due(&self) -> bool {
+        self.inner.post_restart_due.swap(false, Ordering::Relaxed)
+    }
+
+    pub(crate) fn take_restart_reason(&self) -> Option<FailureInfo> {
+        self.inner.restart_reason.lock().unwrap().take()
+    }
+
+    pub fn handle_failure(&self, info: FailureInfo, strategy: Strategy) {
+        let failed = info.actor.clone();
         match strategy {
             Strategy::Stop => self.stop(&failed),
-            Strategy::Restart => self.restart_child(&failed),
-            Strategy::Escalate => self.escalate_failure(),
+            Strategy::Restart => self.restart_failed(&failed, &info),
+            Strategy::Escalate => self.escalate_failure(info),
+            Strategy::OneForAll => {
+                for child in self.inner.children.iter() {
+                    self.restart_failed(&child, &info);
+                }
+            }
+            Strategy::RestForOne => {
+                self.restart_failed(&failed, &info);
+                for child in self.inner.children.created_after(&failed) {
+                    self.restart_failed(&child, &info);
+                }
+            }
         }
     }
 
+    // Restart a child, passing the failure to its `pre_restart`
+    fn restart_failed(&self, actor: &BasicActorRef, info: &FailureInfo) {
+        *actor.cell.inner.restart_reason.lock().unwrap() = Some(info.clone());
+        self.restart_child(actor);
+    }
+
     pub fn restart_child(&self, actor: &BasicActorRef) {
         actor.sys_tell(SystemCmd::Restart.into());
     }
 
-    pub fn escalate_failure(&self) {
+    pub fn escalate_failure(&self, info: FailureInfo) {
+        let info = FailureInfo {
+            actor: self.myself(),
+            panic_msg: info.panic_msg,
+            restart_count: self.restart_count(),
+        };
+
         self.inner
             .parent
             .as_ref()
             .unwrap()
-            .sys_tell(SystemMsg::Failed(self.myself()));
+            .sys_tell(SystemMsg::Failed(info));
     }
 }
 
@@ -307,14 +827,31 @@ where
                 uri,
                 parent,
                 children: Children::new(),
-                is_remote: false,
                 is_terminating: Arc::new(AtomicBool::new(false)),
                 is_restarting: Arc::new(AtomicBool::new(false)),
-                status: Arc::new(AtomicUsize::new(0)),
+                restarts: Arc::new(AtomicUsize::new(0)),
+                started: system.clock().now(),
+                processed: Arc::new(AtomicU64::new(0)),
+                restart_reason: Arc::new(Mutex::new(None)),
+                post_restart_due: Arc::new(AtomicBool::new(false)),
+                poison_at: Arc::new(AtomicU64::new(u64::MAX)),
                 kernel: None,
                 system: system.clone(),
                 mailbox: any_mailbox,
                 sys_mailbox,
+                tasks: Arc::new(DashMap::new()),
+                next_task: Arc::new(AtomicU64::new(0)),
+                notifications: Arc::new(DashMap::new()),
+                has_schedules: Arc::new(AtomicBool::new(false)),
+                receive_timeout: Arc::new(Mutex::new(None)),
+                has_receive_timeout: Arc::new(AtomicBool::new(false)),
+                passivation: Arc::new(Mutex::new(None)),
+                has_passivation: Arc::new(AtomicBool::new(false)),
+                passivated: Arc::new(AtomicBool::new(false)),
+                sinks: Arc::new(Mutex::new(Vec::new())),
+                has_sinks: Arc::new(AtomicBool::new(false)),
+                extensions: Extensions::default(),
+                start: Arc::new(StartWatch::default()),
             }),
         };
 
@@ -347,10 +884,16 @@ where
         self.cell.is_child(actor)
     }
 
+    /// Iterator over children references, in the order they were created
     pub fn children<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
         self.cell.children()
     }
 
+    /// Iterator over children references, in order of name
+    pub fn children_sorted<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
+        self.cell.children_sorted()
+    }
+
     pub fn user_root(&self) -> BasicActorRef {
         self.cell.user_root()
     }
@@ -364,39 +907,162 @@ where
     }
 
     pub(crate) fn send_msg(&self, msg: Envelope<Msg>) -> MsgResult<Envelope<Msg>> {
-        let mb = &self.mailbox;
-        let k = self.cell.kernel();
+        self.try_send_msg(msg).map_err(|e| {
+            let sys = self.system();
+            metrics::dead_letter(&self.uri().path);
+
+            let (msg, reason) = match e {
+                SendError::Terminated(msg) => (msg, DeadLetterReason::Terminated),
+                SendError::Overloaded(msg) => (msg, DeadLetterReason::Overloaded),
+            };
 
-        dispatch(msg, mb, k, &self.system()).map_err(|e| {
-            let dl = e.clone(); // clone the failed message and send to dead letters
             let dl = DeadLetter {
-                msg: format!("{:?}", dl.msg.msg),
-                sender: dl.msg.sender,
+                msg: format!("{:?}", msg.msg),
+                sender: msg.sender.clone(),
                 recipient: self.cell.myself(),
+                reason,
+                payload: Some(DeadLetterPayload::new(msg.msg.clone())),
             };
 
-            self.cell.inner.system.dead_letters().tell(
-                Publish {
-                    topic: "dead_letter".into(),
-                    msg: dl,
-                },
-                None,
-            );
+            match reason {
+                DeadLetterReason::Overloaded => governor::publish_shed(sys, dl),
+                DeadLetterReason::Terminated | DeadLetterReason::Throttled => {
+                    sys.dead_letter(dl, None)
+                }
+            }
 
-            e
+            MsgError::new(msg)
         })
     }
 
+    /// Enqueue a message, returning it if it couldn't be enqueued
+    ///
+    /// Unlike `send_msg` a failed message is not sent to dead letters.
+    pub(crate) fn try_send_msg(&self, msg: Envelope<Msg>) -> Result<(), SendError<Envelope<Msg>>> {
+        let sys = self.system();
+
+        if let Some(governor) = sys.governor() {
+            match governor.admit(sys, &self.uri().path, &msg.sender) {
+                Admission::Accept => {}
+                Admission::Shed => return Err(SendError::Overloaded(msg)),
+                Admission::Delay(delay) => {
+                    self.set_has_schedules();
+                    let job = OnceJob {
+                        id: Uuid::new_v4(),
+                        send_at: sys.clock().now() + delay,
+                        receiver: self.cell.myself(),
+                        sender: msg.sender.clone(),
+                        msg: AnyMessage::new(msg.msg.clone(), true),
+                    };
+
+                    return sys
+                        .timer
+                        .send(Job::Once(job))
+                        .map_err(|_| SendError::Terminated(msg));
+                }
+            }
+        }
+
+        let mb = &self.mailbox;
+        let k = self.cell.kernel();
+
+        dispatch(msg, mb, k, sys)
+            .map(|_| {
+                metrics::msg_enqueued(&self.uri().path);
+                if let Some(governor) = sys.governor() {
+                    governor.enqueued();
+                }
+            })
+            .map_err(|e| SendError::Terminated(e.msg))
+    }
+
     pub(crate) fn send_sys_msg(&self, msg: Envelope<SystemMsg>) -> MsgResult<Envelope<SystemMsg>> {
         self.cell.send_sys_msg(msg)
     }
 
+    pub(crate) fn set_awaiting(&self) {
+        self.mailbox.set_awaiting();
+    }
+
+    pub(crate) fn resume(&self, msg: Option<Envelope<Msg>>) {
+        self.mailbox.resume(msg);
+
+        if !self.mailbox.is_scheduled() {
+            self.mailbox.set_scheduled(true);
+            self.cell.kernel().schedule(self.system());
+        }
+    }
+
     pub fn system(&self) -> &ActorSystem {
         &self.cell.inner.system
     }
 
-    pub(crate) fn handle_failure(&self, failed: BasicActorRef, strategy: Strategy) {
-        self.cell.handle_failure(failed, strategy)
+    pub(crate) fn handle_failure(&self, info: FailureInfo, strategy: Strategy) {
+        self.cell.handle_failure(info, strategy)
+    }
+
+    pub(crate) fn received(&self, count: usize) {
+        self.cell.received(count)
+    }
+
+    pub(crate) fn poll_queued(&self, max: usize, waker: &Waker) -> bool {
+        self.cell.poll_queued(max, waker)
+    }
+
+    pub(crate) fn wake_sinks(&self) {
+        self.cell.wake_sinks()
+    }
+
+    pub(crate) fn info(&self) -> ActorInfo {
+        self.cell.info()
+    }
+
+    pub(crate) fn receive_timeout_due(&self, timeout: &ReceiveTimeout) -> bool {
+        self.cell.receive_timeout_due(timeout)
+    }
+
+    pub(crate) fn set_passivation(&self, idle: Duration) {
+        self.cell.set_passivation(idle)
+    }
+
+    pub(crate) fn passivation_due(&self, timeout: &ReceiveTimeout) -> Option<bool> {
+        self.cell.passivation_due(timeout)
+    }
+
+    pub(crate) fn restart_passivation(&self) {
+        self.cell.restart_passivation()
+    }
+
+    pub(crate) fn passivate<A: Actor>(&self, actor: &mut Option<A>) {
+        self.cell.passivate(actor)
+    }
+
+    pub(crate) fn reactivate(&self) -> bool {
+        self.cell.reactivate()
+    }
+
+    pub(crate) fn restart_count(&self) -> usize {
+        self.cell.restart_count()
+    }
+
+    pub(crate) fn restarted(&self) {
+        self.cell.restarted()
+    }
+
+    pub(crate) fn started(&self) -> oneshot::Receiver<Result<(), CreateError>> {
+        self.cell.started()
+    }
+
+    pub(crate) fn set_started(&self, result: Result<(), CreateError>) {
+        self.cell.set_started(result)
+    }
+
+    pub(crate) fn post_restart_due(&self) -> bool {
+        self.cell.post_restart_due()
+    }
+
+    pub(crate) fn take_restart_reason(&self) -> Option<FailureInfo> {
+        self.cell.take_restart_reason()
     }
 
     pub(crate) fn receive_cmd<A: Actor>(&self, cmd: SystemCmd, actor: &mut Option<A>) {
@@ -406,6 +1072,22 @@ where
     pub(crate) fn death_watch<A: Actor>(&self, terminated: &BasicActorRef, actor: &mut Option<A>) {
         self.cell.death_watch(terminated, actor)
     }
+
+    pub(crate) fn poison_pill_due(&self) -> bool {
+        self.cell.poison_pill_due()
+    }
+
+    pub(crate) fn ahead_of_poison_pill(&self) -> Option<u64> {
+        self.cell.ahead_of_poison_pill()
+    }
+
+    pub(crate) fn set_has_schedules(&self) {
+        self.cell.set_has_schedules()
+    }
+
+    pub(crate) fn terminate<A: Actor>(&self, actor: &mut Option<A>) {
+        self.cell.terminate(actor)
+    }
 }
 
 impl<Msg: Message> fmt::Debug for ExtendedCell<Msg> {
@@ -439,6 +1121,9 @@ pub struct Context<Msg: Message> {
     pub myself: ActorRef<Msg>,
     pub system: ActorSystem,
     pub(crate) kernel: KernelRef,
+
+    // The sender of the message being received in `recv`
+    pub(crate) sender: RefCell<Sender>,
 }
 
 impl<Msg> Context<Msg>
@@ -449,6 +1134,281 @@ where
     pub fn myself(&self) -> ActorRef<Msg> {
         self.myself.clone()
     }
+
+    /// Send `msg` to `target` with the sender of the message being received
+    ///
+    /// Unlike telling `target` with `ctx.myself()` as the sender, the reply
+    /// goes to the original sender, so a router or proxy doesn't break
+    /// request-reply. Outside of `recv`, or in `recv_batch`, the message is
+    /// sent without a sender.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use riker::actors::*;
+    ///
+    /// struct Proxy {
+    ///     target: ActorRef<String>,
+    /// }
+    ///
+    /// impl Actor for Proxy {
+    ///     type Msg = String;
+    ///
+    ///     fn recv(&mut self, ctx: &Context<String>, msg: String, _: Sender) {
+    ///         ctx.forward(&self.target, msg);
+    ///     }
+    /// }
+    /// ```
+    pub fn forward<T>(&self, target: &impl Tell<T>, msg: T) {
+        target.tell(msg, self.sender.borrow().clone());
+    }
+
+    /// Values attached to this actor by type, see `Extensions`
+    pub fn extensions(&self) -> &Extensions {
+        &self.myself.cell.cell.inner.extensions
+    }
+
+    /// Create a child actor with a generated name, see `ActorRefFactory::actor_of_props_anon`
+    pub fn spawn_anon<A>(&self, props: BoxActorProd<A>) -> Result<ActorRef<A::Msg>, CreateError>
+    where
+        A: Actor,
+    {
+        self.actor_of_props_anon(props)
+    }
+
+    /// Create a `CachedSelection`, see `ActorSelectionFactory::select`
+    pub fn select_cached(&self, path: &str) -> Result<CachedSelection, InvalidPath> {
+        let selection = self.select(path)?;
+        Ok(CachedSelection::new(
+            selection,
+            self.system.selection_caches(),
+        ))
+    }
+
+    /// Suspend the actors selected by `path` and their descendants
+    ///
+    /// `path` is relative to this actor, e.g. `"*"` suspends all children.
+    /// See `ActorSystem::suspend`.
+    pub fn suspend_children(&self, path: &str) -> Result<(), InvalidPath> {
+        self.select(path)?.sys_tell(SystemCmd::Suspend.into(), None);
+        Ok(())
+    }
+
+    /// Resume the actors selected by `path` and their descendants, see `suspend_children`
+    pub fn resume_children(&self, path: &str) -> Result<(), InvalidPath> {
+        self.select(path)?.sys_tell(SystemCmd::Resume.into(), None);
+        Ok(())
+    }
+
+    /// Run a future on the system executor, reporting failure to this actor
+    ///
+    /// If the future panics or resolves to `Err`, the actor receives a
+    /// `SystemMsg::TaskFailed` in `sys_recv`. With `TaskFailure::Fail` the
+    /// actor is then failed and its supervisor's strategy is applied.
+    ///
+    /// Unlike `run` the future's output is not returned and the future
+    /// runs to completion even if nothing waits on it.
+    pub fn run_supervised<Fut, T, E>(
+        &self,
+        future: Fut,
+        on_failure: TaskFailure,
+    ) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        E: fmt::Debug,
+    {
+        let myself = self.myself();
+        let f = async move {
+            let error = match AssertUnwindSafe(future).catch_unwind().await {
+                Ok(Ok(_)) => return,
+                Ok(Err(e)) => format!("{:?}", e),
+                Err(panic) => panic_message(&*panic).unwrap_or_else(|| "Task panicked".to_string()),
+            };
+
+            myself.sys_tell(TaskFailed { error, on_failure }.into());
+        };
+
+        self.system.exec.spawn(f)
+    }
+
+    /// Run a future on the system executor for as long as this actor lives
+    ///
+    /// The future is aborted when the actor stops, so it can't outlive
+    /// the actor. It isn't aborted when the actor restarts.
+    pub fn spawn<Fut>(&self, future: Fut) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.myself.cell.cell.spawn_task(future)
+    }
+
+    /// Send `msg` to `target`, returning a future of its typed reply
+    ///
+    /// The message is sent with a temporary actor as the sender, the first
+    /// `Res` the target tells the sender completes the future, see `Ask`
+    /// for how long it waits. Awaiting the
+    /// reply with `continue_with` or `pipe_to_self` doesn't block the actor:
+    ///
+    /// ```
+    /// # use riker::actors::*;
+    ///
+    /// #[derive(Default)]
+    /// struct Doubler;
+    ///
+    /// impl Actor for Doubler {
+    ///     type Msg = u32;
+    ///
+    ///     fn recv(&mut self, _: &Context<u32>, msg: u32, sender: Sender) {
+    ///         sender.unwrap().try_tell(msg * 2, None).unwrap();
+    ///     }
+    /// }
+    ///
+    /// #[derive(Clone, Debug)]
+    /// enum CalcMsg {
+    ///     Calc(ActorRef<u32>, u32),
+    ///     Doubled(u32),
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// struct Calc;
+    ///
+    /// impl Actor for Calc {
+    ///     type Msg = CalcMsg;
+    ///
+    ///     fn recv(&mut self, ctx: &Context<CalcMsg>, msg: CalcMsg, _: Sender) {
+    ///         match msg {
+    ///             CalcMsg::Calc(doubler, n) => {
+    ///                 let doubled = ctx.ask::<u32, _>(&doubler, n).unwrap();
+    ///                 ctx.pipe_to_self(doubled, |res| CalcMsg::Doubled(res.unwrap()))
+    ///                     .unwrap();
+    ///             }
+    ///             CalcMsg::Doubled(n) => println!("doubled: {}", n),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn ask<Res, T>(&self, target: &impl Tell<T>, msg: T) -> Result<Ask<Res>, CreateError>
+    where
+        Res: Message,
+    {
+        let (reply_to, rx) = ReplyTo::new();
+        let ttl = self.system.sys_settings().ask_ttl;
+        let asker = self
+            .system
+            .tmp_actor_of_args::<Asker<Res>, _>((reply_to, ttl))?;
+        target.tell(msg, Some(asker.clone().into()));
+
+        Ok(Ask::new(rx, asker.into()))
+    }
+
+    /// Receive a `ReceiveTimeout` in `sys_recv` if no message is received within `timeout`
+    ///
+    /// The window restarts after each message, and after each `ReceiveTimeout`
+    /// while the actor stays idle. Setting a timeout replaces any previous one.
+    pub fn set_receive_timeout(&self, timeout: Duration) {
+        self.myself.cell.cell.set_receive_timeout(timeout)
+    }
+
+    /// Cancel the timeout set with `set_receive_timeout`
+    pub fn cancel_receive_timeout(&self) {
+        self.myself.cell.cell.cancel_receive_timeout()
+    }
+
+    /// Send `msg` to this actor once, after `delay`
+    ///
+    /// The message is received in `recv` with no sender. Unlike a schedule
+    /// made with `Timer::schedule_once`, it's cancelled when the actor stops,
+    /// or is passivated, so it never becomes a dead letter. Cancel it
+    /// earlier with `cancel_schedule`.
+    pub fn notify_later<T>(&self, msg: T, delay: Duration) -> ScheduleId
+    where
+        T: Message + Into<Msg>,
+    {
+        let due = self.system.clock().now() + delay;
+        let id = self.schedule_once(delay, self.myself(), None, msg);
+        self.myself.cell.cell.add_notification(id, Some(due));
+        id
+    }
+
+    /// Send `msg` to this actor every `interval`, starting after `interval`
+    ///
+    /// Cancelled when the actor stops, like `notify_later`, so a repeating
+    /// schedule doesn't outlive the actor.
+    pub fn notify_interval<T>(&self, msg: T, interval: Duration) -> ScheduleId
+    where
+        T: Message + Into<Msg>,
+    {
+        let id = self.schedule(interval, interval, self.myself(), None, msg);
+        self.myself.cell.cell.add_notification(id, None);
+        id
+    }
+
+    /// Run a future with `spawn`, then send its output mapped by `f` to this actor
+    ///
+    /// The message is received in `recv` with no sender. Nothing is sent
+    /// if the actor stops before the future completes.
+    pub fn pipe_to_self<Fut, F>(&self, future: Fut, f: F) -> Result<(), SpawnError>
+    where
+        Fut: Future + Send + 'static,
+        F: FnOnce(Fut::Output) -> Msg + Send + 'static,
+    {
+        let myself = self.myself();
+        self.spawn(async move {
+            let out = future.await;
+            myself.tell(f(out), None);
+        })
+    }
+
+    /// Await a future, then continue with its output as the next message
+    ///
+    /// The future is polled on the system executor. Until it completes the
+    /// actor processes no other messages, so an actor can await I/O while
+    /// handling a message without blocking an executor thread. The output
+    /// is received in `recv` ahead of any queued messages, with no sender.
+    ///
+    /// If the future panics the actor is failed, as if `recv` had panicked.
+    pub fn continue_with<Fut>(&self, future: Fut) -> Result<(), SpawnError>
+    where
+        Fut: Future + Send + 'static,
+        Fut::Output: Into<Msg>,
+    {
+        let cell = self.myself.cell.clone();
+        let f = async move {
+            match AssertUnwindSafe(future).catch_unwind().await {
+                Ok(out) => cell.resume(Some(Envelope {
+                    msg: out.into(),
+                    sender: None,
+                })),
+                Err(panic) => {
+                    let error =
+                        panic_message(&*panic).unwrap_or_else(|| "Task panicked".to_string());
+                    cell.send_sys_msg(Envelope {
+                        msg: TaskFailed {
+                            error,
+                            on_failure: TaskFailure::Fail,
+                        }
+                        .into(),
+                        sender: None,
+                    })
+                    .ok();
+                    cell.resume(None);
+                }
+            }
+        };
+
+        self.myself.cell.set_awaiting();
+        self.system.exec.spawn(f).inspect_err(|_| {
+            self.myself.cell.resume(None);
+        })
+    }
+}
+
+pub(crate) fn panic_message(panic: &(dyn Any + Send)) -> Option<String> {
+    if let Some(s) = panic.downcast_ref::<&str>() {
+        Some(s.to_string())
+    } else {
+        panic.downcast_ref::<String>().cloned()
+    }
 }
 
 impl<Msg: Message> ActorRefFactory for Context<Msg> {
@@ -506,8 +1466,7 @@ where
     fn select(&self, path: &str) -> Result<ActorSelection, InvalidPath> {
         let (anchor, path_str) = if path.starts_with('/') {
             let anchor = self.system.user_root().clone();
-            let anchor_path = format!("{}/", anchor.path());
-            let path = path.to_string().replace(&anchor_path, "");
+            let path = from_user_root(path, &anchor);
 
             (anchor, path)
         } else {
@@ -532,6 +1491,14 @@ where
     {
         self.system.run(future)
     }
+
+    fn run_blocking<F, T>(&self, f: F) -> Result<RemoteHandle<T>, SpawnError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.system.run_blocking(f)
+    }
 }
 
 impl<Msg> Timer for Context<Msg>
@@ -550,19 +1517,49 @@ where
         T: Message + Into<M>,
         M: Message,
     {
+        receiver.cell.set_has_schedules();
         let id = Uuid::new_v4();
         let msg: M = msg.into();
 
         let job = RepeatJob {
             id,
-            send_at: Instant::now() + initial_delay,
+            send_at: self.system.clock().now() + initial_delay,
             interval,
             receiver: receiver.into(),
             sender,
             msg: AnyMessage::new(msg, false),
         };
 
-        self.system.timer.send(Job::Repeat(job)).unwrap();
+        let _ = self.system.timer.send(Job::Repeat(job));
+        id
+    }
+
+    fn schedule_fn<F, T, M>(
+        &self,
+        initial_delay: Duration,
+        interval: Duration,
+        receiver: ActorRef<M>,
+        sender: Sender,
+        mut f: F,
+    ) -> ScheduleId
+    where
+        F: FnMut() -> T + Send + 'static,
+        T: Into<M>,
+        M: Message,
+    {
+        receiver.cell.set_has_schedules();
+        let id = Uuid::new_v4();
+
+        let job = RepeatJob {
+            id,
+            send_at: self.system.clock().now() + initial_delay,
+            interval,
+            receiver: receiver.into(),
+            sender,
+            msg: AnyMessage::from_fn(move || -> M { f().into() }),
+        };
+
+        let _ = self.system.timer.send(Job::Repeat(job));
         id
     }
 
@@ -577,18 +1574,19 @@ where
         T: Message + Into<M>,
         M: Message,
     {
+        receiver.cell.set_has_schedules();
         let id = Uuid::new_v4();
         let msg: M = msg.into();
 
         let job = OnceJob {
             id,
-            send_at: Instant::now() + delay,
+            send_at: self.system.clock().now() + delay,
             receiver: receiver.into(),
             sender,
             msg: AnyMessage::new(msg, true),
         };
 
-        self.system.timer.send(Job::Once(job)).unwrap();
+        let _ = self.system.timer.send(Job::Once(job));
         id
     }
 
@@ -603,43 +1601,52 @@ where
         T: Message + Into<M>,
         M: Message,
     {
-        let delay = std::cmp::max(time.timestamp() - Utc::now().timestamp(), 0_i64);
-        let delay = Duration::from_secs(delay as u64);
+        let delay = delay_until(self.system.clock(), time);
 
+        receiver.cell.set_has_schedules();
         let id = Uuid::new_v4();
         let msg: M = msg.into();
 
         let job = OnceJob {
             id,
-            send_at: Instant::now() + delay,
+            send_at: self.system.clock().now() + delay,
             receiver: receiver.into(),
             sender,
             msg: AnyMessage::new(msg, true),
         };
 
-        self.system.timer.send(Job::Once(job)).unwrap();
+        let _ = self.system.timer.send(Job::Once(job));
         id
     }
 
     fn cancel_schedule(&self, id: Uuid) {
+        self.myself.cell.cell.remove_notification(id);
         let _ = self.system.timer.send(Job::Cancel(id));
     }
+
+    fn cancel_for(&self, path: &ActorPath) {
+        self.system.cancel_for(path);
+    }
 }
 
 #[derive(Clone)]
 pub struct Children {
-    actors: Arc<DashMap<String, BasicActorRef>>,
+    // children are numbered in order of creation
+    actors: Arc<DashMap<String, (u64, BasicActorRef)>>,
+    next: Arc<AtomicU64>,
 }
 
 impl Children {
     pub fn new() -> Children {
         Children {
             actors: Arc::new(DashMap::new()),
+            next: Arc::new(AtomicU64::new(0)),
         }
     }
 
     pub fn add(&self, actor: BasicActorRef) {
-        self.actors.insert(actor.name().to_string(), actor);
+        let seq = self.next.fetch_add(1, Ordering::Relaxed);
+        self.actors.insert(actor.name().to_string(), (seq, actor));
     }
 
     pub fn remove(&self, actor: &BasicActorRef) {
@@ -650,7 +1657,46 @@ impl Children {
         self.actors.len()
     }
 
+    pub fn contains(&self, actor: &BasicActorRef) -> bool {
+        self.actors
+            .get(actor.name())
+            .is_some_and(|e| e.value().1 == *actor)
+    }
+
+    /// Children in order of creation
     pub fn iter(&self) -> impl Iterator<Item = BasicActorRef> + '_ {
-        self.actors.iter().map(|e| e.value().clone())
+        let mut children = self
+            .actors
+            .iter()
+            .map(|e| e.value().clone())
+            .collect::<Vec<_>>();
+
+        children.sort_by_key(|(seq, _)| *seq);
+        children.into_iter().map(|(_, actor)| actor)
+    }
+
+    /// Children in order of name
+    pub fn sorted(&self) -> impl Iterator<Item = BasicActorRef> + '_ {
+        let mut children = self.iter().collect::<Vec<_>>();
+        children.sort_by(|a, b| a.name().cmp(b.name()));
+        children.into_iter()
+    }
+
+    /// Children created after `actor`, in order of creation
+    pub fn created_after(&self, actor: &BasicActorRef) -> Vec<BasicActorRef> {
+        let seq = match self.actors.get(actor.name()) {
+            Some(e) => e.value().0,
+            None => return Vec::new(),
+        };
+
+        let mut after = self
+            .actors
+            .iter()
+            .filter(|e| e.value().0 > seq)
+            .map(|e| e.value().clone())
+            .collect::<Vec<_>>();
+
+        after.sort_by_key(|(seq, _)| *seq);
+        after.into_iter().map(|(_, actor)| actor).collect()
     }
 }
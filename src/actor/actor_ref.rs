@@ -1,13 +1,21 @@
-use std::fmt;
+use std::{
+    fmt,
+    future::Future,
+    hash::{Hash, Hasher},
+    pin::Pin,
+    task::{Context as TaskContext, Poll},
+};
+
+use futures::channel::oneshot;
 
 use crate::{
     actor::{
         actor_cell::{ActorCell, ExtendedCell},
         props::{ActorArgs, ActorFactory, ActorFactoryArgs},
-        Actor, ActorPath, ActorUri, BoxActorProd, CreateError,
+        Actor, ActorPath, ActorSink, ActorUri, BoxActorProd, CreateError, ReplyTo, SendError,
     },
     kernel::mailbox::AnyEnqueueError,
-    system::{ActorSystem, SystemMsg},
+    system::{ActorInfo, ActorSystem, SystemCmd, SystemMsg},
     AnyMessage, Envelope, Message,
 };
 
@@ -48,11 +56,46 @@ pub trait ActorReference {
     /// True if the given actor is a child of this actor
     fn is_child(&self, actor: &BasicActorRef) -> bool;
 
-    /// Iterator over children references.
+    /// Iterator over children references, in the order they were created
+    ///
+    /// A child that stopped and was created again with the same name is
+    /// ordered by when it was created again.
     fn children<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a>;
 
+    /// Iterator over children references, in order of name
+    fn children_sorted<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a>;
+
     /// Send a system message to this actor
     fn sys_tell(&self, msg: SystemMsg);
+
+    /// Request a snapshot of this actor's state
+    ///
+    /// The request is answered by the system, so the actor doesn't need to
+    /// handle it, and it's answered ahead of queued user messages. The
+    /// receiver is cancelled if the actor has stopped.
+    fn identify(&self) -> oneshot::Receiver<ActorInfo> {
+        let (reply_to, rx) = ReplyTo::new();
+        self.sys_tell(SystemMsg::Identify(reply_to));
+        rx
+    }
+}
+
+/// Completes once an actor has started, see `ActorRef::started`
+///
+/// Completes with `CreateError::Panicked` if the actor panicked while
+/// starting, or with `CreateError::System` if it stopped before starting.
+pub struct Started {
+    rx: oneshot::Receiver<Result<(), CreateError>>,
+}
+
+impl Future for Started {
+    type Output = Result<(), CreateError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.rx)
+            .poll(cx)
+            .map(|started| started.unwrap_or(Err(CreateError::System)))
+    }
 }
 
 pub type BoxedTell<T> = Box<dyn Tell<T> + Send + 'static>;
@@ -76,6 +119,17 @@ where
     }
 }
 
+/// Told with `try_tell`, messages of a type the actor doesn't receive are dropped
+impl<T: Message> Tell<T> for BasicActorRef {
+    fn tell(&self, msg: T, sender: Sender) {
+        let _ = self.try_tell(msg, sender);
+    }
+
+    fn box_clone(&self) -> BoxedTell<T> {
+        Box::new(self.clone())
+    }
+}
+
 impl<T> ActorReference for BoxedTell<T>
 where
     T: Message,
@@ -119,11 +173,16 @@ where
         (**self).is_child(actor)
     }
 
-    /// Iterator over children references.
+    /// Iterator over children references, in the order they were created
     fn children<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
         (**self).children()
     }
 
+    /// Iterator over children references, in order of name
+    fn children_sorted<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
+        (**self).children_sorted()
+    }
+
     fn sys_tell(&self, msg: SystemMsg) {
         (**self).sys_tell(msg)
     }
@@ -186,6 +245,13 @@ impl BasicActorRef {
         self.cell.kernel().sys_init(sys);
     }
 
+    /// Completes once the actor's `pre_start` and `post_start` have returned, see `ActorRef::started`
+    pub fn started(&self) -> Started {
+        Started {
+            rx: self.cell.started(),
+        }
+    }
+
     /// Send a message to this actor
     ///
     /// Returns a result. If the message type is not supported Error is returned.
@@ -207,6 +273,15 @@ impl BasicActorRef {
     ) -> Result<(), AnyEnqueueError> {
         self.cell.send_any_msg(msg, sender.into())
     }
+
+    pub(crate) fn try_tell_any_batch(&self, msgs: Vec<(&mut AnyMessage, Sender)>) {
+        self.cell.send_any_batch(msgs)
+    }
+
+    /// Number of user messages waiting in the actor's mailbox
+    pub(crate) fn queued(&self) -> usize {
+        self.cell.queued()
+    }
 }
 
 impl ActorReference for BasicActorRef {
@@ -249,11 +324,16 @@ impl ActorReference for BasicActorRef {
         self.cell.is_child(actor)
     }
 
-    /// Iterator over children references.
+    /// Iterator over children references, in the order they were created
     fn children<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
         self.cell.children()
     }
 
+    /// Iterator over children references, in order of name
+    fn children_sorted<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
+        self.cell.children_sorted()
+    }
+
     fn sys_tell(&self, msg: SystemMsg) {
         let envelope = Envelope { msg, sender: None };
         let _ = self.cell.send_sys_msg(envelope);
@@ -300,11 +380,16 @@ impl ActorReference for &BasicActorRef {
         self.cell.is_child(actor)
     }
 
-    /// Iterator over children references.
+    /// Iterator over children references, in the order they were created
     fn children<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
         self.cell.children()
     }
 
+    /// Iterator over children references, in order of name
+    fn children_sorted<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
+        self.cell.children_sorted()
+    }
+
     fn sys_tell(&self, msg: SystemMsg) {
         let envelope = Envelope { msg, sender: None };
         let _ = self.cell.send_sys_msg(envelope);
@@ -323,9 +408,20 @@ impl fmt::Display for BasicActorRef {
     }
 }
 
+// Refs are equal if they have the same path in the same system
 impl PartialEq for BasicActorRef {
     fn eq(&self, other: &BasicActorRef) -> bool {
         self.cell.uri().path == other.cell.uri().path
+            && self.cell.system().id() == other.cell.system().id()
+    }
+}
+
+impl Eq for BasicActorRef {}
+
+impl Hash for BasicActorRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cell.uri().path.hash(state);
+        self.cell.system().id().hash(state);
     }
 }
 
@@ -378,6 +474,44 @@ impl<Msg: Message> ActorRef<Msg> {
         ActorRef { cell }
     }
 
+    /// Completes once the actor's `pre_start` and `post_start` have returned
+    ///
+    /// `actor_of` returns before the actor has started. Messages sent
+    /// to the returned reference are queued until then, but an actor
+    /// selection or a lookup of the children created in `pre_start`
+    /// may not find them yet. Awaiting `started` avoids that race.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use riker::actors::*;
+    /// # use futures::executor::block_on;
+    ///
+    /// #[derive(Default)]
+    /// struct Parent;
+    ///
+    /// impl Actor for Parent {
+    ///     type Msg = ();
+    ///
+    ///     fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+    ///         ctx.actor_of::<Parent>("child").unwrap();
+    ///     }
+    ///
+    ///     fn recv(&mut self, _ctx: &Context<()>, _msg: (), _sender: Sender) {}
+    /// }
+    ///
+    /// let sys = ActorSystem::new().unwrap();
+    /// let parent = sys.actor_of::<Parent>("parent").unwrap();
+    ///
+    /// block_on(parent.started()).unwrap();
+    /// assert!(parent.has_children());
+    /// ```
+    pub fn started(&self) -> Started {
+        Started {
+            rx: self.cell.started(),
+        }
+    }
+
     pub fn send_msg(&self, msg: Msg, sender: impl Into<Option<BasicActorRef>>) {
         let envelope = Envelope {
             msg,
@@ -386,6 +520,45 @@ impl<Msg: Message> ActorRef<Msg> {
         // consume the result (we don't return it to user)
         let _ = self.cell.send_msg(envelope);
     }
+
+    /// Send `msg` with the sender of `original`, rather than the forwarding actor
+    ///
+    /// Routers and proxies forward so that the recipient replies to the
+    /// actor which sent the original message. See also `Context::forward`.
+    pub fn forward<T, U>(&self, msg: T, original: &Envelope<U>)
+    where
+        T: Into<Msg>,
+        U: Message,
+    {
+        self.send_msg(msg.into(), original.sender.clone());
+    }
+
+    /// Send a message, returning it if it can't be delivered
+    ///
+    /// Messages are not sent to dead letters when `try_send` fails,
+    /// letting the caller decide whether to retry, reroute or drop them.
+    pub fn try_send(
+        &self,
+        msg: Msg,
+        sender: impl Into<Option<BasicActorRef>>,
+    ) -> Result<(), SendError<Msg>> {
+        let envelope = Envelope {
+            msg,
+            sender: sender.into(),
+        };
+
+        self.cell
+            .try_send_msg(envelope)
+            .map_err(|e| e.map(|envelope| envelope.msg))
+    }
+
+    /// Returns a `Sink` sending to this actor
+    ///
+    /// The sink waits while `max_queued` or more messages are waiting in the
+    /// actor's mailbox. Messages are sent without a sender.
+    pub fn sink(&self, max_queued: usize) -> ActorSink<Msg> {
+        ActorSink::new(self.clone(), max_queued)
+    }
 }
 
 impl<Msg: Message> ActorReference for ActorRef<Msg> {
@@ -428,11 +601,16 @@ impl<Msg: Message> ActorReference for ActorRef<Msg> {
         self.cell.is_child(actor)
     }
 
-    /// Iterator over children references.
+    /// Iterator over children references, in the order they were created
     fn children<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
         self.cell.children()
     }
 
+    /// Iterator over children references, in order of name
+    fn children_sorted<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
+        self.cell.children_sorted()
+    }
+
     fn sys_tell(&self, msg: SystemMsg) {
         let envelope = Envelope { msg, sender: None };
         let _ = self.cell.send_sys_msg(envelope);
@@ -479,11 +657,16 @@ impl<Msg: Message> ActorReference for &ActorRef<Msg> {
         self.cell.is_child(actor)
     }
 
-    /// Iterator over children references.
+    /// Iterator over children references, in the order they were created
     fn children<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
         self.cell.children()
     }
 
+    /// Iterator over children references, in order of name
+    fn children_sorted<'a>(&'a self) -> Box<dyn Iterator<Item = BasicActorRef> + 'a> {
+        self.cell.children_sorted()
+    }
+
     fn sys_tell(&self, msg: SystemMsg) {
         let envelope = Envelope { msg, sender: None };
         let _ = self.cell.send_sys_msg(envelope);
@@ -504,7 +687,16 @@ impl<Msg: Message> fmt::Display for ActorRef<Msg> {
 
 impl<Msg: Message> PartialEq for ActorRef<Msg> {
     fn eq(&self, other: &ActorRef<Msg>) -> bool {
-        self.uri().path == other.uri().path
+        self.uri().path == other.uri().path && self.cell.system().id() == other.cell.system().id()
+    }
+}
+
+impl<Msg: Message> Eq for ActorRef<Msg> {}
+
+impl<Msg: Message> Hash for ActorRef<Msg> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.uri().path.hash(state);
+        self.cell.system().id().hash(state);
     }
 }
 
@@ -514,6 +706,12 @@ impl<Msg: Message> PartialEq for ActorRef<Msg> {
 /// It is advised to return from the actor's factory method quickly and
 /// handle any initialization in the actor's `pre_start` method, which is
 /// invoked after the `ActorRef` is returned.
+///
+/// An actor's name is one or more segments separated by `.`, e.g.
+/// `orders.eu-west.worker-1`, each of one or more unicode letters or digits,
+/// `_` or `-`. Creating an actor with any other name, such as `a/b`, `*`,
+/// `..` or `a..b`, fails with `CreateError::InvalidName`, as does a name
+/// rejected by the system's `NameValidator`, see `SystemBuilder::name_validator`.
 pub trait ActorRefFactory {
     fn actor_of_props<A>(
         &self,
@@ -536,7 +734,66 @@ pub trait ActorRefFactory {
         Args: ActorArgs,
         A: ActorFactoryArgs<Args>;
 
+    /// Create an actor with a generated name, unique among its siblings.
+    ///
+    /// Names have the form `a-3f9c01d2`.
+    fn actor_of_props_anon<A>(
+        &self,
+        props: BoxActorProd<A>,
+    ) -> Result<ActorRef<A::Msg>, CreateError>
+    where
+        A: Actor,
+    {
+        anon(|name| self.actor_of_props(name, props.clone()))
+    }
+
+    /// Create an actor with a generated name, see `actor_of_props_anon`.
+    fn actor_of_anon<A>(&self) -> Result<ActorRef<<A as Actor>::Msg>, CreateError>
+    where
+        A: ActorFactory + Actor,
+    {
+        anon(|name| self.actor_of::<A>(name))
+    }
+
+    /// Create an actor with a generated name, see `actor_of_props_anon`.
+    fn actor_of_args_anon<A, Args>(
+        &self,
+        args: Args,
+    ) -> Result<ActorRef<<A as Actor>::Msg>, CreateError>
+    where
+        Args: ActorArgs,
+        A: ActorFactoryArgs<Args>,
+    {
+        anon(|name| self.actor_of_args::<A, _>(name, args.clone()))
+    }
+
+    /// Stop an actor immediately.
+    ///
+    /// The stop command is a system message and is handled ahead of
+    /// any user messages already waiting in the actor's mailbox.
     fn stop(&self, actor: impl ActorReference);
+
+    /// Stop an actor after it has processed the messages already in its mailbox.
+    ///
+    /// A poison pill is sent to the actor. User messages queued before the
+    /// poison pill is sent are processed first and any that arrive
+    /// afterwards are sent to dead letters, even if the actor is busy
+    /// when the pill is sent. With a priority mailbox the pill counts
+    /// messages rather than marking a place in the queue, see `WithPriority`.
+    fn stop_gracefully(&self, actor: impl ActorReference) {
+        actor.sys_tell(SystemCmd::PoisonPill.into());
+    }
+}
+
+// Create with generated names until one isn't already taken
+fn anon<T>(create: impl Fn(&str) -> Result<T, CreateError>) -> Result<T, CreateError> {
+    loop {
+        let name = format!("a-{:08x}", rand::random::<u32>());
+        match create(&name) {
+            Err(CreateError::AlreadyExists(_)) => continue,
+            res => return res,
+        }
+    }
 }
 
 /// Produces `ActorRef`s under the `temp` guardian actor.
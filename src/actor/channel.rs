@@ -1,55 +1,393 @@
 #![allow(unused_variables)]
 
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
     actor::{
         Actor, ActorRef, ActorRefFactory, ActorReference, BasicActorRef, BoxedTell, Context,
-        CreateError, Receive, Sender,
+        CreateError, Props, Receive, Sender, Tell,
     },
-    system::{SystemEvent, SystemMsg},
-    Message,
+    metrics,
+    system::{SystemEvent, SystemEventType, SystemMsg},
+    AnyMessage, Message,
 };
 
-type Subs<Msg> = HashMap<Topic, Vec<BoxedTell<Msg>>>;
+type Subs<Msg> = HashMap<Topic, Vec<Subscriber<Msg>>>;
 
-/// A specialized actor for providing Publish/Subscribe capabilities to users.
-///
+// Subscribers of a topic are kept in delivery order,
+// highest priority first and then in order of subscription
+#[derive(Clone)]
+struct Subscriber<Msg: Message> {
+    actor: BoxedTell<Msg>,
+    priority: i32,
+    durable: bool,
+}
+
+// Deliver a durable subscriber's messages to `actor`, created at its path
+fn rebind_subscribers<Msg: Message>(subs: &mut [Subscriber<Msg>], actor: &BasicActorRef) {
+    for sub in subs.iter_mut() {
+        if sub.durable && sub.actor.path() == actor.path() {
+            sub.actor = Box::new(actor.clone());
+        }
+    }
+}
+
+// Removes the subscriptions of a stopped actor, except the durable ones
+fn remove_stopped<Msg: Message>(subs: &mut Vec<Subscriber<Msg>>, actor: &dyn ActorReference) {
+    subs.retain(|sub| sub.durable || sub.actor.path() != actor.path());
+}
+
+fn insert_subscriber<Msg: Message>(subs: &mut Vec<Subscriber<Msg>>, sub: Subscriber<Msg>) {
+    let pos = subs
+        .iter()
+        .position(|s| s.priority < sub.priority)
+        .unwrap_or(subs.len());
+
+    subs.insert(pos, sub);
+}
+
+fn remove_subscriber<Msg: Message>(subs: &mut Vec<Subscriber<Msg>>, actor: &dyn ActorReference) {
+    if let Some(pos) = subs.iter().position(|x| x.actor.path() == actor.path()) {
+        subs.remove(pos);
+    }
+}
+
+// Subscribers to wildcard topics, keyed by topic level.
+// `*` and `#` levels are stored as children like any other level.
+struct TopicTrie<Msg: Message> {
+    subs: Vec<Subscriber<Msg>>,
+    children: HashMap<String, TopicTrie<Msg>>,
+}
+
+impl<Msg: Message> Default for TopicTrie<Msg> {
+    fn default() -> Self {
+        TopicTrie {
+            subs: Vec::new(),
+            children: HashMap::new(),
+        }
+    }
+}
+
+impl<Msg: Message> TopicTrie<Msg> {
+    fn insert(&mut self, levels: &[&str], sub: Subscriber<Msg>) {
+        match levels.split_first() {
+            Some((level, rest)) => self
+                .children
+                .entry((*level).to_string())
+                .or_default()
+                .insert(rest, sub),
+            None => insert_subscriber(&mut self.subs, sub),
+        }
+    }
+
+    fn remove(&mut self, levels: &[&str], actor: &dyn ActorReference) {
+        match levels.split_first() {
+            Some((level, rest)) => {
+                if let Some(child) = self.children.get_mut(*level) {
+                    child.remove(rest, actor);
+                    if child.is_empty() {
+                        self.children.remove(*level);
+                    }
+                }
+            }
+            None => remove_subscriber(&mut self.subs, actor),
+        }
+    }
+
+    fn rebind(&mut self, actor: &BasicActorRef) {
+        rebind_subscribers(&mut self.subs, actor);
+        for child in self.children.values_mut() {
+            child.rebind(actor);
+        }
+    }
+
+    fn remove_all(&mut self, actor: &dyn ActorReference) {
+        remove_subscriber(&mut self.subs, actor);
+        for child in self.children.values_mut() {
+            child.remove_all(actor);
+        }
+        self.children.retain(|_, child| !child.is_empty());
+    }
+
+    fn remove_stopped(&mut self, actor: &dyn ActorReference) {
+        remove_stopped(&mut self.subs, actor);
+        for child in self.children.values_mut() {
+            child.remove_stopped(actor);
+        }
+        self.children.retain(|_, child| !child.is_empty());
+    }
+
+    fn is_empty(&self) -> bool {
+        self.subs.is_empty() && self.children.is_empty()
+    }
+
+    // Collects the subscribers of patterns matching `levels`
+    fn matches<'a>(&'a self, levels: &[&str], out: &mut Vec<&'a Subscriber<Msg>>) {
+        if let Some(multi) = self.children.get("#") {
+            out.extend(multi.subs.iter());
+        }
+
+        match levels.split_first() {
+            Some((level, rest)) => {
+                if let Some(child) = self.children.get(*level) {
+                    child.matches(rest, out);
+                }
+                if let Some(child) = self.children.get("*") {
+                    child.matches(rest, out);
+                }
+            }
+            None => out.extend(self.subs.iter()),
+        }
+    }
+}
 
 // Generic Channel
 pub type ChannelCtx<Msg> = Context<ChannelMsg<Msg>>;
 pub type ChannelRef<Msg> = ActorRef<ChannelMsg<Msg>>;
 
 /// A specialized actor for providing Publish/Subscribe capabilities for user level messages
+///
+/// Subscribers are notified in order of their `Subscribe::priority`,
+/// highest first, and in order of subscription among equal priorities.
+/// Subscribers to `All` and to the published topic are ordered together,
+/// with `All` subscribers first among equal priorities.
+///
+/// Messages published to a channel are delivered to each subscriber
+/// in the order they were published.
+///
+/// Topics are hierarchical, with levels separated by `/`. Actors can
+/// subscribe to a wildcard topic, where a `*` level matches any single
+/// level and a trailing `#` level matches any remaining levels, including
+/// none. For example `orders/*/created` matches `orders/1/created` and
+/// `sensor/#` matches both `sensor` and `sensor/kitchen/temp`.
+/// Wildcard subscribers are ordered after `All` and exact topic
+/// subscribers among equal priorities. An actor whose subscriptions
+/// match a topic more than once receives the message for each of them.
+///
+/// A channel created with `retained_channel` keeps the last message
+/// published to each topic and sends it to actors when they subscribe.
+///
+/// Subscriptions are kept while the subscriber restarts and removed once it
+/// stops. A `durable` subscription is kept for the actor's path instead, and
+/// delivered to the next actor created at the path.
 pub struct Channel<Msg: Message> {
     subs: Subs<Msg>,
+    patterns: TopicTrie<Msg>,
+    retained: Option<HashMap<Topic, Msg>>,
+
+    // subscribed to `ActorTerminated`, once there is a subscriber
+    watching_terminated: bool,
+
+    // subscribed to `ActorCreated`, once there is a durable subscriber
+    watching_created: bool,
 }
 
 impl<Msg: Message> Default for Channel<Msg> {
     fn default() -> Self {
         Channel {
             subs: HashMap::new(),
+            patterns: TopicTrie::default(),
+            retained: None,
+            watching_terminated: false,
+            watching_created: false,
         }
     }
 }
 
+impl<Msg: Message> Channel<Msg> {
+    /// A channel that retains the last message published to each topic
+    pub fn retained() -> Self {
+        Channel {
+            subs: HashMap::new(),
+            patterns: TopicTrie::default(),
+            retained: Some(HashMap::new()),
+            watching_terminated: false,
+            watching_created: false,
+        }
+    }
+
+    pub(crate) fn subscribe(&mut self, sub: Subscribe<Msg>) {
+        if let Some(ref retained) = self.retained {
+            if sub.topic == All.into() {
+                for msg in retained.values() {
+                    sub.actor.tell(msg.clone(), None);
+                }
+            } else if sub.topic.is_pattern() {
+                for (topic, msg) in retained.iter() {
+                    if sub.topic.matches(topic) {
+                        sub.actor.tell(msg.clone(), None);
+                    }
+                }
+            } else if let Some(msg) = retained.get(&sub.topic) {
+                sub.actor.tell(msg.clone(), None);
+            }
+        }
+
+        let subscriber = Subscriber {
+            actor: sub.actor,
+            priority: sub.priority.unwrap_or_default(),
+            durable: sub.durable,
+        };
+
+        if sub.topic.is_pattern() {
+            self.patterns.insert(&sub.topic.levels(), subscriber);
+        } else {
+            insert_subscriber(self.subs.entry(sub.topic).or_default(), subscriber);
+        }
+    }
+
+    pub(crate) fn subscribe_with_response(
+        &mut self,
+        sub: SubscribeWithResponse<Msg>,
+        channel: BasicActorRef,
+    ) {
+        let topic = sub.topic.clone();
+        self.subscribe(Subscribe {
+            topic: sub.topic,
+            actor: sub.actor,
+            priority: sub.priority,
+            durable: sub.durable,
+        });
+
+        sub.responder
+            .tell(SubscribedResponse { topic }, Some(channel));
+    }
+
+    pub(crate) fn unsubscribe(&mut self, unsub: Unsubscribe<Msg>) {
+        if unsub.topic.is_pattern() {
+            self.patterns.remove(&unsub.topic.levels(), &unsub.actor);
+        } else {
+            unsubscribe(&mut self.subs, &unsub.topic, &unsub.actor);
+        }
+    }
+
+    pub(crate) fn unsubscribe_all(&mut self, actor: &dyn ActorReference) {
+        let subs = self.subs.clone();
+
+        for topic in subs.keys() {
+            unsubscribe(&mut self.subs, topic, actor);
+        }
+
+        self.patterns.remove_all(actor);
+    }
+
+    pub(crate) fn remove_stopped(&mut self, actor: &dyn ActorReference) {
+        for subs in self.subs.values_mut() {
+            remove_stopped(subs, actor);
+        }
+        self.patterns.remove_stopped(actor);
+    }
+
+    pub(crate) fn publish(&mut self, msg: Publish<Msg>, sender: Sender) {
+        self.deliver(&msg.topic, &msg.msg, sender);
+        self.retain(msg.topic, msg.msg);
+    }
+
+    pub(crate) fn publish_batch(&mut self, mut batch: PublishBatch<Msg>, sender: Sender) {
+        for sub in self.subscribers(&batch.topic) {
+            for msg in batch.msgs.iter() {
+                sub.tell(msg.clone(), sender.clone());
+            }
+        }
+
+        if let Some(last) = batch.msgs.pop() {
+            self.retain(batch.topic, last);
+        }
+    }
+
+    pub(crate) fn publish_with_reply(&mut self, msg: PublishWithReply<Msg>, sender: Sender) {
+        let subscribers = self.deliver(&msg.topic, &msg.msg, Some(msg.reply_to));
+        self.retain(msg.topic.clone(), msg.msg);
+
+        if let Some(sender) = sender {
+            let _ = sender.try_tell(
+                Published {
+                    topic: msg.topic,
+                    subscribers,
+                },
+                None,
+            );
+        }
+    }
+
+    // Subscriptions need to know when their actor stops, and durable ones
+    // when an actor is created at their path
+    fn watch(&mut self, ctx: &ChannelCtx<Msg>, durable: bool) {
+        // the system's channels start before the events channel, which
+        // they're subscribed to by the system
+        let sys_events = match ctx.system.sys_channels.as_ref() {
+            Some(channels) => &channels.sys_events,
+            None => return,
+        };
+        let myself = BasicActorRef::from(ctx.myself());
+
+        if !self.watching_terminated {
+            self.watching_terminated = true;
+            sys_events.subscribe(SysTopic::ActorTerminated, myself.clone());
+        }
+        if durable && !self.watching_created {
+            self.watching_created = true;
+            sys_events.subscribe(SysTopic::ActorCreated, myself);
+        }
+    }
+
+    fn rebind(&mut self, actor: &BasicActorRef) {
+        for subs in self.subs.values_mut() {
+            rebind_subscribers(subs, actor);
+        }
+        self.patterns.rebind(actor);
+    }
+
+    fn retain(&mut self, topic: Topic, msg: Msg) {
+        if let Some(ref mut retained) = self.retained {
+            retained.insert(topic, msg);
+        }
+    }
+
+    // Returns the number of deliveries made
+    fn deliver(&self, topic: &Topic, msg: &Msg, sender: Sender) -> usize {
+        let subs = self.subscribers(topic);
+        for sub in subs.iter() {
+            sub.tell(msg.clone(), sender.clone());
+        }
+
+        subs.len()
+    }
+
+    // Actors subscribed to all topics, to `topic` and to
+    // wildcard topics matching `topic`, in delivery order
+    fn subscribers(&self, topic: &Topic) -> Vec<&BoxedTell<Msg>> {
+        let mut subs = Vec::new();
+
+        if let Some(all) = self.subs.get(&All.into()) {
+            subs.extend(all.iter());
+        }
+
+        if topic != &All.into() {
+            if let Some(exact) = self.subs.get(topic) {
+                subs.extend(exact.iter());
+            }
+            self.patterns.matches(&topic.levels(), &mut subs);
+        }
+
+        // the sort is stable, keeping the order of equal priorities
+        subs.sort_by_key(|sub| Reverse(sub.priority));
+        subs.into_iter().map(|sub| &sub.actor).collect()
+    }
+}
+
 impl<Msg> Actor for Channel<Msg>
 where
     Msg: Message,
 {
     type Msg = ChannelMsg<Msg>;
 
-    // todo subscribe to events to unsub subscribers when they die
-    fn pre_start(&mut self, ctx: &ChannelCtx<Msg>) {
-        // let sub = Subscribe {
-        //     topic: SysTopic::ActorTerminated.into(),
-        //     actor: Box::new(ctx.myself.clone())//.into()
-        // };
-
-        // let msg = ChannelMsg::Subscribe(sub);
-        // ctx.myself.tell(msg, None);
-    }
-
     fn recv(&mut self, ctx: &ChannelCtx<Msg>, msg: ChannelMsg<Msg>, sender: Sender) {
         self.receive(ctx, msg, sender);
     }
@@ -58,14 +396,12 @@ where
     // to this system event. This allows us to remove actors that have been
     // terminated but did not explicity unsubscribe before terminating.
     fn sys_recv(&mut self, _: &ChannelCtx<Msg>, msg: SystemMsg, sender: Sender) {
-        if let SystemMsg::Event(evt) = msg {
-            if let SystemEvent::ActorTerminated(terminated) = evt {
-                let subs = self.subs.clone();
-
-                for topic in subs.keys() {
-                    unsubscribe(&mut self.subs, topic, &terminated.actor);
-                }
+        match msg {
+            SystemMsg::Event(SystemEvent::ActorTerminated(terminated)) => {
+                self.remove_stopped(&terminated.actor);
             }
+            SystemMsg::Event(SystemEvent::ActorCreated(created)) => self.rebind(&created.actor),
+            _ => {}
         }
     }
 }
@@ -79,7 +415,10 @@ where
     fn receive(&mut self, ctx: &ChannelCtx<Msg>, msg: Self::Msg, sender: Sender) {
         match msg {
             ChannelMsg::Publish(p) => self.receive(ctx, p, sender),
+            ChannelMsg::PublishWithReply(p) => self.receive(ctx, p, sender),
+            ChannelMsg::PublishBatch(p) => self.receive(ctx, p, sender),
             ChannelMsg::Subscribe(sub) => self.receive(ctx, sub, sender),
+            ChannelMsg::SubscribeWithResponse(sub) => self.receive(ctx, sub, sender),
             ChannelMsg::Unsubscribe(unsub) => self.receive(ctx, unsub, sender),
             ChannelMsg::UnsubscribeAll(unsub) => self.receive(ctx, unsub, sender),
         }
@@ -93,8 +432,20 @@ where
     type Msg = ChannelMsg<Msg>;
 
     fn receive(&mut self, ctx: &ChannelCtx<Msg>, msg: Subscribe<Msg>, sender: Sender) {
-        let subs = self.subs.entry(msg.topic).or_default();
-        subs.push(msg.actor);
+        self.watch(ctx, msg.durable);
+        self.subscribe(msg);
+    }
+}
+
+impl<Msg> Receive<SubscribeWithResponse<Msg>> for Channel<Msg>
+where
+    Msg: Message,
+{
+    type Msg = ChannelMsg<Msg>;
+
+    fn receive(&mut self, ctx: &ChannelCtx<Msg>, msg: SubscribeWithResponse<Msg>, sender: Sender) {
+        self.watch(ctx, msg.durable);
+        self.subscribe_with_response(msg, ctx.myself().into());
     }
 }
 
@@ -105,7 +456,7 @@ where
     type Msg = ChannelMsg<Msg>;
 
     fn receive(&mut self, ctx: &ChannelCtx<Msg>, msg: Unsubscribe<Msg>, sender: Sender) {
-        unsubscribe(&mut self.subs, &msg.topic, &msg.actor);
+        self.unsubscribe(msg);
     }
 }
 
@@ -116,11 +467,7 @@ where
     type Msg = ChannelMsg<Msg>;
 
     fn receive(&mut self, ctx: &ChannelCtx<Msg>, msg: UnsubscribeAll<Msg>, sender: Sender) {
-        let subs = self.subs.clone();
-
-        for topic in subs.keys() {
-            unsubscribe(&mut self.subs, topic, &msg.actor);
-        }
+        self.unsubscribe_all(&msg.actor);
     }
 }
 
@@ -131,33 +478,38 @@ where
     type Msg = ChannelMsg<Msg>;
 
     fn receive(&mut self, ctx: &ChannelCtx<Msg>, msg: Publish<Msg>, sender: Sender) {
-        // send system event to actors subscribed to all topics
-        if let Some(subs) = self.subs.get(&All.into()) {
-            for sub in subs.iter() {
-                sub.tell(msg.msg.clone(), sender.clone());
-            }
-        }
+        metrics::published(ctx.myself().path(), 1);
+        self.publish(msg, sender);
+    }
+}
 
-        // send system event to actors subscribed to the topic
-        if let Some(subs) = self.subs.get(&msg.topic) {
-            for sub in subs.iter() {
-                sub.tell(msg.msg.clone(), sender.clone());
-            }
-        }
+impl<Msg> Receive<PublishBatch<Msg>> for Channel<Msg>
+where
+    Msg: Message,
+{
+    type Msg = ChannelMsg<Msg>;
+
+    fn receive(&mut self, ctx: &ChannelCtx<Msg>, msg: PublishBatch<Msg>, sender: Sender) {
+        metrics::published(ctx.myself().path(), msg.msgs.len());
+        self.publish_batch(msg, sender);
     }
 }
 
-fn unsubscribe<Msg>(subs: &mut Subs<Msg>, topic: &Topic, actor: &dyn ActorReference) {
-    // Nightly only: self.subs.get(msg_type).unwrap().remove_item(actor);
-    if subs.contains_key(topic) {
-        if let Some(pos) = subs
-            .get(topic)
-            .unwrap()
-            .iter()
-            .position(|x| x.path() == actor.path())
-        {
-            subs.get_mut(topic).unwrap().remove(pos);
-        }
+impl<Msg> Receive<PublishWithReply<Msg>> for Channel<Msg>
+where
+    Msg: Message,
+{
+    type Msg = ChannelMsg<Msg>;
+
+    fn receive(&mut self, ctx: &ChannelCtx<Msg>, msg: PublishWithReply<Msg>, sender: Sender) {
+        metrics::published(ctx.myself().path(), 1);
+        self.publish_with_reply(msg, sender);
+    }
+}
+
+fn unsubscribe<Msg: Message>(subs: &mut Subs<Msg>, topic: &Topic, actor: &dyn ActorReference) {
+    if let Some(subs) = subs.get_mut(topic) {
+        remove_subscriber(subs, actor);
     }
 }
 
@@ -194,7 +546,24 @@ impl Receive<ChannelMsg<SystemEvent>> for EventsChannel {
         // All other variants use the wrapped Channel (self.0) Receive(s)
         match msg {
             ChannelMsg::Publish(p) => self.receive(ctx, p, sender),
+            // system events are delivered without a sender to reply to
+            ChannelMsg::PublishWithReply(p) => self.receive(
+                ctx,
+                Publish {
+                    topic: p.topic,
+                    msg: p.msg,
+                },
+                sender,
+            ),
+            ChannelMsg::PublishBatch(batch) => {
+                for sub in self.0.subscribers(&batch.topic) {
+                    for evt in batch.msgs.iter() {
+                        sub.sys_tell(SystemMsg::Event(evt.clone()));
+                    }
+                }
+            }
             ChannelMsg::Subscribe(sub) => self.0.receive(ctx, sub, sender),
+            ChannelMsg::SubscribeWithResponse(sub) => self.0.receive(ctx, sub, sender),
             ChannelMsg::Unsubscribe(unsub) => self.0.receive(ctx, unsub, sender),
             ChannelMsg::UnsubscribeAll(unsub) => self.0.receive(ctx, unsub, sender),
         }
@@ -210,20 +579,9 @@ impl Receive<Publish<SystemEvent>> for EventsChannel {
         msg: Publish<SystemEvent>,
         sender: Sender,
     ) {
-        // send system event to actors subscribed to all topics
-        if let Some(subs) = self.0.subs.get(&All.into()) {
-            for sub in subs.iter() {
-                let evt = SystemMsg::Event(msg.msg.clone());
-                sub.sys_tell(evt);
-            }
-        }
-
-        // send system event to actors subscribed to the topic
-        if let Some(subs) = self.0.subs.get(&msg.topic) {
-            for sub in subs.iter() {
-                let evt = SystemMsg::Event(msg.msg.clone());
-                sub.sys_tell(evt);
-            }
+        for sub in self.0.subscribers(&msg.topic) {
+            let evt = SystemMsg::Event(msg.msg.clone());
+            sub.sys_tell(evt);
         }
     }
 }
@@ -236,12 +594,152 @@ pub struct DeadLetter {
     pub msg: String,
     pub sender: Sender,
     pub recipient: BasicActorRef,
+    pub reason: DeadLetterReason,
+
+    /// The original message, if it could be retained
+    pub payload: Option<DeadLetterPayload>,
+}
+
+/// The original message of a `DeadLetter`
+///
+/// The message is held as the recipient's message type, e.g. the
+/// `#[actor]` generated enum, not the type that was sent.
+#[derive(Clone)]
+pub struct DeadLetterPayload(Arc<Mutex<AnyMessage>>);
+
+impl DeadLetterPayload {
+    pub fn new<T: Message>(msg: T) -> Self {
+        DeadLetterPayload(Arc::new(Mutex::new(AnyMessage::new(msg, false))))
+    }
+
+    pub(crate) fn from_any(msg: &mut AnyMessage) -> Self {
+        DeadLetterPayload(Arc::new(Mutex::new(msg.retain())))
+    }
+
+    /// Returns a copy of the message if it is a `T`
+    pub fn get<T: Message>(&self) -> Option<T> {
+        self.0.lock().unwrap().take().ok()
+    }
+
+    pub(crate) fn deliver(&self, actor: &BasicActorRef, sender: Sender) -> bool {
+        actor
+            .try_tell_any(&mut self.0.lock().unwrap(), sender)
+            .is_ok()
+    }
+}
+
+impl fmt::Debug for DeadLetterPayload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("DeadLetterPayload")
+    }
 }
 
+/// Why a message was sent to dead letters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeadLetterReason {
+    /// The recipient was terminated or its mailbox was closed
+    Terminated,
+
+    /// The message was shed because the system was overloaded
+    Overloaded,
+
+    /// The message was shed by a `pattern::Throttler` over its rate
+    Throttled,
+}
+
+/// Subscribe an actor to a topic
+///
+/// Created with `Subscribe::new`, e.g.
+/// `Subscribe::new("orders", Box::new(actor)).priority(10).durable()`.
 #[derive(Debug, Clone)]
 pub struct Subscribe<Msg: Message> {
     pub topic: Topic,
     pub actor: BoxedTell<Msg>,
+
+    /// Subscribers with a higher priority are notified first,
+    /// `None` is the same as a priority of `0`
+    pub priority: Option<i32>,
+
+    /// Keep the subscription for the actor's path once it stops, see `Channel`
+    ///
+    /// e.g. for an actor its parent stops and creates again. A restarted
+    /// actor keeps its subscriptions either way.
+    pub durable: bool,
+}
+
+impl<Msg: Message> Subscribe<Msg> {
+    /// Subscribe `actor` to `topic`, with the default priority
+    pub fn new(topic: impl Into<Topic>, actor: BoxedTell<Msg>) -> Self {
+        Subscribe {
+            topic: topic.into(),
+            actor,
+            priority: None,
+            durable: false,
+        }
+    }
+
+    /// Notify the actor ahead of subscribers with a lower priority
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Keep the subscription once the actor stops, see `durable`
+    pub fn durable(mut self) -> Self {
+        self.durable = true;
+        self
+    }
+}
+
+/// Subscribe to a topic and be notified once subscribed
+///
+/// When the subscription is in place the channel sends a
+/// `SubscribedResponse` to `responder`. Messages published after
+/// the response is received are guaranteed to reach `actor`.
+#[derive(Debug, Clone)]
+pub struct SubscribeWithResponse<Msg: Message> {
+    pub topic: Topic,
+    pub actor: BoxedTell<Msg>,
+    pub responder: BoxedTell<SubscribedResponse>,
+    pub priority: Option<i32>,
+
+    /// Keep the subscription once the actor stops, see `Subscribe::durable`
+    pub durable: bool,
+}
+
+impl<Msg: Message> SubscribeWithResponse<Msg> {
+    /// Subscribe `actor` to `topic`, notifying `responder`
+    pub fn new(
+        topic: impl Into<Topic>,
+        actor: BoxedTell<Msg>,
+        responder: BoxedTell<SubscribedResponse>,
+    ) -> Self {
+        SubscribeWithResponse {
+            topic: topic.into(),
+            actor,
+            responder,
+            priority: None,
+            durable: false,
+        }
+    }
+
+    /// Notify the actor ahead of subscribers with a lower priority
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Keep the subscription once the actor stops, see `Subscribe::durable`
+    pub fn durable(mut self) -> Self {
+        self.durable = true;
+        self
+    }
+}
+
+/// Sent by a channel once a `SubscribeWithResponse` is in place
+#[derive(Debug, Clone)]
+pub struct SubscribedResponse {
+    pub topic: Topic,
 }
 
 #[derive(Debug, Clone)]
@@ -261,14 +759,55 @@ pub struct Publish<Msg: Message> {
     pub msg: Msg,
 }
 
+/// Publish several messages to a topic at once
+///
+/// The channel resolves the topic's subscribers once for the batch,
+/// instead of once per message. Each subscriber receives the messages
+/// in order, as if they were published one by one, and a retained
+/// channel retains the last of them.
+#[derive(Debug, Clone)]
+pub struct PublishBatch<Msg: Message> {
+    pub topic: Topic,
+    pub msgs: Vec<Msg>,
+}
+
+/// Publish a message, with subscribers replying to `reply_to`
+///
+/// Each subscriber receives `msg` with `reply_to` as the sender.
+/// The sender of `PublishWithReply` receives a `Published` with the
+/// number of deliveries made, which is the number of replies to expect.
+#[derive(Debug, Clone)]
+pub struct PublishWithReply<Msg: Message> {
+    pub topic: Topic,
+    pub msg: Msg,
+    pub reply_to: BasicActorRef,
+}
+
+/// Sent by a channel to the sender of a `PublishWithReply`
+#[derive(Debug, Clone)]
+pub struct Published {
+    pub topic: Topic,
+    pub subscribers: usize,
+}
+
 #[derive(Debug, Clone)]
 pub enum ChannelMsg<Msg: Message> {
     /// Publish message
     Publish(Publish<Msg>),
 
+    /// Publish message, with replies sent to a given actor
+    PublishWithReply(PublishWithReply<Msg>),
+
+    /// Publish messages, resolving the subscribers once
+    PublishBatch(PublishBatch<Msg>),
+
     /// Subscribe given `ActorRef` to a topic on a channel
     Subscribe(Subscribe<Msg>),
 
+    /// Subscribe given `ActorRef` to a topic on a channel and
+    /// confirm the subscription with a `SubscribedResponse`
+    SubscribeWithResponse(SubscribeWithResponse<Msg>),
+
     /// Unsubscribe the given `ActorRef` from a topic on a channel
     Unsubscribe(Unsubscribe<Msg>),
 
@@ -277,40 +816,96 @@ pub enum ChannelMsg<Msg: Message> {
 }
 
 // publish
-impl<Msg: Message> Into<ChannelMsg<Msg>> for Publish<Msg> {
-    fn into(self) -> ChannelMsg<Msg> {
-        ChannelMsg::Publish(self)
+impl<Msg: Message> From<Publish<Msg>> for ChannelMsg<Msg> {
+    fn from(publish: Publish<Msg>) -> Self {
+        ChannelMsg::Publish(publish)
+    }
+}
+
+impl<Msg: Message> From<PublishWithReply<Msg>> for ChannelMsg<Msg> {
+    fn from(publish: PublishWithReply<Msg>) -> Self {
+        ChannelMsg::PublishWithReply(publish)
+    }
+}
+
+impl<Msg: Message> From<PublishBatch<Msg>> for ChannelMsg<Msg> {
+    fn from(batch: PublishBatch<Msg>) -> Self {
+        ChannelMsg::PublishBatch(batch)
     }
 }
 
 // subscribe
-impl<Msg: Message> Into<ChannelMsg<Msg>> for Subscribe<Msg> {
-    fn into(self) -> ChannelMsg<Msg> {
-        ChannelMsg::Subscribe(self)
+impl<Msg: Message> From<Subscribe<Msg>> for ChannelMsg<Msg> {
+    fn from(sub: Subscribe<Msg>) -> Self {
+        ChannelMsg::Subscribe(sub)
+    }
+}
+
+impl<Msg: Message> From<SubscribeWithResponse<Msg>> for ChannelMsg<Msg> {
+    fn from(sub: SubscribeWithResponse<Msg>) -> Self {
+        ChannelMsg::SubscribeWithResponse(sub)
     }
 }
 
 // unsubscribe
-impl<Msg: Message> Into<ChannelMsg<Msg>> for Unsubscribe<Msg> {
-    fn into(self) -> ChannelMsg<Msg> {
-        ChannelMsg::Unsubscribe(self)
+impl<Msg: Message> From<Unsubscribe<Msg>> for ChannelMsg<Msg> {
+    fn from(unsub: Unsubscribe<Msg>) -> Self {
+        ChannelMsg::Unsubscribe(unsub)
     }
 }
 
 // unsubscribe
-impl<Msg: Message> Into<ChannelMsg<Msg>> for UnsubscribeAll<Msg> {
-    fn into(self) -> ChannelMsg<Msg> {
-        ChannelMsg::UnsubscribeAll(self)
+impl<Msg: Message> From<UnsubscribeAll<Msg>> for ChannelMsg<Msg> {
+    fn from(unsub: UnsubscribeAll<Msg>) -> Self {
+        ChannelMsg::UnsubscribeAll(unsub)
     }
 }
 
 // Topics allow channel subscribers to filter messages by interest
 ///
 /// When publishing a message to a channel a Topic is provided.
+/// Topics are made of levels separated by `/`, and when subscribing
+/// the `*` and `#` levels can be used as wildcards.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Topic(String);
 
-impl<'a> From<&'a str> for Topic {
+impl Topic {
+    /// Returns true if the topic contains a `*` or `#` wildcard level
+    ///
+    /// The `All` topic is not considered a pattern.
+    pub fn is_pattern(&self) -> bool {
+        self != &All.into() && self.0.split('/').any(|l| l == "*" || l == "#")
+    }
+
+    /// Returns true if this topic, used as a pattern, matches `topic`
+    pub fn matches(&self, topic: &Topic) -> bool {
+        if self == &All.into() {
+            return true;
+        }
+
+        let mut levels = topic.levels().into_iter();
+        for level in self.levels() {
+            match (level, levels.next()) {
+                ("#", _) => return true,
+                ("*", Some(_)) => {}
+                (l, Some(t)) if l == t => {}
+                _ => return false,
+            }
+        }
+
+        levels.next().is_none()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn levels(&self) -> Vec<&str> {
+        self.0.split('/').collect()
+    }
+}
+
+impl From<&str> for Topic {
     fn from(topic: &str) -> Self {
         Topic(topic.to_string())
     }
@@ -322,12 +917,28 @@ impl From<String> for Topic {
     }
 }
 
-impl<'a> From<&'a SystemEvent> for Topic {
+impl From<&SystemEvent> for Topic {
     fn from(evt: &SystemEvent) -> Self {
         match *evt {
             SystemEvent::ActorCreated(_) => Topic::from("actor.created"),
             SystemEvent::ActorTerminated(_) => Topic::from("actor.terminated"),
             SystemEvent::ActorRestarted(_) => Topic::from("actor.restarted"),
+            SystemEvent::ActorFailed(_) => Topic::from("actor.failed"),
+            SystemEvent::OverloadChanged(_) => Topic::from("system.overload"),
+            SystemEvent::HealthChanged(_) => Topic::from("actor.health"),
+        }
+    }
+}
+
+impl From<SystemEventType> for Topic {
+    fn from(evt: SystemEventType) -> Self {
+        match evt {
+            SystemEventType::ActorCreated => Topic::from(SysTopic::ActorCreated),
+            SystemEventType::ActorTerminated => Topic::from(SysTopic::ActorTerminated),
+            SystemEventType::ActorRestarted => Topic::from(SysTopic::ActorRestarted),
+            SystemEventType::ActorFailed => Topic::from(SysTopic::ActorFailed),
+            SystemEventType::OverloadChanged => Topic::from(SysTopic::OverloadChanged),
+            SystemEventType::HealthChanged => Topic::from(SysTopic::HealthChanged),
         }
     }
 }
@@ -346,6 +957,9 @@ pub enum SysTopic {
     ActorCreated,
     ActorTerminated,
     ActorRestarted,
+    ActorFailed,
+    OverloadChanged,
+    HealthChanged,
 }
 
 impl From<SysTopic> for Topic {
@@ -354,6 +968,9 @@ impl From<SysTopic> for Topic {
             SysTopic::ActorCreated => Topic::from("actor.created"),
             SysTopic::ActorTerminated => Topic::from("actor.terminated"),
             SysTopic::ActorRestarted => Topic::from("actor.restarted"),
+            SysTopic::ActorFailed => Topic::from("actor.failed"),
+            SysTopic::OverloadChanged => Topic::from("system.overload"),
+            SysTopic::HealthChanged => Topic::from("actor.health"),
         }
     }
 }
@@ -364,3 +981,100 @@ where
 {
     fact.actor_of::<Channel<Msg>>(name)
 }
+
+/// Create a channel that retains the last message published to each topic
+///
+/// Actors subscribing to a topic immediately receive its retained message.
+/// Subscribing to `All` sends the retained messages of every topic.
+pub fn retained_channel<Msg>(
+    name: &str,
+    fact: &impl ActorRefFactory,
+) -> Result<ChannelRef<Msg>, CreateError>
+where
+    Msg: Message,
+{
+    fact.actor_of_props(name, Props::new_from(Channel::retained))
+}
+
+/// Publish and subscribe without building the `ChannelMsg`s
+///
+/// Also used with the system channels, e.g. `sys.sys_events()`, where
+/// the topic can be a `SysTopic`.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+///
+/// #[derive(Default)]
+/// struct Printer;
+///
+/// impl Actor for Printer {
+///     type Msg = String;
+///
+///     fn recv(&mut self, _: &Context<String>, msg: String, _: Sender) {
+///         println!("{}", msg);
+///     }
+/// }
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let chan: ChannelRef<String> = channel("news", &sys).unwrap();
+/// let printer = sys.actor_of::<Printer>("printer").unwrap();
+///
+/// chan.subscribe("sport", printer.clone());
+/// chan.publish("sport", "goal".to_string());
+/// chan.unsubscribe_all(printer);
+/// ```
+impl<Msg: Message> ActorRef<ChannelMsg<Msg>> {
+    /// Publish `msg` to the subscribers of `topic`
+    pub fn publish(&self, topic: impl Into<Topic>, msg: Msg) {
+        self.send_msg(
+            Publish {
+                topic: topic.into(),
+                msg,
+            }
+            .into(),
+            None,
+        );
+    }
+
+    /// Publish `msgs` to the subscribers of `topic`, see `PublishBatch`
+    pub fn publish_batch(&self, topic: impl Into<Topic>, msgs: Vec<Msg>) {
+        self.send_msg(
+            PublishBatch {
+                topic: topic.into(),
+                msgs,
+            }
+            .into(),
+            None,
+        );
+    }
+
+    /// Subscribe `actor` to `topic`, with the default priority
+    pub fn subscribe(&self, topic: impl Into<Topic>, actor: impl Tell<Msg>) {
+        self.send_msg(Subscribe::new(topic, Box::new(actor)).into(), None);
+    }
+
+    /// Unsubscribe `actor` from `topic`
+    pub fn unsubscribe(&self, topic: impl Into<Topic>, actor: impl Tell<Msg>) {
+        self.send_msg(
+            Unsubscribe {
+                topic: topic.into(),
+                actor: Box::new(actor),
+            }
+            .into(),
+            None,
+        );
+    }
+
+    /// Unsubscribe `actor` from every topic
+    pub fn unsubscribe_all(&self, actor: impl Tell<Msg>) {
+        self.send_msg(
+            UnsubscribeAll {
+                actor: Box::new(actor),
+            }
+            .into(),
+            None,
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    any::{Any, TypeId},
+    sync::Arc,
+};
+
+use dashmap::DashMap;
+
+/// Per-actor values, keyed by their type, see `Context::extensions`
+///
+/// Middleware such as metrics, tracing or rate limiters can attach state to
+/// an actor without the actor declaring a field for it. Extensions belong
+/// to the actor, not the instance, so they are kept when the actor restarts
+/// and dropped when it stops.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct RequestCount(u64);
+///
+/// #[derive(Default)]
+/// struct Service;
+///
+/// impl Actor for Service {
+///     type Msg = ();
+///
+///     fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+///         ctx.extensions().insert(RequestCount(0));
+///     }
+///
+///     fn recv(&mut self, ctx: &Context<Self::Msg>, _: Self::Msg, _: Sender) {
+///         ctx.extensions().with(|count: &mut RequestCount| count.0 += 1);
+///         assert!(ctx.extensions().get::<RequestCount>().unwrap().0 > 0);
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct Extensions {
+    values: Arc<DashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+}
+
+impl Extensions {
+    /// Insert `value`, returning the value of the same type it replaced
+    pub fn insert<T: Any + Send + Sync>(&self, value: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|old| old.downcast().ok())
+            .map(|old| *old)
+    }
+
+    /// Returns a copy of the value of type `T`
+    pub fn get<T: Any + Send + Sync + Clone>(&self) -> Option<T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>().cloned())
+    }
+
+    /// Call `f` with the value of type `T`, if there is one
+    pub fn with<T, R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R>
+    where
+        T: Any + Send + Sync,
+    {
+        self.values
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|mut value| value.downcast_mut::<T>().map(f))
+    }
+
+    pub fn contains<T: Any + Send + Sync>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn remove<T: Any + Send + Sync>(&self) -> Option<T> {
+        self.values
+            .remove(&TypeId::of::<T>())
+            .and_then(|(_, value)| value.downcast().ok())
+            .map(|value| *value)
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex},
+    task::{Context as TaskContext, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    actor::{
+        Actor, ActorFactoryArgs, ActorRef, ActorReference, Context, CreateError, Sender,
+        TmpActorRefFactory,
+    },
+    system::{ActorSystem, SystemCmd},
+    Message,
+};
+
+struct Queue<Msg> {
+    msgs: VecDeque<(Msg, Sender)>,
+    waker: Option<Waker>,
+}
+
+struct Shared<Msg> {
+    queue: Mutex<Queue<Msg>>,
+    ready: Condvar,
+}
+
+impl<Msg> Shared<Msg> {
+    fn push(&self, msg: Msg, sender: Sender) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.msgs.push_back((msg, sender));
+
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+        self.ready.notify_one();
+    }
+}
+
+/// Receive messages from actors in code that isn't an actor
+///
+/// An `Inbox` is backed by a temporary actor which queues the
+/// messages it receives. Its `ActorRef` is used as the recipient,
+/// or as the sender so that actors can reply to it.
+///
+/// The temporary actor is stopped when the `Inbox` is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use std::time::Duration;
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let inbox = Inbox::<String>::new(&sys).unwrap();
+///
+/// inbox.sender_ref().tell("hello".to_string(), None);
+///
+/// let msg = inbox.receive(Duration::from_secs(1));
+/// assert_eq!(msg, Some("hello".to_string()));
+/// ```
+pub struct Inbox<Msg: Message> {
+    actor: ActorRef<Msg>,
+    shared: Arc<Shared<Msg>>,
+}
+
+impl<Msg: Message> Inbox<Msg> {
+    pub fn new(sys: &ActorSystem) -> Result<Self, CreateError> {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                msgs: VecDeque::new(),
+                waker: None,
+            }),
+            ready: Condvar::new(),
+        });
+
+        let actor = sys.tmp_actor_of_args::<InboxActor<Msg>, _>(shared.clone())?;
+
+        Ok(Inbox { actor, shared })
+    }
+
+    /// Returns the `ActorRef` whose messages are received by this inbox
+    pub fn sender_ref(&self) -> ActorRef<Msg> {
+        self.actor.clone()
+    }
+
+    /// Receive the next message, blocking for at most `timeout`
+    pub fn receive(&self, timeout: Duration) -> Option<Msg> {
+        self.receive_with_sender(timeout).map(|(msg, _)| msg)
+    }
+
+    /// Receive the next message and its sender, blocking for at most `timeout`
+    pub fn receive_with_sender(&self, timeout: Duration) -> Option<(Msg, Sender)> {
+        let deadline = Instant::now() + timeout;
+        let mut queue = self.shared.queue.lock().unwrap();
+
+        loop {
+            if let Some(msg) = queue.msgs.pop_front() {
+                return Some(msg);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+
+            queue = self
+                .shared
+                .ready
+                .wait_timeout(queue, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// Returns a future that resolves to the next message
+    pub fn receive_async(&self) -> InboxReceive<Msg> {
+        InboxReceive {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<Msg: Message> Drop for Inbox<Msg> {
+    fn drop(&mut self) {
+        self.actor.sys_tell(SystemCmd::Stop.into());
+    }
+}
+
+/// Future returned by `Inbox::receive_async`
+pub struct InboxReceive<Msg> {
+    shared: Arc<Shared<Msg>>,
+}
+
+impl<Msg> Future for InboxReceive<Msg> {
+    type Output = (Msg, Sender);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let mut queue = self.shared.queue.lock().unwrap();
+
+        match queue.msgs.pop_front() {
+            Some(msg) => Poll::Ready(msg),
+            None => {
+                queue.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+struct InboxActor<Msg> {
+    shared: Arc<Shared<Msg>>,
+}
+
+impl<Msg: Message> ActorFactoryArgs<Arc<Shared<Msg>>> for InboxActor<Msg> {
+    fn create_args(shared: Arc<Shared<Msg>>) -> Self {
+        InboxActor { shared }
+    }
+}
+
+impl<Msg: Message> Actor for InboxActor<Msg> {
+    type Msg = Msg;
+
+    fn recv(&mut self, _: &Context<Msg>, msg: Msg, sender: Sender) {
+        self.shared.push(msg, sender);
+    }
+}
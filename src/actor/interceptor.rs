@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use crate::{
+    actor::{Context, Sender},
+    Message,
+};
+
+/// Middleware wrapping the messages an actor receives, see `WithInterceptor`
+///
+/// `around_receive` is called with each message before the actor receives
+/// it. Calling `next.run` passes the message, or a different message, on to
+/// the next interceptor and finally to the actor. Not calling it drops the
+/// message, e.g. when an auth check fails. Code after `next.run` runs once
+/// the actor has handled the message, e.g. to record how long it took.
+///
+/// Interceptors are kept for the life of the actor, including restarts.
+/// Messages are received by the actor in the order they're intercepted.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// # use std::sync::Arc;
+///
+/// #[derive(Default)]
+/// struct Counted(Arc<AtomicUsize>);
+///
+/// impl<Msg: Message> Interceptor<Msg> for Counted {
+///     fn around_receive(&self, ctx: &Context<Msg>, msg: Msg, sender: Sender, next: Next<Msg>) {
+///         self.0.fetch_add(1, Ordering::SeqCst);
+///         next.run(ctx, msg, sender);
+///     }
+/// }
+///
+/// #[derive(Default)]
+/// struct Service;
+///
+/// # impl Actor for Service {
+/// #    type Msg = String;
+/// #    fn recv(&mut self, _ctx: &Context<String>, _msg: String, _sender: Sender) {}
+/// # }
+/// // main
+/// let sys = ActorSystem::new().unwrap();
+///
+/// let props = Props::new::<Service>().with_interceptor(Counted::default());
+/// let actor = sys.actor_of_props("service", props).unwrap();
+/// ```
+pub trait Interceptor<Msg: Message>: Send + Sync + 'static {
+    fn around_receive(&self, ctx: &Context<Msg>, msg: Msg, sender: Sender, next: Next<Msg>);
+}
+
+pub(crate) type Interceptors<Msg> = Arc<[Arc<dyn Interceptor<Msg>>]>;
+
+/// The rest of an interceptor chain, ending with the actor
+pub struct Next<'a, Msg: Message> {
+    chain: &'a [Arc<dyn Interceptor<Msg>>],
+    recv: &'a mut dyn FnMut(&Context<Msg>, Msg, Sender),
+}
+
+impl<'a, Msg: Message> Next<'a, Msg> {
+    pub(crate) fn new(
+        chain: &'a [Arc<dyn Interceptor<Msg>>],
+        recv: &'a mut dyn FnMut(&Context<Msg>, Msg, Sender),
+    ) -> Self {
+        Next { chain, recv }
+    }
+
+    /// Pass `msg` to the next interceptor, or to the actor if this is the last
+    pub fn run(self, ctx: &Context<Msg>, msg: Msg, sender: Sender) {
+        match self.chain.split_first() {
+            Some((interceptor, chain)) => {
+                let next = Next {
+                    chain,
+                    recv: self.recv,
+                };
+                interceptor.around_receive(ctx, msg, sender, next);
+            }
+            None => (self.recv)(ctx, msg, sender),
+        }
+    }
+}
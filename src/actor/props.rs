@@ -2,9 +2,10 @@ use std::{
     fmt,
     panic::{RefUnwindSafe, UnwindSafe},
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
-use crate::actor::Actor;
+use crate::actor::{Actor, Interceptor};
 
 /// Provides instances of `ActorProducer` for use when creating Actors (`actor_of_props`).
 ///
@@ -297,6 +298,46 @@ pub trait ActorProducer: fmt::Debug + Send + UnwindSafe + RefUnwindSafe {
     /// If the provided factory method panics the panic will be caught
     /// by the system, resulting in an error result returning to `actor_of_props`.
     fn produce(&self) -> Self::Actor;
+
+    /// The name of the dispatcher the actor runs on, see `WithDispatcher`
+    ///
+    /// `None` runs the actor on the system executor.
+    fn dispatcher(&self) -> Option<String> {
+        None
+    }
+
+    /// The priority of messages in the actor's mailbox, see `WithPriority`
+    fn priority(&self) -> Option<MsgPriority<<Self::Actor as Actor>::Msg>> {
+        None
+    }
+
+    /// The interceptors wrapping the actor's messages, outermost first, see `WithInterceptor`
+    fn interceptors(&self) -> Vec<Arc<dyn Interceptor<<Self::Actor as Actor>::Msg>>> {
+        Vec::new()
+    }
+
+    /// How long the actor may be idle before it's passivated, see `WithPassivation`
+    fn passivate_after(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The number of messages the actor's priority mailbox is allocated for,
+    /// see `WithPriority::with_priority_capacity`
+    fn mailbox_capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// The message the actor receives first, see `WithInitMsg`
+    fn init_msg(&self) -> Option<<Self::Actor as Actor>::Msg> {
+        None
+    }
+
+    /// The number of messages processed each time the actor runs, see `WithMsgProcessLimit`
+    ///
+    /// `None` uses `mailbox.msg_process_limit`.
+    fn msg_process_limit(&self) -> Option<MsgProcessLimit> {
+        None
+    }
 }
 
 impl<A> ActorProducer for Arc<Mutex<Box<dyn ActorProducer<Actor = A>>>>
@@ -308,6 +349,33 @@ where
     fn produce(&self) -> A {
         self.lock().unwrap().produce()
     }
+    fn dispatcher(&self) -> Option<String> {
+        self.lock().unwrap().dispatcher()
+    }
+
+    fn priority(&self) -> Option<MsgPriority<A::Msg>> {
+        self.lock().unwrap().priority()
+    }
+
+    fn interceptors(&self) -> Vec<Arc<dyn Interceptor<A::Msg>>> {
+        self.lock().unwrap().interceptors()
+    }
+
+    fn passivate_after(&self) -> Option<Duration> {
+        self.lock().unwrap().passivate_after()
+    }
+
+    fn mailbox_capacity(&self) -> Option<usize> {
+        self.lock().unwrap().mailbox_capacity()
+    }
+
+    fn init_msg(&self) -> Option<A::Msg> {
+        self.lock().unwrap().init_msg()
+    }
+
+    fn msg_process_limit(&self) -> Option<MsgProcessLimit> {
+        self.lock().unwrap().msg_process_limit()
+    }
 }
 
 impl<A> ActorProducer for Arc<Mutex<dyn ActorProducer<Actor = A>>>
@@ -319,6 +387,33 @@ where
     fn produce(&self) -> A {
         self.lock().unwrap().produce()
     }
+    fn dispatcher(&self) -> Option<String> {
+        self.lock().unwrap().dispatcher()
+    }
+
+    fn priority(&self) -> Option<MsgPriority<A::Msg>> {
+        self.lock().unwrap().priority()
+    }
+
+    fn interceptors(&self) -> Vec<Arc<dyn Interceptor<A::Msg>>> {
+        self.lock().unwrap().interceptors()
+    }
+
+    fn passivate_after(&self) -> Option<Duration> {
+        self.lock().unwrap().passivate_after()
+    }
+
+    fn mailbox_capacity(&self) -> Option<usize> {
+        self.lock().unwrap().mailbox_capacity()
+    }
+
+    fn init_msg(&self) -> Option<A::Msg> {
+        self.lock().unwrap().init_msg()
+    }
+
+    fn msg_process_limit(&self) -> Option<MsgProcessLimit> {
+        self.lock().unwrap().msg_process_limit()
+    }
 }
 
 impl<A> ActorProducer for Box<dyn ActorProducer<Actor = A>>
@@ -330,6 +425,33 @@ where
     fn produce(&self) -> A {
         (**self).produce()
     }
+    fn dispatcher(&self) -> Option<String> {
+        (**self).dispatcher()
+    }
+
+    fn priority(&self) -> Option<MsgPriority<A::Msg>> {
+        (**self).priority()
+    }
+
+    fn interceptors(&self) -> Vec<Arc<dyn Interceptor<A::Msg>>> {
+        (**self).interceptors()
+    }
+
+    fn passivate_after(&self) -> Option<Duration> {
+        (**self).passivate_after()
+    }
+
+    fn mailbox_capacity(&self) -> Option<usize> {
+        (**self).mailbox_capacity()
+    }
+
+    fn init_msg(&self) -> Option<A::Msg> {
+        (**self).init_msg()
+    }
+
+    fn msg_process_limit(&self) -> Option<MsgProcessLimit> {
+        (**self).msg_process_limit()
+    }
 }
 
 pub struct ActorProps<A: Actor> {
@@ -429,3 +551,716 @@ impl<A: Actor, Args: ActorArgs> fmt::Debug for ActorPropsWithArgs<A, Args> {
 
 pub trait ActorArgs: Clone + Send + Sync + 'static {}
 impl<T: Clone + Send + Sync + 'static> ActorArgs for T {}
+
+/// Places an actor on a named dispatcher
+pub trait WithDispatcher<A: Actor> {
+    /// Run the actor on the dispatcher `name` instead of the system executor
+    ///
+    /// Dispatchers are thread pools defined in config, so that CPU-heavy
+    /// actors can't starve latency-sensitive ones:
+    ///
+    /// ```toml
+    /// [dispatchers.pinned]
+    /// pool_size = 1
+    /// ```
+    ///
+    /// Creating the actor fails with `CreateError::UnknownDispatcher`
+    /// if no dispatcher with the name is configured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use riker::actors::*;
+    ///
+    /// #[derive(Default)]
+    /// struct Cruncher;
+    ///
+    /// # impl Actor for Cruncher {
+    /// #    type Msg = String;
+    /// #    fn recv(&mut self, _ctx: &Context<String>, _msg: String, _sender: Sender) {}
+    /// # }
+    /// // main
+    /// let mut cfg = riker::load_config();
+    /// cfg.set("dispatchers.pinned.pool_size", 1).unwrap();
+    /// let sys = ActorSystem::with_config("crunch", cfg).unwrap();
+    ///
+    /// let props = Props::new::<Cruncher>().with_dispatcher("pinned");
+    /// let actor = sys.actor_of_props("cruncher", props).unwrap();
+    /// ```
+    fn with_dispatcher(self, name: &str) -> BoxActorProd<A>;
+}
+
+impl<A, P> WithDispatcher<A> for Arc<Mutex<P>>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized + 'static,
+{
+    fn with_dispatcher(self, name: &str) -> BoxActorProd<A> {
+        Arc::new(Mutex::new(ActorPropsWithDispatcher {
+            props: self,
+            dispatcher: name.to_string(),
+        }))
+    }
+}
+
+pub struct ActorPropsWithDispatcher<P: ?Sized> {
+    props: Arc<Mutex<P>>,
+    dispatcher: String,
+}
+
+impl<P: ?Sized> UnwindSafe for ActorPropsWithDispatcher<P> {}
+impl<P: ?Sized> RefUnwindSafe for ActorPropsWithDispatcher<P> {}
+
+impl<A, P> ActorProducer for ActorPropsWithDispatcher<P>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized,
+{
+    type Actor = A;
+
+    fn produce(&self) -> A {
+        self.props.lock().unwrap().produce()
+    }
+
+    fn dispatcher(&self) -> Option<String> {
+        Some(self.dispatcher.clone())
+    }
+
+    fn priority(&self) -> Option<MsgPriority<A::Msg>> {
+        self.props.lock().unwrap().priority()
+    }
+
+    fn interceptors(&self) -> Vec<Arc<dyn Interceptor<A::Msg>>> {
+        self.props.lock().unwrap().interceptors()
+    }
+
+    fn passivate_after(&self) -> Option<Duration> {
+        self.props.lock().unwrap().passivate_after()
+    }
+
+    fn mailbox_capacity(&self) -> Option<usize> {
+        self.props.lock().unwrap().mailbox_capacity()
+    }
+
+    fn init_msg(&self) -> Option<A::Msg> {
+        self.props.lock().unwrap().init_msg()
+    }
+
+    fn msg_process_limit(&self) -> Option<MsgProcessLimit> {
+        self.props.lock().unwrap().msg_process_limit()
+    }
+}
+
+impl<P: ?Sized> fmt::Debug for ActorPropsWithDispatcher<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Props[dispatcher: {}]", self.dispatcher)
+    }
+}
+
+/// Returns the priority of a message, higher priorities are received first
+pub type MsgPriority<Msg> = fn(&Msg) -> u8;
+
+/// Gives an actor a priority mailbox
+pub trait WithPriority<A: Actor> {
+    /// Receive messages with a higher `priority` first
+    ///
+    /// Messages of the same priority are received in the order they were
+    /// sent. System messages are still processed ahead of all messages
+    /// and `mailbox.msg_process_limit` applies as for any mailbox.
+    ///
+    /// A poison pill stops the actor once it has received as many messages
+    /// as were queued when the pill was sent. These are taken by priority,
+    /// so a higher priority message sent after the pill is received ahead
+    /// of a lower priority one sent before it, which goes to dead letters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use riker::actors::*;
+    ///
+    /// #[derive(Clone, Debug)]
+    /// enum Work {
+    ///     Control(String),
+    ///     Data(Vec<u8>),
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// struct Worker;
+    ///
+    /// # impl Actor for Worker {
+    /// #    type Msg = Work;
+    /// #    fn recv(&mut self, _ctx: &Context<Work>, _msg: Work, _sender: Sender) {}
+    /// # }
+    /// fn priority(msg: &Work) -> u8 {
+    ///     match msg {
+    ///         Work::Control(_) => 1,
+    ///         Work::Data(_) => 0,
+    ///     }
+    /// }
+    ///
+    /// // main
+    /// let sys = ActorSystem::new().unwrap();
+    ///
+    /// let props = Props::new::<Worker>().with_priority(priority);
+    /// let actor = sys.actor_of_props("worker", props).unwrap();
+    /// ```
+    fn with_priority(self, priority: MsgPriority<A::Msg>) -> BoxActorProd<A>;
+
+    /// Receive messages with a higher `priority` first, allocating the
+    /// mailbox for `capacity` queued messages up front
+    ///
+    /// This avoids growing the mailbox while the first messages arrive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use riker::actors::*;
+    ///
+    /// #[derive(Default)]
+    /// struct Ingest;
+    ///
+    /// # impl Actor for Ingest {
+    /// #    type Msg = u32;
+    /// #    fn recv(&mut self, _ctx: &Context<u32>, _msg: u32, _sender: Sender) {}
+    /// # }
+    /// fn priority(msg: &u32) -> u8 {
+    ///     (*msg > 100) as u8
+    /// }
+    ///
+    /// // main
+    /// let sys = ActorSystem::new().unwrap();
+    ///
+    /// let props = Props::new::<Ingest>().with_priority_capacity(priority, 1024);
+    /// let actor = sys.actor_of_props("ingest", props).unwrap();
+    /// ```
+    fn with_priority_capacity(
+        self,
+        priority: MsgPriority<A::Msg>,
+        capacity: usize,
+    ) -> BoxActorProd<A>;
+}
+
+impl<A, P> WithPriority<A> for Arc<Mutex<P>>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized + 'static,
+{
+    fn with_priority(self, priority: MsgPriority<A::Msg>) -> BoxActorProd<A> {
+        Arc::new(Mutex::new(ActorPropsWithPriority {
+            props: self,
+            priority,
+            capacity: None,
+        }))
+    }
+
+    fn with_priority_capacity(
+        self,
+        priority: MsgPriority<A::Msg>,
+        capacity: usize,
+    ) -> BoxActorProd<A> {
+        Arc::new(Mutex::new(ActorPropsWithPriority {
+            props: self,
+            priority,
+            capacity: Some(capacity),
+        }))
+    }
+}
+
+pub struct ActorPropsWithPriority<A: Actor, P: ?Sized> {
+    props: Arc<Mutex<P>>,
+    priority: MsgPriority<A::Msg>,
+    capacity: Option<usize>,
+}
+
+impl<A: Actor, P: ?Sized> UnwindSafe for ActorPropsWithPriority<A, P> {}
+impl<A: Actor, P: ?Sized> RefUnwindSafe for ActorPropsWithPriority<A, P> {}
+
+impl<A, P> ActorProducer for ActorPropsWithPriority<A, P>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized,
+{
+    type Actor = A;
+
+    fn produce(&self) -> A {
+        self.props.lock().unwrap().produce()
+    }
+
+    fn dispatcher(&self) -> Option<String> {
+        self.props.lock().unwrap().dispatcher()
+    }
+
+    fn priority(&self) -> Option<MsgPriority<A::Msg>> {
+        Some(self.priority)
+    }
+
+    fn interceptors(&self) -> Vec<Arc<dyn Interceptor<A::Msg>>> {
+        self.props.lock().unwrap().interceptors()
+    }
+
+    fn passivate_after(&self) -> Option<Duration> {
+        self.props.lock().unwrap().passivate_after()
+    }
+
+    fn mailbox_capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    fn init_msg(&self) -> Option<A::Msg> {
+        self.props.lock().unwrap().init_msg()
+    }
+
+    fn msg_process_limit(&self) -> Option<MsgProcessLimit> {
+        self.props.lock().unwrap().msg_process_limit()
+    }
+}
+
+impl<A: Actor, P: ?Sized> fmt::Debug for ActorPropsWithPriority<A, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Props[priority]")
+    }
+}
+
+/// Wraps an actor's messages in an `Interceptor`
+pub trait WithInterceptor<A: Actor> {
+    /// Pass every message through `interceptor` before the actor receives it
+    ///
+    /// Interceptors compose in the order they're added: the first one
+    /// added is the outermost, it sees each message first and completes
+    /// last. Actors receiving batches receive the messages which passed
+    /// through every interceptor, in their original order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use riker::actors::*;
+    ///
+    /// struct Trace;
+    ///
+    /// impl Interceptor<String> for Trace {
+    ///     fn around_receive(&self, ctx: &Context<String>, msg: String, sender: Sender, next: Next<String>) {
+    ///         println!("{} received {}", ctx.myself().name(), msg);
+    ///         next.run(ctx, msg, sender);
+    ///     }
+    /// }
+    ///
+    /// struct Upper;
+    ///
+    /// impl Interceptor<String> for Upper {
+    ///     fn around_receive(&self, ctx: &Context<String>, msg: String, sender: Sender, next: Next<String>) {
+    ///         next.run(ctx, msg.to_uppercase(), sender);
+    ///     }
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// struct Shouter;
+    ///
+    /// # impl Actor for Shouter {
+    /// #    type Msg = String;
+    /// #    fn recv(&mut self, _ctx: &Context<String>, _msg: String, _sender: Sender) {}
+    /// # }
+    /// // main
+    /// let sys = ActorSystem::new().unwrap();
+    ///
+    /// // `Trace` sees the original message, the actor the upper case one
+    /// let props = Props::new::<Shouter>()
+    ///     .with_interceptor(Trace)
+    ///     .with_interceptor(Upper);
+    /// let actor = sys.actor_of_props("shouter", props).unwrap();
+    /// ```
+    fn with_interceptor<I>(self, interceptor: I) -> BoxActorProd<A>
+    where
+        I: Interceptor<A::Msg>;
+}
+
+impl<A, P> WithInterceptor<A> for Arc<Mutex<P>>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized + 'static,
+{
+    fn with_interceptor<I>(self, interceptor: I) -> BoxActorProd<A>
+    where
+        I: Interceptor<A::Msg>,
+    {
+        Arc::new(Mutex::new(ActorPropsWithInterceptor {
+            props: self,
+            interceptor: Arc::new(interceptor),
+        }))
+    }
+}
+
+pub struct ActorPropsWithInterceptor<A: Actor, P: ?Sized> {
+    props: Arc<Mutex<P>>,
+    interceptor: Arc<dyn Interceptor<A::Msg>>,
+}
+
+impl<A: Actor, P: ?Sized> UnwindSafe for ActorPropsWithInterceptor<A, P> {}
+impl<A: Actor, P: ?Sized> RefUnwindSafe for ActorPropsWithInterceptor<A, P> {}
+
+impl<A, P> ActorProducer for ActorPropsWithInterceptor<A, P>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized,
+{
+    type Actor = A;
+
+    fn produce(&self) -> A {
+        self.props.lock().unwrap().produce()
+    }
+
+    fn dispatcher(&self) -> Option<String> {
+        self.props.lock().unwrap().dispatcher()
+    }
+
+    fn priority(&self) -> Option<MsgPriority<A::Msg>> {
+        self.props.lock().unwrap().priority()
+    }
+
+    fn interceptors(&self) -> Vec<Arc<dyn Interceptor<A::Msg>>> {
+        let mut interceptors = self.props.lock().unwrap().interceptors();
+        interceptors.push(self.interceptor.clone());
+        interceptors
+    }
+
+    fn passivate_after(&self) -> Option<Duration> {
+        self.props.lock().unwrap().passivate_after()
+    }
+
+    fn mailbox_capacity(&self) -> Option<usize> {
+        self.props.lock().unwrap().mailbox_capacity()
+    }
+
+    fn init_msg(&self) -> Option<A::Msg> {
+        self.props.lock().unwrap().init_msg()
+    }
+
+    fn msg_process_limit(&self) -> Option<MsgProcessLimit> {
+        self.props.lock().unwrap().msg_process_limit()
+    }
+}
+
+impl<A: Actor, P: ?Sized> fmt::Debug for ActorPropsWithInterceptor<A, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Props[interceptor]")
+    }
+}
+
+/// Stops an actor while it's idle
+pub trait WithPassivation<A: Actor> {
+    /// Passivate the actor once it hasn't received a message for `idle`
+    ///
+    /// The actor instance is stopped, calling `post_stop`, but its path,
+    /// `ActorRef`s and mailbox stay valid. The next message recreates the
+    /// actor from its props, calling `pre_start` before the message is
+    /// received. This keeps memory bounded with many rarely used actors,
+    /// such as one actor per entity.
+    ///
+    /// An actor isn't passivated while it has children, queued messages
+    /// or is awaiting a future. Spawned futures and the receive timeout
+    /// are cancelled when it's passivated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use riker::actors::*;
+    /// # use std::time::Duration;
+    ///
+    /// #[derive(Default)]
+    /// struct Order;
+    ///
+    /// # impl Actor for Order {
+    /// #    type Msg = String;
+    /// #    fn recv(&mut self, _ctx: &Context<String>, _msg: String, _sender: Sender) {}
+    /// # }
+    /// // main
+    /// let sys = ActorSystem::new().unwrap();
+    ///
+    /// let props = Props::new::<Order>().auto_passivate(Duration::from_secs(60));
+    /// let order = sys.actor_of_props("order-1", props).unwrap();
+    /// ```
+    fn auto_passivate(self, idle: Duration) -> BoxActorProd<A>;
+}
+
+impl<A, P> WithPassivation<A> for Arc<Mutex<P>>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized + 'static,
+{
+    fn auto_passivate(self, idle: Duration) -> BoxActorProd<A> {
+        Arc::new(Mutex::new(ActorPropsWithPassivation { props: self, idle }))
+    }
+}
+
+pub struct ActorPropsWithPassivation<P: ?Sized> {
+    props: Arc<Mutex<P>>,
+    idle: Duration,
+}
+
+impl<P: ?Sized> UnwindSafe for ActorPropsWithPassivation<P> {}
+impl<P: ?Sized> RefUnwindSafe for ActorPropsWithPassivation<P> {}
+
+impl<A, P> ActorProducer for ActorPropsWithPassivation<P>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized,
+{
+    type Actor = A;
+
+    fn produce(&self) -> A {
+        self.props.lock().unwrap().produce()
+    }
+
+    fn dispatcher(&self) -> Option<String> {
+        self.props.lock().unwrap().dispatcher()
+    }
+
+    fn priority(&self) -> Option<MsgPriority<A::Msg>> {
+        self.props.lock().unwrap().priority()
+    }
+
+    fn interceptors(&self) -> Vec<Arc<dyn Interceptor<A::Msg>>> {
+        self.props.lock().unwrap().interceptors()
+    }
+
+    fn passivate_after(&self) -> Option<Duration> {
+        Some(self.idle)
+    }
+
+    fn mailbox_capacity(&self) -> Option<usize> {
+        self.props.lock().unwrap().mailbox_capacity()
+    }
+
+    fn init_msg(&self) -> Option<A::Msg> {
+        self.props.lock().unwrap().init_msg()
+    }
+
+    fn msg_process_limit(&self) -> Option<MsgProcessLimit> {
+        self.props.lock().unwrap().msg_process_limit()
+    }
+}
+
+impl<P: ?Sized> fmt::Debug for ActorPropsWithPassivation<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Props[passivate after: {:?}]", self.idle)
+    }
+}
+
+/// Sends an actor a message as soon as it's started
+pub trait WithInitMsg<A: Actor> {
+    /// Deliver `msg` to the actor right after `pre_start`
+    ///
+    /// The message is queued when the actor is created, ahead of any
+    /// message sent to the returned `ActorRef`. This guarantees that
+    /// configuration sent this way is received before the actor's
+    /// other messages. It's delivered once, a restarted actor
+    /// doesn't receive it again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use riker::actors::*;
+    ///
+    /// #[derive(Clone, Debug)]
+    /// enum CacheMsg {
+    ///     Configure { size: usize },
+    ///     Get(String),
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// struct Cache;
+    ///
+    /// # impl Actor for Cache {
+    /// #    type Msg = CacheMsg;
+    /// #    fn recv(&mut self, _ctx: &Context<CacheMsg>, _msg: CacheMsg, _sender: Sender) {}
+    /// # }
+    /// // main
+    /// let sys = ActorSystem::new().unwrap();
+    ///
+    /// let props = Props::new::<Cache>().with_init_msg(CacheMsg::Configure { size: 64 });
+    /// let cache = sys.actor_of_props("cache", props).unwrap();
+    ///
+    /// // received after `Configure`
+    /// cache.tell(CacheMsg::Get("key".into()), None);
+    /// ```
+    fn with_init_msg(self, msg: A::Msg) -> BoxActorProd<A>;
+}
+
+impl<A, P> WithInitMsg<A> for Arc<Mutex<P>>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized + 'static,
+{
+    fn with_init_msg(self, msg: A::Msg) -> BoxActorProd<A> {
+        Arc::new(Mutex::new(ActorPropsWithInitMsg { props: self, msg }))
+    }
+}
+
+pub struct ActorPropsWithInitMsg<A: Actor, P: ?Sized> {
+    props: Arc<Mutex<P>>,
+    msg: A::Msg,
+}
+
+impl<A: Actor, P: ?Sized> UnwindSafe for ActorPropsWithInitMsg<A, P> {}
+impl<A: Actor, P: ?Sized> RefUnwindSafe for ActorPropsWithInitMsg<A, P> {}
+
+impl<A, P> ActorProducer for ActorPropsWithInitMsg<A, P>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized,
+{
+    type Actor = A;
+
+    fn produce(&self) -> A {
+        self.props.lock().unwrap().produce()
+    }
+
+    fn dispatcher(&self) -> Option<String> {
+        self.props.lock().unwrap().dispatcher()
+    }
+
+    fn priority(&self) -> Option<MsgPriority<A::Msg>> {
+        self.props.lock().unwrap().priority()
+    }
+
+    fn interceptors(&self) -> Vec<Arc<dyn Interceptor<A::Msg>>> {
+        self.props.lock().unwrap().interceptors()
+    }
+
+    fn passivate_after(&self) -> Option<Duration> {
+        self.props.lock().unwrap().passivate_after()
+    }
+
+    fn mailbox_capacity(&self) -> Option<usize> {
+        self.props.lock().unwrap().mailbox_capacity()
+    }
+
+    fn init_msg(&self) -> Option<A::Msg> {
+        Some(self.msg.clone())
+    }
+
+    fn msg_process_limit(&self) -> Option<MsgProcessLimit> {
+        self.props.lock().unwrap().msg_process_limit()
+    }
+}
+
+impl<A: Actor, P: ?Sized> fmt::Debug for ActorPropsWithInitMsg<A, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Props[init msg: {:?}]", self.msg)
+    }
+}
+
+/// How many messages an actor processes each time it runs
+///
+/// An actor runs while it has messages, up to its limit, then yields its
+/// thread to other actors. A larger limit favours throughput, a smaller
+/// limit keeps a slow actor from holding a thread for a long batch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MsgProcessLimit {
+    /// Process at most this many messages each run
+    Fixed(u32),
+
+    /// Process at most `max` messages each run, fewer if they take longer than `budget`
+    ///
+    /// The limit of each run is the number of messages that fit in `budget`,
+    /// by the average time the actor took per message in earlier runs.
+    /// Fast actors process up to `max` messages, slow actors as few as one.
+    Adaptive { max: u32, budget: Duration },
+}
+
+/// Sets the number of messages an actor processes each time it runs
+pub trait WithMsgProcessLimit<A: Actor> {
+    /// Override `mailbox.msg_process_limit` for this actor
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use riker::actors::*;
+    /// # use std::time::Duration;
+    ///
+    /// #[derive(Default)]
+    /// struct Render;
+    ///
+    /// # impl Actor for Render {
+    /// #    type Msg = u32;
+    /// #    fn recv(&mut self, _ctx: &Context<u32>, _msg: u32, _sender: Sender) {}
+    /// # }
+    /// // main
+    /// let sys = ActorSystem::new().unwrap();
+    ///
+    /// // yield the thread about every 5ms
+    /// let limit = MsgProcessLimit::Adaptive {
+    ///     max: 1000,
+    ///     budget: Duration::from_millis(5),
+    /// };
+    /// let props = Props::new::<Render>().with_msg_process_limit(limit);
+    /// let actor = sys.actor_of_props("render", props).unwrap();
+    /// ```
+    fn with_msg_process_limit(self, limit: MsgProcessLimit) -> BoxActorProd<A>;
+}
+
+impl<A, P> WithMsgProcessLimit<A> for Arc<Mutex<P>>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized + 'static,
+{
+    fn with_msg_process_limit(self, limit: MsgProcessLimit) -> BoxActorProd<A> {
+        Arc::new(Mutex::new(ActorPropsWithProcessLimit {
+            props: self,
+            limit,
+        }))
+    }
+}
+
+pub struct ActorPropsWithProcessLimit<P: ?Sized> {
+    props: Arc<Mutex<P>>,
+    limit: MsgProcessLimit,
+}
+
+impl<P: ?Sized> UnwindSafe for ActorPropsWithProcessLimit<P> {}
+impl<P: ?Sized> RefUnwindSafe for ActorPropsWithProcessLimit<P> {}
+
+impl<A, P> ActorProducer for ActorPropsWithProcessLimit<P>
+where
+    A: Actor + Send + 'static,
+    P: ActorProducer<Actor = A> + ?Sized,
+{
+    type Actor = A;
+
+    fn produce(&self) -> A {
+        self.props.lock().unwrap().produce()
+    }
+
+    fn dispatcher(&self) -> Option<String> {
+        self.props.lock().unwrap().dispatcher()
+    }
+
+    fn priority(&self) -> Option<MsgPriority<A::Msg>> {
+        self.props.lock().unwrap().priority()
+    }
+
+    fn interceptors(&self) -> Vec<Arc<dyn Interceptor<A::Msg>>> {
+        self.props.lock().unwrap().interceptors()
+    }
+
+    fn passivate_after(&self) -> Option<Duration> {
+        self.props.lock().unwrap().passivate_after()
+    }
+
+    fn mailbox_capacity(&self) -> Option<usize> {
+        self.props.lock().unwrap().mailbox_capacity()
+    }
+
+    fn init_msg(&self) -> Option<A::Msg> {
+        self.props.lock().unwrap().init_msg()
+    }
+
+    fn msg_process_limit(&self) -> Option<MsgProcessLimit> {
+        Some(self.limit)
+    }
+}
+
+impl<P: ?Sized> fmt::Debug for ActorPropsWithProcessLimit<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Props[msg process limit: {:?}]", self.limit)
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    error::Error,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context as TaskContext, Poll},
+    time::Duration,
+};
+
+use futures::channel::oneshot;
+
+use crate::{
+    actor::{
+        Actor, ActorFactoryArgs, ActorRefFactory, ActorReference, BasicActorRef, Context, Sender,
+    },
+    system::{SystemCmd, SystemMsg},
+    Message,
+};
+
+/// A typed, one-shot channel to reply to a request
+///
+/// `ReplyTo` can be cloned to satisfy `Message` but only the
+/// first reply is delivered.
+pub struct ReplyTo<T> {
+    tx: Arc<Mutex<Option<oneshot::Sender<T>>>>,
+}
+
+impl<T: Message> ReplyTo<T> {
+    /// Returns a `ReplyTo` and the receiver its reply is sent to
+    pub fn new() -> (Self, oneshot::Receiver<T>) {
+        let (tx, rx) = oneshot::channel();
+        let reply_to = ReplyTo {
+            tx: Arc::new(Mutex::new(Some(tx))),
+        };
+
+        (reply_to, rx)
+    }
+
+    /// Send the reply
+    ///
+    /// The reply is returned if a reply was already sent
+    /// or the receiver was dropped.
+    pub fn reply(&self, msg: T) -> Result<(), T> {
+        match self.tx.lock().unwrap().take() {
+            Some(tx) => tx.send(msg),
+            None => Err(msg),
+        }
+    }
+}
+
+impl<T> Clone for ReplyTo<T> {
+    fn clone(&self) -> Self {
+        ReplyTo {
+            tx: self.tx.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ReplyTo<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ReplyTo")
+    }
+}
+
+/// A request carrying a typed reply channel
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+///
+/// #[derive(Default)]
+/// struct Doubler;
+///
+/// impl Actor for Doubler {
+///     type Msg = Respondable<u32, u32>;
+///
+///     fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+///         let res = msg.request * 2;
+///         let _ = msg.reply(res);
+///     }
+/// }
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let doubler = sys.actor_of::<Doubler>("doubler").unwrap();
+///
+/// let (req, reply) = Respondable::new(21);
+/// doubler.tell(req, None);
+///
+/// let res = futures::executor::block_on(reply).unwrap();
+/// assert_eq!(res, 42);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Respondable<Req, Res> {
+    pub request: Req,
+    pub reply_to: ReplyTo<Res>,
+}
+
+impl<Req: Message, Res: Message> Respondable<Req, Res> {
+    /// Returns the request and the receiver its reply is sent to
+    pub fn new(request: Req) -> (Self, oneshot::Receiver<Res>) {
+        let (reply_to, rx) = ReplyTo::new();
+        (Respondable { request, reply_to }, rx)
+    }
+
+    /// Reply to the request, see `ReplyTo::reply`
+    pub fn reply(&self, res: Res) -> Result<(), Res> {
+        self.reply_to.reply(res)
+    }
+}
+
+/// Why an `Ask` completed without a reply
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AskError {
+    /// No reply was received within `temp.ttl_millis`
+    Timeout,
+
+    /// The temporary actor stopped before a reply, e.g. the system stopped
+    Canceled,
+}
+
+impl fmt::Display for AskError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AskError::Timeout => f.write_str("No reply received before the ask timed out"),
+            AskError::Canceled => f.write_str("The ask was canceled before a reply"),
+        }
+    }
+}
+
+impl Error for AskError {}
+
+type AskReply<Res> = Result<Res, AskError>;
+
+/// A reply to `Context::ask`
+///
+/// Completes with the first message sent to the request's sender. If no
+/// reply is received within `temp.ttl_millis` the temporary actor stops
+/// and the `Ask` completes with `AskError::Timeout`, or with
+/// `AskError::Canceled` if the system stops first. Dropping an `Ask`
+/// before the reply stops waiting for it.
+pub struct Ask<Res> {
+    rx: oneshot::Receiver<AskReply<Res>>,
+    asker: Option<BasicActorRef>,
+}
+
+impl<Res> Ask<Res> {
+    pub(crate) fn new(rx: oneshot::Receiver<AskReply<Res>>, asker: BasicActorRef) -> Self {
+        Ask {
+            rx,
+            asker: Some(asker),
+        }
+    }
+}
+
+impl<Res> Future for Ask<Res> {
+    type Output = AskReply<Res>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let res = Pin::new(&mut self.rx).poll(cx);
+        if res.is_ready() {
+            // the temporary actor stopped itself
+            self.asker = None;
+        }
+        res.map(|reply| reply.unwrap_or(Err(AskError::Canceled)))
+    }
+}
+
+impl<Res> Drop for Ask<Res> {
+    fn drop(&mut self) {
+        if let Some(asker) = self.asker.take() {
+            asker.sys_tell(SystemCmd::Stop.into());
+        }
+    }
+}
+
+impl<Res> fmt::Debug for Ask<Res> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Ask")
+    }
+}
+
+pub(crate) type AskerArgs<Res> = (ReplyTo<AskReply<Res>>, Option<Duration>);
+
+// Temporary actor sending the first message it receives to `reply_to`,
+// or a timeout once it has waited for `ttl`
+pub(crate) struct Asker<Res: Message> {
+    reply_to: ReplyTo<AskReply<Res>>,
+    ttl: Option<Duration>,
+}
+
+impl<Res: Message> ActorFactoryArgs<AskerArgs<Res>> for Asker<Res> {
+    fn create_args((reply_to, ttl): AskerArgs<Res>) -> Self {
+        Asker { reply_to, ttl }
+    }
+}
+
+impl<Res: Message> Actor for Asker<Res> {
+    type Msg = Res;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        if let Some(ttl) = self.ttl {
+            ctx.set_receive_timeout(ttl);
+        }
+    }
+
+    fn sys_recv(&mut self, ctx: &Context<Self::Msg>, msg: SystemMsg, _: Sender) {
+        if let SystemMsg::ReceiveTimeout(_) = msg {
+            let _ = self.reply_to.reply(Err(AskError::Timeout));
+            ctx.stop(ctx.myself());
+        }
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        let _ = self.reply_to.reply(Ok(msg));
+        ctx.stop(ctx.myself());
+    }
+}
@@ -1,8 +1,17 @@
-use std::iter::Peekable;
+use std::{
+    iter::Peekable,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
 
 use crate::{
-    actor::{ActorReference, BasicActorRef, Sender},
-    system::SystemMsg,
+    actor::{
+        Actor, ActorFactoryArgs, ActorPath, ActorRef, ActorReference, BasicActorRef, ChannelMsg,
+        Context, Sender, Subscribe, SysTopic, Tell,
+    },
+    system::{SystemEvent, SystemMsg},
     validate::{validate_path, InvalidPath},
     Message,
 };
@@ -36,7 +45,6 @@ pub struct ActorSelection {
     anchor: BasicActorRef,
     // dl: BasicActorRef,
     path_vec: Vec<Selection>,
-    path: String,
 }
 
 impl ActorSelection {
@@ -62,10 +70,82 @@ impl ActorSelection {
             anchor,
             // dl: dl.clone(),
             path_vec,
-            path,
         })
     }
 
+    // The pattern of the paths the selection covers, see `CacheScope`
+    fn scope(&self) -> Option<Vec<Option<String>>> {
+        let mut pattern: Vec<Option<String>> = self
+            .anchor
+            .path()
+            .segments()
+            .map(|seg| Some(seg.to_string()))
+            .collect();
+        let mut below_anchor = false;
+
+        for seg in &self.path_vec {
+            match seg {
+                Selection::Parent if below_anchor => return None,
+                Selection::Parent => {
+                    pattern.pop()?;
+                }
+                Selection::ChildName(name) if name.is_empty() => return None,
+                Selection::ChildName(name) => {
+                    below_anchor = true;
+                    pattern.push(Some(name.clone()));
+                }
+                // the rest of the path is ignored after `*`
+                Selection::AllChildren => {
+                    pattern.push(None);
+                    break;
+                }
+            }
+        }
+
+        Some(pattern)
+    }
+
+    /// Returns the actors currently in the selection
+    pub fn resolve(&self) -> Vec<BasicActorRef> {
+        fn walk<'a, I>(
+            anchor: &BasicActorRef,
+            mut path_vec: Peekable<I>,
+            refs: &mut Vec<BasicActorRef>,
+        ) where
+            I: Iterator<Item = &'a Selection>,
+        {
+            let seg = path_vec.next();
+
+            match seg {
+                Some(&Selection::Parent) => {
+                    if path_vec.peek().is_none() {
+                        refs.push(anchor.parent());
+                    } else {
+                        walk(&anchor.parent(), path_vec, refs);
+                    }
+                }
+                Some(&Selection::AllChildren) => {
+                    refs.extend(anchor.children());
+                }
+                Some(Selection::ChildName(name)) => {
+                    let child = anchor.children().filter(|c| c.name() == name).last();
+                    if let Some(child) = child {
+                        if path_vec.peek().is_none() {
+                            refs.push(child);
+                        } else {
+                            walk(&child, path_vec, refs);
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+
+        let mut refs = Vec::new();
+        walk(&self.anchor, self.path_vec.iter().peekable(), &mut refs);
+        refs
+    }
+
     pub fn try_tell<Msg>(&self, msg: Msg, sender: impl Into<Option<BasicActorRef>>)
     where
         Msg: Message,
@@ -76,7 +156,6 @@ impl ActorSelection {
             mut path_vec: Peekable<I>,
             msg: Msg,
             sender: &Sender,
-            path: &str,
         ) where
             I: Iterator<Item = &'a Selection>,
             Msg: Message,
@@ -89,7 +168,7 @@ impl ActorSelection {
                         let parent = anchor.parent();
                         let _ = parent.try_tell(msg, sender.clone());
                     } else {
-                        walk(&anchor.parent(), path_vec, msg, sender, path);
+                        walk(&anchor.parent(), path_vec, msg, sender);
                     }
                 }
                 Some(&Selection::AllChildren) => {
@@ -97,7 +176,7 @@ impl ActorSelection {
                         let _ = child.try_tell(msg.clone(), sender.clone());
                     }
                 }
-                Some(&Selection::ChildName(ref name)) => {
+                Some(Selection::ChildName(name)) => {
                     let child = anchor.children().filter(|c| c.name() == name).last();
                     if path_vec.peek().is_none() {
                         if let Some(actor_ref) = child {
@@ -105,12 +184,11 @@ impl ActorSelection {
                         }
                     } else if path_vec.peek().is_some() && child.is_some() {
                         walk(
-                            &child.as_ref().unwrap(),
+                            child.as_ref().unwrap(),
                             // dl,
                             path_vec,
                             msg,
                             sender,
-                            path,
                         );
                     } else {
                         // todo send to deadletters?
@@ -126,7 +204,6 @@ impl ActorSelection {
             self.path_vec.iter().peekable(),
             msg,
             &sender.into(),
-            &self.path,
         );
     }
 
@@ -137,7 +214,6 @@ impl ActorSelection {
             mut path_vec: Peekable<I>,
             msg: SystemMsg,
             sender: &Sender,
-            path: &str,
         ) where
             I: Iterator<Item = &'a Selection>,
         {
@@ -149,7 +225,7 @@ impl ActorSelection {
                         let parent = anchor.parent();
                         parent.sys_tell(msg);
                     } else {
-                        walk(&anchor.parent(), path_vec, msg, sender, path);
+                        walk(&anchor.parent(), path_vec, msg, sender);
                     }
                 }
                 Some(&Selection::AllChildren) => {
@@ -157,7 +233,7 @@ impl ActorSelection {
                         child.sys_tell(msg.clone());
                     }
                 }
-                Some(&Selection::ChildName(ref name)) => {
+                Some(Selection::ChildName(name)) => {
                     let child = anchor.children().filter(|c| c.name() == name).last();
                     if path_vec.peek().is_none() {
                         if let Some(actor_ref) = child {
@@ -165,12 +241,11 @@ impl ActorSelection {
                         }
                     } else if path_vec.peek().is_some() && child.is_some() {
                         walk(
-                            &child.as_ref().unwrap(),
+                            child.as_ref().unwrap(),
                             // dl,
                             path_vec,
                             msg,
                             sender,
-                            path,
                         );
                     } else {
                         // todo send to deadletters?
@@ -186,7 +261,6 @@ impl ActorSelection {
             self.path_vec.iter().peekable(),
             msg,
             &sender.into(),
-            &self.path,
         );
     }
 }
@@ -198,6 +272,189 @@ enum Selection {
     AllChildren,
 }
 
+// An absolute selection `path` relative to the user root
+pub(crate) fn from_user_root(path: &str, user_root: &BasicActorRef) -> String {
+    let path = ActorPath::new(path);
+    if path.is_descendant_of(user_root.path()) {
+        let skip = user_root.path().segments().count();
+        path.segments().skip(skip).collect::<Vec<_>>().join("/")
+    } else {
+        path.to_string()
+    }
+}
+
 pub trait ActorSelectionFactory {
     fn select(&self, path: &str) -> Result<ActorSelection, InvalidPath>;
 }
+
+/// An `ActorSelection` that keeps the actors it resolves to
+///
+/// Unlike `ActorSelection` the hierarchy is only traversed again
+/// after an actor the selection covers has been created or terminated,
+/// making repeated messaging of the same selection cheaper. E.g. the
+/// selection `/user/a/*` covers `/user/a` and its children, but not
+/// `/user/b` or `/user/a/b/c`.
+///
+/// The cache is invalidated from `actor.created` and `actor.terminated`
+/// system events. Since these are delivered asynchronously a selection
+/// used immediately after a change may still resolve to the previous
+/// actors.
+///
+/// A `CachedSelection` is created using `select_cached` on
+/// `ActorSystem` or `Context`.
+#[derive(Debug)]
+pub struct CachedSelection {
+    selection: ActorSelection,
+    scope: Arc<CacheScope>,
+    cache: Mutex<Option<Vec<BasicActorRef>>>,
+}
+
+impl CachedSelection {
+    pub(crate) fn new(selection: ActorSelection, caches: &SelectionCaches) -> Self {
+        let scope = caches.register(selection.scope());
+        CachedSelection {
+            selection,
+            scope,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Returns the actors in the selection, traversing the hierarchy
+    /// only if an actor it covers has changed since the last resolve
+    pub fn resolve(&self) -> Vec<BasicActorRef> {
+        let mut cache = self.cache.lock().unwrap();
+
+        // cleared before the traversal, so that changes during it aren't missed
+        let stale = self.scope.stale.swap(false, Ordering::AcqRel);
+        match *cache {
+            Some(ref refs) if !stale => refs.clone(),
+            _ => {
+                let refs = self.selection.resolve();
+                *cache = Some(refs.clone());
+                refs
+            }
+        }
+    }
+
+    /// True if the next `resolve` traverses the hierarchy
+    pub fn is_stale(&self) -> bool {
+        self.scope.stale.load(Ordering::Acquire) || self.cache.lock().unwrap().is_none()
+    }
+
+    pub fn try_tell<Msg>(&self, msg: Msg, sender: impl Into<Option<BasicActorRef>>)
+    where
+        Msg: Message,
+    {
+        let sender = sender.into();
+        for actor in self.resolve() {
+            let _ = actor.try_tell(msg.clone(), sender.clone());
+        }
+    }
+
+    pub fn sys_tell(&self, msg: SystemMsg) {
+        for actor in self.resolve() {
+            actor.sys_tell(msg.clone());
+        }
+    }
+}
+
+// The paths a `CachedSelection` covers, as segments from the root where
+// `None` matches any name. A selection that can't be reduced to such
+// a pattern, e.g. `a/../b`, covers every path.
+#[derive(Debug)]
+pub(crate) struct CacheScope {
+    pattern: Option<Vec<Option<String>>>,
+    stale: AtomicBool,
+}
+
+impl CacheScope {
+    // A path is covered if it's the path, or an ancestor of a path,
+    // the selection can resolve to
+    fn covers(&self, path: &ActorPath) -> bool {
+        match self.pattern {
+            Some(ref pattern) => {
+                let segments: Vec<&str> = path.segments().collect();
+                segments.len() <= pattern.len()
+                    && segments
+                        .iter()
+                        .zip(pattern)
+                        .all(|(seg, pat)| pat.as_ref().is_none_or(|pat| pat == seg))
+            }
+            None => true,
+        }
+    }
+}
+
+/// The scopes of all `CachedSelection`s of a system
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SelectionCaches(Arc<Mutex<Vec<Weak<CacheScope>>>>);
+
+impl SelectionCaches {
+    fn register(&self, pattern: Option<Vec<Option<String>>>) -> Arc<CacheScope> {
+        let scope = Arc::new(CacheScope {
+            pattern,
+            stale: AtomicBool::new(false),
+        });
+        self.0.lock().unwrap().push(Arc::downgrade(&scope));
+        scope
+    }
+
+    // Mark the selections covering `path` as stale, dropping the scopes
+    // of selections that no longer exist
+    fn invalidate(&self, path: &ActorPath) {
+        self.0
+            .lock()
+            .unwrap()
+            .retain(|scope| match scope.upgrade() {
+                Some(scope) => {
+                    if scope.covers(path) {
+                        scope.stale.store(true, Ordering::Release);
+                    }
+                    true
+                }
+                None => false,
+            });
+    }
+}
+
+/// System actor that invalidates `CachedSelection`s when actors
+/// are created or terminated
+pub(crate) struct SelectionInvalidator {
+    sys_events: ActorRef<ChannelMsg<SystemEvent>>,
+    caches: SelectionCaches,
+}
+
+impl ActorFactoryArgs<(ActorRef<ChannelMsg<SystemEvent>>, SelectionCaches)>
+    for SelectionInvalidator
+{
+    fn create_args(
+        (sys_events, caches): (ActorRef<ChannelMsg<SystemEvent>>, SelectionCaches),
+    ) -> Self {
+        SelectionInvalidator { sys_events, caches }
+    }
+}
+
+impl Actor for SelectionInvalidator {
+    type Msg = SystemEvent;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        for topic in [SysTopic::ActorCreated, SysTopic::ActorTerminated] {
+            self.sys_events
+                .tell(Subscribe::new(topic, Box::new(ctx.myself())), None);
+        }
+    }
+
+    fn sys_recv(&mut self, _: &Context<Self::Msg>, msg: SystemMsg, _: Sender) {
+        match msg {
+            SystemMsg::Event(SystemEvent::ActorCreated(evt)) => {
+                self.caches.invalidate(evt.actor.path())
+            }
+            SystemMsg::Event(SystemEvent::ActorTerminated(evt)) => {
+                self.caches.invalidate(evt.actor.path())
+            }
+            _ => {}
+        }
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {}
+}
@@ -0,0 +1,187 @@
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context as TaskContext, Poll},
+};
+
+use futures::{stream::BoxStream, Sink, SinkExt, Stream, StreamExt};
+
+use crate::{
+    actor::{
+        Actor, ActorFactoryArgs, ActorRef, ActorRefFactory, Context, CreateError, SendError,
+        Sender, TmpActorRefFactory,
+    },
+    Message,
+};
+
+/// A `Sink` sending to an actor, see `ActorRef::sink`
+///
+/// The sink is ready while fewer than `max_queued` messages are waiting in
+/// the actor's mailbox, so a fast producer waits for the actor to catch up.
+pub struct ActorSink<Msg: Message> {
+    actor: ActorRef<Msg>,
+    max_queued: usize,
+}
+
+impl<Msg: Message> ActorSink<Msg> {
+    pub(crate) fn new(actor: ActorRef<Msg>, max_queued: usize) -> Self {
+        ActorSink {
+            actor,
+            max_queued: max_queued.max(1),
+        }
+    }
+}
+
+impl<Msg: Message> Sink<Msg> for ActorSink<Msg> {
+    type Error = SendError<Msg>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.actor.cell.poll_queued(self.max_queued, cx.waker()) {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, msg: Msg) -> Result<(), Self::Error> {
+        self.actor.try_send(msg, None)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Messages sent by a `StreamActor` to its target
+#[derive(Clone, Debug)]
+pub enum StreamMsg<T, E> {
+    Item(T),
+
+    /// The stream failed, no more items are sent
+    Error(E),
+
+    /// The stream ended
+    Complete,
+}
+
+type StreamArgs<T, E, M> = (
+    Arc<Mutex<Option<BoxStream<'static, Result<T, E>>>>>,
+    ActorRef<M>,
+    usize,
+);
+
+/// Forwards the items of a `Stream` to an actor
+///
+/// Items are sent through an `ActorSink`, so the stream is only polled while
+/// the target keeps up. The target is sent `StreamMsg::Complete` when the
+/// stream ends, or `StreamMsg::Error` when it fails, and the `StreamActor`
+/// stops. Stopping the `StreamActor` stops forwarding.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use std::time::Duration;
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let inbox = Inbox::<StreamMsg<u32, ()>>::new(&sys).unwrap();
+///
+/// let stream = futures::stream::iter(vec![Ok(1), Ok(2)]);
+/// StreamActor::start(&sys, stream, inbox.sender_ref(), 10).unwrap();
+///
+/// let timeout = Duration::from_secs(1);
+/// assert!(matches!(inbox.receive(timeout), Some(StreamMsg::Item(1))));
+/// assert!(matches!(inbox.receive(timeout), Some(StreamMsg::Item(2))));
+/// assert!(matches!(inbox.receive(timeout), Some(StreamMsg::Complete)));
+/// ```
+pub struct StreamActor<T, E, M: Message> {
+    stream: Option<BoxStream<'static, Result<T, E>>>,
+    target: ActorRef<M>,
+    max_queued: usize,
+}
+
+impl<T, E, M> StreamActor<T, E, M>
+where
+    T: Message,
+    E: Message,
+    M: Message + From<StreamMsg<T, E>>,
+{
+    /// Start forwarding `stream` to `target`, allowing `max_queued`
+    /// messages to wait in its mailbox
+    pub fn start<S>(
+        factory: &impl TmpActorRefFactory,
+        stream: S,
+        target: ActorRef<M>,
+        max_queued: usize,
+    ) -> Result<ActorRef<()>, CreateError>
+    where
+        S: Stream<Item = Result<T, E>> + Send + 'static,
+    {
+        let stream = Arc::new(Mutex::new(Some(stream.boxed())));
+        factory.tmp_actor_of_args::<StreamActor<T, E, M>, _>((stream, target, max_queued))
+    }
+}
+
+impl<T, E, M> ActorFactoryArgs<StreamArgs<T, E, M>> for StreamActor<T, E, M>
+where
+    T: Message,
+    E: Message,
+    M: Message + From<StreamMsg<T, E>>,
+{
+    fn create_args((stream, target, max_queued): StreamArgs<T, E, M>) -> Self {
+        // a restarted StreamActor has no stream left to forward
+        StreamActor {
+            stream: stream.lock().unwrap().take(),
+            target,
+            max_queued,
+        }
+    }
+}
+
+impl<T, E, M> Actor for StreamActor<T, E, M>
+where
+    T: Message,
+    E: Message,
+    M: Message + From<StreamMsg<T, E>>,
+{
+    type Msg = ();
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let mut stream = match self.stream.take() {
+            Some(stream) => stream,
+            None => return,
+        };
+
+        let mut sink = self.target.sink(self.max_queued);
+        let sys = ctx.system.clone();
+        let myself = ctx.myself();
+
+        let forward = async move {
+            let end = loop {
+                match stream.next().await {
+                    Some(Ok(item)) => {
+                        if sink.send(StreamMsg::Item(item).into()).await.is_err() {
+                            // the target terminated
+                            break None;
+                        }
+                    }
+                    Some(Err(e)) => break Some(StreamMsg::Error(e)),
+                    None => break Some(StreamMsg::Complete),
+                }
+            };
+
+            if let Some(end) = end {
+                let _ = sink.send(end.into()).await;
+            }
+            sys.stop(&myself);
+        };
+
+        ctx.spawn(forward).unwrap();
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {}
+}
@@ -1,3 +1,4 @@
+use regex::Regex;
 use std::{
     fmt,
     hash::{Hash, Hasher},
@@ -10,6 +11,59 @@ impl ActorPath {
     pub fn new(path: &str) -> Self {
         ActorPath(Arc::from(path))
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    // The path as a metrics label, without copying it
+    #[cfg(feature = "metrics")]
+    pub(crate) fn shared(&self) -> Arc<str> {
+        self.0.clone()
+    }
+
+    /// Returns the names along the path, e.g. `user`, `a`, `b` for `/user/a/b`
+    ///
+    /// The root path `/` has no segments.
+    pub fn segments(&self) -> impl Iterator<Item = &str> + '_ {
+        self.0.split('/').filter(|seg| !seg.is_empty())
+    }
+
+    /// Returns the parent's path, or `None` for the root path
+    pub fn parent(&self) -> Option<ActorPath> {
+        match self.0.rfind('/') {
+            _ if self.is_root() => None,
+            Some(0) | None => Some(ActorPath::new("/")),
+            Some(i) => Some(ActorPath::new(&self.0[..i])),
+        }
+    }
+
+    /// Returns the path of a child named `name`
+    pub fn child(&self, name: &str) -> ActorPath {
+        let mut path = String::with_capacity(self.0.len() + name.len() + 1);
+        path.push_str(&self.0);
+        if !self.is_root() {
+            path.push('/');
+        }
+        path.push_str(name);
+
+        ActorPath(Arc::from(path))
+    }
+
+    /// True if this path is below `ancestor`, at any depth
+    pub fn is_descendant_of(&self, ancestor: &ActorPath) -> bool {
+        if ancestor.is_root() {
+            return !self.is_root();
+        }
+
+        self.0.len() > ancestor.0.len()
+            && self.0.starts_with(&*ancestor.0)
+            && self.0.as_bytes()[ancestor.0.len()] == b'/'
+    }
+
+    fn is_root(&self) -> bool {
+        &*self.0 == "/"
+    }
 }
 
 impl PartialEq for ActorPath {
@@ -51,15 +105,15 @@ impl Clone for ActorPath {
 }
 
 /// An `ActorUri` represents the location of an actor, including the
-/// path and actor system host.
+/// path and actor system host and port.
 ///
-/// Note: `host` is currently unused but will be utilized when
-/// networking and clustering are introduced.
+/// The host and port are set with `SystemBuilder::host` and `SystemBuilder::port`.
 #[derive(Clone)]
 pub struct ActorUri {
     pub name: Arc<str>,
     pub path: ActorPath,
     pub host: Arc<str>,
+    pub port: Option<u16>,
 }
 
 impl PartialEq for ActorUri {
@@ -84,6 +138,20 @@ impl fmt::Display for ActorUri {
 
 impl fmt::Debug for ActorUri {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}://{}", self.host, self.path)
+        match self.port {
+            Some(port) => write!(f, "{}:{}://{}", self.host, port, self.path),
+            None => write!(f, "{}://{}", self.host, self.path),
+        }
     }
 }
+
+// Matches actor paths against `path`, in which `*` matches any characters
+pub(crate) fn path_pattern(path: &str) -> Regex {
+    let pattern = path
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join(".*");
+
+    Regex::new(&format!("^{}$", pattern)).unwrap()
+}
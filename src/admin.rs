@@ -0,0 +1,243 @@
+//! A minimal HTTP endpoint for inspecting and managing a running system.
+//!
+//! With the `admin` feature `AdminServer::start` serves:
+//!
+//! - `GET /tree`, the `ActorSystem::tree_snapshot` as JSON
+//! - `GET /actor/{path}/stats`, the actor's `ActorInfo` as JSON
+//! - `GET /deadletters`, the `ActorSystem::recent_dead_letters` as JSON
+//! - `POST /actor/{path}/restart`, restarting the actor with `ActorSystem::restart_path`
+//!
+//! e.g. `GET /actor/user/worker/stats` for the actor at `/user/worker`.
+//! The server is a system actor at `/system/admin`, listening with a
+//! `TcpListenerActor`, so it's stopped with the system. Each connection
+//! serves one request and is closed. There is no authentication, bind
+//! the server to a local or otherwise protected address.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    pin::Pin,
+    task::{Context as TaskContext, Poll},
+};
+
+use futures::{channel::oneshot, Future};
+use serde::Serialize;
+
+use crate::{
+    actor::{
+        Actor, ActorFactoryArgs, ActorRef, ActorRefFactory, ActorReference, Context, CreateError,
+        Sender, Tell,
+    },
+    io::{shared, Shared, TcpEvent, TcpListenerActor, TcpMsg},
+    system::{ActorSystem, Run},
+};
+
+// Requests with a larger head are rejected
+const MAX_HEAD: usize = 8 * 1024;
+
+/// Serves the admin endpoints, see the module documentation
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::admin::AdminServer;
+/// # use futures::executor::block_on;
+///
+/// let sys = ActorSystem::new().unwrap();
+///
+/// let bound = AdminServer::start(&sys, "127.0.0.1:0".parse().unwrap()).unwrap();
+/// let addr = block_on(bound).unwrap();
+/// println!("admin listening on http://{}/tree", addr);
+/// ```
+pub struct AdminServer {
+    bound: Option<oneshot::Sender<Result<SocketAddr, String>>>,
+    addr: SocketAddr,
+
+    // the bytes received on each open connection, by connection path
+    requests: HashMap<String, Vec<u8>>,
+}
+
+impl AdminServer {
+    /// Start the server at `/system/admin`, listening on `addr`
+    ///
+    /// An address with port `0` binds to any free port. The returned future
+    /// completes with the address the server is listening on, or the error
+    /// if binding failed.
+    pub fn start(sys: &ActorSystem, addr: SocketAddr) -> Result<Bound, CreateError> {
+        let (tx, rx) = oneshot::channel();
+        sys.sys_actor_of_args::<AdminServer, _>("admin", (addr, shared(tx)))?;
+        Ok(Bound { rx })
+    }
+
+    fn bound(&mut self, result: Result<SocketAddr, String>) {
+        if let Some(tx) = self.bound.take() {
+            let _ = tx.send(result);
+        }
+    }
+
+    fn receive(&mut self, ctx: &Context<TcpEvent>, conn: ActorRef<TcpMsg>, bytes: Vec<u8>) {
+        let buf = self.requests.entry(conn.path().to_string()).or_default();
+        buf.extend(bytes);
+
+        let head = match buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            Some(end) => String::from_utf8_lossy(&buf[..end]).into_owned(),
+            None if buf.len() > MAX_HEAD => {
+                respond(
+                    &conn,
+                    "431 Request Header Fields Too Large",
+                    error("too large"),
+                );
+                return;
+            }
+            None => return,
+        };
+        self.requests.remove(conn.path().as_str());
+
+        let mut line = head.lines().next().unwrap_or("").split_whitespace();
+        let method = line.next().unwrap_or("");
+        let target = line.next().unwrap_or("");
+        let path = target.split('?').next().unwrap_or("");
+
+        self.route(ctx, conn, method, path);
+    }
+
+    fn route(&self, ctx: &Context<TcpEvent>, conn: ActorRef<TcpMsg>, method: &str, path: &str) {
+        let sys = &ctx.system;
+
+        match (method, path) {
+            ("GET", "/tree") => respond(&conn, "200 OK", json(&sys.tree_snapshot())),
+            ("GET", "/deadletters") => {
+                let dead_letters = sys.recent_dead_letters();
+                run(sys, async move {
+                    match dead_letters.await {
+                        Ok(dead_letters) => respond(&conn, "200 OK", json(&dead_letters)),
+                        Err(_) => respond(&conn, "503 Service Unavailable", error("stopped")),
+                    }
+                });
+            }
+            ("GET", path) if actor_path(path, "/stats").is_some() => {
+                let actor = actor_path(path, "/stats").and_then(|p| sys.select_refs(p).pop());
+                let actor = match actor {
+                    Some(actor) => actor,
+                    None => return respond(&conn, "404 Not Found", error("no such actor")),
+                };
+
+                let info = actor.identify();
+                run(sys, async move {
+                    match info.await {
+                        Ok(info) => respond(&conn, "200 OK", json(&info)),
+                        Err(_) => respond(&conn, "404 Not Found", error("no such actor")),
+                    }
+                });
+            }
+            ("POST", path) if actor_path(path, "/restart").is_some() => {
+                match sys.restart_path(actor_path(path, "/restart").unwrap()) {
+                    Ok(()) => respond(&conn, "202 Accepted", "{}".to_string()),
+                    Err(e) => respond(&conn, "404 Not Found", error(&e.to_string())),
+                }
+            }
+            (_, "/tree") | (_, "/deadletters") => {
+                respond(&conn, "405 Method Not Allowed", error("method not allowed"))
+            }
+            _ => respond(&conn, "404 Not Found", error("not found")),
+        }
+    }
+}
+
+type AdminArgs = (
+    SocketAddr,
+    Shared<oneshot::Sender<Result<SocketAddr, String>>>,
+);
+
+impl ActorFactoryArgs<AdminArgs> for AdminServer {
+    fn create_args((addr, bound): AdminArgs) -> Self {
+        AdminServer {
+            bound: bound.lock().unwrap().take(),
+            addr,
+            requests: HashMap::new(),
+        }
+    }
+}
+
+impl Actor for AdminServer {
+    type Msg = TcpEvent;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let events = Box::new(ctx.myself());
+        if let Err(e) = TcpListenerActor::bind(ctx, "listener", self.addr, events) {
+            self.bound(Err(e.to_string()));
+        }
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        match msg {
+            TcpEvent::Bound(addr) => self.bound(Ok(addr)),
+            TcpEvent::Failed { error, .. } => {
+                self.bound(Err(error));
+                ctx.stop(ctx.myself());
+            }
+            TcpEvent::Connected { .. } => {}
+            TcpEvent::Received { conn, bytes } => self.receive(ctx, conn, bytes),
+            TcpEvent::Closed { conn } => {
+                self.requests.remove(conn.path().as_str());
+            }
+        }
+    }
+}
+
+/// Completes with the address an `AdminServer` is listening on
+///
+/// Completes with an error if the server failed to bind, or stopped before binding.
+pub struct Bound {
+    rx: oneshot::Receiver<Result<SocketAddr, String>>,
+}
+
+impl Future for Bound {
+    type Output = Result<SocketAddr, String>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.rx)
+            .poll(cx)
+            .map(|bound| bound.unwrap_or_else(|_| Err("stopped".to_string())))
+    }
+}
+
+// The actor path of `/actor/{path}{suffix}`
+fn actor_path<'a>(path: &'a str, suffix: &str) -> Option<&'a str> {
+    let path = path.strip_prefix("/actor")?.strip_suffix(suffix)?;
+    if path.len() > 1 && path.starts_with('/') {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+// Responds once a reply is received, without blocking the admin actor
+fn run(sys: &ActorSystem, respond: impl Future<Output = ()> + Send + 'static) {
+    if let Ok(handle) = sys.run(respond) {
+        handle.forget();
+    }
+}
+
+fn json(value: &impl Serialize) -> String {
+    serde_json::to_string(value).unwrap_or_else(|e| error(&e.to_string()))
+}
+
+fn error(msg: &str) -> String {
+    json(&HashMap::from([("error", msg)]))
+}
+
+// Writes the response and closes the connection
+fn respond(conn: &ActorRef<TcpMsg>, status: &str, body: String) {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        body.len()
+    );
+
+    let mut bytes = head.into_bytes();
+    bytes.extend(body.into_bytes());
+    conn.tell(TcpMsg::Write(bytes), None);
+    conn.tell(TcpMsg::Close, None);
+}
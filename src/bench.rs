@@ -0,0 +1,162 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    actor::{
+        Actor, ActorFactoryArgs, ActorRef, ActorRefFactory, Context, CreateError, Inbox, Sender,
+        Tell, TmpActorRefFactory,
+    },
+    system::ActorSystem,
+};
+
+/// Producers sending to consumer actors, for benchmarking message throughput
+///
+/// Each producer is a thread sending its messages round robin to the
+/// consumers. A run completes once every consumer has received its
+/// messages, so the time includes the consumers catching up.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::bench::Topology;
+///
+/// let sys = ActorSystem::new().unwrap();
+///
+/// let report = Topology::new(4, 2).msgs(10_000).run(&sys).unwrap().unwrap();
+/// assert_eq!(report.msgs, 40_000);
+/// println!("{:.0} msgs/sec", report.throughput());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Topology {
+    producers: usize,
+    consumers: usize,
+    msgs: u64,
+    timeout: Duration,
+}
+
+/// The result of running a `Topology`
+#[derive(Clone, Debug)]
+pub struct Report {
+    /// The number of messages received by the consumers
+    pub msgs: u64,
+
+    /// The time from the first message sent to the last one received
+    pub elapsed: Duration,
+}
+
+impl Report {
+    /// Messages received per second
+    pub fn throughput(&self) -> f64 {
+        self.msgs as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl Topology {
+    /// `producers` threads sending to `consumers` actors, 100,000 messages per producer
+    pub fn new(producers: usize, consumers: usize) -> Self {
+        Topology {
+            producers: producers.max(1),
+            consumers: consumers.max(1),
+            msgs: 100_000,
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// The number of messages sent by each producer
+    pub fn msgs(self, msgs: u64) -> Self {
+        Topology { msgs, ..self }
+    }
+
+    /// How long to wait for the consumers, before the run is reported as timed out
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Topology { timeout, ..self }
+    }
+
+    /// Create the consumers under `/temp`, send the messages and stop the consumers
+    ///
+    /// Returns `None` if the consumers didn't receive every message within the timeout.
+    pub fn run(&self, sys: &ActorSystem) -> Result<Option<Report>, CreateError> {
+        let done = Inbox::<()>::new(sys)?;
+        let total = self.msgs * self.producers as u64;
+
+        let mut consumers = Vec::with_capacity(self.consumers);
+        for i in 0..self.consumers {
+            // the first consumers receive the remainder of the round robin
+            let expected =
+                total / self.consumers as u64 + ((i as u64) < total % self.consumers as u64) as u64;
+            let consumer = sys.tmp_actor_of_args::<Consumer, _>((expected, done.sender_ref()))?;
+            consumers.push(consumer);
+        }
+
+        let start = Instant::now();
+        let producers: Vec<_> = (0..self.producers)
+            .map(|p| {
+                let consumers = consumers.clone();
+                let msgs = self.msgs;
+                thread::spawn(move || {
+                    for i in 0..msgs {
+                        let n = (p as u64 * msgs + i) as usize % consumers.len();
+                        consumers[n].tell(i, None);
+                    }
+                })
+            })
+            .collect();
+        for producer in producers {
+            let _ = producer.join();
+        }
+
+        let finished = (0..self.consumers).all(|_| {
+            let left = self.timeout.checked_sub(start.elapsed());
+            left.and_then(|left| done.receive(left)).is_some()
+        });
+        let elapsed = start.elapsed();
+
+        for consumer in &consumers {
+            sys.stop(consumer);
+        }
+
+        Ok(if finished {
+            Some(Report {
+                msgs: total,
+                elapsed,
+            })
+        } else {
+            None
+        })
+    }
+}
+
+// Counts its messages, reporting once it has received `expected`
+struct Consumer {
+    expected: u64,
+    received: u64,
+    done: ActorRef<()>,
+}
+
+impl ActorFactoryArgs<(u64, ActorRef<()>)> for Consumer {
+    fn create_args((expected, done): (u64, ActorRef<()>)) -> Self {
+        if expected == 0 {
+            done.tell((), None);
+        }
+
+        Consumer {
+            expected,
+            received: 0,
+            done,
+        }
+    }
+}
+
+impl Actor for Consumer {
+    type Msg = u64;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {
+        self.received += 1;
+        if self.received == self.expected {
+            self.done.tell((), None);
+        }
+    }
+}
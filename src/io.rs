@@ -0,0 +1,49 @@
+pub(crate) mod tcp;
+pub(crate) mod udp;
+
+// Public riker::io API
+pub use self::tcp::{
+    TcpConnectionActor, TcpEvent, TcpListenerActor, TcpListenerMsg, TcpMsg, TcpOpened,
+};
+pub use self::udp::{SendTo, UdpActor, UdpEvent};
+
+use std::{
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{actor::BoxedTell, system::ActorSystem};
+
+// Args shared by the IO actors, behind a `Mutex` since sockets and
+// `BoxedTell` aren't `Sync`. A socket is taken by the first actor
+// instance, a restarted instance has none.
+pub(crate) type Shared<T> = Arc<Mutex<Option<T>>>;
+
+pub(crate) fn shared<T>(value: T) -> Shared<T> {
+    Arc::new(Mutex::new(Some(value)))
+}
+
+type Handler<T> = Arc<Mutex<BoxedTell<T>>>;
+
+fn handler<T>(handler: BoxedTell<T>) -> Handler<T> {
+    Arc::new(Mutex::new(handler))
+}
+
+// Spawn a thread named `<system>-io`, like the system's other threads.
+// Panics if the thread can't be spawned, as `thread::spawn` does.
+fn spawn<F>(sys: &ActorSystem, f: F) -> JoinHandle<()>
+where
+    F: FnOnce() + Send + 'static,
+{
+    thread::Builder::new()
+        .name(format!("{}-io", sys.name()))
+        .spawn(f)
+        .expect("failed to spawn thread")
+}
+
+// Size of the buffer sockets are read into
+const READ_BUFFER: usize = 8 * 1024;
+
+// How often blocking socket loops check whether their actor stopped
+const POLL: Duration = Duration::from_millis(50);
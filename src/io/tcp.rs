@@ -0,0 +1,388 @@
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    actor::{
+        Actor, ActorFactoryArgs, ActorRef, ActorRefFactory, BoxedTell, Context, CreateError,
+        Sender, StateBlob, Tell,
+    },
+    io::{handler, shared, spawn, Handler, Shared, POLL, READ_BUFFER},
+    system::{ActorSystem, FailureInfo},
+};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sent to a handler by the TCP actors
+#[derive(Clone, Debug)]
+pub enum TcpEvent {
+    /// A `TcpListenerActor` is accepting connections on this address
+    Bound(SocketAddr),
+
+    /// Binding or connecting to `addr` failed, the actor stopped
+    Failed { addr: SocketAddr, error: String },
+
+    /// A connection was accepted or established
+    Connected {
+        conn: ActorRef<TcpMsg>,
+        peer: SocketAddr,
+    },
+
+    /// Bytes read from a connection
+    Received {
+        conn: ActorRef<TcpMsg>,
+        bytes: Vec<u8>,
+    },
+
+    /// The connection was closed and its actor stopped
+    Closed { conn: ActorRef<TcpMsg> },
+}
+
+/// Messages to a `TcpConnectionActor`
+#[derive(Clone, Debug)]
+pub enum TcpMsg {
+    Write(Vec<u8>),
+    Close,
+
+    /// Sent to the actor once its connection is open, or failed to open
+    Opened(TcpOpened),
+}
+
+/// The outcome of opening a connection, see `TcpMsg::Opened`
+#[derive(Clone, Debug)]
+pub struct TcpOpened(Shared<Opened>);
+
+// The stream, a clone of it to read from and the peer's address
+type Opened = Result<(TcpStream, TcpStream, SocketAddr), (SocketAddr, io::Error)>;
+
+/// Sent to a `TcpListenerActor` by its accept loop
+#[derive(Clone, Debug)]
+pub struct TcpListenerMsg(Shared<(TcpStream, SocketAddr)>);
+
+/// Accepts TCP connections, starting a `TcpConnectionActor` for each
+///
+/// The handler is sent `TcpEvent::Bound` once the listener is bound and the
+/// events of every accepted connection. Connections are children of the
+/// listener, stopping the listener closes them. A restarted listener closes
+/// its socket before binding `addr` again.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::io::{TcpEvent, TcpListenerActor};
+/// # use std::time::Duration;
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let inbox = Inbox::<TcpEvent>::new(&sys).unwrap();
+///
+/// let addr = "127.0.0.1:0".parse().unwrap();
+/// TcpListenerActor::bind(&sys, "listener", addr, Box::new(inbox.sender_ref())).unwrap();
+///
+/// match inbox.receive(Duration::from_secs(1)) {
+///     Some(TcpEvent::Bound(addr)) => println!("listening on {}", addr),
+///     other => panic!("not bound: {:?}", other),
+/// }
+/// ```
+pub struct TcpListenerActor {
+    addr: SocketAddr,
+    handler: Handler<TcpEvent>,
+    open: Arc<AtomicBool>,
+    accepting: Option<JoinHandle<()>>,
+}
+
+impl TcpListenerActor {
+    /// Start a listener on `addr`, an address with port `0` binds to any free port
+    pub fn bind(
+        factory: &impl ActorRefFactory,
+        name: &str,
+        addr: SocketAddr,
+        events: BoxedTell<TcpEvent>,
+    ) -> Result<ActorRef<TcpListenerMsg>, CreateError> {
+        factory.actor_of_args::<TcpListenerActor, _>(name, (addr, handler(events)))
+    }
+}
+
+impl ActorFactoryArgs<(SocketAddr, Handler<TcpEvent>)> for TcpListenerActor {
+    fn create_args((addr, handler): (SocketAddr, Handler<TcpEvent>)) -> Self {
+        TcpListenerActor {
+            addr,
+            handler,
+            open: Arc::new(AtomicBool::new(true)),
+            accepting: None,
+        }
+    }
+}
+
+impl TcpListenerActor {
+    // Stop the accept loop, which closes the listener
+    fn close(&mut self) {
+        self.open.store(false, Ordering::Relaxed);
+        if let Some(accepting) = self.accepting.take() {
+            let _ = accepting.join();
+        }
+    }
+}
+
+impl Actor for TcpListenerActor {
+    type Msg = TcpListenerMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let handler = self.handler.lock().unwrap().clone();
+
+        // the listener is polled so that the accept loop sees when the actor stops
+        let listener = TcpListener::bind(self.addr).and_then(|listener| {
+            listener.set_nonblocking(true)?;
+            Ok(listener)
+        });
+
+        let listener = match listener {
+            Ok(listener) => listener,
+            Err(e) => {
+                let failed = TcpEvent::Failed {
+                    addr: self.addr,
+                    error: e.to_string(),
+                };
+                handler.tell(failed, None);
+                ctx.stop(ctx.myself());
+                return;
+            }
+        };
+
+        let addr = listener.local_addr().unwrap_or(self.addr);
+        handler.tell(TcpEvent::Bound(addr), Some(ctx.myself().into()));
+
+        let myself = ctx.myself();
+        let open = self.open.clone();
+        self.accepting = Some(spawn(&ctx.system, move || accept(listener, myself, open)));
+    }
+
+    fn post_stop(&mut self) {
+        self.close();
+    }
+
+    fn pre_restart(&mut self, _: &Context<Self::Msg>, _: Option<FailureInfo>) {
+        self.close();
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        if let Some((stream, peer)) = msg.0.lock().unwrap().take() {
+            let conn = shared(Connection::Accepted(stream, peer));
+            let _ = ctx.actor_of_args_anon::<TcpConnectionActor, _>((conn, self.handler.clone()));
+        }
+    }
+}
+
+fn accept(listener: TcpListener, myself: ActorRef<TcpListenerMsg>, open: Arc<AtomicBool>) {
+    while open.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                if stream.set_nonblocking(false).is_ok() {
+                    myself.tell(TcpListenerMsg(shared((stream, peer))), None);
+                }
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(POLL),
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(_) => break,
+        }
+    }
+}
+
+enum Connection {
+    Accepted(TcpStream, SocketAddr),
+    Connect(SocketAddr),
+}
+
+/// A TCP connection, accepted by a `TcpListenerActor` or made with `connect`
+///
+/// The handler is sent `TcpEvent::Connected`, then `TcpEvent::Received` for
+/// the bytes read until the connection closes. `TcpMsg::Write` writes to the
+/// connection and `TcpMsg::Close`, or stopping the actor, closes it. A
+/// restarted actor keeps the connection.
+pub struct TcpConnectionActor {
+    conn: Option<Connection>,
+    handler: BoxedTell<TcpEvent>,
+    stream: Option<TcpStream>,
+    // received while connecting, handled once the connection is open
+    pending: Vec<TcpMsg>,
+}
+
+impl TcpConnectionActor {
+    /// Start a connection to `addr`
+    ///
+    /// The actor connects on its own thread, waiting up to 5 seconds. Writes
+    /// sent meanwhile are written once it has connected.
+    pub fn connect(
+        factory: &impl ActorRefFactory,
+        name: &str,
+        addr: SocketAddr,
+        events: BoxedTell<TcpEvent>,
+    ) -> Result<ActorRef<TcpMsg>, CreateError> {
+        let conn = shared(Connection::Connect(addr));
+        factory.actor_of_args::<TcpConnectionActor, _>(name, (conn, handler(events)))
+    }
+}
+
+impl ActorFactoryArgs<(Shared<Connection>, Handler<TcpEvent>)> for TcpConnectionActor {
+    fn create_args((conn, handler): (Shared<Connection>, Handler<TcpEvent>)) -> Self {
+        TcpConnectionActor {
+            conn: conn.lock().unwrap().take(),
+            handler: handler.lock().unwrap().clone(),
+            stream: None,
+            pending: Vec::new(),
+        }
+    }
+}
+
+fn open(conn: Connection) -> Opened {
+    let (stream, peer) = match conn {
+        Connection::Accepted(stream, peer) => (stream, peer),
+        Connection::Connect(addr) => {
+            let stream =
+                TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).map_err(|e| (addr, e))?;
+            (stream, addr)
+        }
+    };
+
+    let reader = stream.try_clone().map_err(|e| (peer, e))?;
+    Ok((stream, reader, peer))
+}
+
+impl TcpConnectionActor {
+    fn opened(&mut self, ctx: &Context<TcpMsg>, opened: Opened) {
+        let (stream, reader, peer) = match opened {
+            Ok(opened) => opened,
+            Err((addr, e)) => {
+                let failed = TcpEvent::Failed {
+                    addr,
+                    error: e.to_string(),
+                };
+                self.handler.tell(failed, None);
+                ctx.stop(ctx.myself());
+                return;
+            }
+        };
+
+        let connected = TcpEvent::Connected {
+            conn: ctx.myself(),
+            peer,
+        };
+        self.handler.tell(connected, Some(ctx.myself().into()));
+
+        let sys = ctx.system.clone();
+        let myself = ctx.myself();
+        let handler = self.handler.clone();
+        spawn(&ctx.system, move || read(reader, myself, handler, sys));
+
+        self.stream = Some(stream);
+        for msg in std::mem::take(&mut self.pending) {
+            self.write(msg);
+        }
+    }
+
+    fn write(&mut self, msg: TcpMsg) {
+        let stream = match self.stream.as_mut() {
+            Some(stream) => stream,
+            None => return self.pending.push(msg),
+        };
+
+        // the reader sees the shutdown, sends `Closed` and stops the actor
+        match msg {
+            TcpMsg::Write(bytes) => {
+                if stream.write_all(&bytes).is_err() {
+                    let _ = stream.shutdown(Shutdown::Both);
+                }
+            }
+            TcpMsg::Close => {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+            TcpMsg::Opened(_) => {}
+        }
+    }
+}
+
+impl Actor for TcpConnectionActor {
+    type Msg = TcpMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        // a restarted connection has nothing left to open
+        match self.conn.take() {
+            Some(Connection::Connect(addr)) => {
+                // connecting blocks, so it's done off the dispatcher
+                let myself = ctx.myself();
+                spawn(&ctx.system, move || {
+                    let opened = TcpOpened(shared(open(Connection::Connect(addr))));
+                    myself.tell(TcpMsg::Opened(opened), None);
+                });
+            }
+            Some(conn) => self.opened(ctx, open(conn)),
+            None => {}
+        }
+    }
+
+    fn post_stop(&mut self) {
+        if let Some(stream) = self.stream.as_ref() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    // the reader keeps running, the next instance takes over the stream
+    fn export_state(&mut self) -> Option<StateBlob> {
+        let state = (self.stream.take(), std::mem::take(&mut self.pending));
+        Some(StateBlob::new(state))
+    }
+
+    fn import_state(&mut self, state: StateBlob) {
+        if let Ok((stream, pending)) = state.downcast() {
+            self.stream = stream;
+            self.pending = pending;
+        }
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        match msg {
+            TcpMsg::Opened(opened) => {
+                if let Some(opened) = opened.0.lock().unwrap().take() {
+                    self.opened(ctx, opened);
+                }
+            }
+            msg => self.write(msg),
+        }
+    }
+}
+
+fn read(
+    mut reader: TcpStream,
+    myself: ActorRef<TcpMsg>,
+    handler: BoxedTell<TcpEvent>,
+    sys: ActorSystem,
+) {
+    let mut buf = vec![0; READ_BUFFER];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                let received = TcpEvent::Received {
+                    conn: myself.clone(),
+                    bytes: buf[..n].to_vec(),
+                };
+                handler.tell(received, Some(myself.clone().into()));
+            }
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(_) => break,
+        }
+    }
+
+    let closed = TcpEvent::Closed {
+        conn: myself.clone(),
+    };
+    handler.tell(closed, Some(myself.clone().into()));
+    sys.stop(&myself);
+}
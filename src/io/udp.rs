@@ -0,0 +1,196 @@
+use std::{
+    io::ErrorKind,
+    net::{SocketAddr, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+
+use crate::{
+    actor::{
+        Actor, ActorFactoryArgs, ActorRef, ActorRefFactory, BoxedTell, Context, CreateError, Sender,
+    },
+    io::{handler, spawn, Handler, POLL},
+    system::FailureInfo,
+};
+
+// Large enough for any UDP payload
+const MAX_DATAGRAM: usize = 64 * 1024;
+
+/// Sent to a handler by a `UdpActor`
+#[derive(Clone, Debug)]
+pub enum UdpEvent {
+    /// The socket is bound to this address
+    Bound(SocketAddr),
+
+    /// Binding to `addr` failed, the actor stopped
+    Failed { addr: SocketAddr, error: String },
+
+    /// A datagram received from `peer`
+    Datagram { peer: SocketAddr, bytes: Vec<u8> },
+}
+
+/// Send a datagram from a `UdpActor` to `peer`
+#[derive(Clone, Debug)]
+pub struct SendTo {
+    pub peer: SocketAddr,
+    pub bytes: Vec<u8>,
+}
+
+/// A bound UDP socket
+///
+/// The handler is sent `UdpEvent::Bound`, then a `UdpEvent::Datagram` for
+/// each datagram received. Stopping the actor closes the socket, a restarted
+/// actor closes it before binding `addr` again.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::io::{SendTo, UdpActor, UdpEvent};
+/// # use std::time::Duration;
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let inbox = Inbox::<UdpEvent>::new(&sys).unwrap();
+///
+/// let addr = "127.0.0.1:0".parse().unwrap();
+/// let udp = UdpActor::bind(&sys, "udp", addr, Box::new(inbox.sender_ref())).unwrap();
+///
+/// let addr = match inbox.receive(Duration::from_secs(1)) {
+///     Some(UdpEvent::Bound(addr)) => addr,
+///     other => panic!("not bound: {:?}", other),
+/// };
+///
+/// // send a datagram to ourselves
+/// udp.tell(SendTo { peer: addr, bytes: b"beacon".to_vec() }, None);
+/// match inbox.receive(Duration::from_secs(1)) {
+///     Some(UdpEvent::Datagram { bytes, .. }) => assert_eq!(bytes, b"beacon"),
+///     other => panic!("no datagram: {:?}", other),
+/// }
+/// ```
+pub struct UdpActor {
+    addr: SocketAddr,
+    handler: Handler<UdpEvent>,
+    socket: Option<UdpSocket>,
+    open: Arc<AtomicBool>,
+    reading: Option<JoinHandle<()>>,
+}
+
+impl UdpActor {
+    /// Start a socket bound to `addr`, an address with port `0` binds to any free port
+    pub fn bind(
+        factory: &impl ActorRefFactory,
+        name: &str,
+        addr: SocketAddr,
+        events: BoxedTell<UdpEvent>,
+    ) -> Result<ActorRef<SendTo>, CreateError> {
+        factory.actor_of_args::<UdpActor, _>(name, (addr, handler(events)))
+    }
+}
+
+impl ActorFactoryArgs<(SocketAddr, Handler<UdpEvent>)> for UdpActor {
+    fn create_args((addr, handler): (SocketAddr, Handler<UdpEvent>)) -> Self {
+        UdpActor {
+            addr,
+            handler,
+            socket: None,
+            open: Arc::new(AtomicBool::new(true)),
+            reading: None,
+        }
+    }
+}
+
+impl UdpActor {
+    // Stop the reader and close the socket
+    fn close(&mut self) {
+        self.open.store(false, Ordering::Relaxed);
+        self.socket = None;
+        if let Some(reading) = self.reading.take() {
+            let _ = reading.join();
+        }
+    }
+}
+
+impl Actor for UdpActor {
+    type Msg = SendTo;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let handler = self.handler.lock().unwrap().clone();
+
+        // reads time out so that the reader sees when the actor stops
+        let sockets = UdpSocket::bind(self.addr).and_then(|socket| {
+            socket.set_read_timeout(Some(POLL))?;
+            let reader = socket.try_clone()?;
+            Ok((socket, reader))
+        });
+
+        let (socket, reader) = match sockets {
+            Ok(sockets) => sockets,
+            Err(e) => {
+                let failed = UdpEvent::Failed {
+                    addr: self.addr,
+                    error: e.to_string(),
+                };
+                handler.tell(failed, None);
+                ctx.stop(ctx.myself());
+                return;
+            }
+        };
+
+        let addr = socket.local_addr().unwrap_or(self.addr);
+        handler.tell(UdpEvent::Bound(addr), Some(ctx.myself().into()));
+
+        let myself = ctx.myself();
+        let open = self.open.clone();
+        self.reading = Some(spawn(&ctx.system, move || {
+            read(reader, myself, handler, open)
+        }));
+
+        self.socket = Some(socket);
+    }
+
+    fn post_stop(&mut self) {
+        self.close();
+    }
+
+    fn pre_restart(&mut self, _: &Context<Self::Msg>, _: Option<FailureInfo>) {
+        self.close();
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        // datagrams are unreliable, a failed send is dropped like a lost one
+        if let Some(socket) = self.socket.as_ref() {
+            let _ = socket.send_to(&msg.bytes, msg.peer);
+        }
+    }
+}
+
+fn read(
+    reader: UdpSocket,
+    myself: ActorRef<SendTo>,
+    handler: BoxedTell<UdpEvent>,
+    open: Arc<AtomicBool>,
+) {
+    let mut buf = vec![0; MAX_DATAGRAM];
+    while open.load(Ordering::Relaxed) {
+        match reader.recv_from(&mut buf) {
+            Ok((n, peer)) => {
+                let datagram = UdpEvent::Datagram {
+                    peer,
+                    bytes: buf[..n].to_vec(),
+                };
+                handler.tell(datagram, Some(myself.clone().into()));
+            }
+
+            // read timed out, or an earlier send was refused by its peer
+            Err(ref e)
+                if e.kind() == ErrorKind::WouldBlock
+                    || e.kind() == ErrorKind::TimedOut
+                    || e.kind() == ErrorKind::Interrupted
+                    || e.kind() == ErrorKind::ConnectionRefused => {}
+            Err(_) => break,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+mod dispatcher;
 pub(crate) mod kernel_ref;
 pub(crate) mod mailbox;
 pub(crate) mod provider;
@@ -5,6 +6,8 @@ pub(crate) mod queue;
 
 use crate::system::ActorSystem;
 
+pub use self::dispatcher::{Dispatcher, DispatcherRef};
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum KernelMsg {
@@ -14,27 +17,90 @@ pub enum KernelMsg {
     Sys(ActorSystem),
 }
 use std::{
+    cell::RefCell,
+    future::Future,
     panic::{catch_unwind, AssertUnwindSafe},
+    pin::Pin,
     sync::{Arc, Mutex},
+    task::{Context as TaskContext, Poll},
 };
 
-use futures::{channel::mpsc::channel, task::SpawnExt, StreamExt};
+use futures::{channel::mpsc::channel, FutureExt, StreamExt};
 use slog::warn;
 
 use crate::{
-    actor::actor_cell::ExtendedCell,
+    actor::actor_cell::{panic_message, ExtendedCell},
+    actor::interceptor::Interceptors,
     actor::*,
     kernel::{
         kernel_ref::KernelRef,
         mailbox::{flush_to_deadletters, run_mailbox, Mailbox},
     },
-    system::{ActorRestarted, ActorTerminated, SystemMsg},
+    metrics,
+    system::{ActorRestarted, ActorTerminated, FailureInfo, SystemCmd, SystemMsg, Timer},
     Message,
 };
 
+/// How an actor shares its executor's threads, set by `dispatcher.fairness`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fairness {
+    /// Yield the thread after each mailbox run, so that an actor with
+    /// queued messages runs again only after other scheduled actors
+    /// have had a turn (`"round_robin"`)
+    RoundRobin,
+
+    /// Keep the thread while the actor has queued messages (`"greedy"`)
+    Greedy,
+}
+
+impl Fairness {
+    /// Parse a `dispatcher.fairness` value
+    pub(crate) fn parse(fairness: &str) -> Option<Self> {
+        match fairness {
+            "round_robin" => Some(Fairness::RoundRobin),
+            "greedy" => Some(Fairness::Greedy),
+            _ => None,
+        }
+    }
+}
+
+// Returns `Pending` once, moving the task to the back of the executor's queue.
+// A task waking itself while polled is polled again at once by the
+// `ThreadPool`, so the task is woken from a task spawned behind the others.
+struct YieldNow<'a> {
+    exec: &'a dyn Dispatcher,
+    yielded: bool,
+}
+
+impl Future for YieldNow<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+
+        let waker = cx.waker().clone();
+        if self
+            .exec
+            .execute(async move { waker.wake() }.boxed())
+            .is_err()
+        {
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+}
+
 pub struct Dock<A: Actor> {
     pub actor: Arc<Mutex<Option<A>>>,
     pub cell: ExtendedCell<A::Msg>,
+
+    // The instance that panicked, kept for `Actor::pre_restart`
+    pub(crate) failed: Arc<Mutex<Option<A>>>,
+
+    pub(crate) interceptors: Interceptors<A::Msg>,
 }
 
 impl<A: Actor> Clone for Dock<A> {
@@ -42,6 +108,8 @@ impl<A: Actor> Clone for Dock<A> {
         Dock {
             actor: self.actor.clone(),
             cell: self.cell.clone(),
+            failed: self.failed.clone(),
+            interceptors: self.interceptors.clone(),
         }
     }
 }
@@ -55,10 +123,20 @@ pub fn kernel<A>(
 where
     A: Actor + 'static,
 {
+    let dispatcher = props.lock().unwrap().dispatcher();
+    let exec = match dispatcher {
+        Some(name) => sys
+            .dispatcher(&name)
+            .ok_or(CreateError::UnknownDispatcher(name))?,
+        None => sys.default_dispatcher(),
+    };
+
+    let fairness = sys.sys_settings().fairness;
     let (tx, mut rx) = channel::<KernelMsg>(1000); // todo config?
     let kr = KernelRef { tx };
 
     let mut asys = sys.clone();
+    let aexec = exec.clone();
     let akr = kr.clone();
     let actor = start_actor(&props)?;
     let cell = cell.init(&kr);
@@ -66,8 +144,14 @@ where
     let mut dock = Dock {
         actor: Arc::new(Mutex::new(Some(actor))),
         cell: cell.clone(),
+        failed: Arc::new(Mutex::new(None)),
+        interceptors: props.interceptors().into(),
     };
 
+    if let Some(idle) = props.passivate_after() {
+        cell.set_passivation(idle);
+    }
+
     let actor_ref = ActorRef::new(cell);
 
     let f = async move {
@@ -78,14 +162,48 @@ where
                         myself: actor_ref.clone(),
                         system: asys.clone(),
                         kernel: akr.clone(),
+                        sender: RefCell::new(None),
                     };
 
-                    let _ = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                    // a passivated actor is recreated when a message arrives
+                    if mailbox.has_msgs() && dock.cell.reactivate() {
+                        reactivate_actor(&dock, &ctx, &props);
+                    }
+
+                    let run = std::panic::catch_unwind(AssertUnwindSafe(|| {
                         run_mailbox(&mailbox, ctx, &mut dock)
-                    })); //.unwrap();
+                    }));
+
+                    // Message the parent (this failed actor's supervisor)
+                    // to decide how to handle the failure
+                    if let Err(panic) = run {
+                        // a panic before `post_start` has returned fails starting
+                        dock.cell.set_started(Err(CreateError::Panicked));
+                        let info = FailureInfo {
+                            actor: actor_ref.clone().into(),
+                            panic_msg: panic_message(&*panic),
+                            restart_count: dock.cell.restart_count(),
+                        };
+                        actor_ref.parent().sys_tell(SystemMsg::Failed(info));
+                    }
+
+                    if fairness == Fairness::RoundRobin {
+                        YieldNow {
+                            exec: &*aexec,
+                            yielded: false,
+                        }
+                        .await;
+                    }
                 }
                 KernelMsg::RestartActor => {
-                    restart_actor(&dock, actor_ref.clone().into(), &props, &asys);
+                    let ctx = Context {
+                        myself: actor_ref.clone(),
+                        system: asys.clone(),
+                        kernel: akr.clone(),
+                        sender: RefCell::new(None),
+                    };
+
+                    restart_actor(&dock, &ctx, &props);
                 }
                 KernelMsg::TerminateActor => {
                     terminate_actor(&mailbox, actor_ref.clone().into(), &asys);
@@ -98,21 +216,51 @@ where
         }
     };
 
-    sys.exec.spawn(f).unwrap();
+    exec.execute(f.boxed()).unwrap();
     Ok(kr)
 }
 
-fn restart_actor<A>(
-    dock: &Dock<A>,
-    actor_ref: BasicActorRef,
-    props: &BoxActorProd<A>,
-    sys: &ActorSystem,
-) where
+fn restart_actor<A>(dock: &Dock<A>, ctx: &Context<A::Msg>, props: &BoxActorProd<A>)
+where
     A: Actor,
 {
+    let actor_ref: BasicActorRef = ctx.myself().into();
+    let sys = &ctx.system;
+
+    // the new instance replaces a passivated one too
+    dock.cell.reactivate();
+
     let mut a = dock.actor.lock().unwrap();
+    let reason = dock.cell.take_restart_reason();
+    let old = a.take().or_else(|| dock.failed.lock().unwrap().take());
+    let mut state = None;
+    if let Some(mut old) = old {
+        match catch_unwind(AssertUnwindSafe(|| old.export_state())) {
+            Ok(exported) => state = exported,
+            Err(_) => warn!(sys.log(), "Actor panicked in export_state: {:?}", actor_ref),
+        }
+
+        let restarting = catch_unwind(AssertUnwindSafe(|| old.pre_restart(ctx, reason)));
+        if restarting.is_err() {
+            warn!(sys.log(), "Actor panicked in pre_restart: {:?}", actor_ref);
+        }
+    }
+
     match start_actor(props) {
-        Ok(actor) => {
+        Ok(mut actor) => {
+            if let Some(state) = state {
+                let importing = catch_unwind(AssertUnwindSafe(|| actor.import_state(state)));
+                if importing.is_err() {
+                    warn!(sys.log(), "Actor panicked in import_state: {:?}", actor_ref);
+                    // start cold rather than with partly imported state
+                    if let Ok(fresh) = start_actor(props) {
+                        actor = fresh;
+                    }
+                }
+            }
+
+            metrics::actor_restarted(actor_ref.path());
+            dock.cell.restarted();
             *a = Some(actor);
             actor_ref.sys_tell(SystemMsg::ActorInit);
             sys.publish_event(ActorRestarted { actor: actor_ref }.into());
@@ -123,12 +271,34 @@ fn restart_actor<A>(
     }
 }
 
+fn reactivate_actor<A>(dock: &Dock<A>, ctx: &Context<A::Msg>, props: &BoxActorProd<A>)
+where
+    A: Actor,
+{
+    match start_actor(props) {
+        Ok(actor) => {
+            *dock.actor.lock().unwrap() = Some(actor);
+            ctx.myself.sys_tell(SystemMsg::ActorInit);
+        }
+        Err(_) => {
+            // stop rather than leave the messages waiting for an instance
+            let sys = &ctx.system;
+            warn!(sys.log(), "Actor failed to reactivate: {:?}", ctx.myself);
+            ctx.myself.sys_tell(SystemCmd::Stop.into());
+        }
+    }
+}
+
 fn terminate_actor<Msg>(mbox: &Mailbox<Msg>, actor_ref: BasicActorRef, sys: &ActorSystem)
 where
     Msg: Message,
 {
     sys.provider.unregister(actor_ref.path());
+    actor_ref.cell.set_started(Err(CreateError::System));
     flush_to_deadletters(mbox, &actor_ref, sys);
+    if actor_ref.cell.has_schedules() {
+        sys.cancel_for(actor_ref.path());
+    }
     sys.publish_event(
         ActorTerminated {
             actor: actor_ref.clone(),
@@ -136,9 +306,22 @@ where
         .into(),
     );
 
-    let parent = actor_ref.parent();
-    if !parent.is_root() {
-        parent.sys_tell(ActorTerminated { actor: actor_ref }.into());
+    // the root and the guardians are terminated with an initial system
+    // without the system actors, once the system has shut down
+    if actor_ref.path() == "/user" {
+        sys.terminated();
+    }
+
+    // the root is the last actor to stop
+    if actor_ref.path() == "/" {
+        sys.shutdown_dispatchers();
+    }
+
+    // the root's parent is a placeholder without a mailbox
+    if actor_ref.path() != "/" {
+        actor_ref
+            .parent()
+            .sys_tell(ActorTerminated { actor: actor_ref }.into());
     }
 }
 
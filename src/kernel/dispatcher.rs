@@ -0,0 +1,76 @@
+use std::{sync::Arc, time::Duration};
+
+use futures::{
+    executor::ThreadPool,
+    future::BoxFuture,
+    task::{SpawnError, SpawnExt},
+};
+
+use crate::metrics;
+
+/// Runs the actors' kernels, see `SystemBuilder::dispatcher`
+///
+/// Each actor runs as a task that is woken when its mailbox is scheduled.
+/// The default dispatcher is a `futures` `ThreadPool`. Implement
+/// `Dispatcher` to run actors on another executor, e.g. rayon, glommio
+/// or a single threaded executor on wasm.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::kernel::Dispatcher;
+/// # use futures::{executor::ThreadPool, future::BoxFuture, task::{SpawnError, SpawnExt}};
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// # use std::sync::Arc;
+///
+/// // A thread pool counting the tasks it executes
+/// struct Counting {
+///     pool: ThreadPool,
+///     tasks: Arc<AtomicUsize>,
+/// }
+///
+/// impl Dispatcher for Counting {
+///     fn execute(&self, task: BoxFuture<'static, ()>) -> Result<(), SpawnError> {
+///         self.tasks.fetch_add(1, Ordering::Relaxed);
+///         self.pool.spawn(task)
+///     }
+/// }
+///
+/// let tasks = Arc::new(AtomicUsize::new(0));
+/// let dispatcher = Counting {
+///     pool: ThreadPool::new().unwrap(),
+///     tasks: tasks.clone(),
+/// };
+///
+/// let sys = SystemBuilder::new().dispatcher(dispatcher).create().unwrap();
+/// assert!(tasks.load(Ordering::Relaxed) > 0);
+/// ```
+pub trait Dispatcher: Send + Sync + 'static {
+    /// Run `task` to completion
+    ///
+    /// `task` must not be polled to completion on the calling thread:
+    /// an actor's task runs for as long as the actor.
+    fn execute(&self, task: BoxFuture<'static, ()>) -> Result<(), SpawnError>;
+
+    /// Invoked once the system has shut down and every actor has stopped
+    ///
+    /// Tasks that are still running, such as the task calling `shutdown`,
+    /// are allowed to complete.
+    fn shutdown(&self) {}
+
+    /// Invoked with the time a kernel message waited before it ran
+    ///
+    /// The default records `riker_executor_queue_lag_seconds`.
+    fn task_lag(&self, lag: Duration) {
+        metrics::executor_lag(lag);
+    }
+}
+
+pub type DispatcherRef = Arc<dyn Dispatcher>;
+
+impl Dispatcher for ThreadPool {
+    fn execute(&self, task: BoxFuture<'static, ()>) -> Result<(), SpawnError> {
+        self.spawn(task)
+    }
+}
@@ -1,6 +1,6 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
-use futures::{channel::mpsc::Sender, task::SpawnExt, SinkExt};
+use futures::{channel::mpsc::Sender, FutureExt, SinkExt};
 
 use crate::{
     actor::{MsgError, MsgResult},
@@ -36,10 +36,16 @@ impl KernelRef {
 
     fn send(&self, msg: KernelMsg, sys: &ActorSystem) {
         let mut tx = self.tx.clone();
-        sys.exec
-            .spawn(async move {
-                drop(tx.send(msg).await);
-            })
+        let scheduled_at = Instant::now();
+        let dispatcher = sys.default_dispatcher().clone();
+        sys.default_dispatcher()
+            .execute(
+                async move {
+                    dispatcher.task_lag(scheduled_at.elapsed());
+                    drop(tx.send(msg).await);
+                }
+                .boxed(),
+            )
             .unwrap();
     }
 }
@@ -80,6 +86,3 @@ pub fn dispatch_any(
         }
     })
 }
-
-unsafe impl Send for KernelRef {}
-unsafe impl Sync for KernelRef {}
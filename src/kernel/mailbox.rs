@@ -1,20 +1,27 @@
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+    atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    Arc, Mutex,
 };
 use std::thread;
-
-use config::Config;
+use std::time::{Duration, Instant};
 
 use crate::{
     actor::actor_cell::ExtendedCell,
+    actor::interceptor::Interceptors,
     actor::*,
     kernel::{
-        queue::{queue, EnqueueResult, QueueEmpty, QueueReader, QueueWriter},
+        queue::{
+            priority_queue, queue, EnqueueResult, QueueEmpty, QueueKind, QueueReader, QueueWriter,
+        },
         Dock,
     },
-    system::ActorCreated,
-    system::{ActorSystem, SystemEvent, SystemMsg},
+    metrics,
+    system::{ActorCreated, ActorFailed},
+    system::{
+        ActorSystem, FailureInfo, ReceiveTimeout, SystemCmd, SystemEvent, SystemMsg, TaskFailed,
+        TaskFailure,
+    },
     AnyMessage, Envelope, Message,
 };
 
@@ -39,12 +46,45 @@ pub trait AnySender: Send + Sync {
     fn set_sched(&self, b: bool);
 
     fn is_sched(&self) -> bool;
+
+    /// Number of messages currently waiting in the mailbox
+    fn queued(&self) -> usize;
+
+    /// Number of messages ever added to the mailbox
+    fn enqueued(&self) -> u64;
+
+    /// Number of messages ever taken from the mailbox
+    fn dequeued(&self) -> u64;
+}
+
+// Set while the actor awaits a future started with `Context::continue_with`.
+// No messages are processed until the future's output is resumed with.
+struct Continuation<Msg: Message> {
+    awaiting: AtomicBool,
+    resumed: Mutex<Option<Envelope<Msg>>>,
+}
+
+impl<Msg: Message> Continuation<Msg> {
+    fn new() -> Self {
+        Continuation {
+            awaiting: AtomicBool::new(false),
+            resumed: Mutex::new(None),
+        }
+    }
 }
 
+// The queue and the messages routed to it, ahead of the main queue
+type ControlQueue<Msg> = (QueueWriter<Msg>, fn(&Msg) -> bool);
+
 #[derive(Clone)]
 pub struct MailboxSender<Msg: Message> {
     queue: QueueWriter<Msg>,
+    control: Option<ControlQueue<Msg>>,
     scheduled: Arc<AtomicBool>,
+    queued: Arc<AtomicUsize>,
+    enqueued: Arc<AtomicU64>,
+    dequeued: Arc<AtomicU64>,
+    continuation: Arc<Continuation<Msg>>,
 }
 
 impl<Msg> MailboxSender<Msg>
@@ -52,7 +92,30 @@ where
     Msg: Message,
 {
     pub fn try_enqueue(&self, msg: Envelope<Msg>) -> EnqueueResult<Msg> {
-        self.queue.try_enqueue(msg)
+        // count before enqueuing so a concurrent dequeue can't underflow
+        self.queued.fetch_add(1, Ordering::Relaxed);
+        let queue = match self.control {
+            Some((ref control, is_control)) if is_control(&msg.msg) => control,
+            _ => &self.queue,
+        };
+        queue
+            .try_enqueue(msg)
+            .inspect(|_| {
+                self.enqueued.fetch_add(1, Ordering::Relaxed);
+            })
+            .inspect_err(|_| {
+                self.queued.fetch_sub(1, Ordering::Relaxed);
+            })
+    }
+
+    pub(crate) fn set_awaiting(&self) {
+        self.continuation.awaiting.store(true, Ordering::Relaxed);
+    }
+
+    /// Stop awaiting, resuming with `msg` before any queued message
+    pub(crate) fn resume(&self, msg: Option<Envelope<Msg>>) {
+        *self.continuation.resumed.lock().unwrap() = msg;
+        self.continuation.awaiting.store(false, Ordering::Relaxed);
     }
 }
 
@@ -89,10 +152,19 @@ where
     fn is_sched(&self) -> bool {
         self.is_scheduled()
     }
-}
 
-unsafe impl<Msg: Message> Send for MailboxSender<Msg> {}
-unsafe impl<Msg: Message> Sync for MailboxSender<Msg> {}
+    fn queued(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    fn enqueued(&self) -> u64 {
+        self.enqueued.load(Ordering::Relaxed)
+    }
+
+    fn dequeued(&self) -> u64 {
+        self.dequeued.load(Ordering::Relaxed)
+    }
+}
 
 #[derive(Clone)]
 pub struct Mailbox<Msg: Message> {
@@ -100,25 +172,45 @@ pub struct Mailbox<Msg: Message> {
 }
 
 pub struct MailboxInner<Msg: Message> {
-    msg_process_limit: u32,
+    limit: ProcessLimiter,
     queue: QueueReader<Msg>,
     sys_queue: QueueReader<SystemMsg>,
+    control_queue: QueueReader<SystemMsg>,
     suspended: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
     scheduled: Arc<AtomicBool>,
+    queued: Arc<AtomicUsize>,
+    dequeued: Arc<AtomicU64>,
+    continuation: Arc<Continuation<Msg>>,
 }
 
 impl<Msg: Message> Mailbox<Msg> {
-    #[allow(dead_code)]
-    pub fn dequeue(&self) -> Envelope<Msg> {
-        self.inner.queue.dequeue()
+    /// Stop accepting user messages, returning those still queued
+    pub fn close(&self) -> Vec<Envelope<Msg>> {
+        let msgs = self.inner.queue.close();
+        let count = msgs.len();
+        self.inner.queued.fetch_sub(count, Ordering::Relaxed);
+        self.inner
+            .dequeued
+            .fetch_add(count as u64, Ordering::Relaxed);
+        msgs
     }
 
     pub fn try_dequeue(&self) -> Result<Envelope<Msg>, QueueEmpty> {
-        self.inner.queue.try_dequeue()
+        self.inner.queue.try_dequeue().inspect(|_| {
+            self.inner.queued.fetch_sub(1, Ordering::Relaxed);
+            self.inner.dequeued.fetch_add(1, Ordering::Relaxed);
+        })
     }
 
+    /// Dequeue the next system message, control messages first
     pub fn sys_try_dequeue(&self) -> Result<Envelope<SystemMsg>, QueueEmpty> {
-        self.inner.sys_queue.try_dequeue()
+        self.control_try_dequeue()
+            .or_else(|_| self.inner.sys_queue.try_dequeue())
+    }
+
+    fn control_try_dequeue(&self) -> Result<Envelope<SystemMsg>, QueueEmpty> {
+        self.inner.control_queue.try_dequeue()
     }
 
     pub fn has_msgs(&self) -> bool {
@@ -126,7 +218,7 @@ impl<Msg: Message> Mailbox<Msg> {
     }
 
     pub fn has_sys_msgs(&self) -> bool {
-        self.inner.sys_queue.has_msgs()
+        self.inner.control_queue.has_msgs() || self.inner.sys_queue.has_msgs()
     }
 
     pub fn set_suspended(&self, b: bool) {
@@ -137,8 +229,78 @@ impl<Msg: Message> Mailbox<Msg> {
         self.inner.suspended.load(Ordering::Relaxed)
     }
 
+    // Set by `SystemCmd::Suspend`, unlike `suspended` only the user can resume
+    fn set_paused(&self, b: bool) {
+        self.inner.paused.store(b, Ordering::Relaxed);
+    }
+
+    fn is_paused(&self) -> bool {
+        self.inner.paused.load(Ordering::Relaxed)
+    }
+
+    fn is_awaiting(&self) -> bool {
+        self.inner.continuation.awaiting.load(Ordering::Relaxed)
+    }
+
+    fn has_resumed(&self) -> bool {
+        self.inner.continuation.resumed.lock().unwrap().is_some()
+    }
+
+    fn take_resumed(&self) -> Option<Envelope<Msg>> {
+        self.inner.continuation.resumed.lock().unwrap().take()
+    }
+
     fn msg_process_limit(&self) -> u32 {
-        self.inner.msg_process_limit
+        self.inner.limit.current()
+    }
+}
+
+// The number of messages processed each run, with `MsgProcessLimit::Adaptive`
+// derived from the time the actor takes per message
+struct ProcessLimiter {
+    limit: MsgProcessLimit,
+
+    // average nanoseconds per message, 0 until a run is measured
+    per_msg: AtomicU64,
+}
+
+impl ProcessLimiter {
+    fn new(limit: MsgProcessLimit) -> Self {
+        ProcessLimiter {
+            limit,
+            per_msg: AtomicU64::new(0),
+        }
+    }
+
+    fn current(&self) -> u32 {
+        match self.limit {
+            MsgProcessLimit::Fixed(limit) => limit.max(1),
+            MsgProcessLimit::Adaptive { max, budget } => {
+                let max = max.max(1);
+                match self.per_msg.load(Ordering::Relaxed) {
+                    0 => max,
+                    per_msg => (budget.as_nanos() as u64 / per_msg).clamp(1, max as u64) as u32,
+                }
+            }
+        }
+    }
+
+    fn is_adaptive(&self) -> bool {
+        matches!(self.limit, MsgProcessLimit::Adaptive { .. })
+    }
+
+    // Updates the average, weighting the latest run by a quarter
+    fn record(&self, count: usize, elapsed: Duration) {
+        if count == 0 || !self.is_adaptive() {
+            return;
+        }
+
+        let sample = (elapsed.as_nanos() as u64 / count as u64).max(1);
+        let avg = match self.per_msg.load(Ordering::Relaxed) {
+            0 => sample,
+            avg => (avg * 3 + sample) / 4,
+        };
+        self.per_msg.store(avg.max(1), Ordering::Relaxed);
     }
 }
 
@@ -156,32 +318,57 @@ where
 }
 
 pub fn mailbox<Msg>(
-    msg_process_limit: u32,
+    msg_process_limit: MsgProcessLimit,
+    kind: QueueKind,
+    priority: Option<MsgPriority<Msg>>,
+    capacity: usize,
 ) -> (MailboxSender<Msg>, MailboxSender<SystemMsg>, Mailbox<Msg>)
 where
     Msg: Message,
 {
-    let (qw, qr) = queue::<Msg>();
-    let (sqw, sqr) = queue::<SystemMsg>();
+    let (qw, qr) = match priority {
+        Some(priority) => priority_queue(kind, priority, capacity),
+        None => queue::<Msg>(kind),
+    };
+    let (sqw, sqr) = queue::<SystemMsg>(QueueKind::Channel);
+    let (cqw, cqr) = queue::<SystemMsg>(QueueKind::Channel);
 
     let scheduled = Arc::new(AtomicBool::new(false));
+    let queued = Arc::new(AtomicUsize::new(0));
+    let dequeued = Arc::new(AtomicU64::new(0));
+    let continuation = Arc::new(Continuation::new());
 
     let sender = MailboxSender {
         queue: qw,
+        control: None,
         scheduled: scheduled.clone(),
+        queued: queued.clone(),
+        enqueued: Arc::new(AtomicU64::new(0)),
+        dequeued: dequeued.clone(),
+        continuation: continuation.clone(),
     };
 
     let sys_sender = MailboxSender {
         queue: sqw,
+        control: Some((cqw, is_control as fn(&SystemMsg) -> bool)),
         scheduled: scheduled.clone(),
+        queued: Arc::new(AtomicUsize::new(0)),
+        enqueued: Arc::new(AtomicU64::new(0)),
+        dequeued: Arc::new(AtomicU64::new(0)),
+        continuation: Arc::new(Continuation::new()),
     };
 
     let mailbox = MailboxInner {
-        msg_process_limit,
+        limit: ProcessLimiter::new(msg_process_limit),
         queue: qr,
         sys_queue: sqr,
+        control_queue: cqr,
         suspended: Arc::new(AtomicBool::new(true)),
+        paused: Arc::new(AtomicBool::new(false)),
         scheduled,
+        queued,
+        dequeued,
+        continuation,
     };
 
     let mailbox = Mailbox {
@@ -191,26 +378,45 @@ where
     (sender, sys_sender, mailbox)
 }
 
+// Supervision and lifecycle messages are queued separately from events,
+// so that a failure isn't delayed by a backlog of events.
+// `ActorInit` is a control message so that commands can't overtake it.
+fn is_control(msg: &SystemMsg) -> bool {
+    matches!(
+        msg,
+        SystemMsg::ActorInit | SystemMsg::Command(_) | SystemMsg::Failed(_)
+    )
+}
+
 pub fn run_mailbox<A>(mbox: &Mailbox<A::Msg>, ctx: Context<A::Msg>, dock: &mut Dock<A>)
 where
     A: Actor,
 {
-    let sen = Sentinel {
-        actor: ctx.myself().into(),
-        parent: ctx.myself().parent(),
-        mbox,
-    };
+    let sen = Sentinel { mbox };
 
     let mut actor = dock.actor.lock().unwrap().take();
     let cell = &mut dock.cell;
+    let interceptors = &dock.interceptors;
 
-    process_sys_msgs(&sen.mbox, &ctx, cell, &mut actor);
+    let run = panic::catch_unwind(AssertUnwindSafe(|| {
+        process_sys_msgs(sen.mbox, &ctx, cell, &mut actor);
 
-    if actor.is_some() && !sen.mbox.is_suspended() {
-        process_msgs(&sen.mbox, &ctx, cell, &mut actor);
-    }
+        if actor.is_some() && !sen.mbox.is_suspended() && !sen.mbox.is_paused() {
+            if actor.as_ref().unwrap().receives_batches() {
+                process_batch(sen.mbox, &ctx, cell, interceptors, &mut actor);
+            } else {
+                process_msgs(sen.mbox, &ctx, cell, interceptors, &mut actor);
+            }
+        }
+
+        process_sys_msgs(sen.mbox, &ctx, cell, &mut actor);
+    }));
 
-    process_sys_msgs(&sen.mbox, &ctx, cell, &mut actor);
+    if let Err(panic) = run {
+        // keep the failed instance for `Actor::pre_restart`
+        *dock.failed.lock().unwrap() = actor.take();
+        panic::resume_unwind(panic);
+    }
 
     if actor.is_some() {
         let mut a = dock.actor.lock().unwrap();
@@ -219,7 +425,10 @@ where
 
     sen.mbox.set_scheduled(false);
 
-    let has_msgs = sen.mbox.has_msgs() || sen.mbox.has_sys_msgs();
+    // queued messages wait while the actor awaits a continuation or is paused
+    let has_msgs = (sen.mbox.has_msgs() && !sen.mbox.is_awaiting() && !sen.mbox.is_paused())
+        || sen.mbox.has_resumed()
+        || sen.mbox.has_sys_msgs();
     if has_msgs && !sen.mbox.is_scheduled() {
         ctx.kernel.schedule(&ctx.system);
     }
@@ -229,29 +438,127 @@ fn process_msgs<A>(
     mbox: &Mailbox<A::Msg>,
     ctx: &Context<A::Msg>,
     cell: &ExtendedCell<A::Msg>,
+    interceptors: &Interceptors<A::Msg>,
     actor: &mut Option<A>,
 ) where
     A: Actor,
 {
+    let limit = mbox.msg_process_limit();
+    let started = Instant::now();
     let mut count = 0;
 
-    loop {
-        if count < mbox.msg_process_limit() {
-            match mbox.try_dequeue() {
-                Ok(msg) => {
-                    let (msg, sender) = (msg.msg, msg.sender);
-                    actor.as_mut().unwrap().recv(ctx, msg, sender);
-                    process_sys_msgs(&mbox, &ctx, cell, actor);
+    while count < limit && !mbox.is_awaiting() {
+        // a resumed continuation is processed ahead of queued messages
+        let (msg, queued) = match mbox.take_resumed() {
+            Some(msg) => (msg, false),
+            None => match mbox.try_dequeue() {
+                Ok(msg) => (msg, true),
+                Err(_) => break,
+            },
+        };
+        if queued {
+            cell.wake_sinks();
+        }
 
-                    count += 1;
-                }
-                Err(_) => {
-                    break;
-                }
+        let (msg, sender) = (msg.msg, msg.sender);
+        let a = actor.as_mut().unwrap();
+        let mut recv = |ctx: &Context<A::Msg>, msg, sender: Sender| {
+            ctx.sender.replace(sender.clone());
+            a.recv(ctx, msg, sender);
+            ctx.sender.replace(None);
+        };
+        Next::new(interceptors, &mut recv).run(ctx, msg, sender);
+        cell.received(1);
+
+        // a resumed continuation was never enqueued, nor counted by the pill
+        if queued {
+            metrics::msg_processed(ctx.myself.path());
+            if let Some(governor) = ctx.system.governor() {
+                governor.dequeued();
             }
-        } else {
+        }
+
+        if queued && cell.poison_pill_due() {
+            // Messages enqueued after the poison pill are not processed.
+            // They're flushed to dead letters when the actor terminates.
+            mbox.set_suspended(true);
+            cell.terminate(actor);
             break;
         }
+
+        process_sys_msgs(mbox, ctx, cell, actor);
+
+        count += 1;
+    }
+
+    mbox.inner.limit.record(count as usize, started.elapsed());
+}
+
+// Process up to `msg_process_limit` messages in a single `recv_batch`
+fn process_batch<A>(
+    mbox: &Mailbox<A::Msg>,
+    ctx: &Context<A::Msg>,
+    cell: &ExtendedCell<A::Msg>,
+    interceptors: &Interceptors<A::Msg>,
+    actor: &mut Option<A>,
+) where
+    A: Actor,
+{
+    if mbox.is_awaiting() {
+        return;
+    }
+
+    let mut limit = mbox.msg_process_limit() as usize;
+    let mut envelopes = Vec::new();
+
+    // a resumed continuation is processed ahead of queued messages
+    if let Some(msg) = mbox.take_resumed() {
+        envelopes.push(msg);
+    }
+    let resumed = envelopes.len();
+
+    // messages enqueued after a poison pill are left out of the batch
+    if let Some(ahead) = cell.ahead_of_poison_pill() {
+        limit = limit.min(resumed + ahead as usize);
+    }
+
+    while envelopes.len() < limit {
+        match mbox.try_dequeue() {
+            Ok(msg) => envelopes.push(msg),
+            Err(_) => break,
+        }
+    }
+
+    if envelopes.is_empty() {
+        return;
+    }
+    if envelopes.len() > resumed {
+        cell.wake_sinks();
+    }
+
+    // the batch holds the messages which passed through every interceptor
+    let count = envelopes.len();
+    let mut msgs = Vec::with_capacity(count);
+    for msg in envelopes {
+        let mut recv = |_: &Context<A::Msg>, msg, _| msgs.push(msg);
+        Next::new(interceptors, &mut recv).run(ctx, msg.msg, msg.sender);
+    }
+    if !msgs.is_empty() {
+        let started = Instant::now();
+        actor.as_mut().unwrap().recv_batch(ctx, msgs);
+        mbox.inner.limit.record(count, started.elapsed());
+    }
+    cell.received(count);
+    metrics::msgs_processed(ctx.myself.path(), count - resumed);
+    if let Some(governor) = ctx.system.governor() {
+        for _ in resumed..count {
+            governor.dequeued();
+        }
+    }
+
+    if count > resumed && cell.poison_pill_due() {
+        mbox.set_suspended(true);
+        cell.terminate(actor);
     }
 }
 
@@ -273,12 +580,58 @@ fn process_sys_msgs<A>(
     }
 
     for msg in sys_msgs {
-        match msg.msg {
-            SystemMsg::ActorInit => handle_init(mbox, ctx, cell, actor),
-            SystemMsg::Command(cmd) => cell.receive_cmd(cmd, actor),
-            SystemMsg::Event(evt) => handle_evt(evt, ctx, cell, actor),
-            SystemMsg::Failed(failed) => handle_failed(failed, cell, actor),
+        // Control messages that arrive meanwhile are handled first,
+        // so a failure waits for at most one staged message
+        while let Ok(control) = mbox.control_try_dequeue() {
+            handle_sys_msg(control, mbox, ctx, cell, actor);
+        }
+        handle_sys_msg(msg, mbox, ctx, cell, actor);
+    }
+}
+
+fn handle_sys_msg<A>(
+    msg: Envelope<SystemMsg>,
+    mbox: &Mailbox<A::Msg>,
+    ctx: &Context<A::Msg>,
+    cell: &ExtendedCell<A::Msg>,
+    actor: &mut Option<A>,
+) where
+    A: Actor,
+{
+    match msg.msg {
+        SystemMsg::ActorInit => handle_init(mbox, ctx, cell, actor),
+        SystemMsg::Command(SystemCmd::Suspend) => pause(true, mbox, cell),
+        SystemMsg::Command(SystemCmd::Resume) => pause(false, mbox, cell),
+        SystemMsg::Command(cmd) => cell.receive_cmd(cmd, actor),
+        SystemMsg::Event(evt) => handle_evt(evt, ctx, cell, actor),
+        SystemMsg::Failed(info) => handle_failed(info, ctx, cell, actor),
+        SystemMsg::TaskFailed(failed) => handle_task_failed(failed, mbox, ctx, cell, actor),
+        SystemMsg::ReceiveTimeout(timeout) => {
+            handle_receive_timeout(timeout, mbox, ctx, cell, actor)
+        }
+        SystemMsg::Identify(reply_to) => {
+            let _ = reply_to.reply(cell.info());
         }
+        // an actor without an instance, e.g. while restarting, doesn't reply
+        SystemMsg::CheckHealth(reply_to) => {
+            if let Some(actor) = actor.as_ref() {
+                let _ = reply_to.reply(actor.health());
+            }
+        }
+    }
+}
+
+// Pause the actor and its descendants, see `ActorSystem::suspend`
+fn pause<Msg: Message>(paused: bool, mbox: &Mailbox<Msg>, cell: &ExtendedCell<Msg>) {
+    mbox.set_paused(paused);
+
+    let cmd = if paused {
+        SystemCmd::Suspend
+    } else {
+        SystemCmd::Resume
+    };
+    for child in cell.children() {
+        child.sys_tell(cmd.clone().into());
     }
 }
 
@@ -291,6 +644,9 @@ fn handle_init<A>(
     A: Actor,
 {
     actor.as_mut().unwrap().pre_start(ctx);
+    if cell.post_restart_due() {
+        actor.as_mut().unwrap().post_restart(ctx);
+    }
     mbox.set_suspended(false);
 
     if cell.is_user() {
@@ -303,13 +659,93 @@ fn handle_init<A>(
     }
 
     actor.as_mut().unwrap().post_start(ctx);
+    cell.set_started(Ok(()));
+}
+
+fn handle_failed<A>(
+    info: FailureInfo,
+    ctx: &Context<A::Msg>,
+    cell: &ExtendedCell<A::Msg>,
+    actor: &mut Option<A>,
+) where
+    A: Actor,
+{
+    ctx.system.publish_event(ActorFailed::from(&info).into());
+
+    let strategy = actor.as_mut().unwrap().decide(&info);
+    cell.handle_failure(info, strategy)
 }
 
-fn handle_failed<A>(failed: BasicActorRef, cell: &ExtendedCell<A::Msg>, actor: &mut Option<A>)
+fn handle_receive_timeout<A>(
+    timeout: ReceiveTimeout,
+    mbox: &Mailbox<A::Msg>,
+    ctx: &Context<A::Msg>,
+    cell: &ExtendedCell<A::Msg>,
+    actor: &mut Option<A>,
+) where
+    A: Actor,
+{
+    if let Some(due) = cell.passivation_due(&timeout) {
+        if due {
+            passivate(mbox, cell, actor);
+        }
+    } else if cell.receive_timeout_due(&timeout) && actor.is_some() {
+        actor
+            .as_mut()
+            .unwrap()
+            .sys_recv(ctx, SystemMsg::ReceiveTimeout(timeout), None);
+    }
+}
+
+// Only an actor with nothing left to do is passivated, otherwise its window restarts
+fn passivate<A>(mbox: &Mailbox<A::Msg>, cell: &ExtendedCell<A::Msg>, actor: &mut Option<A>)
 where
     A: Actor,
 {
-    cell.handle_failure(failed, actor.as_mut().unwrap().supervisor_strategy())
+    let idle = actor.is_some()
+        && !cell.has_children()
+        && !mbox.has_msgs()
+        && !mbox.is_awaiting()
+        && !mbox.has_resumed();
+
+    if idle {
+        // user messages wait for `ActorInit` of the new instance
+        mbox.set_suspended(true);
+        cell.passivate(actor);
+    } else {
+        cell.restart_passivation();
+    }
+}
+
+fn handle_task_failed<A>(
+    failed: TaskFailed,
+    mbox: &Mailbox<A::Msg>,
+    ctx: &Context<A::Msg>,
+    cell: &ExtendedCell<A::Msg>,
+    actor: &mut Option<A>,
+) where
+    A: Actor,
+{
+    let on_failure = failed.on_failure;
+    let info = FailureInfo {
+        actor: cell.myself().into(),
+        panic_msg: Some(failed.error.clone()),
+        restart_count: cell.restart_count(),
+    };
+
+    if actor.is_some() {
+        actor
+            .as_mut()
+            .unwrap()
+            .sys_recv(ctx, SystemMsg::TaskFailed(failed), None);
+    }
+
+    if on_failure == TaskFailure::Fail {
+        // Same as a panic in `recv`, see `Sentinel`. The mailbox is
+        // resumed when the supervisor restarts the actor.
+        mbox.set_suspended(true);
+        cell.parent().sys_tell(SystemMsg::Failed(info));
+    }
 }
 
 fn handle_evt<A>(
@@ -333,8 +769,6 @@ fn handle_evt<A>(
 }
 
 struct Sentinel<'a, Msg: Message> {
-    parent: BasicActorRef,
-    actor: BasicActorRef,
     mbox: &'a Mailbox<Msg>,
 }
 
@@ -351,8 +785,8 @@ where
             // self.kernel.park_actor(self.actor.uri.uid, None);
             self.mbox.set_scheduled(false);
 
-            // Message the parent (this failed actor's supervisor) to decide how to handle the failure
-            self.parent.sys_tell(SystemMsg::Failed(self.actor.clone()));
+            // The kernel messages the parent once the panic is caught,
+            // see `kernel::kernel`
         }
     }
 }
@@ -361,32 +795,23 @@ pub fn flush_to_deadletters<Msg>(mbox: &Mailbox<Msg>, actor: &BasicActorRef, sys
 where
     Msg: Message,
 {
-    while let Ok(Envelope { msg, sender }) = mbox.try_dequeue() {
+    // closing the mailbox first, any message sent meanwhile is either
+    // flushed or dead lettered by its sender
+    for Envelope { msg, sender } in mbox.close() {
+        metrics::msg_flushed(actor.path());
+        if let Some(governor) = sys.governor() {
+            governor.dequeued();
+        }
+        metrics::dead_letter(actor.path());
+
         let dl = DeadLetter {
             msg: format!("{:?}", msg),
             sender,
             recipient: actor.clone(),
+            reason: DeadLetterReason::Terminated,
+            payload: Some(DeadLetterPayload::new(msg)),
         };
 
-        sys.dead_letters().tell(
-            Publish {
-                topic: "dead_letter".into(),
-                msg: dl,
-            },
-            None,
-        );
-    }
-}
-
-#[derive(Clone, Debug)]
-pub struct MailboxConfig {
-    pub msg_process_limit: u32,
-}
-
-impl<'a> From<&'a Config> for MailboxConfig {
-    fn from(cfg: &Config) -> Self {
-        MailboxConfig {
-            msg_process_limit: cfg.get_int("mailbox.msg_process_limit").unwrap() as u32,
-        }
+        sys.dead_letter(dl, None);
     }
 }
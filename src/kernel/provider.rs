@@ -1,4 +1,4 @@
-use dashmap::DashMap;
+use dashmap::{mapref::entry::Entry, DashMap};
 use slog::trace;
 
 use std::sync::Arc;
@@ -6,9 +6,11 @@ use std::sync::Arc;
 use crate::system::LoggingSystem;
 use crate::{
     actor::actor_cell::{ActorCell, ExtendedCell},
+    actor::uri::path_pattern,
     actor::*,
     kernel::kernel,
     kernel::mailbox::mailbox,
+    kernel::queue::QueueKind,
     system::{ActorSystem, SysActors, SystemMsg},
     validate::validate_name,
 };
@@ -16,22 +18,22 @@ use crate::{
 #[derive(Clone)]
 pub struct Provider {
     inner: Arc<ProviderInner>,
-    log: LoggingSystem,
 }
 
+// The actors created by the provider, by path. A path is reserved,
+// without an actor, while the actor is being created.
 struct ProviderInner {
-    paths: DashMap<ActorPath, ()>,
+    paths: DashMap<ActorPath, Option<BasicActorRef>>,
 }
 
 impl Provider {
-    pub fn new(log: LoggingSystem) -> Self {
+    pub fn new() -> Self {
         let inner = ProviderInner {
             paths: DashMap::new(),
         };
 
         Provider {
             inner: Arc::new(inner),
-            log,
         }
     }
 
@@ -46,19 +48,39 @@ impl Provider {
         A: Actor + 'static,
     {
         validate_name(name)?;
+        if !sys.accepts_name(name, parent) {
+            return Err(CreateError::InvalidName(name.into()));
+        }
 
-        let path = ActorPath::new(&format!("{}/{}", parent.path(), name));
+        let path = parent.path().child(name);
         trace!(sys.log(), "Attempting to create actor at: {}", path);
 
         self.register(&path)?;
 
         let uri = ActorUri {
-            path,
+            path: path.clone(),
             name: Arc::from(name),
             host: sys.host(),
+            port: sys.port(),
+        };
+
+        let (priority, capacity, init_msg, limit) = {
+            let props = props.lock().unwrap();
+            (
+                props.priority(),
+                props.mailbox_capacity(),
+                props.init_msg(),
+                props.msg_process_limit(),
+            )
         };
 
-        let (sender, sys_sender, mb) = mailbox::<A::Msg>(sys.sys_settings().msg_process_limit);
+        let settings = sys.sys_settings();
+        let (sender, sys_sender, mb) = mailbox::<A::Msg>(
+            limit.unwrap_or_else(|| settings.default_process_limit()),
+            settings.queue,
+            priority,
+            capacity.unwrap_or(0),
+        );
 
         let cell = ExtendedCell::new(
             uri,
@@ -70,29 +92,90 @@ impl Provider {
             sender,
         );
 
-        let k = kernel(props, cell.clone(), mb, sys)?;
+        // free the name so creating the actor can be retried
+        let k = kernel(props, cell.clone(), mb, sys).inspect_err(|_| self.unregister(&path))?;
         let cell = cell.init(&k);
 
         let actor = ActorRef::new(cell);
         let child = BasicActorRef::from(actor.clone());
+        self.inner
+            .paths
+            .insert(child.path().clone(), Some(child.clone()));
         parent.cell.add_child(child);
         actor.sys_tell(SystemMsg::ActorInit);
+        // queued while the mailbox is suspended, so it's received after `pre_start`
+        if let Some(msg) = init_msg {
+            actor.tell(msg, None);
+        }
 
         Ok(actor)
     }
 
     fn register(&self, path: &ActorPath) -> Result<(), CreateError> {
-        let old = self.inner.paths.insert(path.clone(), ());
-        if old.is_some() {
-            Err(CreateError::AlreadyExists(path.clone()))
-        } else {
-            Ok(())
+        match self.inner.paths.entry(path.clone()) {
+            Entry::Occupied(_) => Err(CreateError::AlreadyExists(path.clone())),
+            Entry::Vacant(entry) => {
+                entry.insert(None);
+                Ok(())
+            }
         }
     }
 
     pub fn unregister(&self, path: &ActorPath) {
         self.inner.paths.remove(path);
     }
+
+    /// Returns the paths of the actors at or below `prefix`, ordered by path
+    pub fn paths(&self, prefix: &ActorPath) -> Vec<ActorPath> {
+        let mut paths: Vec<ActorPath> = self
+            .inner
+            .paths
+            .iter()
+            .filter(|actor| actor.value().is_some())
+            .filter(|actor| actor.key() == prefix || actor.key().is_descendant_of(prefix))
+            .map(|actor| actor.key().clone())
+            .collect();
+        paths.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        paths
+    }
+
+    /// Returns the number of actors created by the provider that haven't terminated
+    pub fn count(&self) -> usize {
+        self.inner
+            .paths
+            .iter()
+            .filter(|actor| actor.value().is_some())
+            .count()
+    }
+
+    /// Returns the live actor at `path`
+    pub fn get(&self, path: &ActorPath) -> Option<BasicActorRef> {
+        self.inner
+            .paths
+            .get(path)
+            .and_then(|actor| actor.value().clone())
+    }
+
+    /// Returns the actors with a path matching `glob`, ordered by path
+    ///
+    /// A path without a `*` is looked up directly, other paths are
+    /// matched against every actor, with `*` matching any characters.
+    pub fn select(&self, glob: &str) -> Vec<BasicActorRef> {
+        if !glob.contains('*') {
+            return self.get(&ActorPath::new(glob)).into_iter().collect();
+        }
+
+        let pattern = path_pattern(glob);
+        let mut actors: Vec<BasicActorRef> = self
+            .inner
+            .paths
+            .iter()
+            .filter(|actor| pattern.is_match(actor.key().as_str()))
+            .filter_map(|actor| actor.value().clone())
+            .collect();
+        actors.sort_by(|a, b| a.path().as_str().cmp(b.path().as_str()));
+        actors
+    }
 }
 
 pub fn create_root(sys: &ActorSystem) -> SysActors {
@@ -110,9 +193,11 @@ fn root(sys: &ActorSystem) -> BasicActorRef {
     let uri = ActorUri {
         name: Arc::from("root"),
         path: ActorPath::new("/"),
-        host: Arc::from("localhost"),
+        host: sys.host(),
+        port: sys.port(),
     };
-    let (sender, sys_sender, _mb) = mailbox::<SystemMsg>(100);
+    let (sender, sys_sender, _mb) =
+        mailbox::<SystemMsg>(MsgProcessLimit::Fixed(100), QueueKind::Channel, None, 0);
 
     // Big bang: all actors have a parent.
     // This means root also needs a parent.
@@ -138,7 +223,8 @@ fn root(sys: &ActorSystem) -> BasicActorRef {
     // root
     let props: BoxActorProd<Guardian> =
         Props::new_args::<Guardian, _>(("root".to_string(), sys.log()));
-    let (sender, sys_sender, mb) = mailbox::<SystemMsg>(100);
+    let (sender, sys_sender, mb) =
+        mailbox::<SystemMsg>(MsgProcessLimit::Fixed(100), QueueKind::Channel, None, 0);
 
     let cell = ExtendedCell::new(
         uri,
@@ -161,12 +247,14 @@ fn guardian(name: &str, path: &str, root: &BasicActorRef, sys: &ActorSystem) ->
     let uri = ActorUri {
         name: Arc::from(name),
         path: ActorPath::new(path),
-        host: Arc::from("localhost"),
+        host: sys.host(),
+        port: sys.port(),
     };
 
     let props: BoxActorProd<Guardian> =
         Props::new_args::<Guardian, _>((name.to_string(), sys.log()));
-    let (sender, sys_sender, mb) = mailbox::<SystemMsg>(100);
+    let (sender, sys_sender, mb) =
+        mailbox::<SystemMsg>(MsgProcessLimit::Fixed(100), QueueKind::Channel, None, 0);
 
     let cell = ExtendedCell::new(
         uri,
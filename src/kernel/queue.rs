@@ -1,18 +1,78 @@
-use std::sync::{
-    mpsc::{channel, Receiver, Sender},
-    Mutex,
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Mutex,
+    },
 };
 
-use crate::{Envelope, Message};
+use crate::{actor::MsgPriority, Envelope, Message};
 
-pub fn queue<Msg: Message>() -> (QueueWriter<Msg>, QueueReader<Msg>) {
-    let (tx, rx) = channel::<Envelope<Msg>>();
+use riker_core::{mpsc_queue, Consumer, Producer};
+
+/// The queue implementation backing mailboxes, set by `mailbox.queue`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QueueKind {
+    /// A `std::sync::mpsc` channel (`"channel"`)
+    Channel,
+
+    /// A lock-free queue, avoiding contention when many actors
+    /// send to the same actor (`"lockfree"`)
+    LockFree,
+}
+
+impl QueueKind {
+    /// Parse a `mailbox.queue` value
+    pub(crate) fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "channel" => Some(QueueKind::Channel),
+            "lockfree" => Some(QueueKind::LockFree),
+            _ => None,
+        }
+    }
+}
+
+pub fn queue<Msg: Message>(kind: QueueKind) -> (QueueWriter<Msg>, QueueReader<Msg>) {
+    queue_with(kind, None, 0)
+}
+
+/// A queue that dequeues messages with a higher priority first
+///
+/// Messages of the same priority are dequeued in the order they were enqueued.
+/// The heap is allocated for `capacity` messages.
+pub fn priority_queue<Msg: Message>(
+    kind: QueueKind,
+    priority: MsgPriority<Msg>,
+    capacity: usize,
+) -> (QueueWriter<Msg>, QueueReader<Msg>) {
+    queue_with(kind, Some(priority), capacity)
+}
+
+fn queue_with<Msg: Message>(
+    kind: QueueKind,
+    priority: Option<MsgPriority<Msg>>,
+    capacity: usize,
+) -> (QueueWriter<Msg>, QueueReader<Msg>) {
+    let (tx, rx) = match kind {
+        QueueKind::Channel => {
+            let (tx, rx) = channel::<Envelope<Msg>>();
+            (Tx::Channel(tx), Rx::Channel(rx))
+        }
+        QueueKind::LockFree => {
+            let (producer, consumer) = mpsc_queue();
+            (Tx::LockFree(producer), Rx::LockFree(consumer))
+        }
+    };
 
     let qw = QueueWriter { tx };
 
     let qr = QueueReaderInner {
         rx,
         next_item: None,
+        priority,
+        prioritized: BinaryHeap::with_capacity(capacity),
+        seq: 0,
     };
 
     let qr = QueueReader {
@@ -22,17 +82,65 @@ pub fn queue<Msg: Message>() -> (QueueWriter<Msg>, QueueReader<Msg>) {
     (qw, qr)
 }
 
+enum Tx<Msg: Message> {
+    Channel(Sender<Envelope<Msg>>),
+    LockFree(Producer<Envelope<Msg>>),
+}
+
+impl<Msg: Message> Clone for Tx<Msg> {
+    fn clone(&self) -> Self {
+        match self {
+            Tx::Channel(tx) => Tx::Channel(tx.clone()),
+            Tx::LockFree(queue) => Tx::LockFree(queue.clone()),
+        }
+    }
+}
+
+enum Rx<Msg: Message> {
+    Channel(Receiver<Envelope<Msg>>),
+    LockFree(Consumer<Envelope<Msg>>),
+    // sending fails once the queue is closed
+    Closed,
+}
+
+impl<Msg: Message> Rx<Msg> {
+    fn try_recv(&mut self) -> Option<Envelope<Msg>> {
+        match self {
+            Rx::Channel(rx) => rx.try_recv().ok(),
+            Rx::LockFree(consumer) => consumer.pop(),
+            Rx::Closed => None,
+        }
+    }
+
+    // Returns the messages sent before the queue was closed
+    fn close(&mut self) -> Vec<Envelope<Msg>> {
+        let mut msgs = Vec::new();
+        match std::mem::replace(self, Rx::Closed) {
+            // dropping the receiver fails the later sends
+            Rx::Channel(rx) => msgs.extend(rx.try_iter()),
+            Rx::LockFree(mut consumer) => {
+                consumer.close();
+                while let Some(msg) = consumer.pop() {
+                    msgs.push(msg);
+                }
+            }
+            Rx::Closed => {}
+        }
+        msgs
+    }
+}
+
 #[derive(Clone)]
 pub struct QueueWriter<Msg: Message> {
-    tx: Sender<Envelope<Msg>>,
+    tx: Tx<Msg>,
 }
 
 impl<Msg: Message> QueueWriter<Msg> {
     pub fn try_enqueue(&self, msg: Envelope<Msg>) -> EnqueueResult<Msg> {
-        self.tx
-            .send(msg)
-            .map(|_| ())
-            .map_err(|e| EnqueueError { msg: e.0 })
+        match &self.tx {
+            Tx::Channel(tx) => tx.send(msg).map_err(|e| EnqueueError { msg: e.0 }),
+            Tx::LockFree(queue) => queue.push(msg).map_err(|msg| EnqueueError { msg }),
+        }
     }
 }
 
@@ -41,44 +149,98 @@ pub struct QueueReader<Msg: Message> {
 }
 
 struct QueueReaderInner<Msg: Message> {
-    rx: Receiver<Envelope<Msg>>,
+    rx: Rx<Msg>,
     next_item: Option<Envelope<Msg>>,
+    priority: Option<MsgPriority<Msg>>,
+    prioritized: BinaryHeap<Prioritized<Msg>>,
+    seq: u64,
 }
 
-impl<Msg: Message> QueueReader<Msg> {
-    #[allow(dead_code)]
-    pub fn dequeue(&self) -> Envelope<Msg> {
-        let mut inner = self.inner.lock().unwrap();
-        if let Some(item) = inner.next_item.take() {
-            item
-        } else {
-            inner.rx.recv().unwrap()
+impl<Msg: Message> QueueReaderInner<Msg> {
+    // Move the received messages into the priority heap
+    fn prioritize(&mut self, priority: MsgPriority<Msg>) {
+        while let Some(msg) = self.rx.try_recv() {
+            self.seq += 1;
+            self.prioritized.push(Prioritized {
+                priority: priority(&msg.msg),
+                seq: Reverse(self.seq),
+                msg,
+            });
         }
     }
+}
 
+impl<Msg: Message> QueueReader<Msg> {
     pub fn try_dequeue(&self) -> DequeueResult<Envelope<Msg>> {
         let mut inner = self.inner.lock().unwrap();
         if let Some(item) = inner.next_item.take() {
             Ok(item)
+        } else if let Some(priority) = inner.priority {
+            inner.prioritize(priority);
+            inner.prioritized.pop().map(|p| p.msg).ok_or(QueueEmpty)
         } else {
-            inner.rx.try_recv().map_err(|_| QueueEmpty)
+            inner.rx.try_recv().ok_or(QueueEmpty)
         }
     }
 
+    /// Stop accepting messages, returning those still queued
+    ///
+    /// A message is either returned or its enqueue fails, so no message
+    /// sent while the queue is closed is lost.
+    pub fn close(&self) -> Vec<Envelope<Msg>> {
+        let mut inner = self.inner.lock().unwrap();
+        let mut msgs: Vec<_> = inner.next_item.take().into_iter().collect();
+        let mut prioritized = std::mem::take(&mut inner.prioritized).into_sorted_vec();
+        msgs.extend(prioritized.drain(..).rev().map(|p| p.msg));
+        msgs.extend(inner.rx.close());
+        msgs
+    }
+
     pub fn has_msgs(&self) -> bool {
         let mut inner = self.inner.lock().unwrap();
+        if let Some(priority) = inner.priority {
+            inner.prioritize(priority);
+            return !inner.prioritized.is_empty();
+        }
+
         inner.next_item.is_some() || {
             match inner.rx.try_recv() {
-                Ok(item) => {
+                Some(item) => {
                     inner.next_item = Some(item);
                     true
                 }
-                Err(_) => false,
+                None => false,
             }
         }
     }
 }
 
+struct Prioritized<Msg: Message> {
+    priority: u8,
+    seq: Reverse<u64>,
+    msg: Envelope<Msg>,
+}
+
+impl<Msg: Message> PartialEq for Prioritized<Msg> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<Msg: Message> Eq for Prioritized<Msg> {}
+
+impl<Msg: Message> PartialOrd for Prioritized<Msg> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Msg: Message> Ord for Prioritized<Msg> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.priority, self.seq).cmp(&(other.priority, other.seq))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct EnqueueError<T> {
     pub msg: T,
@@ -1,28 +1,69 @@
 #![crate_name = "riker"]
+#![forbid(unsafe_code)]
 #![deny(clippy::all)]
 // #![deny(clippy::pedantic)]
 // #![deny(clippy::nursery)]
 #![allow(clippy::new_ret_no_self)]
 #![allow(clippy::large_enum_variant)]
 
+mod metrics;
+#[cfg(feature = "serde")]
+mod serialize;
 mod validate;
 
 pub mod actor;
+#[cfg(feature = "admin")]
+pub mod admin;
+pub mod bench;
+pub mod io;
 pub mod kernel;
+pub mod pattern;
 pub mod system;
+pub mod testkit;
 
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::env;
 use std::fmt;
 use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
 
-use config::{Config, File};
+use config::{Config, Environment, File};
 
 use crate::actor::BasicActorRef;
 
+pub use riker_core::{ArcMessage, Message};
+
+/// Load the system and application config
+///
+/// Config is layered, each layer overriding the previous ones:
+///
+/// 1. Defaults for every key used by riker
+/// 2. The system config, `config/riker.toml` or the file at `RIKER_CONF`
+/// 3. The application config, `config/app` or the file at `APP_CONF`
+/// 4. `RIKER_*` environment variables, with `__` separating the key's levels,
+///    e.g. `RIKER_MAILBOX__MSG_PROCESS_LIMIT=500` sets `mailbox.msg_process_limit`
 pub fn load_config() -> Config {
     let mut cfg = Config::new();
+    set_defaults(&mut cfg);
+
+    // load the system config
+    // riker.toml contains settings for anything related to the actor framework and its modules
+    let path = env::var("RIKER_CONF").unwrap_or_else(|_| "config/riker.toml".into());
+    cfg.merge(File::with_name(&path).required(false)).unwrap();
 
+    // load the user application config
+    // app.toml or app.yaml contains settings specific to the user application
+    let path = env::var("APP_CONF").unwrap_or_else(|_| "config/app".into());
+    cfg.merge(File::with_name(&path).required(false)).unwrap();
+
+    // environment overrides, e.g. per deployment
+    cfg.merge(Environment::with_prefix("RIKER").separator("__"))
+        .unwrap();
+    cfg
+}
+
+// Defaults for every key used by riker, so that a minimal config works
+pub(crate) fn set_defaults(cfg: &mut Config) {
     cfg.set_default("debug", true).unwrap();
     cfg.set_default("log.level", "debug").unwrap();
     cfg.set_default("log.log_format", "{date} {time} {level} [{module}] {body}")
@@ -30,21 +71,39 @@ pub fn load_config() -> Config {
     cfg.set_default("log.date_format", "%Y-%m-%d").unwrap();
     cfg.set_default("log.time_format", "%H:%M:%S%:z").unwrap();
     cfg.set_default("mailbox.msg_process_limit", 1000).unwrap();
+    cfg.set_default("mailbox.msg_process_budget_millis", 0)
+        .unwrap();
+    cfg.set_default("mailbox.queue", "channel").unwrap();
     cfg.set_default("dispatcher.pool_size", (num_cpus::get() * 2) as i64)
         .unwrap();
     cfg.set_default("dispatcher.stack_size", 0).unwrap();
+    cfg.set_default("dispatcher.fairness", "round_robin")
+        .unwrap();
+    cfg.set_default("dispatcher.blocking_pool_size", 16)
+        .unwrap();
     cfg.set_default("scheduler.frequency_millis", 50).unwrap();
-
-    // load the system config
-    // riker.toml contains settings for anything related to the actor framework and its modules
-    let path = env::var("RIKER_CONF").unwrap_or_else(|_| "config/riker.toml".into());
-    cfg.merge(File::with_name(&path).required(false)).unwrap();
-
-    // load the user application config
-    // app.toml or app.yaml contains settings specific to the user application
-    let path = env::var("APP_CONF").unwrap_or_else(|_| "config/app".into());
-    cfg.merge(File::with_name(&path).required(false)).unwrap();
-    cfg
+    cfg.set_default("scheduler.coalesce_repeats", false)
+        .unwrap();
+    cfg.set_default("deadletters.capacity", 100).unwrap();
+    cfg.set_default("deadletters.replay_capacity", 1000)
+        .unwrap();
+    cfg.set_default("deadletters.suppress", Vec::<String>::new())
+        .unwrap();
+    cfg.set_default("deadletters.log_every", 1).unwrap();
+    cfg.set_default("health.interval_millis", 1000).unwrap();
+    cfg.set_default("temp.ttl_millis", 60_000).unwrap();
+    cfg.set_default("governor.enabled", false).unwrap();
+    cfg.set_default("governor.max_msgs_per_sec", 1_000_000)
+        .unwrap();
+    cfg.set_default("governor.resume_msgs_per_sec", 800_000)
+        .unwrap();
+    cfg.set_default("governor.max_queued", 1_000_000).unwrap();
+    cfg.set_default("governor.resume_queued", 800_000).unwrap();
+    cfg.set_default("governor.policy", "shed").unwrap();
+    cfg.set_default("governor.delay_millis", 100).unwrap();
+    cfg.set_default("testkit.time_factor", 1.0).unwrap();
+    cfg.set_default("testkit.expect_timeout_millis", 3000)
+        .unwrap();
 }
 
 /// Wraps message and sender
@@ -54,70 +113,183 @@ pub struct Envelope<T: Message> {
     pub msg: T,
 }
 
-unsafe impl<T: Message> Send for Envelope<T> {}
+/// A message of any type, used where the recipient's message type isn't known
+///
+/// Clones share the payload. A one time message is taken by the first
+/// clone to `take` it, so a scheduled message can be cloned with its
+/// envelope. Any other message is copied by each `take`, e.g. each time a
+/// repeating schedule fires, which requires a `Message`, or produced by the
+/// function given to `from_fn`. A one time message created with `once`
+/// doesn't need to be `Clone`.
+#[derive(Clone)]
+pub struct AnyMessage {
+    one_time: bool,
+    msg: Arc<Mutex<Option<Box<dyn Any + Send>>>>,
+    type_id: TypeId,
+    type_name: &'static str,
 
-pub trait Message: Debug + Clone + Send + 'static {}
-impl<T: Debug + Clone + Send + 'static> Message for T {}
+    // Copies the payload, or calls the function given to `from_fn`
+    produce: Option<ProduceAny>,
+}
 
-pub struct AnyMessage {
-    pub one_time: bool,
-    pub msg: Option<Box<dyn Any + Send>>,
+type ProduceAny = fn(&mut (dyn Any + Send)) -> Box<dyn Any + Send>;
+
+type MsgFn<T> = Box<dyn FnMut() -> T + Send>;
+
+/// Returned by `AnyMessage::take` if the message isn't a `T`
+#[derive(Clone, Debug, PartialEq)]
+pub enum DowncastAnyMessageError {
+    /// The message is of another type
+    WrongType {
+        expected: TypeId,
+        expected_name: &'static str,
+        actual: TypeId,
+        actual_name: &'static str,
+    },
+
+    /// The one time message was already taken
+    Taken { expected_name: &'static str },
+}
+
+impl fmt::Display for DowncastAnyMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DowncastAnyMessageError::WrongType {
+                expected_name,
+                actual_name,
+                ..
+            } => write!(
+                f,
+                "Expected a message of type `{}`, found `{}`",
+                expected_name, actual_name
+            ),
+            DowncastAnyMessageError::Taken { expected_name } => write!(
+                f,
+                "Expected a message of type `{}`, it was already taken",
+                expected_name
+            ),
+        }
+    }
 }
 
-pub struct DowncastAnyMessageError;
+fn clone_any<T: Message>(msg: &mut (dyn Any + Send)) -> Box<dyn Any + Send> {
+    Box::new(msg.downcast_ref::<T>().unwrap().clone())
+}
+
+fn call_any<T: Any + Send>(f: &mut (dyn Any + Send)) -> Box<dyn Any + Send> {
+    Box::new((f.downcast_mut::<MsgFn<T>>().unwrap())())
+}
 
 impl AnyMessage {
     pub fn new<T>(msg: T, one_time: bool) -> Self
     where
         T: Any + Message,
     {
+        Self {
+            produce: Some(clone_any::<T>),
+            ..Self::with(msg, one_time)
+        }
+    }
+
+    /// A message produced by calling `f` on each `take`
+    pub fn from_fn<T, F>(f: F) -> Self
+    where
+        T: Any + Send,
+        F: FnMut() -> T + Send + 'static,
+    {
+        let f: MsgFn<T> = Box::new(f);
+        Self {
+            one_time: false,
+            msg: Arc::new(Mutex::new(Some(Box::new(f)))),
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            produce: Some(call_any::<T>),
+        }
+    }
+
+    /// A message that is taken once, which doesn't need to be `Clone`
+    pub fn once<T>(msg: T) -> Self
+    where
+        T: Any + Send,
+    {
+        Self::with(msg, true)
+    }
+
+    fn with<T: Any + Send>(msg: T, one_time: bool) -> Self {
         Self {
             one_time,
-            msg: Some(Box::new(msg)),
+            msg: Arc::new(Mutex::new(Some(Box::new(msg)))),
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            produce: None,
         }
     }
 
+    /// Whether the message is taken by the first `take`, instead of being copied
+    pub fn is_one_time(&self) -> bool {
+        self.one_time
+    }
+
+    /// Returns the name of the message's type
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
     pub fn take<T>(&mut self) -> Result<T, DowncastAnyMessageError>
     where
-        T: Any + Message,
+        T: Any + Send,
     {
-        if self.one_time {
-            match self.msg.take() {
-                Some(m) => {
-                    if m.is::<T>() {
-                        Ok(*m.downcast::<T>().unwrap())
-                    } else {
-                        Err(DowncastAnyMessageError)
-                    }
-                }
-                None => Err(DowncastAnyMessageError),
-            }
-        } else {
-            match self.msg.as_ref() {
-                Some(m) if m.is::<T>() => Ok(m.downcast_ref::<T>().cloned().unwrap()),
-                Some(_) => Err(DowncastAnyMessageError),
-                None => Err(DowncastAnyMessageError),
-            }
+        if self.type_id != TypeId::of::<T>() {
+            return Err(DowncastAnyMessageError::WrongType {
+                expected: TypeId::of::<T>(),
+                expected_name: std::any::type_name::<T>(),
+                actual: self.type_id,
+                actual_name: self.type_name,
+            });
+        }
+
+        let mut msg = self.msg.lock().unwrap();
+        let taken = match (self.one_time, self.produce) {
+            (false, Some(produce)) => msg.as_deref_mut().map(produce),
+            _ => msg.take(),
+        };
+
+        match taken {
+            Some(m) => Ok(*m.downcast::<T>().unwrap()),
+            None => Err(DowncastAnyMessageError::Taken {
+                expected_name: std::any::type_name::<T>(),
+            }),
         }
     }
-}
 
-impl Clone for AnyMessage {
-    fn clone(&self) -> Self {
-        panic!("Can't clone a message of type `AnyMessage`");
+    /// Move the payload to a message that is copied by each `take`
+    ///
+    /// A payload that isn't a `Message` stays one time.
+    pub(crate) fn retain(&mut self) -> AnyMessage {
+        if !self.one_time {
+            return self.clone();
+        }
+
+        AnyMessage {
+            one_time: self.produce.is_none(),
+            msg: Arc::new(Mutex::new(self.msg.lock().unwrap().take())),
+            ..self.clone()
+        }
     }
 }
 
 impl Debug for AnyMessage {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str("AnyMessage")
+        write!(f, "AnyMessage({})", self.type_name)
     }
 }
 
 pub mod actors {
     pub use crate::actor::*;
     pub use crate::system::{
-        ActorSystem, Run, ScheduleId, SystemBuilder, SystemEvent, SystemMsg, Timer,
+        ActorInfo, ActorSystem, FailureInfo, Health, Listing, LogEvent, LogRecord, ReceiveTimeout,
+        RespawnPolicy, RespawnRequest, Run, ScheduleId, SystemBuilder, SystemEvent, SystemMsg,
+        TaskFailed, TaskFailure, Timer,
     };
-    pub use crate::{AnyMessage, Message};
+    pub use crate::{AnyMessage, ArcMessage, Message};
 }
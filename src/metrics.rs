@@ -0,0 +1,101 @@
+//! Instrumentation hooks for the `metrics` crate facade.
+//!
+//! When the `metrics` feature is enabled the actor system records the
+//! following. Actor metrics are labeled with the actor's top-level guardian,
+//! `user`, `system` or `temp`, so that the number of series stays bounded
+//! however many actors are created:
+//!
+//! - `riker_messages_processed_total` (counter): user messages handled by actors
+//! - `riker_mailbox_size` (gauge): user messages waiting in actors' mailboxes
+//! - `riker_actor_restarts_total` (counter): actors restarted by their supervisor
+//! - `riker_dead_letters_total` (counter): messages routed to dead letters
+//! - `riker_channel_published_total` (counter): messages published to a
+//!   channel, labeled with the channel's `path`
+//! - `riker_executor_queue_lag_seconds` (histogram): time between scheduling
+//!   kernel work and the executor starting to run it
+//!
+//! Any recorder installed through the `metrics` facade (e.g. a Prometheus
+//! exporter) receives these. Without the feature every hook is a no-op.
+#![cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use ::metrics::{counter, gauge, histogram};
+
+use crate::actor::ActorPath;
+
+/// A user message was added to the actor's mailbox
+#[inline]
+pub(crate) fn msg_enqueued(path: &ActorPath) {
+    #[cfg(feature = "metrics")]
+    gauge!("riker_mailbox_size", "guardian" => guardian(path)).increment(1.0);
+}
+
+/// A user message was dequeued and handled by the actor
+#[inline]
+pub(crate) fn msg_processed(path: &ActorPath) {
+    #[cfg(feature = "metrics")]
+    {
+        gauge!("riker_mailbox_size", "guardian" => guardian(path)).decrement(1.0);
+        counter!("riker_messages_processed_total", "guardian" => guardian(path)).increment(1);
+    }
+}
+
+/// A batch of user messages was dequeued and handled by the actor
+#[inline]
+pub(crate) fn msgs_processed(path: &ActorPath, count: usize) {
+    #[cfg(feature = "metrics")]
+    {
+        gauge!("riker_mailbox_size", "guardian" => guardian(path)).decrement(count as f64);
+        counter!("riker_messages_processed_total", "guardian" => guardian(path))
+            .increment(count as u64);
+    }
+}
+
+/// A user message was removed from the mailbox without being handled,
+/// e.g. flushed to dead letters on termination
+#[inline]
+pub(crate) fn msg_flushed(path: &ActorPath) {
+    #[cfg(feature = "metrics")]
+    gauge!("riker_mailbox_size", "guardian" => guardian(path)).decrement(1.0);
+}
+
+/// The actor was restarted by its supervisor
+#[inline]
+pub(crate) fn actor_restarted(path: &ActorPath) {
+    #[cfg(feature = "metrics")]
+    counter!("riker_actor_restarts_total", "guardian" => guardian(path)).increment(1);
+}
+
+/// A message for `recipient` was routed to dead letters
+#[inline]
+pub(crate) fn dead_letter(recipient: &ActorPath) {
+    #[cfg(feature = "metrics")]
+    counter!("riker_dead_letters_total", "guardian" => guardian(recipient)).increment(1);
+}
+
+/// `count` messages were published to the channel at `path`
+#[inline]
+pub(crate) fn published(path: &ActorPath, count: usize) {
+    #[cfg(feature = "metrics")]
+    counter!("riker_channel_published_total", "path" => path.shared()).increment(count as u64);
+}
+
+/// Time a kernel message waited on the executor before running
+#[inline]
+pub(crate) fn executor_lag(lag: Duration) {
+    #[cfg(feature = "metrics")]
+    histogram!("riker_executor_queue_lag_seconds").record(lag.as_secs_f64());
+}
+
+// The top-level guardian of the actor at `path`, a static label
+#[cfg(feature = "metrics")]
+fn guardian(path: &ActorPath) -> &'static str {
+    match path.segments().next() {
+        Some("user") => "user",
+        Some("system") => "system",
+        Some("temp") => "temp",
+        _ => "root",
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    actor::{
+        Actor, ActorFactoryArgs, ActorRef, ActorRefFactory, ActorSelection, BasicActorRef,
+        BoxedTell, Context, CreateError, DeadLetter, DeadLetterPayload, DeadLetterReason, Sender,
+        Tell, TmpActorRefFactory,
+    },
+    system::{SystemMsg, Timer},
+    Message,
+};
+
+/// At most `msgs` messages in each period of `per`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rate {
+    pub msgs: u32,
+    pub per: Duration,
+}
+
+impl Rate {
+    pub fn new(msgs: u32, per: Duration) -> Self {
+        Rate { msgs, per }
+    }
+}
+
+/// What a `Throttler` does with messages over its rate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThrottlePolicy {
+    /// Buffer up to this many messages and forward them in later periods.
+    /// Messages that don't fit are shed.
+    Buffer(usize),
+
+    /// Send the excess to dead letters, with `DeadLetterReason::Throttled`
+    Shed,
+}
+
+/// Messages to a `Throttler`
+#[derive(Clone, Debug)]
+pub enum ThrottleMsg<Msg> {
+    Msg(Msg),
+
+    // Start the next period, forwarding buffered messages
+    #[doc(hidden)]
+    Flush,
+}
+
+impl<Msg> From<Msg> for ThrottleMsg<Msg> {
+    fn from(msg: Msg) -> Self {
+        ThrottleMsg::Msg(msg)
+    }
+}
+
+/// Forwards messages to a target at no more than a `Rate`
+///
+/// The sender of each message is kept, so the target can reply to the
+/// original sender. Periods are measured with the system clock.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::pattern::{Rate, ThrottlePolicy, Throttler};
+/// # use std::time::Duration;
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let inbox = Inbox::<u32>::new(&sys).unwrap();
+///
+/// // forward at most 10 messages each second, buffering up to 100
+/// let rate = Rate::new(10, Duration::from_secs(1));
+/// let throttler = sys
+///     .actor_of_args::<Throttler<u32>, _>(
+///         "api-throttle",
+///         (inbox.sender_ref(), rate, ThrottlePolicy::Buffer(100)),
+///     )
+///     .unwrap();
+///
+/// throttler.tell(1u32, None);
+/// assert_eq!(inbox.receive(Duration::from_secs(1)), Some(1));
+/// ```
+pub struct Throttler<Msg: Message> {
+    target: ActorRef<Msg>,
+    rate: Rate,
+    policy: ThrottlePolicy,
+    period_start: Option<Instant>,
+    sent: u32,
+    buffer: VecDeque<(Msg, Sender)>,
+}
+
+impl<Msg: Message> ActorFactoryArgs<(ActorRef<Msg>, Rate, ThrottlePolicy)> for Throttler<Msg> {
+    fn create_args((target, rate, policy): (ActorRef<Msg>, Rate, ThrottlePolicy)) -> Self {
+        Throttler {
+            target,
+            rate,
+            policy,
+            period_start: None,
+            sent: 0,
+            buffer: VecDeque::new(),
+        }
+    }
+}
+
+impl<Msg: Message> Throttler<Msg> {
+    // Start a new period if the current one has passed
+    fn next_period(&mut self, now: Instant) {
+        match self.period_start {
+            Some(start) if now < start + self.rate.per => {}
+            _ => {
+                self.period_start = Some(now);
+                self.sent = 0;
+            }
+        }
+    }
+
+    fn forward(&mut self, msg: Msg, sender: Sender) {
+        self.sent += 1;
+        self.target.tell(msg, sender);
+    }
+
+    fn shed(&self, ctx: &Context<ThrottleMsg<Msg>>, msg: Msg, sender: Sender) {
+        let dl = DeadLetter {
+            msg: format!("{:?}", msg),
+            sender,
+            recipient: self.target.clone().into(),
+            reason: DeadLetterReason::Throttled,
+            payload: Some(DeadLetterPayload::new(msg)),
+        };
+        ctx.system.dead_letter(dl, Some(ctx.myself().into()));
+    }
+
+    // Forward buffered messages, waiting for the next period for the rest
+    fn flush(&mut self, ctx: &Context<ThrottleMsg<Msg>>) {
+        let now = ctx.system.clock().now();
+        self.next_period(now);
+
+        while self.sent < self.rate.msgs {
+            match self.buffer.pop_front() {
+                Some((msg, sender)) => self.forward(msg, sender),
+                None => return,
+            }
+        }
+
+        if !self.buffer.is_empty() {
+            let start = self.period_start.unwrap();
+            let delay = start + self.rate.per - now;
+            ctx.schedule_once(delay, ctx.myself(), None, ThrottleMsg::Flush);
+        }
+    }
+}
+
+impl<Msg: Message> Actor for Throttler<Msg> {
+    type Msg = ThrottleMsg<Msg>;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        let msg = match msg {
+            ThrottleMsg::Msg(msg) => msg,
+            ThrottleMsg::Flush => return self.flush(ctx),
+        };
+
+        // buffered messages go first, they are forwarded by the scheduled flush
+        if self.buffer.is_empty() {
+            self.next_period(ctx.system.clock().now());
+            if self.sent < self.rate.msgs {
+                return self.forward(msg, sender);
+            }
+        }
+
+        match self.policy {
+            ThrottlePolicy::Buffer(max) if self.buffer.len() < max => {
+                self.buffer.push_back((msg, sender));
+                if self.buffer.len() == 1 {
+                    self.flush(ctx);
+                }
+            }
+            _ => self.shed(ctx, msg, sender),
+        }
+    }
+}
+
+/// The replies gathered by a `ScatterGather`
+#[derive(Clone, Debug)]
+pub struct Gathered<Res> {
+    /// Replies in the order they were received
+    pub replies: Vec<Res>,
+
+    /// Whether the timeout passed before enough replies were received
+    pub timed_out: bool,
+}
+
+/// Send a request to several actors and gather their replies
+///
+/// The request is sent with a temporary actor as the sender and recipients
+/// reply by telling the sender a `Res`. Once `count` replies are received,
+/// by default one from each recipient, or when the timeout passes, the
+/// initiator is sent a single `Gathered` message and the temporary
+/// actor stops.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::pattern::{Gathered, ScatterGather};
+/// # use std::time::Duration;
+///
+/// #[derive(Default)]
+/// struct Quote;
+///
+/// impl Actor for Quote {
+///     type Msg = String;
+///
+///     fn recv(&mut self, ctx: &Context<Self::Msg>, _: Self::Msg, sender: Sender) {
+///         let price = ctx.myself().name().len() as u32;
+///         sender.unwrap().try_tell(price, None).unwrap();
+///     }
+/// }
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let a = sys.actor_of::<Quote>("a").unwrap();
+/// let bb = sys.actor_of::<Quote>("bb").unwrap();
+///
+/// let inbox = Inbox::<Gathered<u32>>::new(&sys).unwrap();
+/// ScatterGather::new("quote".to_string(), vec![a.into(), bb.into()])
+///     .timeout(Duration::from_secs(1))
+///     .run(&sys, Box::new(inbox.sender_ref()))
+///     .unwrap();
+///
+/// let mut gathered = inbox.receive(Duration::from_secs(2)).unwrap();
+/// gathered.replies.sort();
+/// assert_eq!(gathered.replies, vec![1, 2]);
+/// assert!(!gathered.timed_out);
+/// ```
+pub struct ScatterGather<Req: Message> {
+    request: Req,
+    recipients: Vec<BasicActorRef>,
+    count: usize,
+    timeout: Duration,
+}
+
+impl<Req: Message> ScatterGather<Req> {
+    pub fn new(request: Req, recipients: Vec<BasicActorRef>) -> Self {
+        ScatterGather {
+            request,
+            count: recipients.len(),
+            recipients,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Send the request to the actors currently in `selection`
+    pub fn selection(request: Req, selection: &ActorSelection) -> Self {
+        ScatterGather::new(request, selection.resolve())
+    }
+
+    /// Deliver once `count` replies are received
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Deliver the replies received so far once `timeout` passes,
+    /// 5 seconds by default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send the request, the replies are delivered to `initiator`
+    pub fn run<Res: Message>(
+        self,
+        factory: &impl TmpActorRefFactory,
+        initiator: BoxedTell<Gathered<Res>>,
+    ) -> Result<(), CreateError> {
+        let args = Arc::new(Mutex::new((self, initiator)));
+        factory.tmp_actor_of_args::<Gatherer<Req, Res>, _>(args)?;
+        Ok(())
+    }
+}
+
+impl<Req: Message> Clone for ScatterGather<Req> {
+    fn clone(&self) -> Self {
+        ScatterGather {
+            request: self.request.clone(),
+            recipients: self.recipients.clone(),
+            count: self.count,
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl<Req: Message> fmt::Debug for ScatterGather<Req> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ScatterGather[{:?}, {} recipients]",
+            self.request,
+            self.recipients.len()
+        )
+    }
+}
+
+// Temporary actor gathering the replies of a `ScatterGather`
+struct Gatherer<Req: Message, Res: Message> {
+    request: ScatterGather<Req>,
+    initiator: BoxedTell<Gathered<Res>>,
+    deadline: Option<Instant>,
+    replies: Vec<Res>,
+}
+
+// Args are behind a `Mutex` since a request and `BoxedTell` aren't `Sync`
+type GathererArgs<Req, Res> = Arc<Mutex<(ScatterGather<Req>, BoxedTell<Gathered<Res>>)>>;
+
+impl<Req: Message, Res: Message> ActorFactoryArgs<GathererArgs<Req, Res>> for Gatherer<Req, Res> {
+    fn create_args(args: GathererArgs<Req, Res>) -> Self {
+        let (request, initiator) = args.lock().unwrap().clone();
+        Gatherer {
+            request,
+            initiator,
+            deadline: None,
+            replies: Vec::new(),
+        }
+    }
+}
+
+impl<Req: Message, Res: Message> Gatherer<Req, Res> {
+    fn deliver(&mut self, ctx: &Context<Res>, timed_out: bool) {
+        let gathered = Gathered {
+            replies: std::mem::take(&mut self.replies),
+            timed_out,
+        };
+        self.initiator.tell(gathered, None);
+        ctx.stop(ctx.myself());
+    }
+}
+
+impl<Req: Message, Res: Message> Actor for Gatherer<Req, Res> {
+    type Msg = Res;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        if self.request.count == 0 {
+            return self.deliver(ctx, false);
+        }
+
+        for recipient in &self.request.recipients {
+            let _ = recipient.try_tell(self.request.request.clone(), ctx.myself());
+        }
+
+        self.deadline = Some(ctx.system.clock().now() + self.request.timeout);
+        ctx.set_receive_timeout(self.request.timeout);
+    }
+
+    fn sys_recv(&mut self, ctx: &Context<Self::Msg>, msg: SystemMsg, _: Sender) {
+        if let SystemMsg::ReceiveTimeout(_) = msg {
+            self.deliver(ctx, true);
+        }
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        self.replies.push(msg);
+        if self.replies.len() >= self.request.count {
+            return self.deliver(ctx, false);
+        }
+
+        // the receive timeout restarts on each reply, keep it at the deadline
+        let now = ctx.system.clock().now();
+        match self.deadline {
+            Some(deadline) if now < deadline => ctx.set_receive_timeout(deadline - now),
+            _ => self.deliver(ctx, true),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! `serde` support for the core message and event types.
+//!
+//! With the `serde` feature `SystemEvent`, `DeadLetter`, `LogRecord`, `LogEntry`,
+//! `Envelope<T>`, `ActorTreeNode`, `ActorInfo` and their parts implement
+//! `Serialize`, so they can be
+//! shipped to external pipelines, e.g. for auditing. Actor references are
+//! serialized as their path. Since a path doesn't identify a live actor,
+//! only the types without actor references, `Topic` and `ActorPath`,
+//! implement `Deserialize`.
+
+use serde::{
+    ser::{SerializeStruct, Serializer},
+    Deserialize, Deserializer, Serialize,
+};
+
+use crate::{
+    actor::{
+        ActorPath, ActorRef, ActorReference, BasicActorRef, DeadLetter, DeadLetterReason, Topic,
+    },
+    system::{
+        ActorCreated, ActorFailed, ActorInfo, ActorRestarted, ActorTerminated, ActorTreeNode,
+        Health, HealthChanged, LogEntry, LogEvent, LogRecord, OverloadChanged, SystemEvent,
+    },
+    Envelope, Message,
+};
+
+impl Serialize for ActorPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ActorPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = String::deserialize(deserializer)?;
+        Ok(ActorPath::new(&path))
+    }
+}
+
+impl Serialize for Topic {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Topic {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Topic::from)
+    }
+}
+
+impl Serialize for BasicActorRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.path().serialize(serializer)
+    }
+}
+
+impl<Msg: Message> Serialize for ActorRef<Msg> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.path().serialize(serializer)
+    }
+}
+
+impl<T: Message + Serialize> Serialize for Envelope<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Envelope", 2)?;
+        s.serialize_field("sender", &self.sender)?;
+        s.serialize_field("msg", &self.msg)?;
+        s.end()
+    }
+}
+
+impl Serialize for SystemEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            SystemEvent::ActorCreated(evt) => {
+                serializer.serialize_newtype_variant("SystemEvent", 0, "ActorCreated", evt)
+            }
+            SystemEvent::ActorRestarted(evt) => {
+                serializer.serialize_newtype_variant("SystemEvent", 1, "ActorRestarted", evt)
+            }
+            SystemEvent::ActorTerminated(evt) => {
+                serializer.serialize_newtype_variant("SystemEvent", 2, "ActorTerminated", evt)
+            }
+            SystemEvent::ActorFailed(evt) => {
+                serializer.serialize_newtype_variant("SystemEvent", 3, "ActorFailed", evt)
+            }
+            SystemEvent::OverloadChanged(evt) => {
+                serializer.serialize_newtype_variant("SystemEvent", 4, "OverloadChanged", evt)
+            }
+            SystemEvent::HealthChanged(evt) => {
+                serializer.serialize_newtype_variant("SystemEvent", 5, "HealthChanged", evt)
+            }
+        }
+    }
+}
+
+// Serialize events that only carry the actor
+macro_rules! serialize_actor_event {
+    ($evt:ident) => {
+        impl Serialize for $evt {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut s = serializer.serialize_struct(stringify!($evt), 1)?;
+                s.serialize_field("actor", &self.actor)?;
+                s.end()
+            }
+        }
+    };
+}
+
+serialize_actor_event!(ActorCreated);
+serialize_actor_event!(ActorRestarted);
+serialize_actor_event!(ActorTerminated);
+
+impl Serialize for ActorFailed {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("ActorFailed", 3)?;
+        s.serialize_field("actor", &self.actor)?;
+        s.serialize_field("reason", &self.reason)?;
+        s.serialize_field("restart_count", &self.restart_count)?;
+        s.end()
+    }
+}
+
+impl Serialize for OverloadChanged {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("OverloadChanged", 3)?;
+        s.serialize_field("overloaded", &self.overloaded)?;
+        s.serialize_field("rate", &self.rate)?;
+        s.serialize_field("queued", &self.queued)?;
+        s.end()
+    }
+}
+
+impl Serialize for HealthChanged {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("HealthChanged", 2)?;
+        s.serialize_field("actor", &self.actor)?;
+        s.serialize_field("health", &self.health)?;
+        s.end()
+    }
+}
+
+impl Serialize for Health {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Health::Healthy => serializer.serialize_unit_variant("Health", 0, "Healthy"),
+            Health::Degraded(reason) => {
+                serializer.serialize_newtype_variant("Health", 1, "Degraded", reason)
+            }
+            Health::Unhealthy(reason) => {
+                serializer.serialize_newtype_variant("Health", 2, "Unhealthy", reason)
+            }
+        }
+    }
+}
+
+impl Serialize for DeadLetterReason {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (index, name) = match self {
+            DeadLetterReason::Terminated => (0, "Terminated"),
+            DeadLetterReason::Overloaded => (1, "Overloaded"),
+            DeadLetterReason::Throttled => (2, "Throttled"),
+        };
+        serializer.serialize_unit_variant("DeadLetterReason", index, name)
+    }
+}
+
+// The payload is the original message as `Any`, it isn't serialized
+impl Serialize for DeadLetter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("DeadLetter", 4)?;
+        s.serialize_field("msg", &self.msg)?;
+        s.serialize_field("sender", &self.sender)?;
+        s.serialize_field("recipient", &self.recipient)?;
+        s.serialize_field("reason", &self.reason)?;
+        s.end()
+    }
+}
+
+impl Serialize for LogRecord {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("LogRecord", 4)?;
+        s.serialize_field("time", &self.time.to_rfc3339())?;
+        s.serialize_field("level", self.level.as_str())?;
+        s.serialize_field("actor", &self.actor)?;
+        s.serialize_field("event", &self.event)?;
+        s.end()
+    }
+}
+
+impl Serialize for LogEntry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("LogEntry", 4)?;
+        s.serialize_field("time", &self.time.to_rfc3339())?;
+        s.serialize_field("level", self.level.as_str())?;
+        s.serialize_field("module", &self.module)?;
+        s.serialize_field("msg", &self.msg)?;
+        s.end()
+    }
+}
+
+impl Serialize for LogEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            LogEvent::ActorCreated => {
+                serializer.serialize_unit_variant("LogEvent", 0, "ActorCreated")
+            }
+            LogEvent::ActorRestarted => {
+                serializer.serialize_unit_variant("LogEvent", 1, "ActorRestarted")
+            }
+            LogEvent::ActorTerminated => {
+                serializer.serialize_unit_variant("LogEvent", 2, "ActorTerminated")
+            }
+            LogEvent::ActorFailed(evt) => {
+                serializer.serialize_newtype_variant("LogEvent", 3, "ActorFailed", evt)
+            }
+            LogEvent::DeadLetter(dl) => {
+                serializer.serialize_newtype_variant("LogEvent", 4, "DeadLetter", dl)
+            }
+        }
+    }
+}
+
+impl Serialize for ActorTreeNode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("ActorTreeNode", 5)?;
+        s.serialize_field("path", &self.path)?;
+        s.serialize_field("name", &self.name)?;
+        s.serialize_field("children_count", &self.children_count)?;
+        s.serialize_field("mailbox_depth", &self.mailbox_depth)?;
+        s.serialize_field("children", &self.children)?;
+        s.end()
+    }
+}
+
+// The uptime is serialized in milliseconds
+impl Serialize for ActorInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("ActorInfo", 5)?;
+        s.serialize_field("path", &self.path)?;
+        s.serialize_field("uptime_millis", &(self.uptime.as_millis() as u64))?;
+        s.serialize_field("msg_processed", &self.msg_processed)?;
+        s.serialize_field("mailbox_len", &self.mailbox_len)?;
+        s.serialize_field("restarts", &self.restarts)?;
+        s.end()
+    }
+}
@@ -1,32 +1,184 @@
+pub(crate) mod clock;
+pub(crate) mod dead_letters;
+pub(crate) mod event_sink;
+pub(crate) mod extension;
+pub(crate) mod governor;
+pub(crate) mod health;
 pub(crate) mod logger;
+#[cfg(feature = "persist")]
+pub(crate) mod persist;
+pub(crate) mod receptionist;
+pub(crate) mod respawn;
 pub(crate) mod timer;
+pub(crate) mod tree;
 
-use std::fmt;
+use std::{fmt, time::Duration};
 
-use crate::actor::BasicActorRef;
+use crate::actor::{ActorPath, BasicActorRef, ReplyTo};
 
 // Public riker::system API (plus the pub data types in this file)
-pub use self::timer::{BasicTimer, ScheduleId, Timer};
+pub use self::clock::{Clock, ClockRef, ManualClock, SystemClock};
+pub use self::event_sink::EventSink;
+pub use self::extension::SystemExtension;
+pub use self::logger::{LogEntry, LogEvent, LogRecord, LoggerRef, LoggingChannelMsg, SetLogFilter};
+#[cfg(feature = "persist")]
+pub use self::persist::{FileStore, PersistentScheduler, ScheduleStore, StoredSchedule};
+pub use self::receptionist::Listing;
+pub use self::respawn::{RespawnPolicy, RespawnRequest};
+pub use self::timer::{
+    BasicTimer, Job, ManualTimer, OnceJob, RepeatJob, ScheduleId, SysJob, Timer, TimerFactory,
+    TimerRef,
+};
+pub use self::tree::{ActorTreeDiff, ActorTreeNode};
+pub use crate::validate::NameValidator;
 
 #[derive(Clone, Debug)]
 pub enum SystemMsg {
     ActorInit,
     Command(SystemCmd),
     Event(SystemEvent),
-    Failed(BasicActorRef),
-}
+    Failed(FailureInfo),
+    TaskFailed(TaskFailed),
+    ReceiveTimeout(ReceiveTimeout),
+
+    /// Reply with the actor's `ActorInfo`, handled by the system, not the actor
+    Identify(ReplyTo<ActorInfo>),
 
-unsafe impl Send for SystemMsg {}
+    /// Reply with the actor's `Actor::health`, handled by the kernel, not `recv`
+    CheckHealth(ReplyTo<Health>),
+}
 
 #[derive(Clone, Debug)]
 pub enum SystemCmd {
+    /// Stop the actor immediately, ahead of any queued user messages
     Stop,
+
+    /// Stop the actor once the user messages queued at the time
+    /// the command is sent have been processed
+    PoisonPill,
+
     Restart,
+
+    /// Stop processing user messages until `Resume` is received,
+    /// the command is forwarded to the actor's children
+    Suspend,
+
+    /// Resume processing user messages after `Suspend`
+    Resume,
+}
+
+/// A live actor's state, see `ActorReference::identify`
+#[derive(Clone, Debug)]
+pub struct ActorInfo {
+    pub path: ActorPath,
+
+    /// Time since the actor was created, by the system clock
+    pub uptime: Duration,
+
+    /// Number of user messages the actor has processed, across restarts
+    pub msg_processed: u64,
+
+    /// Number of user messages waiting in the actor's mailbox
+    pub mailbox_len: usize,
+
+    /// Number of times the actor has been restarted
+    pub restarts: usize,
+}
+
+/// An actor's health, see `Actor::health`
+///
+/// Ordered from best to worst.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Health {
+    Healthy,
+
+    /// Working, but impaired, e.g. a fallback is in use
+    Degraded(String),
+    Unhealthy(String),
+}
+
+/// The health of the actors watched with `ActorSystem::watch_health`
+#[derive(Clone, Debug)]
+pub struct HealthReport {
+    /// The watched actors, ordered by path
+    pub actors: Vec<ActorHealth>,
+}
+
+impl HealthReport {
+    /// The worst health of any watched actor, `Healthy` if none is watched
+    pub fn status(&self) -> Health {
+        self.actors
+            .iter()
+            .map(|a| a.health.clone())
+            .max()
+            .unwrap_or(Health::Healthy)
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.status() == Health::Healthy
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ActorHealth {
+    pub actor: BasicActorRef,
+    pub health: Health,
+}
+
+/// Why an actor failed, passed to its supervisor's `Actor::decide`
+#[derive(Clone, Debug)]
+pub struct FailureInfo {
+    /// The failed actor
+    pub actor: BasicActorRef,
+
+    /// The panic message, if the panic payload was a string
+    pub panic_msg: Option<String>,
+
+    /// Number of times the failed actor has been restarted
+    pub restart_count: usize,
+}
+
+/// A future started with `ctx.run_supervised` panicked or returned an error
+#[derive(Clone, Debug)]
+pub struct TaskFailed {
+    pub error: String,
+    pub on_failure: TaskFailure,
+}
+
+/// How the failure of a supervised future is handled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TaskFailure {
+    /// Report the failure to the actor's `sys_recv` only
+    Notify,
+
+    /// Report the failure to the actor's `sys_recv` and then fail the actor,
+    /// deferring to its supervisor's strategy as if the actor had panicked
+    Fail,
 }
 
-impl Into<SystemMsg> for SystemCmd {
-    fn into(self) -> SystemMsg {
-        SystemMsg::Command(self)
+/// No user message was received within the window set with `ctx.set_receive_timeout`
+///
+/// Received in `sys_recv`, then again after each further window without a message.
+#[derive(Clone, Debug)]
+pub struct ReceiveTimeout {
+    pub(crate) id: ScheduleId,
+}
+
+impl From<ReceiveTimeout> for SystemMsg {
+    fn from(timeout: ReceiveTimeout) -> Self {
+        SystemMsg::ReceiveTimeout(timeout)
+    }
+}
+
+impl From<TaskFailed> for SystemMsg {
+    fn from(failed: TaskFailed) -> Self {
+        SystemMsg::TaskFailed(failed)
+    }
+}
+
+impl From<SystemCmd> for SystemMsg {
+    fn from(cmd: SystemCmd) -> Self {
+        SystemMsg::Command(cmd)
     }
 }
 
@@ -40,11 +192,20 @@ pub enum SystemEvent {
 
     /// An actor was started
     ActorTerminated(ActorTerminated),
+
+    /// An actor failed and its supervisor applied its strategy
+    ActorFailed(ActorFailed),
+
+    /// The system entered or left the overloaded state
+    OverloadChanged(OverloadChanged),
+
+    /// The health of an actor watched with `ActorSystem::watch_health` changed
+    HealthChanged(HealthChanged),
 }
 
-impl Into<SystemMsg> for SystemEvent {
-    fn into(self) -> SystemMsg {
-        SystemMsg::Event(self)
+impl From<SystemEvent> for SystemMsg {
+    fn from(evt: SystemEvent) -> Self {
+        SystemMsg::Event(evt)
     }
 }
 
@@ -63,39 +224,95 @@ pub struct ActorTerminated {
     pub actor: BasicActorRef,
 }
 
-impl Into<SystemEvent> for ActorCreated {
-    fn into(self) -> SystemEvent {
-        SystemEvent::ActorCreated(self)
+#[derive(Clone, Debug)]
+pub struct ActorFailed {
+    pub actor: BasicActorRef,
+
+    /// The panic message or task error, if known
+    pub reason: Option<String>,
+
+    /// Number of times the actor had been restarted before failing
+    pub restart_count: usize,
+}
+
+impl From<&FailureInfo> for ActorFailed {
+    fn from(info: &FailureInfo) -> Self {
+        ActorFailed {
+            actor: info.actor.clone(),
+            reason: info.panic_msg.clone(),
+            restart_count: info.restart_count,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OverloadChanged {
+    pub overloaded: bool,
+
+    /// User messages per second at the time of the change
+    pub rate: u64,
+
+    /// User messages waiting in mailboxes at the time of the change
+    pub queued: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct HealthChanged {
+    pub actor: BasicActorRef,
+    pub health: Health,
+}
+
+impl From<ActorCreated> for SystemEvent {
+    fn from(evt: ActorCreated) -> Self {
+        SystemEvent::ActorCreated(evt)
     }
 }
 
-impl Into<SystemEvent> for ActorRestarted {
-    fn into(self) -> SystemEvent {
-        SystemEvent::ActorRestarted(self)
+impl From<ActorFailed> for SystemEvent {
+    fn from(evt: ActorFailed) -> Self {
+        SystemEvent::ActorFailed(evt)
     }
 }
 
-impl Into<SystemEvent> for ActorTerminated {
-    fn into(self) -> SystemEvent {
-        SystemEvent::ActorTerminated(self)
+impl From<ActorRestarted> for SystemEvent {
+    fn from(evt: ActorRestarted) -> Self {
+        SystemEvent::ActorRestarted(evt)
     }
 }
 
-impl Into<SystemMsg> for ActorCreated {
-    fn into(self) -> SystemMsg {
-        SystemMsg::Event(SystemEvent::ActorCreated(self))
+impl From<ActorTerminated> for SystemEvent {
+    fn from(evt: ActorTerminated) -> Self {
+        SystemEvent::ActorTerminated(evt)
     }
 }
 
-impl Into<SystemMsg> for ActorRestarted {
-    fn into(self) -> SystemMsg {
-        SystemMsg::Event(SystemEvent::ActorRestarted(self))
+impl From<OverloadChanged> for SystemEvent {
+    fn from(evt: OverloadChanged) -> Self {
+        SystemEvent::OverloadChanged(evt)
     }
 }
 
-impl Into<SystemMsg> for ActorTerminated {
-    fn into(self) -> SystemMsg {
-        SystemMsg::Event(SystemEvent::ActorTerminated(self))
+impl From<HealthChanged> for SystemEvent {
+    fn from(evt: HealthChanged) -> Self {
+        SystemEvent::HealthChanged(evt)
+    }
+}
+
+impl From<ActorCreated> for SystemMsg {
+    fn from(evt: ActorCreated) -> Self {
+        SystemMsg::Event(SystemEvent::ActorCreated(evt))
+    }
+}
+
+impl From<ActorRestarted> for SystemMsg {
+    fn from(evt: ActorRestarted) -> Self {
+        SystemMsg::Event(SystemEvent::ActorRestarted(evt))
+    }
+}
+
+impl From<ActorTerminated> for SystemMsg {
+    fn from(evt: ActorTerminated) -> Self {
+        SystemMsg::Event(SystemEvent::ActorTerminated(evt))
     }
 }
 
@@ -104,24 +321,243 @@ pub enum SystemEventType {
     ActorTerminated,
     ActorRestarted,
     ActorCreated,
+    ActorFailed,
+    OverloadChanged,
+    HealthChanged,
 }
 
+/// Error type when an `ActorSystem` fails to start
+///
+/// `source` returns the underlying error, if any, e.g. the `io::Error`
+/// of a thread pool that couldn't spawn its threads.
 pub enum SystemError {
-    ModuleFailed(String),
+    /// A system actor failed to start
+    ModuleFailed {
+        name: String,
+        source: CreateError,
+    },
+
+    /// A system channel failed to start
+    ChannelFailed {
+        name: String,
+        source: CreateError,
+    },
+
     InvalidName(String),
+    InvalidConfig(Vec<ConfigError>),
+
+    /// A thread pool, the default dispatcher, a named dispatcher or the
+    /// blocking pool, failed to start
+    Executor(io::Error),
+
+    /// The timer failed to start, see `TimerFactory`
+    Timer(io::Error),
 }
 
 impl fmt::Display for SystemError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            SystemError::ModuleFailed(ref m) => f.write_str(&format!(
+            SystemError::ModuleFailed { ref name, .. } => f.write_str(&format!(
                 "Failed to create actor system. Cause: Sub module failed to start ({})",
-                m
+                name
+            )),
+            SystemError::ChannelFailed { ref name, .. } => f.write_str(&format!(
+                "Failed to create actor system. Cause: System channel failed to start ({})",
+                name
             )),
             SystemError::InvalidName(ref name) => f.write_str(&format!(
                 "Failed to create actor system. Cause: Invalid actor system name ({})",
                 name
             )),
+            SystemError::InvalidConfig(ref errors) => {
+                let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                f.write_str(&format!(
+                    "Failed to create actor system. Cause: Invalid configuration ({})",
+                    errors.join("; ")
+                ))
+            }
+            SystemError::Executor(ref e) => f.write_str(&format!(
+                "Failed to create actor system. Cause: Thread pool failed to start ({})",
+                e
+            )),
+            SystemError::Timer(ref e) => f.write_str(&format!(
+                "Failed to create actor system. Cause: Timer failed to start ({})",
+                e
+            )),
+        }
+    }
+}
+
+impl Error for SystemError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            SystemError::ModuleFailed { ref source, .. }
+            | SystemError::ChannelFailed { ref source, .. } => Some(source),
+            SystemError::InvalidName(_) => None,
+            // the first invalid key, `Display` lists them all
+            SystemError::InvalidConfig(ref errors) => errors.first().map(|e| e as &dyn Error),
+            SystemError::Executor(ref e) | SystemError::Timer(ref e) => Some(e),
+        }
+    }
+}
+
+/// Error type when no live actor exists at the path given to
+/// `ActorSystem::stop_path` or `ActorSystem::restart_path`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActorNotFound(pub String);
+
+impl fmt::Display for ActorNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "No actor exists at the path ({})", self.0)
+    }
+}
+
+impl Error for ActorNotFound {}
+
+/// A missing or invalid configuration key
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigError {
+    Missing(String),
+    Invalid { key: String, reason: String },
+}
+
+impl ConfigError {
+    fn invalid(key: &str, reason: impl fmt::Display) -> Self {
+        ConfigError::Invalid {
+            key: key.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::Missing(ref key) => write!(f, "{} is missing", key),
+            ConfigError::Invalid {
+                ref key,
+                ref reason,
+            } => write!(f, "{} is invalid: {}", key, reason),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+// Fills in the defaults of missing keys and validates every key used by riker,
+// so that reading config once the system is created can't fail
+fn valid_config(mut cfg: Config) -> Result<Config, SystemError> {
+    set_defaults(&mut cfg);
+
+    let mut errors = match SystemSettings::from_config(&cfg) {
+        Ok(_) => Vec::new(),
+        Err(errors) => errors,
+    };
+
+    for &key in &["debug", "scheduler.coalesce_repeats", "governor.enabled"] {
+        read_key(cfg.get_bool(key), key, &mut errors);
+    }
+
+    for &key in &["log.log_format", "log.date_format", "log.time_format"] {
+        read_key(cfg.get_str(key), key, &mut errors);
+    }
+
+    let positive = |key: &str, errors: &mut Vec<ConfigError>| {
+        if let Some(value) = read_key(cfg.get_int(key), key, errors) {
+            if value <= 0 {
+                errors.push(ConfigError::invalid(key, "must be a positive integer"));
+            }
+        }
+    };
+
+    for &key in &[
+        "dispatcher.pool_size",
+        "dispatcher.blocking_pool_size",
+        "scheduler.frequency_millis",
+        "health.interval_millis",
+        "deadletters.log_every",
+        "testkit.expect_timeout_millis",
+    ] {
+        positive(key, &mut errors);
+    }
+
+    let key = "deadletters.suppress";
+    read_key(cfg.get_array(key), key, &mut errors);
+
+    let key = "testkit.time_factor";
+    if let Some(factor) = read_key(cfg.get_float(key), key, &mut errors) {
+        if factor <= 0.0 {
+            errors.push(ConfigError::invalid(key, "must be a positive number"));
+        }
+    }
+
+    let names = cfg.get_table("dispatchers").unwrap_or_default();
+    for name in names.keys() {
+        positive(&format!("dispatchers.{}.pool_size", name), &mut errors);
+    }
+
+    for &key in &[
+        "dispatcher.stack_size",
+        "deadletters.capacity",
+        "deadletters.replay_capacity",
+        "governor.max_msgs_per_sec",
+        "governor.resume_msgs_per_sec",
+        "governor.max_queued",
+        "governor.resume_queued",
+        "governor.delay_millis",
+    ] {
+        if let Some(value) = read_key(cfg.get_int(key), key, &mut errors) {
+            if value < 0 {
+                errors.push(ConfigError::invalid(key, "must not be negative"));
+            }
+        }
+    }
+
+    let one_of = |key: &str, allowed: &[&str], errors: &mut Vec<ConfigError>| {
+        if let Some(value) = read_key(cfg.get_str(key), key, errors) {
+            if !allowed.contains(&value.as_str()) {
+                errors.push(ConfigError::invalid(
+                    key,
+                    format!("expected one of {:?}, found \"{}\"", allowed, value),
+                ));
+            }
+        }
+    };
+
+    one_of("governor.policy", &["shed", "delay"], &mut errors);
+
+    let key = "log.level";
+    if let Some(level) = read_key(cfg.get_str(key), key, &mut errors) {
+        if slog::Level::from_str(&level).is_err() {
+            errors.push(ConfigError::invalid(
+                key,
+                format!("\"{}\" is not a log level", level),
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(cfg)
+    } else {
+        Err(SystemError::InvalidConfig(errors))
+    }
+}
+
+// Reads a key, recording it in `errors` if it's missing or has the wrong type
+fn read_key<T>(
+    value: Result<T, config::ConfigError>,
+    key: &str,
+    errors: &mut Vec<ConfigError>,
+) -> Option<T> {
+    match value {
+        Ok(value) => Some(value),
+        Err(config::ConfigError::NotFound(_)) => {
+            errors.push(ConfigError::Missing(key.to_string()));
+            None
+        }
+        Err(e) => {
+            errors.push(ConfigError::invalid(key, e));
+            None
         }
     }
 }
@@ -132,9 +568,17 @@ impl fmt::Debug for SystemError {
     }
 }
 use std::{
+    collections::{HashMap, VecDeque},
+    convert::TryFrom,
+    error::Error,
+    io,
     ops::Deref,
-    sync::{Arc, Mutex},
-    time::{Duration, Instant},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
 };
 
 use chrono::prelude::*;
@@ -150,11 +594,26 @@ use futures::{
 use uuid::Uuid;
 
 use crate::{
-    actor::{props::ActorFactory, *},
-    kernel::provider::{create_root, Provider},
-    load_config,
+    actor::{
+        props::ActorFactory,
+        selection::{from_user_root, SelectionCaches, SelectionInvalidator},
+        *,
+    },
+    kernel::{
+        provider::{create_root, Provider},
+        queue::QueueKind,
+        Dispatcher, DispatcherRef, Fairness,
+    },
+    load_config, set_defaults,
+    system::clock::delay_until,
+    system::dead_letters::{DeadLetterBuffer, DeadLetterBufferMsg},
+    system::event_sink::{EventPublisher, SharedSink},
+    system::extension::SystemExtensions,
+    system::governor::Governor,
+    system::health::{HealthMonitor, HealthMsg},
     system::logger::*,
-    system::timer::*,
+    system::receptionist::{Receptionist, ReceptionistMsg},
+    system::respawn::RespawnPolicies,
     validate::{validate_name, InvalidPath},
     AnyMessage, Message,
 };
@@ -167,9 +626,22 @@ pub struct ProtoSystem {
     id: Uuid,
     name: String,
     pub host: Arc<str>,
+    port: Option<u16>,
     config: Config,
     pub(crate) sys_settings: SystemSettings,
+    pub(crate) governor: Option<Governor>,
+    dispatcher: DispatcherRef,
+    dispatchers: HashMap<String, DispatcherRef>,
+    retained_dead_letters: RetainedDeadLetters,
+    selection_caches: SelectionCaches,
+    respawn_policies: RespawnPolicies,
+    termination: TerminationWatch,
+    clock: ClockRef,
+    started: Instant,
     started_at: DateTime<Utc>,
+    extensions: SystemExtensions,
+    name_validator: Option<Box<dyn NameValidator>>,
+    closed: AtomicBool,
 }
 
 #[derive(Default)]
@@ -178,6 +650,13 @@ pub struct SystemBuilder {
     cfg: Option<Config>,
     log: Option<Logger>,
     exec: Option<ThreadPool>,
+    dispatcher: Option<DispatcherRef>,
+    clock: Option<ClockRef>,
+    timer: Option<Box<dyn TimerFactory>>,
+    host: Option<String>,
+    port: Option<u16>,
+    extensions: Vec<fn(&ActorSystem)>,
+    name_validator: Option<Box<dyn NameValidator>>,
 }
 
 impl SystemBuilder {
@@ -187,14 +666,38 @@ impl SystemBuilder {
 
     pub fn create(self) -> Result<ActorSystem, SystemError> {
         let name = self.name.unwrap_or_else(|| "riker".to_string());
-        let cfg = self.cfg.unwrap_or_else(load_config);
-        let exec = self.exec.unwrap_or_else(|| default_exec(&cfg));
+        let cfg = valid_config(self.cfg.unwrap_or_else(load_config))?;
+        let exec = match self.exec {
+            Some(exec) => exec,
+            None => default_exec(&name, &cfg)?,
+        };
         let log = self
             .log
-            .map(|log| LoggingSystem::new(log, None))
+            .map(|log| LoggingSystem::new(log, slog::Level::Trace))
             .unwrap_or_else(|| default_log(&cfg));
+        let clock = self.clock.unwrap_or_else(|| Arc::new(SystemClock));
+        let timer = self.timer.unwrap_or_else(|| Box::new(BasicTimer::start));
+        let addr = NodeAddr {
+            host: self.host.map_or_else(|| default_addr().host, Arc::from),
+            port: self.port,
+        };
 
-        ActorSystem::create(name.as_ref(), exec, log, cfg)
+        let sys = ActorSystem::create(
+            name.as_ref(),
+            exec,
+            self.dispatcher,
+            log,
+            cfg,
+            clock,
+            &*timer,
+            addr,
+            self.name_validator,
+        )?;
+        for register in self.extensions {
+            register(&sys);
+        }
+
+        Ok(sys)
     }
 
     pub fn name(self, name: &str) -> Self {
@@ -218,12 +721,101 @@ impl SystemBuilder {
         }
     }
 
+    /// Run the actors on `dispatcher` instead of the system executor
+    ///
+    /// Actors created `WithDispatcher` still run on the named dispatchers.
+    /// `exec` remains the executor of `ActorSystem::run` and of the tasks
+    /// spawned by actors.
+    pub fn dispatcher(self, dispatcher: impl Dispatcher) -> Self {
+        SystemBuilder {
+            dispatcher: Some(Arc::new(dispatcher)),
+            ..self
+        }
+    }
+
     pub fn log(self, log: Logger) -> Self {
         SystemBuilder {
             log: Some(log),
             ..self
         }
     }
+
+    /// Use `clock` instead of the operating system clock, e.g. a `ManualClock` in tests
+    pub fn clock(self, clock: impl Clock + 'static) -> Self {
+        SystemBuilder {
+            clock: Some(Arc::new(clock)),
+            ..self
+        }
+    }
+
+    /// Schedule on `timer` instead of the default timer thread, see `ManualTimer`
+    ///
+    /// The timer's clock becomes the system clock.
+    pub fn timer(self, timer: ManualTimer) -> Self {
+        SystemBuilder {
+            clock: Some(Arc::new(timer.clock().clone())),
+            timer: Some(Box::new(timer)),
+            ..self
+        }
+    }
+
+    /// Start the timer the system schedules on with `factory`, instead of `BasicTimer::start`
+    pub fn timer_factory(self, factory: impl TimerFactory + 'static) -> Self {
+        SystemBuilder {
+            timer: Some(Box::new(factory)),
+            ..self
+        }
+    }
+
+    /// The host the system is identified by in actor URIs, defaults to `localhost`
+    pub fn host(self, host: &str) -> Self {
+        SystemBuilder {
+            host: Some(host.to_string()),
+            ..self
+        }
+    }
+
+    /// The port the system is identified by in actor URIs
+    pub fn port(self, port: u16) -> Self {
+        SystemBuilder {
+            port: Some(port),
+            ..self
+        }
+    }
+
+    /// Only allow actors under `/user` to be created with names `validator` accepts
+    ///
+    /// Names must follow the actor name grammar, see `ActorRefFactory`, the
+    /// validator can restrict them further, e.g. to ASCII. The names of
+    /// system and temporary actors are chosen by the system and aren't
+    /// passed to the validator.
+    pub fn name_validator(self, validator: impl NameValidator) -> Self {
+        SystemBuilder {
+            name_validator: Some(Box::new(validator)),
+            ..self
+        }
+    }
+
+    /// Register the extension `E` once the system has started, see `ActorSystem::register_extension`
+    pub fn extension<E: SystemExtension>(mut self) -> Self {
+        self.extensions.push(|sys| {
+            sys.register_extension::<E>();
+        });
+        self
+    }
+}
+
+// The address of the node a system runs on
+pub(crate) struct NodeAddr {
+    host: Arc<str>,
+    port: Option<u16>,
+}
+
+fn default_addr() -> NodeAddr {
+    NodeAddr {
+        host: Arc::from("localhost"),
+        port: None,
+    }
 }
 
 /// Holds fields related to logging system.
@@ -233,13 +825,23 @@ pub struct LoggingSystem {
     log: Logger,
     /// Global logger guard
     global_logger_guard: Option<GlobalLoggerGuard>,
+    /// Runtime level and filters, see `LoggerRef`
+    control: Arc<LogControl>,
 }
 
 impl LoggingSystem {
-    pub(crate) fn new(log: Logger, global_logger_guard: Option<GlobalLoggerGuard>) -> Self {
+    // Lines below `level` aren't logged to `log`
+    pub(crate) fn new(log: Logger, level: slog::Level) -> Self {
+        let control = Arc::new(LogControl::new(level));
+        let drain = ControlledDrain {
+            inner: log,
+            control: control.clone(),
+        };
+
         Self {
-            log,
-            global_logger_guard,
+            log: Logger::root(drain, slog::o!()),
+            global_logger_guard: None,
+            control,
         }
     }
 }
@@ -257,19 +859,22 @@ impl Deref for LoggingSystem {
 /// The `ActorSystem` provides a runtime on which actors are executed.
 /// It also provides common services such as channels and scheduling.
 /// The `ActorSystem` is the heart of a Riker application,
-/// starting several threads when it is created. Create only one instance
-/// of `ActorSystem` per application.
-#[allow(dead_code)]
+/// starting several threads when it is created. Several systems can run
+/// in one process, each with its own executors and timer. Their threads
+/// are named after the system, see also `ActorSystem::new_isolated`.
 #[derive(Clone)]
 pub struct ActorSystem {
     proto: Arc<ProtoSystem>,
     sys_actors: Option<SysActors>,
     log: LoggingSystem,
-    debug: bool,
     pub exec: ThreadPool,
+    blocking_exec: ThreadPool,
     pub timer: TimerRef,
     pub sys_channels: Option<SysChannels>,
     pub(crate) provider: Provider,
+    dl_buffer: Option<ActorRef<DeadLetterBufferMsg>>,
+    receptionist: Option<ActorRef<ReceptionistMsg>>,
+    health: Option<ActorRef<HealthMsg>>,
 }
 
 impl ActorSystem {
@@ -277,40 +882,108 @@ impl ActorSystem {
     ///
     /// Requires a type that implements the `Model` trait.
     pub fn new() -> Result<ActorSystem, SystemError> {
-        let cfg = load_config();
-        let exec = default_exec(&cfg);
+        let cfg = valid_config(load_config())?;
+        let exec = default_exec("riker", &cfg)?;
         let log = default_log(&cfg);
 
-        ActorSystem::create("riker", exec, log, cfg)
+        ActorSystem::create(
+            "riker",
+            exec,
+            None,
+            log,
+            cfg,
+            Arc::new(SystemClock),
+            &BasicTimer::start,
+            default_addr(),
+            None,
+        )
     }
 
     /// Create a new `ActorSystem` instance with provided name
     ///
     /// Requires a type that implements the `Model` trait.
     pub fn with_name(name: &str) -> Result<ActorSystem, SystemError> {
-        let cfg = load_config();
-        let exec = default_exec(&cfg);
+        let cfg = valid_config(load_config())?;
+        let exec = default_exec(name, &cfg)?;
         let log = default_log(&cfg);
 
-        ActorSystem::create(name, exec, log, cfg)
+        ActorSystem::create(
+            name,
+            exec,
+            None,
+            log,
+            cfg,
+            Arc::new(SystemClock),
+            &BasicTimer::start,
+            default_addr(),
+            None,
+        )
     }
 
     /// Create a new `ActorSystem` instance bypassing default config behavior
+    ///
+    /// Keys missing from `cfg` take their default values. Creation fails with
+    /// `SystemError::InvalidConfig`, listing every invalid key, if any key has
+    /// a value of the wrong type or out of range.
     pub fn with_config(name: &str, cfg: Config) -> Result<ActorSystem, SystemError> {
-        let exec = default_exec(&cfg);
+        let cfg = valid_config(cfg)?;
+        let exec = default_exec(name, &cfg)?;
         let log = default_log(&cfg);
 
-        ActorSystem::create(name, exec, log, cfg)
+        ActorSystem::create(
+            name,
+            exec,
+            None,
+            log,
+            cfg,
+            Arc::new(SystemClock),
+            &BasicTimer::start,
+            default_addr(),
+            None,
+        )
     }
 
+    /// Create a new `ActorSystem` instance sharing no process wide state
+    ///
+    /// Systems normally install their logger as the global logger, so that
+    /// the `log` macros are logged too. The global logger is shared by the
+    /// systems created while it's installed. An isolated system only logs
+    /// to its own logger, so it can be created and dropped, e.g. per test,
+    /// without affecting other systems. A system created by `SystemBuilder`
+    /// with a `log` is isolated too.
+    pub fn new_isolated(name: &str) -> Result<ActorSystem, SystemError> {
+        let cfg = valid_config(load_config())?;
+        let exec = default_exec(name, &cfg)?;
+        let log = isolated_log(&cfg);
+
+        ActorSystem::create(
+            name,
+            exec,
+            None,
+            log,
+            cfg,
+            Arc::new(SystemClock),
+            &BasicTimer::start,
+            default_addr(),
+            None,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn create(
         name: &str,
         exec: ThreadPool,
+        dispatcher: Option<DispatcherRef>,
         log: LoggingSystem,
         cfg: Config,
+        clock: ClockRef,
+        timer: &dyn TimerFactory,
+        addr: NodeAddr,
+        name_validator: Option<Box<dyn NameValidator>>,
     ) -> Result<ActorSystem, SystemError> {
         validate_name(name).map_err(|_| SystemError::InvalidName(name.into()))?;
-        // Process Configuration
+        let sys_settings = SystemSettings::from_config(&cfg).map_err(SystemError::InvalidConfig)?;
+        // Process Configuration, validated by `valid_config`
         let debug = cfg.get_bool("debug").unwrap();
 
         // Until the logger has started, use println
@@ -318,30 +991,48 @@ impl ActorSystem {
             debug!(log, "Starting actor system: System[{}]", name);
         }
 
-        let prov = Provider::new(log.clone());
-        let timer = BasicTimer::start(&cfg);
+        let prov = Provider::new();
+        let timer = timer
+            .start(name, &cfg, clock.clone())
+            .map_err(SystemError::Timer)?;
 
         // 1. create proto system
         let proto = ProtoSystem {
             id: Uuid::new_v4(),
             name: name.to_string(),
-            host: Arc::from("localhost"),
+            host: addr.host,
+            port: addr.port,
             config: cfg.clone(),
-            sys_settings: SystemSettings::from(&cfg),
-            started_at: Utc::now(),
+            sys_settings,
+            governor: Governor::from_config(&cfg),
+            dispatcher: dispatcher.unwrap_or_else(|| Arc::new(exec.clone())),
+            dispatchers: dispatchers(name, &cfg)?,
+            retained_dead_letters: RetainedDeadLetters::from(&cfg),
+            selection_caches: SelectionCaches::default(),
+            respawn_policies: RespawnPolicies::default(),
+            termination: TerminationWatch::default(),
+            started: clock.now(),
+            started_at: clock.wall(),
+            clock,
+            extensions: SystemExtensions::default(),
+            name_validator,
+            closed: AtomicBool::new(false),
         };
 
         // 2. create uninitialized system
         let mut sys = ActorSystem {
             proto: Arc::new(proto),
-            debug,
             exec,
+            blocking_exec: blocking_exec(name, &cfg)?,
             log,
             // event_store: None,
             timer,
             sys_channels: None,
             sys_actors: None,
             provider: prov.clone(),
+            dl_buffer: None,
+            receptionist: None,
+            health: None,
         };
 
         // 3. create initial actor hierarchy
@@ -356,105 +1047,445 @@ impl ActorSystem {
             &prov,
             &sys,
             "dl_logger",
-            (sys.dead_letters().clone(), sys.log()),
+            (
+                sys.dead_letters().clone(),
+                sys.log(),
+                DeadLetterLogConfig::from(&cfg),
+            ),
         )?;
 
-        sys.complete_start();
+        // 6. start recent dead letters buffer
+        let capacity = cfg.get_int("deadletters.capacity").unwrap() as usize;
+        sys.dl_buffer = Some(sys_actor_of_args::<DeadLetterBuffer, _>(
+            &prov,
+            &sys,
+            "dl_buffer",
+            (sys.dead_letters().clone(), capacity),
+        )?);
+
+        // 7. start cached selection invalidator
+        let _invalidator = sys_actor_of_args::<SelectionInvalidator, _>(
+            &prov,
+            &sys,
+            "selection_invalidator",
+            (sys.sys_events().clone(), sys.proto.selection_caches.clone()),
+        )?;
+
+        // 8. start the receptionist
+        sys.receptionist = Some(sys_actor_of_args::<Receptionist, _>(
+            &prov,
+            &sys,
+            "receptionist",
+            sys.sys_events().clone(),
+        )?);
+
+        // 9. start the health monitor
+        let interval = cfg.get_int("health.interval_millis").unwrap() as u64;
+        sys.health = Some(sys_actor_of_args::<HealthMonitor, _>(
+            &prov,
+            &sys,
+            "health",
+            (sys.sys_events().clone(), Duration::from_millis(interval)),
+        )?);
+
+        sys.complete_start();
+
+        debug!(sys.log, "Actor system [{}] [{}] started", sys.id(), name);
+
+        Ok(sys)
+    }
+
+    fn complete_start(&self) {
+        self.sys_actors.as_ref().unwrap().user.sys_init(self);
+    }
+
+    /// Returns the system start date
+    pub fn start_date(&self) -> &DateTime<Utc> {
+        &self.proto.started_at
+    }
+
+    /// Returns the number of seconds since the system started
+    pub fn uptime(&self) -> u64 {
+        self.uptime_millis() / 1000
+    }
+
+    /// Returns the number of milliseconds since the system started
+    pub fn uptime_millis(&self) -> u64 {
+        self.clock()
+            .now()
+            .duration_since(self.proto.started)
+            .as_millis() as u64
+    }
+
+    /// Returns the `Clock` used by the system
+    pub fn clock(&self) -> &dyn Clock {
+        self.proto.clock.as_ref()
+    }
+
+    /// Returns the hostname used when the system started
+    ///
+    /// The host is used in actor addressing, see `SystemBuilder::host`.
+    pub fn host(&self) -> Arc<str> {
+        self.proto.host.clone()
+    }
+
+    /// Returns the port used in actor addressing, see `SystemBuilder::port`
+    pub fn port(&self) -> Option<u16> {
+        self.proto.port
+    }
+
+    /// Returns the UUID assigned to the system
+    pub fn id(&self) -> Uuid {
+        self.proto.id
+    }
+
+    /// Returns the name of the system
+    pub fn name(&self) -> String {
+        self.proto.name.clone()
+    }
+
+    pub fn print_tree(&self) {
+        fn print_node(node: &ActorTreeNode, indent: &str) {
+            println!("{}└─ {}", indent, node.name);
+
+            for child in &node.children {
+                print_node(child, &(indent.to_string() + "   "));
+            }
+        }
+
+        println!("{}", self.name());
+        for child in &self.tree_snapshot().children {
+            print_node(child, "");
+        }
+    }
+
+    /// Returns a snapshot of the actor hierarchy, from the root actor
+    pub fn tree_snapshot(&self) -> ActorTreeNode {
+        ActorTreeNode::new(self.root())
+    }
+
+    /// Returns the actors created and stopped since the `prev` snapshot was taken
+    ///
+    /// Comparing snapshots taken over time shows actors that are
+    /// created but never stopped.
+    pub fn tree_diff(&self, prev: &ActorTreeNode) -> ActorTreeDiff {
+        self.tree_snapshot().diff(prev)
+    }
+
+    /// Returns the system root's actor reference
+    fn root(&self) -> &BasicActorRef {
+        &self.sys_actors.as_ref().unwrap().root
+    }
+
+    /// Returns the user root actor reference
+    pub fn user_root(&self) -> &BasicActorRef {
+        &self.sys_actors.as_ref().unwrap().user
+    }
+
+    /// Returns the system root actor reference
+    pub fn sys_root(&self) -> &BasicActorRef {
+        &self.sys_actors.as_ref().unwrap().sysm
+    }
+
+    /// Reutrns the temp root actor reference
+    pub fn temp_root(&self) -> &BasicActorRef {
+        &self.sys_actors.as_ref().unwrap().temp
+    }
+
+    /// Returns a reference to the system events channel
+    pub fn sys_events(&self) -> &ActorRef<ChannelMsg<SystemEvent>> {
+        &self.sys_channels.as_ref().unwrap().sys_events
+    }
+
+    /// Returns a reference to the dead letters channel
+    pub fn dead_letters(&self) -> &ActorRef<DLChannelMsg> {
+        &self.sys_channels.as_ref().unwrap().dead_letters
+    }
+
+    /// Returns a reference to the logging channel
+    ///
+    /// Subscribe to receive actor lifecycle events and dead letters as `LogRecord`s.
+    pub fn log_channel(&self) -> &ActorRef<LoggingChannelMsg> {
+        &self.sys_channels.as_ref().unwrap().logging
+    }
+
+    /// Returns the system's logger, to change its level and filters or subscribe to its lines
+    pub fn logger(&self) -> LoggerRef {
+        LoggerRef::new(
+            self.log.control.clone(),
+            self.sys_channels.as_ref().unwrap().log_entries.clone(),
+        )
+    }
+
+    /// Sets the maximum level of `LogRecord`s published for actors matching `path`
+    ///
+    /// `path` may contain `*` wildcards, e.g. `sys.set_log_filter("/user/db/*", Level::Debug)`.
+    pub fn set_log_filter(&self, path: &str, level: slog::Level) {
+        self.log_channel().tell(
+            SetLogFilter {
+                path: path.to_string(),
+                level,
+            },
+            None,
+        );
+    }
+
+    /// Returns the most recent dead letters, oldest first
+    ///
+    /// The number of dead letters kept is set by `deadletters.capacity`.
+    pub fn recent_dead_letters(&self) -> RecentDeadLetters {
+        let (tx, rx) = oneshot::channel();
+        let tx = Arc::new(Mutex::new(Some(tx)));
+
+        self.dl_buffer
+            .as_ref()
+            .unwrap()
+            .tell(DeadLetterBufferMsg::Query(tx), None);
+
+        rx
+    }
+
+    /// Register `actor` as providing `service`
+    ///
+    /// Clients find the actors providing a service with `lookup`, without
+    /// knowing where they are in the hierarchy. An actor may register for
+    /// several services and is deregistered when it terminates.
+    pub fn register(&self, service: &str, actor: impl Into<BasicActorRef>) {
+        self.receptionist().tell(
+            ReceptionistMsg::Register(service.to_string(), actor.into()),
+            None,
+        );
+    }
+
+    /// Remove `actor` from the actors providing `service`
+    pub fn deregister(&self, service: &str, actor: impl Into<BasicActorRef>) {
+        self.receptionist().tell(
+            ReceptionistMsg::Deregister(service.to_string(), actor.into()),
+            None,
+        );
+    }
+
+    /// Returns the actors currently registered for `service`
+    pub fn lookup(&self, service: &str) -> oneshot::Receiver<Listing> {
+        let (reply_to, rx) = ReplyTo::new();
+        self.receptionist()
+            .tell(ReceptionistMsg::Lookup(service.to_string(), reply_to), None);
+
+        rx
+    }
+
+    /// Send `actor` the `Listing` of `service` now and whenever it changes
+    pub fn subscribe_listing(&self, service: &str, actor: impl Tell<Listing>) {
+        self.receptionist().tell(
+            ReceptionistMsg::Subscribe(service.to_string(), Box::new(actor)),
+            None,
+        );
+    }
 
-        debug!(sys.log, "Actor system [{}] [{}] started", sys.id(), name);
+    fn receptionist(&self) -> &ActorRef<ReceptionistMsg> {
+        self.receptionist.as_ref().unwrap()
+    }
 
-        Ok(sys)
+    /// Check the health of `actor` every `health.interval_millis`
+    ///
+    /// The actor is asked for its `Actor::health` with a system message,
+    /// so it doesn't handle the check in `recv`, but a check waits while
+    /// the actor is processing a message. An actor that doesn't answer
+    /// before the next check is `Unhealthy`. Each change is published as a
+    /// `HealthChanged` event. Actors are watched until they terminate.
+    pub fn watch_health(&self, actor: impl Into<BasicActorRef>) {
+        self.health_monitor()
+            .tell(HealthMsg::Watch(actor.into()), None);
     }
 
-    fn complete_start(&self) {
-        self.sys_actors.as_ref().unwrap().user.sys_init(self);
+    /// Stop checking the health of `actor`
+    pub fn unwatch_health(&self, actor: impl Into<BasicActorRef>) {
+        self.health_monitor()
+            .tell(HealthMsg::Unwatch(actor.into()), None);
     }
 
-    /// Returns the system start date
-    pub fn start_date(&self) -> &DateTime<Utc> {
-        &self.proto.started_at
+    /// Returns the last checked health of every watched actor
+    pub fn health_report(&self) -> oneshot::Receiver<HealthReport> {
+        let (reply_to, rx) = ReplyTo::new();
+        self.health_monitor()
+            .tell(HealthMsg::Report(reply_to), None);
+
+        rx
     }
 
-    /// Returns the number of seconds since the system started
-    pub fn uptime(&self) -> u64 {
-        let now = Utc::now();
-        now.time()
-            .signed_duration_since(self.start_date().time())
-            .num_seconds() as u64
+    fn health_monitor(&self) -> &ActorRef<HealthMsg> {
+        self.health.as_ref().unwrap()
     }
 
-    /// Returns the hostname used when the system started
+    /// Pass every system event and dead letter to `sink`
     ///
-    /// The host is used in actor addressing.
-    ///
-    /// Currently not used, but will be once system clustering is introduced.
-    pub fn host(&self) -> Arc<str> {
-        self.proto.host.clone()
+    /// The sink runs in a system actor named `name`, stopping the
+    /// returned actor removes the sink.
+    pub fn add_event_sink(
+        &self,
+        name: &str,
+        sink: impl EventSink,
+    ) -> Result<BasicActorRef, CreateError> {
+        let sink: SharedSink = Arc::new(Mutex::new(Box::new(sink)));
+        let args = (self.sys_events().clone(), self.dead_letters().clone(), sink);
+        let publisher = self.sys_actor_of_args::<EventPublisher, _>(name, args)?;
+        Ok(publisher.into())
     }
 
-    /// Returns the UUID assigned to the system
-    pub fn id(&self) -> Uuid {
-        self.proto.id
+    /// Register a policy to recreate actors that dead letters are sent to
+    pub fn add_respawn_policy(&self, policy: RespawnPolicy) {
+        self.proto.respawn_policies.add(policy);
     }
 
-    /// Returns the name of the system
-    pub fn name(&self) -> String {
-        self.proto.name.clone()
+    /// Retain a dead letter for replay, count it towards respawn policies
+    /// and publish it to the dead letters channel
+    pub(crate) fn dead_letter(&self, dl: DeadLetter, sender: Sender) {
+        // the dead letters channel is stopped with the system
+        if self.proto.closed.load(Ordering::Acquire) {
+            return;
+        }
+
+        self.proto.retained_dead_letters.retain(&dl);
+        self.proto.respawn_policies.record(self, &dl);
+
+        self.dead_letters().tell(
+            Publish {
+                topic: "dead_letter".into(),
+                msg: dl,
+            },
+            sender,
+        );
     }
 
-    pub fn print_tree(&self) {
-        fn print_node(sys: &ActorSystem, node: &BasicActorRef, indent: &str) {
-            if node.is_root() {
-                println!("{}", sys.name());
+    /// Re-deliver the retained dead letters of the actor at `path`
+    ///
+    /// Messages are delivered, in the order they were dead lettered, to the
+    /// actor currently at `path`, e.g. after it has been recreated. Returns the
+    /// number of messages delivered. The dead letters are kept if there is
+    /// no actor at `path`.
+    ///
+    /// The number of retained dead letters is set by `deadletters.replay_capacity`.
+    pub fn dead_letters_replay(&self, path: &str) -> usize {
+        let actor = match self.find_actor(path) {
+            Some(actor) => actor,
+            None => return 0,
+        };
 
-                for actor in node.children() {
-                    print_node(sys, &actor, "");
-                }
-            } else {
-                println!("{}└─ {}", indent, node.name());
+        self.proto
+            .retained_dead_letters
+            .take(path)
+            .into_iter()
+            .filter(|dl| {
+                dl.payload
+                    .as_ref()
+                    .map(|p| p.deliver(&actor, dl.sender.clone()))
+                    .unwrap_or(false)
+            })
+            .count()
+    }
 
-                for actor in node.children() {
-                    print_node(sys, &actor, &(indent.to_string() + "   "));
-                }
-            }
-        }
+    /// Suspend the actor at `path` and its descendants
+    ///
+    /// Suspended actors keep receiving messages but process only system
+    /// messages, such as `Stop`, until resumed with `resume`. User messages
+    /// are queued, not dead lettered. Returns `false` if there is no actor
+    /// at `path`.
+    pub fn suspend(&self, path: &str) -> bool {
+        self.find_actor(path)
+            .map(|actor| actor.sys_tell(SystemCmd::Suspend.into()))
+            .is_some()
+    }
 
-        let root = &self.sys_actors.as_ref().unwrap().root;
-        print_node(self, &root, "");
+    /// Resume the actor at `path` and its descendants, see `suspend`
+    ///
+    /// Returns `false` if there is no actor at `path`.
+    pub fn resume(&self, path: &str) -> bool {
+        self.find_actor(path)
+            .map(|actor| actor.sys_tell(SystemCmd::Resume.into()))
+            .is_some()
     }
 
-    /// Returns the system root's actor reference
-    #[allow(dead_code)]
-    fn root(&self) -> &BasicActorRef {
-        &self.sys_actors.as_ref().unwrap().root
+    /// Stop the actor at `path`, e.g. `/user/foo/bar`, and its descendants
+    ///
+    /// The actor is looked up in the system's index of actors, so it can be
+    /// managed without holding its reference, e.g. from an admin endpoint.
+    /// Fails if there is no live actor at `path`. The root actor and the
+    /// guardians aren't indexed, so they can't be stopped by path.
+    pub fn stop_path(&self, path: &str) -> Result<(), ActorNotFound> {
+        self.send_cmd(path, SystemCmd::Stop)
     }
 
-    /// Returns the user root actor reference
-    pub fn user_root(&self) -> &BasicActorRef {
-        &self.sys_actors.as_ref().unwrap().user
+    /// Restart the actor at `path`, see `stop_path`
+    ///
+    /// The actor is restarted as if it had failed and its supervisor chose
+    /// `Strategy::Restart`: its children are stopped and it's recreated
+    /// from its `Props`.
+    pub fn restart_path(&self, path: &str) -> Result<(), ActorNotFound> {
+        self.send_cmd(path, SystemCmd::Restart)
     }
 
-    /// Returns the system root actor reference
-    pub fn sys_root(&self) -> &BasicActorRef {
-        &self.sys_actors.as_ref().unwrap().sysm
+    fn send_cmd(&self, path: &str, cmd: SystemCmd) -> Result<(), ActorNotFound> {
+        let actor = self
+            .provider
+            .get(&ActorPath::new(path))
+            .ok_or_else(|| ActorNotFound(path.to_string()))?;
+        actor.sys_tell(cmd.into());
+        Ok(())
     }
 
-    /// Reutrns the temp root actor reference
-    pub fn temp_root(&self) -> &BasicActorRef {
-        &self.sys_actors.as_ref().unwrap().temp
+    /// Returns the actors with a path matching `path_glob`, ordered by path
+    ///
+    /// `*` matches any characters, including `/`, e.g. `/user/workers/*`
+    /// matches every descendant of `/user/workers`, and `/user/*-1` every
+    /// actor under `/user` with a name ending in `-1`. A path without `*`
+    /// is looked up in the system's index of actors instead of traversing
+    /// the hierarchy. The root actor and the `/user`, `/system` and `/temp`
+    /// guardians are never matched.
+    pub fn select_refs(&self, path_glob: &str) -> Vec<BasicActorRef> {
+        self.provider.select(path_glob)
     }
 
-    /// Returns a reference to the system events channel
-    pub fn sys_events(&self) -> &ActorRef<ChannelMsg<SystemEvent>> {
-        &self.sys_channels.as_ref().unwrap().sys_events
+    /// Returns the paths of the live actors at or below `prefix`, ordered by path
+    ///
+    /// E.g. `/user` lists every user actor. Like `select_refs` this reads
+    /// the system's index of actors, so no actor's children are locked.
+    /// The root actor and the guardians aren't listed.
+    pub fn list_actors(&self, prefix: &str) -> Vec<ActorPath> {
+        let prefix = match prefix.trim_end_matches('/') {
+            "" => "/",
+            prefix => prefix,
+        };
+        self.provider.paths(&ActorPath::new(prefix))
     }
 
-    /// Returns a reference to the dead letters channel
-    pub fn dead_letters(&self) -> &ActorRef<DLChannelMsg> {
-        &self.sys_channels.as_ref().unwrap().dead_letters
+    /// Returns the number of live actors, including system and temporary actors
+    ///
+    /// The root actor and the guardians aren't counted.
+    pub fn actor_count(&self) -> usize {
+        self.provider.count()
+    }
+
+    /// Returns the number of live temporary actors, e.g. of unanswered asks
+    pub fn temp_actor_count(&self) -> usize {
+        self.list_actors("/temp").len()
+    }
+
+    fn find_actor(&self, path: &str) -> Option<BasicActorRef> {
+        let root = self.sys_actors.as_ref().unwrap().root.clone();
+
+        ActorPath::new(path)
+            .segments()
+            .try_fold(root, |actor, name| {
+                actor.children().find(|child| child.name() == name)
+            })
     }
 
     pub fn publish_event(&self, evt: SystemEvent) {
+        // the system events channel is stopped with the system
+        if self.proto.closed.load(Ordering::Acquire) {
+            return;
+        }
+
         let topic = Topic::from(&evt);
         self.sys_events().tell(Publish { topic, msg: evt }, None);
     }
@@ -464,10 +1495,42 @@ impl ActorSystem {
         &self.proto.config
     }
 
+    // Names of actors under `/user` must also be accepted by the `NameValidator`
+    pub(crate) fn accepts_name(&self, name: &str, parent: &BasicActorRef) -> bool {
+        let user = self.user_root().path();
+        let is_user = parent.path() == user || parent.path().is_descendant_of(user);
+
+        match self.proto.name_validator {
+            Some(ref validator) if is_user => validator.is_valid(name),
+            _ => true,
+        }
+    }
+
     pub(crate) fn sys_settings(&self) -> &SystemSettings {
         &self.proto.sys_settings
     }
 
+    pub(crate) fn governor(&self) -> Option<&Governor> {
+        self.proto.governor.as_ref()
+    }
+
+    /// Returns the dispatcher `name`, see `WithDispatcher`
+    pub(crate) fn dispatcher(&self, name: &str) -> Option<&DispatcherRef> {
+        self.proto.dispatchers.get(name)
+    }
+
+    /// Returns the dispatcher running actors without a named dispatcher
+    pub(crate) fn default_dispatcher(&self) -> &DispatcherRef {
+        &self.proto.dispatcher
+    }
+
+    pub(crate) fn shutdown_dispatchers(&self) {
+        self.proto.dispatcher.shutdown();
+        for dispatcher in self.proto.dispatchers.values() {
+            dispatcher.shutdown();
+        }
+    }
+
     /// Create an actor under the system root
     pub fn sys_actor_of_props<A>(
         &self,
@@ -478,7 +1541,7 @@ impl ActorSystem {
         A: Actor,
     {
         self.provider
-            .create_actor(props, name, &self.sys_root(), self)
+            .create_actor(props, name, self.sys_root(), self)
     }
 
     pub fn sys_actor_of<A>(&self, name: &str) -> Result<ActorRef<<A as Actor>::Msg>, CreateError>
@@ -486,7 +1549,7 @@ impl ActorSystem {
         A: ActorFactory,
     {
         self.provider
-            .create_actor(Props::new::<A>(), name, &self.sys_root(), self)
+            .create_actor(Props::new::<A>(), name, self.sys_root(), self)
     }
 
     pub fn sys_actor_of_args<A, Args>(
@@ -499,7 +1562,53 @@ impl ActorSystem {
         A: ActorFactoryArgs<Args>,
     {
         self.provider
-            .create_actor(Props::new_args::<A, _>(args), name, &self.sys_root(), self)
+            .create_actor(Props::new_args::<A, _>(args), name, self.sys_root(), self)
+    }
+
+    /// Create an actor under the actor at `parent`, e.g. `/user/workers/pool-1`
+    ///
+    /// The actor is supervised by the parent as if the parent had created it.
+    /// Fails with `CreateError::ParentNotFound` if no actor exists at `parent`.
+    pub fn actor_of_props_at<A>(
+        &self,
+        parent: &str,
+        name: &str,
+        props: BoxActorProd<A>,
+    ) -> Result<ActorRef<A::Msg>, CreateError>
+    where
+        A: Actor,
+    {
+        let parent = self
+            .find_actor(parent)
+            .ok_or_else(|| CreateError::ParentNotFound(parent.to_string()))?;
+
+        self.provider.create_actor(props, name, &parent, self)
+    }
+
+    /// Create an actor under the actor at `parent`, see `actor_of_props_at`
+    pub fn actor_of_at<A>(
+        &self,
+        parent: &str,
+        name: &str,
+    ) -> Result<ActorRef<<A as Actor>::Msg>, CreateError>
+    where
+        A: ActorFactory,
+    {
+        self.actor_of_props_at(parent, name, Props::new::<A>())
+    }
+
+    /// Create an actor under the actor at `parent`, see `actor_of_props_at`
+    pub fn actor_of_args_at<A, Args>(
+        &self,
+        parent: &str,
+        name: &str,
+        args: Args,
+    ) -> Result<ActorRef<<A as Actor>::Msg>, CreateError>
+    where
+        Args: ActorArgs,
+        A: ActorFactoryArgs<Args>,
+    {
+        self.actor_of_props_at(parent, name, Props::new_args::<A, _>(args))
     }
 
     #[inline]
@@ -514,7 +1623,14 @@ impl ActorSystem {
     ///
     /// Does not block. Returns a future which is completed when all
     /// actors have successfully stopped.
+    ///
+    /// Once the user actors have stopped the system actors are stopped,
+    /// and the timer thread with them. Messages sent from then on are
+    /// dropped, not dead lettered. The executor threads exit once the
+    /// system and the references to its actors are dropped.
     pub fn shutdown(&self) -> Shutdown {
+        self.proto.extensions.before_shutdown(self);
+
         let (tx, rx) = oneshot::channel::<()>();
         let tx = Arc::new(Mutex::new(Some(tx)));
 
@@ -522,10 +1638,47 @@ impl ActorSystem {
 
         rx
     }
-}
 
-unsafe impl Send for ActorSystem {}
-unsafe impl Sync for ActorSystem {}
+    /// Create and register the extension `E`, returning the registered instance
+    ///
+    /// The extension is created with `SystemExtension::create`, then its
+    /// `started` hook is invoked. If `E` is already registered the existing
+    /// instance is returned. Its `before_shutdown` hook is invoked by `shutdown`.
+    pub fn register_extension<E: SystemExtension>(&self) -> Arc<E> {
+        self.proto.extensions.register::<E>(self)
+    }
+
+    /// Returns the registered extension `E`
+    pub fn extension<E: SystemExtension>(&self) -> Option<Arc<E>> {
+        self.proto.extensions.get::<E>()
+    }
+
+    /// Returns a future which is completed once the user root, and so
+    /// every user actor, has stopped
+    ///
+    /// Unlike `shutdown` this does not stop the system, for example
+    /// `main` can wait for actors to stop the system when they're done.
+    pub fn await_termination(&self) -> Termination {
+        self.proto.termination.watch()
+    }
+
+    /// Returns `true` once the user root has stopped
+    pub fn is_terminated(&self) -> bool {
+        self.proto.termination.is_terminated()
+    }
+
+    pub(crate) fn terminated(&self) {
+        self.proto.termination.terminate();
+    }
+
+    // Stop the system actors and the timer, once the user actors have stopped
+    fn close(&self) {
+        self.proto.closed.store(true, Ordering::Release);
+        self.log.control.publish_to(None);
+        let _ = self.timer.send(Job::Stop);
+        self.stop(self.sys_actors.as_ref().unwrap().root.clone());
+    }
+}
 
 impl ActorRefFactory for ActorSystem {
     fn actor_of_props<A>(
@@ -537,7 +1690,7 @@ impl ActorRefFactory for ActorSystem {
         A: Actor,
     {
         self.provider
-            .create_actor(props, name, &self.user_root(), self)
+            .create_actor(props, name, self.user_root(), self)
     }
 
     fn actor_of<A>(&self, name: &str) -> Result<ActorRef<<A as Actor>::Msg>, CreateError>
@@ -545,7 +1698,7 @@ impl ActorRefFactory for ActorSystem {
         A: ActorFactory,
     {
         self.provider
-            .create_actor(Props::new::<A>(), name, &self.user_root(), self)
+            .create_actor(Props::new::<A>(), name, self.user_root(), self)
     }
 
     fn actor_of_args<A, Args>(
@@ -558,7 +1711,7 @@ impl ActorRefFactory for ActorSystem {
         A: ActorFactoryArgs<Args>,
     {
         self.provider
-            .create_actor(Props::new_args::<A, _>(args), name, &self.user_root(), self)
+            .create_actor(Props::new_args::<A, _>(args), name, self.user_root(), self)
     }
 
     fn stop(&self, actor: impl ActorReference) {
@@ -576,7 +1729,7 @@ impl ActorRefFactory for &ActorSystem {
         A: Actor,
     {
         self.provider
-            .create_actor(props, name, &self.user_root(), self)
+            .create_actor(props, name, self.user_root(), self)
     }
 
     fn actor_of<A>(&self, name: &str) -> Result<ActorRef<<A as Actor>::Msg>, CreateError>
@@ -584,7 +1737,7 @@ impl ActorRefFactory for &ActorSystem {
         A: ActorFactory,
     {
         self.provider
-            .create_actor(Props::new::<A>(), name, &self.user_root(), self)
+            .create_actor(Props::new::<A>(), name, self.user_root(), self)
     }
 
     fn actor_of_args<A, Args>(
@@ -597,7 +1750,7 @@ impl ActorRefFactory for &ActorSystem {
         A: ActorFactoryArgs<Args>,
     {
         self.provider
-            .create_actor(Props::new_args::<A, _>(args), name, &self.user_root(), self)
+            .create_actor(Props::new_args::<A, _>(args), name, self.user_root(), self)
     }
 
     fn stop(&self, actor: impl ActorReference) {
@@ -612,7 +1765,7 @@ impl TmpActorRefFactory for ActorSystem {
     {
         let name = format!("{}", rand::random::<u64>());
         self.provider
-            .create_actor(props, &name, &self.temp_root(), self)
+            .create_actor(props, &name, self.temp_root(), self)
     }
 
     fn tmp_actor_of<A>(&self) -> Result<ActorRef<<A as Actor>::Msg>, CreateError>
@@ -621,7 +1774,7 @@ impl TmpActorRefFactory for ActorSystem {
     {
         let name = format!("{}", rand::random::<u64>());
         self.provider
-            .create_actor(Props::new::<A>(), &name, &self.temp_root(), self)
+            .create_actor(Props::new::<A>(), &name, self.temp_root(), self)
     }
 
     fn tmp_actor_of_args<A, Args>(
@@ -633,26 +1786,33 @@ impl TmpActorRefFactory for ActorSystem {
         A: ActorFactoryArgs<Args>,
     {
         let name = format!("{}", rand::random::<u64>());
-        self.provider.create_actor(
-            Props::new_args::<A, _>(args),
-            &name,
-            &self.temp_root(),
-            self,
-        )
+        self.provider
+            .create_actor(Props::new_args::<A, _>(args), &name, self.temp_root(), self)
+    }
+}
+
+impl ActorSystem {
+    /// Create a `CachedSelection`, see `ActorSelectionFactory::select`
+    pub fn select_cached(&self, path: &str) -> Result<CachedSelection, InvalidPath> {
+        let selection = self.select(path)?;
+        Ok(CachedSelection::new(
+            selection,
+            &self.proto.selection_caches,
+        ))
+    }
+
+    pub(crate) fn selection_caches(&self) -> &SelectionCaches {
+        &self.proto.selection_caches
     }
 }
 
 impl ActorSelectionFactory for ActorSystem {
     fn select(&self, path: &str) -> Result<ActorSelection, InvalidPath> {
         let anchor = self.user_root();
-        let (anchor, path_str) = if path.starts_with('/') {
-            let anchor = self.user_root();
-            let anchor_path = format!("{}/", anchor.path());
-            let path = path.to_string().replace(&anchor_path, "");
-
-            (anchor, path)
+        let path_str = if path.starts_with('/') {
+            from_user_root(path, anchor)
         } else {
-            (anchor, path.to_string())
+            path.to_string()
         };
 
         ActorSelection::new(
@@ -670,6 +1830,16 @@ pub trait Run {
     where
         Fut: Future + Send + 'static,
         <Fut as Future>::Output: Send;
+
+    /// Run a blocking closure on the blocking pool
+    ///
+    /// The blocking pool is separate from the executor that runs actors
+    /// so that blocking I/O or long computations don't stall message
+    /// processing. Its size is set by `dispatcher.blocking_pool_size`.
+    fn run_blocking<F, T>(&self, f: F) -> Result<RemoteHandle<T>, SpawnError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static;
 }
 
 impl Run for ActorSystem {
@@ -680,6 +1850,14 @@ impl Run for ActorSystem {
     {
         self.exec.spawn_with_handle(future)
     }
+
+    fn run_blocking<F, T>(&self, f: F) -> Result<RemoteHandle<T>, SpawnError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.blocking_exec.spawn_with_handle(async move { f() })
+    }
 }
 
 impl fmt::Debug for ActorSystem {
@@ -707,12 +1885,13 @@ impl Timer for ActorSystem {
         T: Message + Into<M>,
         M: Message,
     {
+        receiver.cell.set_has_schedules();
         let id = Uuid::new_v4();
         let msg: M = msg.into();
 
         let job = RepeatJob {
             id,
-            send_at: Instant::now() + initial_delay,
+            send_at: self.clock().now() + initial_delay,
             interval,
             receiver: receiver.into(),
             sender,
@@ -723,6 +1902,35 @@ impl Timer for ActorSystem {
         id
     }
 
+    fn schedule_fn<F, T, M>(
+        &self,
+        initial_delay: Duration,
+        interval: Duration,
+        receiver: ActorRef<M>,
+        sender: Sender,
+        mut f: F,
+    ) -> ScheduleId
+    where
+        F: FnMut() -> T + Send + 'static,
+        T: Into<M>,
+        M: Message,
+    {
+        receiver.cell.set_has_schedules();
+        let id = Uuid::new_v4();
+
+        let job = RepeatJob {
+            id,
+            send_at: self.clock().now() + initial_delay,
+            interval,
+            receiver: receiver.into(),
+            sender,
+            msg: AnyMessage::from_fn(move || -> M { f().into() }),
+        };
+
+        let _ = self.timer.send(Job::Repeat(job));
+        id
+    }
+
     fn schedule_once<T, M>(
         &self,
         delay: Duration,
@@ -734,12 +1942,13 @@ impl Timer for ActorSystem {
         T: Message + Into<M>,
         M: Message,
     {
+        receiver.cell.set_has_schedules();
         let id = Uuid::new_v4();
         let msg: M = msg.into();
 
         let job = OnceJob {
             id,
-            send_at: Instant::now() + delay,
+            send_at: self.clock().now() + delay,
             receiver: receiver.into(),
             sender,
             msg: AnyMessage::new(msg, true),
@@ -760,15 +1969,15 @@ impl Timer for ActorSystem {
         T: Message + Into<M>,
         M: Message,
     {
-        let delay = std::cmp::max(time.timestamp() - Utc::now().timestamp(), 0_i64);
-        let delay = Duration::from_secs(delay as u64);
+        let delay = delay_until(self.clock(), time);
 
+        receiver.cell.set_has_schedules();
         let id = Uuid::new_v4();
         let msg: M = msg.into();
 
         let job = OnceJob {
             id,
-            send_at: Instant::now() + delay,
+            send_at: self.clock().now() + delay,
             receiver: receiver.into(),
             sender,
             msg: AnyMessage::new(msg, true),
@@ -781,6 +1990,10 @@ impl Timer for ActorSystem {
     fn cancel_schedule(&self, id: Uuid) {
         let _ = self.timer.send(Job::Cancel(id));
     }
+
+    fn cancel_for(&self, path: &ActorPath) {
+        let _ = self.timer.send(Job::CancelFor(path.clone()));
+    }
 }
 
 // helper functions
@@ -794,8 +2007,11 @@ fn sys_actor_of_props<A>(
 where
     A: Actor,
 {
-    prov.create_actor(props, name, &sys.sys_root(), sys)
-        .map_err(|_| SystemError::ModuleFailed(name.into()))
+    prov.create_actor(props, name, sys.sys_root(), sys)
+        .map_err(|source| SystemError::ModuleFailed {
+            name: name.into(),
+            source,
+        })
 }
 
 fn sys_actor_of<A>(
@@ -806,8 +2022,11 @@ fn sys_actor_of<A>(
 where
     A: ActorFactory,
 {
-    prov.create_actor(Props::new::<A>(), name, &sys.sys_root(), sys)
-        .map_err(|_| SystemError::ModuleFailed(name.into()))
+    prov.create_actor(Props::new::<A>(), name, sys.sys_root(), sys)
+        .map_err(|source| SystemError::ModuleFailed {
+            name: name.into(),
+            source,
+        })
 }
 
 #[allow(dead_code)]
@@ -821,34 +2040,210 @@ where
     Args: ActorArgs,
     A: ActorFactoryArgs<Args>,
 {
-    prov.create_actor(Props::new_args::<A, _>(args), name, &sys.sys_root(), sys)
-        .map_err(|_| SystemError::ModuleFailed(name.into()))
+    prov.create_actor(Props::new_args::<A, _>(args), name, sys.sys_root(), sys)
+        .map_err(|source| SystemError::ModuleFailed {
+            name: name.into(),
+            source,
+        })
 }
 
 fn sys_channels(prov: &Provider, sys: &ActorSystem) -> Result<SysChannels, SystemError> {
-    let sys_events = sys_actor_of::<EventsChannel>(prov, sys, "sys_events")?;
-    let dead_letters = sys_actor_of::<Channel<DeadLetter>>(prov, sys, "dead_letters")?;
-
-    // subscribe the dead_letters channel to actor terminated events
-    // so that any future subscribed actors that terminate are automatically
-    // unsubscribed from the dead_letters channel
-    // let msg = ChannelMsg::Subscribe(SysTopic::ActorTerminated.into(), dl.clone());
-    // es.tell(msg, None);
+    let sys_events =
+        sys_actor_of::<EventsChannel>(prov, sys, "sys_events").map_err(channel_failed)?;
+    let dead_letters =
+        sys_actor_of::<Channel<DeadLetter>>(prov, sys, "dead_letters").map_err(channel_failed)?;
+    let logging = sys_actor_of_args::<LoggingChannel, _>(
+        prov,
+        sys,
+        "logging",
+        (
+            sys_events.clone(),
+            dead_letters.clone(),
+            LoggerConfig::from(sys.config()).level,
+        ),
+    )
+    .map_err(channel_failed)?;
+
+    let log_entries =
+        sys_actor_of::<Channel<LogEntry>>(prov, sys, "log_entries").map_err(channel_failed)?;
+
+    // subscribe the channels to actor terminated events so that
+    // subscribers that stop are unsubscribed, the logging channel
+    // subscribes itself
+    for channel in [
+        BasicActorRef::from(sys_events.clone()),
+        dead_letters.clone().into(),
+        log_entries.clone().into(),
+    ] {
+        sys_events.subscribe(SysTopic::ActorTerminated, channel);
+    }
+    sys.log.control.publish_to(Some(log_entries.clone()));
 
     Ok(SysChannels {
         sys_events,
         dead_letters,
+        logging,
+        log_entries,
     })
 }
 
+// The channels are system actors, reported as channels
+fn channel_failed(err: SystemError) -> SystemError {
+    match err {
+        SystemError::ModuleFailed { name, source } => SystemError::ChannelFailed { name, source },
+        err => err,
+    }
+}
+
+struct RetainedDeadLetters {
+    capacity: usize,
+    letters: Mutex<VecDeque<DeadLetter>>,
+}
+
+impl RetainedDeadLetters {
+    fn retain(&self, dl: &DeadLetter) {
+        if self.capacity == 0 || dl.payload.is_none() {
+            return;
+        }
+
+        let mut letters = self.letters.lock().unwrap();
+        if letters.len() == self.capacity {
+            letters.pop_front();
+        }
+        letters.push_back(dl.clone());
+    }
+
+    fn take(&self, path: &str) -> Vec<DeadLetter> {
+        let mut letters = self.letters.lock().unwrap();
+        let (taken, kept) = letters
+            .drain(..)
+            .partition(|dl: &DeadLetter| dl.recipient.path() == path);
+
+        *letters = kept;
+        taken.into()
+    }
+}
+
+impl From<&Config> for RetainedDeadLetters {
+    fn from(config: &Config) -> Self {
+        RetainedDeadLetters {
+            capacity: config.get_int("deadletters.replay_capacity").unwrap() as usize,
+            letters: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
 pub struct SystemSettings {
     pub msg_process_limit: u32,
+
+    /// `mailbox.msg_process_budget_millis`, `None` if it's `0`
+    pub msg_process_budget: Option<Duration>,
+
+    /// `temp.ttl_millis`, `None` if it's `0`
+    pub ask_ttl: Option<Duration>,
+    pub(crate) queue: QueueKind,
+    pub(crate) fairness: Fairness,
 }
 
-impl<'a> From<&'a Config> for SystemSettings {
-    fn from(config: &Config) -> Self {
-        SystemSettings {
-            msg_process_limit: config.get_int("mailbox.msg_process_limit").unwrap() as u32,
+impl SystemSettings {
+    /// Read the settings from `config`, returning every missing or invalid key
+    pub fn from_config(config: &Config) -> Result<Self, Vec<ConfigError>> {
+        let mut errors = Vec::new();
+
+        let key = "mailbox.msg_process_limit";
+        let msg_process_limit = read_key(config.get_int(key), key, &mut errors).and_then(|limit| {
+            match u32::try_from(limit) {
+                Ok(limit) if limit > 0 => Some(limit),
+                _ => {
+                    errors.push(ConfigError::invalid(key, "must be a positive integer"));
+                    None
+                }
+            }
+        });
+
+        let key = "mailbox.msg_process_budget_millis";
+        let msg_process_budget = read_key(config.get_int(key), key, &mut errors).and_then(
+            |budget| match u64::try_from(budget) {
+                Ok(0) => Some(None),
+                Ok(budget) => Some(Some(Duration::from_millis(budget))),
+                Err(_) => {
+                    errors.push(ConfigError::invalid(key, "must not be negative"));
+                    None
+                }
+            },
+        );
+
+        let key = "temp.ttl_millis";
+        let ask_ttl = read_key(config.get_int(key), key, &mut errors).and_then(|ttl| {
+            match u64::try_from(ttl) {
+                Ok(0) => Some(None),
+                Ok(ttl) => Some(Some(Duration::from_millis(ttl))),
+                Err(_) => {
+                    errors.push(ConfigError::invalid(key, "must not be negative"));
+                    None
+                }
+            }
+        });
+
+        let key = "mailbox.queue";
+        let queue = read_key(config.get_str(key), key, &mut errors).and_then(|queue| {
+            let kind = QueueKind::parse(&queue);
+            if kind.is_none() {
+                errors.push(ConfigError::invalid(
+                    key,
+                    format!("expected \"channel\" or \"lockfree\", found \"{}\"", queue),
+                ));
+            }
+            kind
+        });
+
+        let key = "dispatcher.fairness";
+        let fairness = read_key(config.get_str(key), key, &mut errors).and_then(|fairness| {
+            let parsed = Fairness::parse(&fairness);
+            if parsed.is_none() {
+                errors.push(ConfigError::invalid(
+                    key,
+                    format!(
+                        "expected \"round_robin\" or \"greedy\", found \"{}\"",
+                        fairness
+                    ),
+                ));
+            }
+            parsed
+        });
+
+        match (
+            msg_process_limit,
+            msg_process_budget,
+            ask_ttl,
+            queue,
+            fairness,
+        ) {
+            (
+                Some(msg_process_limit),
+                Some(msg_process_budget),
+                Some(ask_ttl),
+                Some(queue),
+                Some(fairness),
+            ) if errors.is_empty() => Ok(SystemSettings {
+                msg_process_limit,
+                msg_process_budget,
+                ask_ttl,
+                queue,
+                fairness,
+            }),
+            _ => Err(errors),
+        }
+    }
+
+    /// The limit of actors that don't set one with `WithMsgProcessLimit`
+    pub fn default_process_limit(&self) -> MsgProcessLimit {
+        match self.msg_process_budget {
+            Some(budget) => MsgProcessLimit::Adaptive {
+                max: self.msg_process_limit,
+                budget,
+            },
+            None => MsgProcessLimit::Fixed(self.msg_process_limit),
         }
     }
 }
@@ -858,7 +2253,7 @@ struct ThreadPoolConfig {
     stack_size: usize,
 }
 
-impl<'a> From<&'a Config> for ThreadPoolConfig {
+impl From<&Config> for ThreadPoolConfig {
     fn from(config: &Config) -> Self {
         ThreadPoolConfig {
             pool_size: config.get_int("dispatcher.pool_size").unwrap() as usize,
@@ -867,14 +2262,52 @@ impl<'a> From<&'a Config> for ThreadPoolConfig {
     }
 }
 
-fn default_exec(cfg: &Config) -> ThreadPool {
+// Thread names are prefixed with the system name, telling apart the threads of several systems
+fn default_exec(sys_name: &str, cfg: &Config) -> Result<ThreadPool, SystemError> {
     let exec_cfg = ThreadPoolConfig::from(cfg);
     ThreadPoolBuilder::new()
         .pool_size(exec_cfg.pool_size)
         .stack_size(exec_cfg.stack_size)
-        .name_prefix("pool-thread-#")
+        .name_prefix(format!("{}-pool-thread-#", sys_name))
         .create()
-        .unwrap()
+        .map_err(SystemError::Executor)
+}
+
+// Named dispatchers, each configured as `dispatchers.<name>.pool_size`
+fn dispatchers(
+    sys_name: &str,
+    cfg: &Config,
+) -> Result<HashMap<String, DispatcherRef>, SystemError> {
+    let exec_cfg = ThreadPoolConfig::from(cfg);
+    let names = cfg.get_table("dispatchers").unwrap_or_default();
+
+    names
+        .into_keys()
+        .map(|name| {
+            let pool_size = cfg
+                .get_int(&format!("dispatchers.{}.pool_size", name))
+                .unwrap_or(1) as usize;
+            let exec = ThreadPoolBuilder::new()
+                .pool_size(pool_size)
+                .stack_size(exec_cfg.stack_size)
+                .name_prefix(format!("{}-{}-thread-#", sys_name, name))
+                .create()
+                .map_err(SystemError::Executor)?;
+
+            Ok((name, Arc::new(exec) as DispatcherRef))
+        })
+        .collect()
+}
+
+fn blocking_exec(sys_name: &str, cfg: &Config) -> Result<ThreadPool, SystemError> {
+    let exec_cfg = ThreadPoolConfig::from(cfg);
+    let pool_size = cfg.get_int("dispatcher.blocking_pool_size").unwrap() as usize;
+    ThreadPoolBuilder::new()
+        .pool_size(pool_size)
+        .stack_size(exec_cfg.stack_size)
+        .name_prefix(format!("{}-blocking-thread-#", sys_name))
+        .create()
+        .map_err(SystemError::Executor)
 }
 
 #[derive(Clone)]
@@ -889,10 +2322,55 @@ pub struct SysActors {
 pub struct SysChannels {
     pub sys_events: ActorRef<ChannelMsg<SystemEvent>>,
     pub dead_letters: ActorRef<DLChannelMsg>,
+    pub logging: ActorRef<LoggingChannelMsg>,
+    pub log_entries: ActorRef<ChannelMsg<LogEntry>>,
 }
 
 pub type Shutdown = oneshot::Receiver<()>;
 
+pub type Termination = oneshot::Receiver<()>;
+
+pub type RecentDeadLetters = oneshot::Receiver<Vec<DeadLetter>>;
+
+#[derive(Default)]
+struct TerminationWatch {
+    state: Mutex<TerminationState>,
+}
+
+#[derive(Default)]
+struct TerminationState {
+    terminated: bool,
+    waiters: Vec<oneshot::Sender<()>>,
+}
+
+impl TerminationWatch {
+    fn watch(&self) -> Termination {
+        let (tx, rx) = oneshot::channel();
+        let mut state = self.state.lock().unwrap();
+
+        if state.terminated {
+            let _ = tx.send(());
+        } else {
+            state.waiters.push(tx);
+        }
+
+        rx
+    }
+
+    fn is_terminated(&self) -> bool {
+        self.state.lock().unwrap().terminated
+    }
+
+    fn terminate(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.terminated = true;
+
+        for tx in state.waiters.drain(..) {
+            let _ = tx.send(());
+        }
+    }
+}
+
 #[derive(Clone)]
 struct ShutdownActor {
     tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
@@ -910,25 +2388,34 @@ impl ShutdownActor {
     }
 }
 
+#[derive(Clone, Debug)]
+enum ShutdownMsg {
+    Event(SystemEvent),
+    Subscribed,
+}
+
+impl From<SystemEvent> for ShutdownMsg {
+    fn from(evt: SystemEvent) -> Self {
+        ShutdownMsg::Event(evt)
+    }
+}
+
+impl From<SubscribedResponse> for ShutdownMsg {
+    fn from(_: SubscribedResponse) -> Self {
+        ShutdownMsg::Subscribed
+    }
+}
+
 impl Actor for ShutdownActor {
-    type Msg = SystemEvent;
+    type Msg = ShutdownMsg;
 
     fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
-        let sub = Subscribe {
-            topic: SysTopic::ActorTerminated.into(),
-            actor: Box::new(ctx.myself.clone()),
-        };
+        let sub = SubscribeWithResponse::new(
+            SysTopic::ActorTerminated,
+            Box::new(ctx.myself.clone()),
+            Box::new(ctx.myself.clone()),
+        );
         ctx.system.sys_events().tell(sub, None);
-
-        // todo this is prone to failing since there is no
-        // confirmation that ShutdownActor has subscribed to
-        // the ActorTerminated events yet.
-        // It may be that the user root actor is Sterminated
-        // before the subscription is complete.
-
-        // std::thread::sleep_ms(1000);
-        // send stop to all /user children
-        ctx.system.stop(ctx.system.user_root());
     }
 
     fn sys_recv(
@@ -937,18 +2424,26 @@ impl Actor for ShutdownActor {
         msg: SystemMsg,
         sender: Option<BasicActorRef>,
     ) {
-        if let SystemMsg::Event(evt) = msg {
-            if let SystemEvent::ActorTerminated(terminated) = evt {
-                self.receive(ctx, terminated, sender);
-            }
+        if let SystemMsg::Event(SystemEvent::ActorTerminated(terminated)) = msg {
+            self.receive(ctx, terminated, sender);
         }
     }
 
-    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Option<BasicActorRef>) {}
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Option<BasicActorRef>) {
+        match msg {
+            ShutdownMsg::Event(SystemEvent::ActorTerminated(terminated)) => {
+                self.receive(ctx, terminated, sender)
+            }
+            ShutdownMsg::Event(_) => {}
+            // only once subscribed to ActorTerminated is it safe to stop
+            // the user root, otherwise its termination could be missed
+            ShutdownMsg::Subscribed => ctx.system.stop(ctx.system.user_root()),
+        }
+    }
 }
 
 impl Receive<ActorTerminated> for ShutdownActor {
-    type Msg = SystemEvent;
+    type Msg = ShutdownMsg;
 
     fn receive(
         &mut self,
@@ -962,6 +2457,7 @@ impl Receive<ActorTerminated> for ShutdownActor {
                     tx.send(()).unwrap();
                 }
             }
+            ctx.system.close();
         }
     }
 }
@@ -0,0 +1,83 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+
+pub type ClockRef = Arc<dyn Clock>;
+
+/// Source of time used by the actor system
+///
+/// Monotonic time (`now`) is used for uptime and for scheduling,
+/// wall time (`wall`) is used for timestamps and to convert
+/// `schedule_at_time` times to delays.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    fn wall(&self) -> DateTime<Utc>;
+}
+
+/// The default `Clock`, backed by the operating system
+#[derive(Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn wall(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A `Clock` that only moves when advanced, for use in tests
+///
+/// Cloned `ManualClock`s share the same time, so a clone can be
+/// kept to advance the clock given to the `SystemBuilder`.
+#[derive(Clone)]
+pub struct ManualClock {
+    start: Instant,
+    start_wall: DateTime<Utc>,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock {
+            start: Instant::now(),
+            start_wall: Utc::now(),
+            elapsed: Arc::new(Mutex::new(Duration::from_millis(0))),
+        }
+    }
+
+    /// Move the clock forward by `duration`
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + *self.elapsed.lock().unwrap()
+    }
+
+    fn wall(&self) -> DateTime<Utc> {
+        let elapsed = chrono::Duration::from_std(*self.elapsed.lock().unwrap()).unwrap();
+        self.start_wall + elapsed
+    }
+}
+
+/// Delay from the clock's wall time until `time`, zero if `time` has passed
+pub(crate) fn delay_until(clock: &dyn Clock, time: DateTime<Utc>) -> Duration {
+    time.signed_duration_since(clock.wall())
+        .to_std()
+        .unwrap_or_else(|_| Duration::from_millis(0))
+}
@@ -0,0 +1,73 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use futures::channel::oneshot;
+
+use crate::actor::{
+    Actor, ActorFactoryArgs, ActorRef, All, BasicActorRef, ChannelMsg, Context, DeadLetter,
+    Subscribe, Tell,
+};
+
+pub(crate) type QueryTx = Arc<Mutex<Option<oneshot::Sender<Vec<DeadLetter>>>>>;
+
+#[derive(Clone, Debug)]
+pub(crate) enum DeadLetterBufferMsg {
+    DeadLetter(DeadLetter),
+    Query(QueryTx),
+}
+
+impl From<DeadLetter> for DeadLetterBufferMsg {
+    fn from(dl: DeadLetter) -> Self {
+        DeadLetterBufferMsg::DeadLetter(dl)
+    }
+}
+
+/// System actor that keeps the most recent dead letters
+///
+/// The number of dead letters kept is set by `deadletters.capacity`.
+pub(crate) struct DeadLetterBuffer {
+    dl_chan: ActorRef<ChannelMsg<DeadLetter>>,
+    capacity: usize,
+    letters: VecDeque<DeadLetter>,
+}
+
+impl ActorFactoryArgs<(ActorRef<ChannelMsg<DeadLetter>>, usize)> for DeadLetterBuffer {
+    fn create_args((dl_chan, capacity): (ActorRef<ChannelMsg<DeadLetter>>, usize)) -> Self {
+        DeadLetterBuffer {
+            dl_chan,
+            capacity,
+            letters: VecDeque::with_capacity(capacity),
+        }
+    }
+}
+
+impl Actor for DeadLetterBuffer {
+    type Msg = DeadLetterBufferMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let sub = Box::new(ctx.myself());
+        self.dl_chan.tell(Subscribe::new(All, sub), None);
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Option<BasicActorRef>) {
+        match msg {
+            DeadLetterBufferMsg::DeadLetter(dl) => {
+                if self.capacity == 0 {
+                    return;
+                }
+
+                if self.letters.len() == self.capacity {
+                    self.letters.pop_front();
+                }
+                self.letters.push_back(dl);
+            }
+            DeadLetterBufferMsg::Query(tx) => {
+                if let Some(tx) = tx.lock().unwrap().take() {
+                    let _ = tx.send(self.letters.iter().cloned().collect());
+                }
+            }
+        }
+    }
+}
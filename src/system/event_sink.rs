@@ -0,0 +1,89 @@
+use std::sync::{Arc, Mutex};
+
+use crate::actor::{
+    Actor, ActorFactoryArgs, ActorRef, All, ChannelMsg, Context, DLChannelMsg, DeadLetter, Sender,
+    Subscribe, Tell,
+};
+use crate::system::{SystemEvent, SystemMsg};
+
+/// Receives every system event and dead letter, see `ActorSystem::add_event_sink`
+///
+/// Implement `EventSink` to publish to an external broker, such as NATS or
+/// Kafka, giving operators system observability without a subscriber actor.
+/// With the `serde` feature events and dead letters are `Serialize`.
+///
+/// Both methods are called from the sink's actor, one event at a time.
+/// A sink that blocks delays later events but no other actors.
+pub trait EventSink: Send + 'static {
+    fn system_event(&mut self, evt: &SystemEvent);
+
+    fn dead_letter(&mut self, dl: &DeadLetter);
+}
+
+pub(crate) type SharedSink = Arc<Mutex<Box<dyn EventSink>>>;
+
+#[derive(Clone, Debug)]
+pub(crate) enum EventPublisherMsg {
+    Event(SystemEvent),
+    DeadLetter(DeadLetter),
+}
+
+impl From<SystemEvent> for EventPublisherMsg {
+    fn from(evt: SystemEvent) -> Self {
+        EventPublisherMsg::Event(evt)
+    }
+}
+
+impl From<DeadLetter> for EventPublisherMsg {
+    fn from(dl: DeadLetter) -> Self {
+        EventPublisherMsg::DeadLetter(dl)
+    }
+}
+
+/// System actor passing system events and dead letters to an `EventSink`
+pub(crate) struct EventPublisher {
+    sys_events: ActorRef<ChannelMsg<SystemEvent>>,
+    dead_letters: ActorRef<DLChannelMsg>,
+    sink: SharedSink,
+}
+
+type EventPublisherArgs = (
+    ActorRef<ChannelMsg<SystemEvent>>,
+    ActorRef<DLChannelMsg>,
+    SharedSink,
+);
+
+impl ActorFactoryArgs<EventPublisherArgs> for EventPublisher {
+    fn create_args((sys_events, dead_letters, sink): EventPublisherArgs) -> Self {
+        EventPublisher {
+            sys_events,
+            dead_letters,
+            sink,
+        }
+    }
+}
+
+impl Actor for EventPublisher {
+    type Msg = EventPublisherMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.sys_events
+            .tell(Subscribe::new(All, Box::new(ctx.myself())), None);
+
+        self.dead_letters
+            .tell(Subscribe::new(All, Box::new(ctx.myself())), None);
+    }
+
+    fn sys_recv(&mut self, _: &Context<Self::Msg>, msg: SystemMsg, _: Sender) {
+        if let SystemMsg::Event(evt) = msg {
+            self.sink.lock().unwrap().system_event(&evt);
+        }
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        match msg {
+            EventPublisherMsg::Event(evt) => self.sink.lock().unwrap().system_event(&evt),
+            EventPublisherMsg::DeadLetter(dl) => self.sink.lock().unwrap().dead_letter(&dl),
+        }
+    }
+}
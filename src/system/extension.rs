@@ -0,0 +1,122 @@
+use std::{
+    any::{Any, TypeId},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::system::ActorSystem;
+
+/// A plugin hooking into the life of an `ActorSystem`, see `ActorSystem::register_extension`
+///
+/// An extension is created once per system. It can start its own system
+/// actors in `started`, read its settings from `ActorSystem::config`, and
+/// flush or release resources in `before_shutdown`.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::system::SystemExtension;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// #[derive(Default)]
+/// struct Audit {
+///     started: AtomicBool,
+/// }
+///
+/// impl SystemExtension for Audit {
+///     fn create(_: &ActorSystem) -> Self {
+///         Audit::default()
+///     }
+///
+///     fn started(&self, _: &ActorSystem) {
+///         self.started.store(true, Ordering::SeqCst);
+///     }
+/// }
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let audit = sys.register_extension::<Audit>();
+/// assert!(audit.started.load(Ordering::SeqCst));
+/// assert!(sys.extension::<Audit>().is_some());
+/// ```
+pub trait SystemExtension: Send + Sync + 'static {
+    fn create(sys: &ActorSystem) -> Self
+    where
+        Self: Sized;
+
+    /// Invoked once the extension is registered on a started system
+    fn started(&self, _sys: &ActorSystem) {}
+
+    /// Invoked when the system is shut down, before any actor is stopped
+    ///
+    /// Extensions are shut down in the reverse of the order they were registered.
+    fn before_shutdown(&self, _sys: &ActorSystem) {}
+}
+
+struct Registered {
+    id: TypeId,
+    any: Arc<dyn Any + Send + Sync>,
+    hooks: Arc<dyn SystemExtension>,
+}
+
+#[derive(Default)]
+pub(crate) struct SystemExtensions {
+    registered: Mutex<Vec<Registered>>,
+    shutting_down: AtomicBool,
+}
+
+impl SystemExtensions {
+    pub(crate) fn get<E: SystemExtension>(&self) -> Option<Arc<E>> {
+        self.registered
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|ext| ext.id == TypeId::of::<E>())
+            .and_then(|ext| ext.any.clone().downcast().ok())
+    }
+
+    pub(crate) fn register<E: SystemExtension>(&self, sys: &ActorSystem) -> Arc<E> {
+        if let Some(ext) = self.get::<E>() {
+            return ext;
+        }
+
+        // created outside the lock, so that `create` can use other extensions
+        let ext = Arc::new(E::create(sys));
+        {
+            let mut registered = self.registered.lock().unwrap();
+            if let Some(existing) = registered.iter().find(|ext| ext.id == TypeId::of::<E>()) {
+                return existing.any.clone().downcast().unwrap();
+            }
+            registered.push(Registered {
+                id: TypeId::of::<E>(),
+                any: ext.clone(),
+                hooks: ext.clone(),
+            });
+        }
+
+        ext.started(sys);
+        ext
+    }
+
+    // Extensions are shut down once, even if the system is shut down again
+    pub(crate) fn before_shutdown(&self, sys: &ActorSystem) {
+        if self.shutting_down.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let hooks: Vec<_> = self
+            .registered
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|ext| ext.hooks.clone())
+            .collect();
+
+        for ext in hooks {
+            ext.before_shutdown(sys);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use config::Config;
+
+use crate::{
+    actor::{ActorPath, ActorReference, DeadLetter, Sender},
+    system::{ActorSystem, OverloadChanged},
+};
+
+/// What to do with a message while the system is overloaded
+#[derive(Clone, Copy)]
+enum ShedPolicy {
+    /// Send the message to dead letters
+    Shed,
+
+    /// Retry delivery of the message after the configured delay
+    Delay,
+}
+
+pub(crate) enum Admission {
+    Accept,
+    Shed,
+    Delay(Duration),
+}
+
+struct GovernorConfig {
+    max_rate: u64,
+    resume_rate: u64,
+    max_queued: usize,
+    resume_queued: usize,
+    policy: ShedPolicy,
+    delay: Duration,
+}
+
+impl From<&Config> for GovernorConfig {
+    fn from(config: &Config) -> Self {
+        let policy = match config.get_str("governor.policy").unwrap().as_str() {
+            "delay" => ShedPolicy::Delay,
+            _ => ShedPolicy::Shed,
+        };
+
+        GovernorConfig {
+            max_rate: config.get_int("governor.max_msgs_per_sec").unwrap() as u64,
+            resume_rate: config.get_int("governor.resume_msgs_per_sec").unwrap() as u64,
+            max_queued: config.get_int("governor.max_queued").unwrap() as usize,
+            resume_queued: config.get_int("governor.resume_queued").unwrap() as usize,
+            policy,
+            delay: Duration::from_millis(config.get_int("governor.delay_millis").unwrap() as u64),
+        }
+    }
+}
+
+/// System wide ingress governor
+///
+/// Tracks the rate of user messages accepted by actors under `/user` and the
+/// total number of user messages waiting in mailboxes. When either exceeds
+/// its configured maximum the system is considered overloaded and messages
+/// from non-system senders are shed or delayed. The system recovers once
+/// both have dropped below their (lower) resume thresholds, which prevents
+/// flapping around a single threshold.
+///
+/// Delayed messages are offered again when their delay expires and are
+/// delayed again if the system is still overloaded.
+///
+/// Messages sent by, or to, system actors are never governed.
+pub(crate) struct Governor {
+    cfg: GovernorConfig,
+    started: Instant,
+    // start of the current rate window, in millis since `started`
+    window_start: AtomicU64,
+    window_count: AtomicU64,
+    last_rate: AtomicU64,
+    queued: AtomicUsize,
+    overloaded: AtomicBool,
+}
+
+impl Governor {
+    /// Returns a `Governor` if enabled in the config
+    pub(crate) fn from_config(config: &Config) -> Option<Governor> {
+        if !config.get_bool("governor.enabled").unwrap_or(false) {
+            return None;
+        }
+
+        Some(Governor {
+            cfg: GovernorConfig::from(config),
+            started: Instant::now(),
+            window_start: AtomicU64::new(0),
+            window_count: AtomicU64::new(0),
+            last_rate: AtomicU64::new(0),
+            queued: AtomicUsize::new(0),
+            overloaded: AtomicBool::new(false),
+        })
+    }
+
+    pub(crate) fn admit(
+        &self,
+        sys: &ActorSystem,
+        recipient: &ActorPath,
+        sender: &Sender,
+    ) -> Admission {
+        if !is_governed(recipient, sender) {
+            return Admission::Accept;
+        }
+
+        let now = self.started.elapsed().as_millis() as u64;
+        let start = self.window_start.load(Ordering::Relaxed);
+        let elapsed = now.saturating_sub(start);
+
+        if elapsed >= 1000
+            && self
+                .window_start
+                .compare_exchange(start, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            let count = self.window_count.swap(0, Ordering::Relaxed);
+            self.last_rate
+                .store(count * 1000 / elapsed, Ordering::Relaxed);
+        }
+
+        // messages accepted so far in this window are a lower bound on the current rate
+        let rate = std::cmp::max(
+            self.last_rate.load(Ordering::Relaxed),
+            self.window_count.load(Ordering::Relaxed),
+        );
+        let queued = self.queued.load(Ordering::Relaxed);
+
+        if self.overloaded.load(Ordering::Relaxed) {
+            if rate <= self.cfg.resume_rate
+                && queued <= self.cfg.resume_queued
+                && self.set_overloaded(false)
+            {
+                sys.publish_event(
+                    OverloadChanged {
+                        overloaded: false,
+                        rate,
+                        queued,
+                    }
+                    .into(),
+                );
+            }
+        } else if (rate > self.cfg.max_rate || queued > self.cfg.max_queued)
+            && self.set_overloaded(true)
+        {
+            sys.publish_event(
+                OverloadChanged {
+                    overloaded: true,
+                    rate,
+                    queued,
+                }
+                .into(),
+            );
+        }
+
+        if self.overloaded.load(Ordering::Relaxed) {
+            match self.cfg.policy {
+                ShedPolicy::Shed => Admission::Shed,
+                ShedPolicy::Delay => Admission::Delay(self.cfg.delay),
+            }
+        } else {
+            self.window_count.fetch_add(1, Ordering::Relaxed);
+            Admission::Accept
+        }
+    }
+
+    pub(crate) fn enqueued(&self) {
+        self.queued.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn dequeued(&self) {
+        // saturate since messages enqueued before the governor
+        // counted them (system startup) can still be dequeued
+        let _ = self
+            .queued
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |q| q.checked_sub(1));
+    }
+
+    // returns true if the state changed
+    fn set_overloaded(&self, overloaded: bool) -> bool {
+        self.overloaded
+            .compare_exchange(
+                !overloaded,
+                overloaded,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+}
+
+/// Publish a message shed by the governor to dead letters
+///
+/// Dead letter subscribers are usually user actors. The publish is sent
+/// on behalf of the dead letters channel so that forwarding it to them
+/// isn't itself shed, which would loop while the system is overloaded.
+pub(crate) fn publish_shed(sys: &ActorSystem, dl: DeadLetter) {
+    sys.dead_letter(dl, Some(sys.dead_letters().clone().into()));
+}
+
+fn is_governed(recipient: &ActorPath, sender: &Sender) -> bool {
+    let from_system = sender
+        .as_ref()
+        .map(|s| s.path().as_str().starts_with("/system/"))
+        .unwrap_or(false);
+
+    recipient.as_str().starts_with("/user/") && !from_system
+}
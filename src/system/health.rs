@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use crate::{
+    actor::{
+        Actor, ActorFactoryArgs, ActorPath, ActorRef, ActorReference, BasicActorRef, ChannelMsg,
+        Context, ReplyTo, Sender, Subscribe, SysTopic, Tell,
+    },
+    system::{
+        ActorHealth, Health, HealthChanged, HealthReport, Run, SystemEvent, SystemMsg, Timer,
+    },
+};
+
+#[derive(Clone, Debug)]
+pub(crate) enum HealthMsg {
+    Watch(BasicActorRef),
+    Unwatch(BasicActorRef),
+    Report(ReplyTo<HealthReport>),
+    Tick,
+
+    // the reply to a check, `None` if the actor stopped without replying
+    Checked(ActorPath, Option<Health>),
+    Event(SystemEvent),
+}
+
+impl From<SystemEvent> for HealthMsg {
+    fn from(evt: SystemEvent) -> Self {
+        HealthMsg::Event(evt)
+    }
+}
+
+struct Watched {
+    actor: BasicActorRef,
+    health: Health,
+
+    // a check was sent and not yet answered
+    pending: bool,
+}
+
+/// System actor checking the health of the watched actors, see `ActorSystem::watch_health`
+///
+/// Every `health.interval_millis` each watched actor is sent a check,
+/// answered by the kernel with `Actor::health`. An actor that hasn't
+/// answered by the next check is unhealthy. Watched actors are assumed
+/// healthy until checked, and removed when they terminate.
+pub(crate) struct HealthMonitor {
+    sys_events: ActorRef<ChannelMsg<SystemEvent>>,
+    interval: std::time::Duration,
+    watched: HashMap<ActorPath, Watched>,
+}
+
+type HealthMonitorArgs = (ActorRef<ChannelMsg<SystemEvent>>, std::time::Duration);
+
+impl ActorFactoryArgs<HealthMonitorArgs> for HealthMonitor {
+    fn create_args((sys_events, interval): HealthMonitorArgs) -> Self {
+        HealthMonitor {
+            sys_events,
+            interval,
+            watched: HashMap::new(),
+        }
+    }
+}
+
+impl HealthMonitor {
+    fn receive_event(&mut self, evt: SystemEvent) {
+        if let SystemEvent::ActorTerminated(terminated) = evt {
+            self.watched.remove(terminated.actor.path());
+        }
+    }
+
+    fn set_health(&mut self, ctx: &Context<HealthMsg>, path: &ActorPath, health: Health) {
+        let watched = match self.watched.get_mut(path) {
+            Some(watched) => watched,
+            None => return,
+        };
+
+        if watched.health != health {
+            watched.health = health.clone();
+            let changed = HealthChanged {
+                actor: watched.actor.clone(),
+                health,
+            };
+            ctx.system.publish_event(changed.into());
+        }
+    }
+
+    fn check(&mut self, ctx: &Context<HealthMsg>) {
+        let unanswered: Vec<_> = self
+            .watched
+            .iter()
+            .filter(|(_, watched)| watched.pending)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in unanswered {
+            let health = Health::Unhealthy("not responding".to_string());
+            self.set_health(ctx, &path, health);
+        }
+
+        for (path, watched) in self.watched.iter_mut() {
+            if watched.pending {
+                continue;
+            }
+            watched.pending = true;
+
+            let (reply_to, rx) = ReplyTo::new();
+            watched.actor.sys_tell(SystemMsg::CheckHealth(reply_to));
+
+            let myself = ctx.myself();
+            let path = path.clone();
+            let checked = async move {
+                let health = rx.await.ok();
+                myself.tell(HealthMsg::Checked(path, health), None);
+            };
+            if let Ok(handle) = ctx.run(checked) {
+                handle.forget();
+            }
+        }
+    }
+
+    fn report(&self) -> HealthReport {
+        let mut actors: Vec<_> = self
+            .watched
+            .values()
+            .map(|watched| ActorHealth {
+                actor: watched.actor.clone(),
+                health: watched.health.clone(),
+            })
+            .collect();
+        actors.sort_by(|a, b| a.actor.path().as_str().cmp(b.actor.path().as_str()));
+
+        HealthReport { actors }
+    }
+}
+
+impl Actor for HealthMonitor {
+    type Msg = HealthMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.sys_events.tell(
+            Subscribe::new(SysTopic::ActorTerminated, Box::new(ctx.myself())),
+            None,
+        );
+
+        ctx.schedule(
+            self.interval,
+            self.interval,
+            ctx.myself(),
+            None,
+            HealthMsg::Tick,
+        );
+    }
+
+    fn sys_recv(&mut self, _: &Context<Self::Msg>, msg: SystemMsg, _: Sender) {
+        if let SystemMsg::Event(evt) = msg {
+            self.receive_event(evt);
+        }
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        match msg {
+            HealthMsg::Watch(actor) => {
+                self.watched.entry(actor.path().clone()).or_insert(Watched {
+                    actor,
+                    health: Health::Healthy,
+                    pending: false,
+                });
+            }
+            HealthMsg::Unwatch(actor) => {
+                self.watched.remove(actor.path());
+            }
+            HealthMsg::Report(reply_to) => {
+                let _ = reply_to.reply(self.report());
+            }
+            HealthMsg::Tick => self.check(ctx),
+            HealthMsg::Checked(path, health) => {
+                if let Some(watched) = self.watched.get_mut(&path) {
+                    watched.pending = false;
+                }
+                let health = health.unwrap_or_else(|| Health::Unhealthy("not running".to_string()));
+                self.set_health(ctx, &path, health);
+            }
+            HealthMsg::Event(evt) => self.receive_event(evt),
+        }
+    }
+}
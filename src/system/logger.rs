@@ -1,12 +1,16 @@
+use crate::actor::uri::path_pattern;
 use crate::actor::{
-    Actor, ActorFactoryArgs, ActorRef, All, BasicActorRef, ChannelMsg, Context, DeadLetter,
-    Subscribe, Tell,
+    Actor, ActorFactoryArgs, ActorRef, ActorReference, All, BasicActorRef, Channel, ChannelMsg,
+    Context, DeadLetter, Publish, Subscribe, Tell, Topic, Unsubscribe, UnsubscribeAll,
 };
-use crate::system::LoggingSystem;
+use crate::system::{ActorFailed, LoggingSystem, SystemEvent, SystemMsg};
+use chrono::prelude::*;
 use config::Config;
+use regex::Regex;
 use slog::{info, o, Drain, Level, Logger, Never, OwnedKVList, Record};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
 
 pub(crate) type GlobalLoggerGuard = Arc<slog_scope::GlobalLoggerGuard>;
 
@@ -14,17 +18,15 @@ pub(crate) type GlobalLoggerGuard = Arc<slog_scope::GlobalLoggerGuard>;
 pub struct LoggerConfig {
     time_fmt: String,
     date_fmt: String,
-    log_fmt: String,
     filter: Vec<String>,
-    level: Level,
+    pub(crate) level: Level,
 }
 
-impl<'a> From<&'a Config> for LoggerConfig {
+impl From<&Config> for LoggerConfig {
     fn from(config: &Config) -> Self {
         LoggerConfig {
             time_fmt: config.get_str("log.time_format").unwrap(),
             date_fmt: config.get_str("log.date_format").unwrap(),
-            log_fmt: config.get_str("log.log_format").unwrap(),
             filter: config
                 .get_array("log.filter")
                 .unwrap_or_default()
@@ -39,18 +41,197 @@ impl<'a> From<&'a Config> for LoggerConfig {
     }
 }
 
+// The global logger is installed by the first system and shared by the
+// systems created while it's installed. It's reset once they're all dropped,
+// dropping one system doesn't reset it under the others.
+static GLOBAL_LOGGER: Mutex<Weak<slog_scope::GlobalLoggerGuard>> = Mutex::new(Weak::new());
+
 pub(crate) fn default_log(cfg: &Config) -> LoggingSystem {
+    let mut log = isolated_log(cfg);
+
+    let mut global = GLOBAL_LOGGER.lock().unwrap();
+    let scope_guard = match global.upgrade() {
+        Some(scope_guard) => scope_guard,
+        None => {
+            let scope_guard = Arc::new(slog_scope::set_global_logger(log.log.clone()));
+            let _log_guard = slog_stdlog::init(); // will not call `.unwrap()` because this might be called more than once
+            *global = Arc::downgrade(&scope_guard);
+            scope_guard
+        }
+    };
+
+    log.global_logger_guard = Some(scope_guard);
+    log
+}
+
+// Logs to the console without installing the global logger
+pub(crate) fn isolated_log(cfg: &Config) -> LoggingSystem {
+    let level = LoggerConfig::from(cfg).level;
+    LoggingSystem::new(console_logger(cfg), level)
+}
+
+// The level is filtered by the `LoggingSystem`, so that it can be changed
+fn console_logger(cfg: &Config) -> Logger {
     let cfg = LoggerConfig::from(cfg);
 
-    let drain = DefaultConsoleLogger::new(cfg.clone())
-        .filter_level(cfg.level)
-        .fuse();
-    let logger = Logger::root(drain, o!());
+    let drain = DefaultConsoleLogger::new(cfg).fuse();
+    Logger::root(drain, o!())
+}
+
+/// A line logged by an actor system's logger, see `LoggerRef`
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    pub time: DateTime<Utc>,
+    pub level: Level,
+    pub module: String,
+    pub msg: String,
+}
+
+/// Controls a system's logger at runtime, see `ActorSystem::logger`
+///
+/// Every line logged by the system, that passes the level and filters, is
+/// published as a `LogEntry` on the `log.entries` topic of the `entries`
+/// channel, e.g. to show live logs in a UI. The level and filters apply to
+/// the configured logger too.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::system::LogEntry;
+///
+/// #[derive(Default)]
+/// struct LogView;
+///
+/// impl Actor for LogView {
+///     type Msg = LogEntry;
+///
+///     fn recv(&mut self, _: &Context<LogEntry>, entry: LogEntry, _: Sender) {
+///         println!("{} {}", entry.level.as_short_str(), entry.msg);
+///     }
+/// }
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let view = sys.actor_of::<LogView>("log-view").unwrap();
+///
+/// let logger = sys.logger();
+/// logger.set_level(slog::Level::Debug);
+/// logger.add_filter("riker::kernel*");
+/// logger.entries().tell(Subscribe::new("log.entries", Box::new(view)), None);
+/// ```
+#[derive(Clone)]
+pub struct LoggerRef {
+    control: Arc<LogControl>,
+    entries: ActorRef<ChannelMsg<LogEntry>>,
+}
+
+impl LoggerRef {
+    pub(crate) fn new(control: Arc<LogControl>, entries: ActorRef<ChannelMsg<LogEntry>>) -> Self {
+        LoggerRef { control, entries }
+    }
+
+    /// Only log lines at `level` or more severe, until it's changed again
+    pub fn set_level(&self, level: Level) {
+        self.control
+            .level
+            .store(level.as_usize(), Ordering::Relaxed);
+    }
+
+    pub fn level(&self) -> Level {
+        self.control.level()
+    }
+
+    /// Stop logging lines of the modules matching `module_glob`
+    ///
+    /// `*` matches any characters, e.g. `riker::kernel*`. Like `log.filter`
+    /// a filter suppresses lines, but is matched against the whole module.
+    pub fn add_filter(&self, module_glob: &str) {
+        self.control
+            .filters
+            .lock()
+            .unwrap()
+            .push(path_pattern(module_glob));
+    }
+
+    /// The channel `LogEntry`s are published to, on the `log.entries` topic
+    pub fn entries(&self) -> &ActorRef<ChannelMsg<LogEntry>> {
+        &self.entries
+    }
+}
+
+// The runtime level and module filters of a system's logger, and the
+// channel its lines are published to, once the channel is started
+pub(crate) struct LogControl {
+    level: AtomicUsize,
+    filters: Mutex<Vec<Regex>>,
+    entries: Mutex<Option<ActorRef<ChannelMsg<LogEntry>>>>,
+}
 
-    let scope_guard = slog_scope::set_global_logger(logger.clone());
-    let _log_guard = slog_stdlog::init(); // will not call `.unwrap()` because this might be called more than once
+impl LogControl {
+    pub(crate) fn new(level: Level) -> Self {
+        LogControl {
+            level: AtomicUsize::new(level.as_usize()),
+            filters: Mutex::new(Vec::new()),
+            entries: Mutex::new(None),
+        }
+    }
+
+    // Publish to `entries`, or stop publishing with `None`. The channel's
+    // reference is dropped on shutdown, since it references the system.
+    pub(crate) fn publish_to(&self, entries: Option<ActorRef<ChannelMsg<LogEntry>>>) {
+        *self.entries.lock().unwrap() = entries;
+    }
 
-    LoggingSystem::new(logger, Some(Arc::new(scope_guard)))
+    fn level(&self) -> Level {
+        Level::from_usize(self.level.load(Ordering::Relaxed)).unwrap_or(Level::Info)
+    }
+
+    fn is_enabled(&self, record: &Record) -> bool {
+        record.level().is_at_least(self.level())
+            && !self
+                .filters
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|filter| filter.is_match(record.module()))
+    }
+}
+
+// Applies the `LogControl` to the lines logged to `inner`, publishing them
+pub(crate) struct ControlledDrain {
+    pub(crate) inner: Logger,
+    pub(crate) control: Arc<LogControl>,
+}
+
+impl Drain for ControlledDrain {
+    type Ok = ();
+    type Err = Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        if !self.control.is_enabled(record) {
+            return Ok(());
+        }
+
+        // released before publishing, in case publishing logs
+        let entries = self.control.entries.lock().unwrap().clone();
+        if let Some(entries) = entries {
+            let entry = LogEntry {
+                time: Utc::now(),
+                level: record.level(),
+                module: record.module().to_string(),
+                msg: record.msg().to_string(),
+            };
+            entries.tell(
+                Publish {
+                    topic: "log.entries".into(),
+                    msg: entry,
+                },
+                None,
+            );
+        }
+
+        Drain::log(&self.inner, record, values)
+    }
 }
 
 struct DefaultConsoleLogger {
@@ -90,15 +271,68 @@ impl Drain for DefaultConsoleLogger {
     }
 }
 
+/// Which dead letters the `DeadLetterLogger` logs
+///
+/// Dead letters to recipients matching a `deadletters.suppress` path, which
+/// may contain `*` wildcards, aren't logged. Of the others one in every
+/// `deadletters.log_every` is logged. Dead letter metrics count every
+/// dead letter either way.
+#[derive(Clone)]
+pub struct DeadLetterLogConfig {
+    suppress: Vec<Regex>,
+    log_every: u64,
+}
+
+impl From<&Config> for DeadLetterLogConfig {
+    fn from(config: &Config) -> Self {
+        DeadLetterLogConfig {
+            suppress: config
+                .get_array("deadletters.suppress")
+                .unwrap_or_default()
+                .into_iter()
+                .map(|path| path_pattern(&path.to_string()))
+                .collect(),
+            log_every: config.get_int("deadletters.log_every").unwrap() as u64,
+        }
+    }
+}
+
 /// Simple actor that subscribes to the dead letters channel and logs using the default logger
+///
+/// See `DeadLetterLogConfig` for the dead letters it logs.
 pub struct DeadLetterLogger {
     dl_chan: ActorRef<ChannelMsg<DeadLetter>>,
     logger: LoggingSystem,
+    cfg: DeadLetterLogConfig,
+    sampled: u64,
 }
 
-impl ActorFactoryArgs<(ActorRef<ChannelMsg<DeadLetter>>, LoggingSystem)> for DeadLetterLogger {
-    fn create_args((dl_chan, logger): (ActorRef<ChannelMsg<DeadLetter>>, LoggingSystem)) -> Self {
-        DeadLetterLogger { dl_chan, logger }
+type DeadLetterLoggerArgs = (
+    ActorRef<ChannelMsg<DeadLetter>>,
+    LoggingSystem,
+    DeadLetterLogConfig,
+);
+
+impl ActorFactoryArgs<DeadLetterLoggerArgs> for DeadLetterLogger {
+    fn create_args((dl_chan, logger, cfg): DeadLetterLoggerArgs) -> Self {
+        DeadLetterLogger {
+            dl_chan,
+            logger,
+            cfg,
+            sampled: 0,
+        }
+    }
+}
+
+impl DeadLetterLogger {
+    fn should_log(&mut self, dl: &DeadLetter) -> bool {
+        let recipient = dl.recipient.path().as_str();
+        if self.cfg.suppress.iter().any(|p| p.is_match(recipient)) {
+            return false;
+        }
+
+        self.sampled += 1;
+        (self.sampled - 1).is_multiple_of(self.cfg.log_every)
     }
 }
 
@@ -107,19 +341,255 @@ impl Actor for DeadLetterLogger {
 
     fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
         let sub = Box::new(ctx.myself());
-        self.dl_chan.tell(
-            Subscribe {
-                topic: All.into(),
-                actor: sub,
-            },
-            None,
-        );
+        self.dl_chan.tell(Subscribe::new(All, sub), None);
     }
 
     fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Option<BasicActorRef>) {
-        info!(
-            self.logger,
-            "DeadLetter: {:?} => {:?} ({:?})", msg.sender, msg.recipient, msg.msg
-        )
+        if self.should_log(&msg) {
+            info!(
+                self.logger,
+                "DeadLetter: {:?} => {:?} ({:?})", msg.sender, msg.recipient, msg.msg
+            )
+        }
+    }
+}
+
+/// A structured record published by the `LoggingChannel`
+#[derive(Clone, Debug)]
+pub struct LogRecord {
+    pub time: DateTime<Utc>,
+    pub level: Level,
+
+    /// The actor the record is about. For dead letters, the recipient
+    pub actor: BasicActorRef,
+    pub event: LogEvent,
+}
+
+#[derive(Clone, Debug)]
+pub enum LogEvent {
+    ActorCreated,
+    ActorRestarted,
+    ActorTerminated,
+    ActorFailed(ActorFailed),
+    DeadLetter(DeadLetter),
+}
+
+impl LogEvent {
+    fn level(&self) -> Level {
+        match *self {
+            LogEvent::ActorCreated | LogEvent::ActorTerminated => Level::Debug,
+            LogEvent::ActorRestarted => Level::Warning,
+            LogEvent::ActorFailed(_) => Level::Error,
+            LogEvent::DeadLetter(_) => Level::Info,
+        }
+    }
+
+    fn topic(&self) -> Topic {
+        match *self {
+            LogEvent::ActorCreated => Topic::from("actor.created"),
+            LogEvent::ActorRestarted => Topic::from("actor.restarted"),
+            LogEvent::ActorTerminated => Topic::from("actor.terminated"),
+            LogEvent::ActorFailed(_) => Topic::from("actor.failed"),
+            LogEvent::DeadLetter(_) => Topic::from("dead_letter"),
+        }
+    }
+}
+
+/// Sets the maximum level of records published for actors matching `path`
+///
+/// `path` may contain `*` wildcards, e.g. `/user/db/*`. When several filters
+/// match an actor the longest (most specific) one applies.
+#[derive(Clone, Debug)]
+pub struct SetLogFilter {
+    pub path: String,
+    pub level: Level,
+}
+
+#[derive(Clone, Debug)]
+pub enum LoggingChannelMsg {
+    Channel(ChannelMsg<LogRecord>),
+    SetFilter(SetLogFilter),
+    DeadLetter(DeadLetter),
+    Event(SystemEvent),
+}
+
+impl From<ChannelMsg<LogRecord>> for LoggingChannelMsg {
+    fn from(msg: ChannelMsg<LogRecord>) -> Self {
+        LoggingChannelMsg::Channel(msg)
+    }
+}
+
+impl From<Subscribe<LogRecord>> for LoggingChannelMsg {
+    fn from(sub: Subscribe<LogRecord>) -> Self {
+        LoggingChannelMsg::Channel(sub.into())
+    }
+}
+
+impl From<Unsubscribe<LogRecord>> for LoggingChannelMsg {
+    fn from(unsub: Unsubscribe<LogRecord>) -> Self {
+        LoggingChannelMsg::Channel(unsub.into())
+    }
+}
+
+impl From<UnsubscribeAll<LogRecord>> for LoggingChannelMsg {
+    fn from(unsub: UnsubscribeAll<LogRecord>) -> Self {
+        LoggingChannelMsg::Channel(unsub.into())
+    }
+}
+
+impl From<SetLogFilter> for LoggingChannelMsg {
+    fn from(filter: SetLogFilter) -> Self {
+        LoggingChannelMsg::SetFilter(filter)
+    }
+}
+
+impl From<DeadLetter> for LoggingChannelMsg {
+    fn from(dl: DeadLetter) -> Self {
+        LoggingChannelMsg::DeadLetter(dl)
+    }
+}
+
+impl From<SystemEvent> for LoggingChannelMsg {
+    fn from(evt: SystemEvent) -> Self {
+        LoggingChannelMsg::Event(evt)
+    }
+}
+
+struct LogFilter {
+    path: String,
+    pattern: Regex,
+    level: Level,
+}
+
+/// System actor that publishes actor lifecycle events and dead letters as `LogRecord`s
+///
+/// Records are filtered by actor path and level (see `SetLogFilter`) before
+/// being published to subscribers. Records for actors that don't match any
+/// filter use the configured `log.level`.
+pub struct LoggingChannel {
+    sys_events: ActorRef<ChannelMsg<SystemEvent>>,
+    dl_chan: ActorRef<ChannelMsg<DeadLetter>>,
+    default_level: Level,
+    filters: Vec<LogFilter>,
+    channel: Channel<LogRecord>,
+}
+
+type LoggingChannelArgs = (
+    ActorRef<ChannelMsg<SystemEvent>>,
+    ActorRef<ChannelMsg<DeadLetter>>,
+    Level,
+);
+
+impl ActorFactoryArgs<LoggingChannelArgs> for LoggingChannel {
+    fn create_args((sys_events, dl_chan, default_level): LoggingChannelArgs) -> Self {
+        LoggingChannel {
+            sys_events,
+            dl_chan,
+            default_level,
+            filters: Vec::new(),
+            channel: Channel::default(),
+        }
+    }
+}
+
+impl LoggingChannel {
+    fn set_filter(&mut self, filter: SetLogFilter) {
+        self.filters.retain(|f| f.path != filter.path);
+
+        self.filters.push(LogFilter {
+            pattern: path_pattern(&filter.path),
+            path: filter.path,
+            level: filter.level,
+        });
+    }
+
+    fn level_for(&self, path: &str) -> Level {
+        self.filters
+            .iter()
+            .filter(|f| f.pattern.is_match(path))
+            .max_by_key(|f| f.path.len())
+            .map(|f| f.level)
+            .unwrap_or(self.default_level)
+    }
+
+    fn publish(&mut self, ctx: &Context<LoggingChannelMsg>, actor: BasicActorRef, event: LogEvent) {
+        let level = event.level();
+        if !level.is_at_least(self.level_for(actor.path().as_str())) {
+            return;
+        }
+
+        let topic = event.topic();
+        let record = LogRecord {
+            time: ctx.system.clock().wall(),
+            level,
+            actor,
+            event,
+        };
+
+        self.channel.publish(Publish { topic, msg: record }, None);
+    }
+
+    fn receive_event(&mut self, ctx: &Context<LoggingChannelMsg>, evt: SystemEvent) {
+        match evt {
+            SystemEvent::ActorCreated(created) => {
+                self.publish(ctx, created.actor, LogEvent::ActorCreated)
+            }
+            SystemEvent::ActorRestarted(restarted) => {
+                self.publish(ctx, restarted.actor, LogEvent::ActorRestarted)
+            }
+            SystemEvent::ActorTerminated(terminated) => {
+                self.channel.remove_stopped(&terminated.actor);
+                self.publish(ctx, terminated.actor, LogEvent::ActorTerminated)
+            }
+            SystemEvent::ActorFailed(failed) => {
+                self.publish(ctx, failed.actor.clone(), LogEvent::ActorFailed(failed))
+            }
+            SystemEvent::OverloadChanged(_) | SystemEvent::HealthChanged(_) => {}
+        }
+    }
+}
+
+impl Actor for LoggingChannel {
+    type Msg = LoggingChannelMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.sys_events
+            .tell(Subscribe::new(All, Box::new(ctx.myself())), None);
+
+        self.dl_chan
+            .tell(Subscribe::new(All, Box::new(ctx.myself())), None);
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Option<BasicActorRef>) {
+        match msg {
+            LoggingChannelMsg::Channel(ChannelMsg::Publish(p)) => self.channel.publish(p, sender),
+            LoggingChannelMsg::Channel(ChannelMsg::PublishWithReply(p)) => {
+                self.channel.publish_with_reply(p, sender)
+            }
+            LoggingChannelMsg::Channel(ChannelMsg::PublishBatch(p)) => {
+                self.channel.publish_batch(p, sender)
+            }
+            LoggingChannelMsg::Channel(ChannelMsg::Subscribe(sub)) => self.channel.subscribe(sub),
+            LoggingChannelMsg::Channel(ChannelMsg::SubscribeWithResponse(sub)) => self
+                .channel
+                .subscribe_with_response(sub, ctx.myself().into()),
+            LoggingChannelMsg::Channel(ChannelMsg::Unsubscribe(unsub)) => {
+                self.channel.unsubscribe(unsub)
+            }
+            LoggingChannelMsg::Channel(ChannelMsg::UnsubscribeAll(unsub)) => {
+                self.channel.unsubscribe_all(&unsub.actor)
+            }
+            LoggingChannelMsg::SetFilter(filter) => self.set_filter(filter),
+            LoggingChannelMsg::DeadLetter(dl) => {
+                self.publish(ctx, dl.recipient.clone(), LogEvent::DeadLetter(dl))
+            }
+            LoggingChannelMsg::Event(evt) => self.receive_event(ctx, evt),
+        }
+    }
+
+    fn sys_recv(&mut self, ctx: &Context<Self::Msg>, msg: SystemMsg, _: Option<BasicActorRef>) {
+        if let SystemMsg::Event(evt) = msg {
+            self.receive_event(ctx, evt);
+        }
     }
 }
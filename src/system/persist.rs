@@ -0,0 +1,382 @@
+use std::{
+    any::type_name,
+    collections::HashMap,
+    fs, io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use slog::warn;
+use uuid::Uuid;
+
+use crate::{
+    actor::{Actor, ActorFactoryArgs, ActorRef, Context, CreateError, Sender},
+    system::{ActorSystem, ScheduleId, Timer},
+    Message,
+};
+
+/// A schedule as kept by a `ScheduleStore`
+///
+/// The receiver is kept as its path and the message as JSON, tagged with
+/// the name of its type, so that the schedule can be restored by a new
+/// system once the process restarts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoredSchedule {
+    pub id: ScheduleId,
+
+    /// The name of the message type, `std::any::type_name` of the message
+    pub kind: String,
+    pub receiver: String,
+
+    /// When the message is next due, by the wall clock
+    pub due: DateTime<Utc>,
+
+    /// The interval of a repeated schedule, `None` if it's sent once
+    pub interval: Option<Duration>,
+    pub msg: Value,
+}
+
+impl StoredSchedule {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "id": self.id.to_string(),
+            "kind": self.kind,
+            "receiver": self.receiver,
+            "due": self.due.to_rfc3339(),
+            "interval_millis": self.interval.map(|i| i.as_millis() as u64),
+            "msg": self.msg,
+        })
+    }
+
+    /// Read a schedule written by `to_json`, `None` if it's malformed
+    pub fn from_json(value: &Value) -> Option<Self> {
+        Some(StoredSchedule {
+            id: Uuid::parse_str(value["id"].as_str()?).ok()?,
+            kind: value["kind"].as_str()?.to_string(),
+            receiver: value["receiver"].as_str()?.to_string(),
+            due: DateTime::parse_from_rfc3339(value["due"].as_str()?)
+                .ok()?
+                .with_timezone(&Utc),
+            interval: value["interval_millis"].as_u64().map(Duration::from_millis),
+            msg: value["msg"].clone(),
+        })
+    }
+}
+
+/// Keeps the schedules of a `PersistentScheduler`
+///
+/// A schedule is saved when it's created and each time a repeated schedule
+/// is sent, and removed once it's sent for the last time or cancelled.
+pub trait ScheduleStore: Send + Sync + 'static {
+    fn save(&self, schedule: &StoredSchedule) -> io::Result<()>;
+    fn remove(&self, id: ScheduleId) -> io::Result<()>;
+    fn load(&self) -> io::Result<Vec<StoredSchedule>>;
+}
+
+/// Keeps each schedule as a JSON file in a directory
+///
+/// Files are replaced atomically, so a crash never leaves a partly written
+/// schedule. Files that can't be read as a schedule are skipped.
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    /// Keep the schedules in `dir`, creating it if needed
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(FileStore { dir })
+    }
+
+    fn path(&self, id: ScheduleId) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+}
+
+impl ScheduleStore for FileStore {
+    fn save(&self, schedule: &StoredSchedule) -> io::Result<()> {
+        let tmp = self.dir.join(format!("{}.tmp", schedule.id));
+        fs::write(&tmp, schedule.to_json().to_string())?;
+        fs::rename(tmp, self.path(schedule.id))
+    }
+
+    fn remove(&self, id: ScheduleId) -> io::Result<()> {
+        match fs::remove_file(self.path(id)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn load(&self) -> io::Result<Vec<StoredSchedule>> {
+        let mut schedules = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            let schedule = fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                .and_then(|value| StoredSchedule::from_json(&value));
+            schedules.extend(schedule);
+        }
+
+        schedules.sort_by_key(|s| s.due);
+        Ok(schedules)
+    }
+}
+
+type Deliver = Arc<dyn Fn(&ActorSystem, &str) + Send + Sync>;
+
+struct Scheduled {
+    stored: StoredSchedule,
+    timer: ScheduleId,
+    deliver: Deliver,
+}
+
+type Schedules = Arc<Mutex<HashMap<ScheduleId, Scheduled>>>;
+
+/// Schedules messages that survive the process restarting
+///
+/// Messages are scheduled like `Timer::schedule_once` and `Timer::schedule`,
+/// but to a receiver path, with a message that is `Serialize` and
+/// `DeserializeOwned`. Each schedule is kept in a `ScheduleStore` until
+/// it's sent for the last time or cancelled. Once the process restarts,
+/// `restore` schedules the stored messages of a type again on the new
+/// system, e.g. for a reminder due in 7 days.
+///
+/// The receiver is looked up when the message is due, messages are dropped
+/// if there is no actor at the path. A message that became due while the
+/// process was down is sent as soon as it's restored. Schedules are tagged
+/// with the name of the message type, renaming or moving the type leaves
+/// its stored schedules unrestored.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::system::{FileStore, PersistentScheduler};
+/// # use std::time::Duration;
+///
+/// let sys = ActorSystem::new().unwrap();
+/// let dir = std::env::temp_dir().join(format!("riker-doc-{}", std::process::id()));
+///
+/// let scheduler = PersistentScheduler::start(&sys, FileStore::new(&dir).unwrap()).unwrap();
+///
+/// // schedules stored by a previous run
+/// scheduler.restore::<String>().unwrap();
+///
+/// let day = Duration::from_secs(24 * 60 * 60);
+/// let id = scheduler
+///     .schedule_once(day * 7, "/user/reminders", "renew".to_string())
+///     .unwrap();
+/// # scheduler.cancel(id).unwrap();
+/// # std::fs::remove_dir_all(dir).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct PersistentScheduler {
+    sys: ActorSystem,
+    store: Arc<dyn ScheduleStore>,
+    schedules: Schedules,
+    actor: ActorRef<Due>,
+}
+
+impl PersistentScheduler {
+    /// Start the system actor at `/system/persistent_scheduler`, keeping schedules in `store`
+    pub fn start(sys: &ActorSystem, store: impl ScheduleStore) -> Result<Self, CreateError> {
+        let schedules = Schedules::default();
+        let store: Arc<dyn ScheduleStore> = Arc::new(store);
+        let actor = sys.sys_actor_of_args::<PersistentTimer, _>(
+            "persistent_scheduler",
+            (store.clone(), schedules.clone()),
+        )?;
+
+        Ok(PersistentScheduler {
+            sys: sys.clone(),
+            store,
+            schedules,
+            actor,
+        })
+    }
+
+    /// Send `msg` to the actor at `receiver` once, after `delay`
+    pub fn schedule_once<M>(
+        &self,
+        delay: Duration,
+        receiver: &str,
+        msg: M,
+    ) -> io::Result<ScheduleId>
+    where
+        M: Message + Serialize + DeserializeOwned,
+    {
+        self.schedule(delay, None, receiver, msg)
+    }
+
+    /// Send `msg` to the actor at `receiver` after `initial_delay`, then every `interval`
+    pub fn schedule_repeat<M>(
+        &self,
+        initial_delay: Duration,
+        interval: Duration,
+        receiver: &str,
+        msg: M,
+    ) -> io::Result<ScheduleId>
+    where
+        M: Message + Serialize + DeserializeOwned,
+    {
+        self.schedule(initial_delay, Some(interval), receiver, msg)
+    }
+
+    /// Cancel a schedule, removing it from the store
+    pub fn cancel(&self, id: ScheduleId) -> io::Result<()> {
+        if let Some(scheduled) = self.schedules.lock().unwrap().remove(&id) {
+            self.sys.cancel_schedule(scheduled.timer);
+        }
+        self.store.remove(id)
+    }
+
+    /// Schedule the stored messages of type `M` on this system, returning their number
+    ///
+    /// Create the receivers first, messages that are already due are sent
+    /// right away. Stored messages that can't be read as an `M` are left in
+    /// the store.
+    pub fn restore<M>(&self) -> io::Result<usize>
+    where
+        M: Message + Serialize + DeserializeOwned,
+    {
+        let now = self.sys.clock().wall();
+        let mut restored = 0;
+
+        for stored in self.store.load()? {
+            if stored.kind != type_name::<M>() || self.is_scheduled(stored.id) {
+                continue;
+            }
+            let msg: M = match serde_json::from_value(stored.msg.clone()) {
+                Ok(msg) => msg,
+                Err(_) => continue,
+            };
+
+            let delay = (stored.due - now).to_std().unwrap_or_default();
+            self.register(stored, delay, msg);
+            restored += 1;
+        }
+
+        Ok(restored)
+    }
+
+    fn schedule<M>(
+        &self,
+        delay: Duration,
+        interval: Option<Duration>,
+        receiver: &str,
+        msg: M,
+    ) -> io::Result<ScheduleId>
+    where
+        M: Message + Serialize + DeserializeOwned,
+    {
+        let stored = StoredSchedule {
+            id: Uuid::new_v4(),
+            kind: type_name::<M>().to_string(),
+            receiver: receiver.to_string(),
+            due: self.sys.clock().wall() + wall(delay),
+            interval,
+            msg: serde_json::to_value(&msg)?,
+        };
+
+        self.store.save(&stored)?;
+        let id = stored.id;
+        self.register(stored, delay, msg);
+        Ok(id)
+    }
+
+    fn is_scheduled(&self, id: ScheduleId) -> bool {
+        self.schedules.lock().unwrap().contains_key(&id)
+    }
+
+    fn register<M: Message>(&self, stored: StoredSchedule, delay: Duration, msg: M) {
+        let id = stored.id;
+        let timer = match stored.interval {
+            Some(interval) => self
+                .sys
+                .schedule(delay, interval, self.actor.clone(), None, Due(id)),
+            None => self
+                .sys
+                .schedule_once(delay, self.actor.clone(), None, Due(id)),
+        };
+
+        // messages aren't `Sync`
+        let msg = Mutex::new(msg);
+        let deliver: Deliver = Arc::new(move |sys: &ActorSystem, receiver: &str| {
+            if let Some(receiver) = sys.select_refs(receiver).pop() {
+                let msg = msg.lock().unwrap().clone();
+                let _ = receiver.try_tell(msg, None);
+            }
+        });
+
+        self.schedules.lock().unwrap().insert(
+            id,
+            Scheduled {
+                stored,
+                timer,
+                deliver,
+            },
+        );
+    }
+}
+
+fn wall(duration: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX)
+}
+
+// A schedule is due, sent by the system timer
+#[derive(Clone, Debug)]
+struct Due(ScheduleId);
+
+type PersistentTimerArgs = (Arc<dyn ScheduleStore>, Schedules);
+
+// Sends the due messages, updating the store
+struct PersistentTimer {
+    store: Arc<dyn ScheduleStore>,
+    schedules: Schedules,
+}
+
+impl ActorFactoryArgs<PersistentTimerArgs> for PersistentTimer {
+    fn create_args((store, schedules): PersistentTimerArgs) -> Self {
+        PersistentTimer { store, schedules }
+    }
+}
+
+impl Actor for PersistentTimer {
+    type Msg = Due;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, Due(id): Self::Msg, _sender: Sender) {
+        let mut schedules = self.schedules.lock().unwrap();
+        let scheduled = match schedules.get_mut(&id) {
+            Some(scheduled) => scheduled,
+            // cancelled after the timer sent it
+            None => return,
+        };
+
+        (scheduled.deliver)(&ctx.system, &scheduled.stored.receiver);
+
+        let result = match scheduled.stored.interval {
+            Some(interval) => {
+                scheduled.stored.due = ctx.system.clock().wall() + wall(interval);
+                self.store.save(&scheduled.stored)
+            }
+            None => {
+                schedules.remove(&id);
+                self.store.remove(id)
+            }
+        };
+
+        if let Err(e) = result {
+            warn!(ctx.system.log(), "Failed to store schedule {}: {}", id, e);
+        }
+    }
+}
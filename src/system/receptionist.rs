@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use crate::actor::{
+    Actor, ActorFactoryArgs, ActorRef, ActorReference, BasicActorRef, BoxedTell, ChannelMsg,
+    Context, ReplyTo, Sender, Subscribe, SysTopic, Tell,
+};
+use crate::system::{SystemEvent, SystemMsg};
+
+/// The actors registered for a service, see `ActorSystem::register`
+#[derive(Clone, Debug)]
+pub struct Listing {
+    pub service: String,
+    pub actors: Vec<BasicActorRef>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum ReceptionistMsg {
+    Register(String, BasicActorRef),
+    Deregister(String, BasicActorRef),
+    Lookup(String, ReplyTo<Listing>),
+    Subscribe(String, BoxedTell<Listing>),
+    Event(SystemEvent),
+}
+
+impl From<SystemEvent> for ReceptionistMsg {
+    fn from(evt: SystemEvent) -> Self {
+        ReceptionistMsg::Event(evt)
+    }
+}
+
+/// System actor keeping the actors registered for each service name
+///
+/// Registered actors are removed when they terminate. Subscribers are
+/// sent the service's `Listing` when they subscribe and on every change.
+pub(crate) struct Receptionist {
+    sys_events: ActorRef<ChannelMsg<SystemEvent>>,
+    services: HashMap<String, Vec<BasicActorRef>>,
+    subscribers: HashMap<String, Vec<BoxedTell<Listing>>>,
+}
+
+impl ActorFactoryArgs<ActorRef<ChannelMsg<SystemEvent>>> for Receptionist {
+    fn create_args(sys_events: ActorRef<ChannelMsg<SystemEvent>>) -> Self {
+        Receptionist {
+            sys_events,
+            services: HashMap::new(),
+            subscribers: HashMap::new(),
+        }
+    }
+}
+
+impl Receptionist {
+    fn listing(&self, service: &str) -> Listing {
+        Listing {
+            service: service.to_string(),
+            actors: self.services.get(service).cloned().unwrap_or_default(),
+        }
+    }
+
+    fn changed(&self, service: &str) {
+        if let Some(subscribers) = self.subscribers.get(service) {
+            let listing = self.listing(service);
+            for sub in subscribers {
+                sub.tell(listing.clone(), None);
+            }
+        }
+    }
+
+    fn receive_event(&mut self, evt: SystemEvent) {
+        if let SystemEvent::ActorTerminated(terminated) = evt {
+            let mut changed = Vec::new();
+            for (service, actors) in self.services.iter_mut() {
+                let len = actors.len();
+                actors.retain(|a| a != &terminated.actor);
+                if actors.len() != len {
+                    changed.push(service.clone());
+                }
+            }
+
+            for service in changed {
+                self.changed(&service);
+            }
+
+            for subscribers in self.subscribers.values_mut() {
+                subscribers.retain(|s| s.path() != terminated.actor.path());
+            }
+        }
+    }
+}
+
+impl Actor for Receptionist {
+    type Msg = ReceptionistMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.sys_events.tell(
+            Subscribe::new(SysTopic::ActorTerminated, Box::new(ctx.myself())),
+            None,
+        );
+    }
+
+    fn sys_recv(&mut self, _: &Context<Self::Msg>, msg: SystemMsg, _: Sender) {
+        if let SystemMsg::Event(evt) = msg {
+            self.receive_event(evt);
+        }
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        match msg {
+            ReceptionistMsg::Register(service, actor) => {
+                let actors = self.services.entry(service.clone()).or_default();
+                if !actors.contains(&actor) {
+                    actors.push(actor);
+                    self.changed(&service);
+                }
+            }
+            ReceptionistMsg::Deregister(service, actor) => {
+                if let Some(actors) = self.services.get_mut(&service) {
+                    let len = actors.len();
+                    actors.retain(|a| a != &actor);
+                    if actors.len() != len {
+                        self.changed(&service);
+                    }
+                }
+            }
+            ReceptionistMsg::Lookup(service, reply_to) => {
+                let _ = reply_to.reply(self.listing(&service));
+            }
+            ReceptionistMsg::Subscribe(service, sub) => {
+                sub.tell(self.listing(&service), None);
+                self.subscribers.entry(service).or_default().push(sub);
+            }
+            ReceptionistMsg::Event(evt) => self.receive_event(evt),
+        }
+    }
+}
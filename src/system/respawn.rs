@@ -0,0 +1,184 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use futures::task::SpawnExt;
+
+use crate::{
+    actor::{
+        Actor, ActorPath, ActorRef, ActorRefFactory, ActorReference, BoxActorProd, BoxedTell,
+        DeadLetter, DeadLetterReason,
+    },
+    system::ActorSystem,
+    Message,
+};
+
+type RespawnFn = Arc<dyn Fn(&ActorSystem, &ActorPath) + Send + Sync>;
+
+/// Sent to a manager actor when a `RespawnPolicy` is triggered
+#[derive(Clone, Debug)]
+pub struct RespawnRequest {
+    /// Path of the actor the dead letters were addressed to
+    pub path: ActorPath,
+
+    /// Number of dead letters counted since the policy last triggered
+    pub dead_letters: usize,
+}
+
+enum RespawnAction {
+    Callback(RespawnFn),
+    Notify(BoxedTell<RespawnRequest>),
+}
+
+/// A policy to recreate actors that are missing from the hierarchy
+///
+/// Dead letters addressed to terminated actors under `prefix` are
+/// counted and when `threshold` is reached the policy is triggered
+/// and the count is reset. Dead letters shed by the governor are
+/// not counted.
+///
+/// Policies are registered using `ActorSystem::add_respawn_policy`.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+///
+/// #[derive(Default)]
+/// struct Worker;
+///
+/// impl Actor for Worker {
+///     type Msg = String;
+///
+///     fn recv(&mut self, _: &Context<String>, _: String, _: Sender) {}
+/// }
+///
+/// let sys = ActorSystem::new().unwrap();
+///
+/// // recreate `/user/worker` after 10 dead letters
+/// let policy = RespawnPolicy::new("/user/worker", 10).recreate("worker", Props::new::<Worker>());
+/// sys.add_respawn_policy(policy);
+/// ```
+pub struct RespawnPolicy {
+    prefix: String,
+    threshold: usize,
+    action: Option<RespawnAction>,
+}
+
+impl RespawnPolicy {
+    pub fn new(prefix: &str, threshold: usize) -> Self {
+        RespawnPolicy {
+            prefix: prefix.to_string(),
+            threshold: threshold.max(1),
+            action: None,
+        }
+    }
+
+    /// Invoke `f` with the path of the dead letter that triggered the policy
+    ///
+    /// `f` runs on the system executor.
+    pub fn callback<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&ActorSystem, &ActorPath) + Send + Sync + 'static,
+    {
+        self.action = Some(RespawnAction::Callback(Arc::new(f)));
+        self
+    }
+
+    /// Send a `RespawnRequest` to `manager`
+    pub fn notify<Msg>(mut self, manager: ActorRef<Msg>) -> Self
+    where
+        Msg: Message,
+        RespawnRequest: Into<Msg>,
+    {
+        self.action = Some(RespawnAction::Notify(Box::new(manager)));
+        self
+    }
+
+    /// Recreate the actor as a top level user actor named `name` using `props`
+    ///
+    /// If the actor already exists it is not created again.
+    pub fn recreate<A>(self, name: &str, props: BoxActorProd<A>) -> Self
+    where
+        A: Actor,
+    {
+        let name = name.to_string();
+        self.callback(move |sys, _| {
+            let _ = sys.actor_of_props(&name, props.clone());
+        })
+    }
+}
+
+impl fmt::Debug for RespawnPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RespawnPolicy")
+            .field("prefix", &self.prefix)
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+struct PolicyState {
+    policy: RespawnPolicy,
+    count: usize,
+}
+
+/// Registered respawn policies and their dead letter counts
+#[derive(Default)]
+pub(crate) struct RespawnPolicies {
+    policies: Mutex<Vec<PolicyState>>,
+}
+
+impl RespawnPolicies {
+    pub fn add(&self, policy: RespawnPolicy) {
+        self.policies
+            .lock()
+            .unwrap()
+            .push(PolicyState { policy, count: 0 });
+    }
+
+    /// Count a dead letter, triggering any policy that reaches its threshold
+    pub fn record(&self, sys: &ActorSystem, dl: &DeadLetter) {
+        // the recipient is alive, its messages were shed
+        if let DeadLetterReason::Overloaded | DeadLetterReason::Throttled = dl.reason {
+            return;
+        }
+
+        let path = dl.recipient.path();
+        let mut policies = self.policies.lock().unwrap();
+
+        for state in policies.iter_mut() {
+            if !path.as_str().starts_with(&state.policy.prefix) {
+                continue;
+            }
+
+            state.count += 1;
+            if state.count < state.policy.threshold {
+                continue;
+            }
+
+            let dead_letters = state.count;
+            state.count = 0;
+
+            match state.policy.action {
+                Some(RespawnAction::Callback(ref f)) => {
+                    let f = f.clone();
+                    let path = path.clone();
+                    let s = sys.clone();
+                    let _ = sys.exec.spawn(async move { f(&s, &path) });
+                }
+                Some(RespawnAction::Notify(ref manager)) => {
+                    manager.tell(
+                        RespawnRequest {
+                            path: path.clone(),
+                            dead_letters,
+                        },
+                        None,
+                    );
+                }
+                None => {}
+            }
+        }
+    }
+}
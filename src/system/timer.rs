@@ -1,5 +1,7 @@
 use std::{
-    sync::mpsc,
+    collections::HashMap,
+    io,
+    sync::{mpsc, Arc, Mutex},
     thread,
     time::{Duration, Instant},
 };
@@ -9,7 +11,11 @@ use config::Config;
 use uuid::Uuid;
 
 use crate::{
-    actor::{ActorRef, BasicActorRef, Sender},
+    actor::{ActorPath, ActorRef, ActorReference, BasicActorRef, Sender},
+    system::{
+        clock::{Clock, ClockRef, ManualClock},
+        SystemMsg,
+    },
     AnyMessage, Message,
 };
 
@@ -17,6 +23,28 @@ pub type TimerRef = mpsc::Sender<Job>;
 
 pub type ScheduleId = Uuid;
 
+/// Starts the timer an `ActorSystem` schedules on, see `SystemBuilder::timer_factory`
+///
+/// The timer receives `Job`s on the returned `TimerRef`, measuring time with
+/// the system clock. Cancel jobs may name a job that was already sent.
+/// `name` is the system's name, e.g. to name the timer's thread after.
+/// An error fails the creation of the system with `SystemError::Timer`.
+///
+/// Implemented for functions with the signature of `BasicTimer::start`,
+/// the default timer.
+pub trait TimerFactory {
+    fn start(&self, name: &str, cfg: &Config, clock: ClockRef) -> io::Result<TimerRef>;
+}
+
+impl<F> TimerFactory for F
+where
+    F: Fn(&str, &Config, ClockRef) -> io::Result<TimerRef>,
+{
+    fn start(&self, name: &str, cfg: &Config, clock: ClockRef) -> io::Result<TimerRef> {
+        self(name, cfg, clock)
+    }
+}
+
 pub trait Timer {
     fn schedule<T, M>(
         &self,
@@ -30,6 +58,22 @@ pub trait Timer {
         T: Message + Into<M>,
         M: Message;
 
+    /// Like `schedule`, but each time the job fires a new message is made by `f`
+    ///
+    /// e.g. to send a message with the current time.
+    fn schedule_fn<F, T, M>(
+        &self,
+        initial_delay: Duration,
+        interval: Duration,
+        receiver: ActorRef<M>,
+        sender: Sender,
+        f: F,
+    ) -> ScheduleId
+    where
+        F: FnMut() -> T + Send + 'static,
+        T: Into<M>,
+        M: Message;
+
     fn schedule_once<T, M>(
         &self,
         delay: Duration,
@@ -53,12 +97,23 @@ pub trait Timer {
         M: Message;
 
     fn cancel_schedule(&self, id: Uuid);
+
+    /// Cancel every schedule to the actor at `path`
+    ///
+    /// Done by the system when an actor terminates, so that repeat jobs
+    /// aren't sent to it forever.
+    fn cancel_for(&self, path: &ActorPath);
 }
 
 pub enum Job {
     Once(OnceJob),
     Repeat(RepeatJob),
+    Sys(SysJob),
     Cancel(Uuid),
+    /// Cancel the jobs sent to the actor at the path, e.g. once it terminated
+    CancelFor(ActorPath),
+    /// Sent once the system has shut down, the timer drops its jobs and stops
+    Stop,
 }
 
 pub struct OnceJob {
@@ -92,63 +147,195 @@ impl RepeatJob {
     }
 }
 
+/// A system message sent once, such as an actor's `ReceiveTimeout`
+pub struct SysJob {
+    pub id: Uuid,
+    pub send_at: Instant,
+    pub receiver: BasicActorRef,
+    pub msg: SystemMsg,
+}
+
+impl SysJob {
+    pub fn send(self) {
+        self.receiver.sys_tell(self.msg);
+    }
+}
+
+type Batch<'a> = (BasicActorRef, Vec<(&'a mut AnyMessage, Sender)>);
+
+// Messages due in the same tick, grouped by receiver so that
+// each receiver's mailbox is scheduled once
+#[derive(Default)]
+struct Batches<'a> {
+    batches: HashMap<String, Batch<'a>>,
+}
+
+impl<'a> Batches<'a> {
+    fn add(&mut self, receiver: &BasicActorRef, msg: &'a mut AnyMessage, sender: Sender) {
+        self.batches
+            .entry(receiver.path().to_string())
+            .or_insert_with(|| (receiver.clone(), Vec::new()))
+            .1
+            .push((msg, sender));
+    }
+
+    fn send(self) {
+        for (receiver, msgs) in self.batches.into_values() {
+            receiver.try_tell_any_batch(msgs);
+        }
+    }
+}
+
 // Default timer implementation
 
+/// The default `Timer`, run on its own thread
+///
+/// Every `scheduler.frequency_millis` the jobs that are due are sent,
+/// with the messages due for the same actor enqueued as a batch.
+///
+/// With `scheduler.coalesce_repeats` enabled a repeat job is skipped
+/// while its receiver still has messages waiting in its mailbox, so
+/// that repeated messages don't pile up behind a slow receiver.
 pub struct BasicTimer {
     once_jobs: Vec<OnceJob>,
     repeat_jobs: Vec<RepeatJob>,
+    sys_jobs: Vec<SysJob>,
+    clock: ClockRef,
+    coalesce_repeats: bool,
 }
 
 impl BasicTimer {
-    pub fn start(cfg: &Config) -> TimerRef {
+    /// Start the timer on its own thread, named `<name>-timer`
+    ///
+    /// Fails if the thread can't be spawned.
+    pub fn start(name: &str, cfg: &Config, clock: ClockRef) -> io::Result<TimerRef> {
         let cfg = BasicTimerConfig::from(cfg);
+        let mut process = BasicTimer::new(clock, cfg.coalesce_repeats);
+
+        let (tx, rx) = mpsc::channel();
+        thread::Builder::new()
+            .name(format!("{}-timer", name))
+            .spawn(move || loop {
+                // take every job sent since the last tick,
+                // so that a burst of jobs doesn't delay those behind it
+                loop {
+                    match rx.try_recv() {
+                        Ok(Job::Stop) => return,
+                        Ok(job) => process.receive(job),
+                        // every `TimerRef` was dropped with its system
+                        Err(mpsc::TryRecvError::Disconnected) => return,
+                        Err(mpsc::TryRecvError::Empty) => break,
+                    }
+                }
 
-        let mut process = BasicTimer {
+                process.execute_jobs();
+
+                thread::sleep(Duration::from_millis(cfg.frequency_millis));
+            })?;
+
+        Ok(tx)
+    }
+
+    fn new(clock: ClockRef, coalesce_repeats: bool) -> Self {
+        BasicTimer {
             once_jobs: Vec::new(),
             repeat_jobs: Vec::new(),
-        };
+            sys_jobs: Vec::new(),
+            clock,
+            coalesce_repeats,
+        }
+    }
 
-        let (tx, rx) = mpsc::channel();
-        thread::spawn(move || loop {
-            process.execute_once_jobs();
-            process.execute_repeat_jobs();
-
-            if let Ok(job) = rx.try_recv() {
-                match job {
-                    Job::Cancel(id) => process.cancel(&id),
-                    Job::Once(job) => process.schedule_once(job),
-                    Job::Repeat(job) => process.schedule_repeat(job),
-                }
+    fn receive(&mut self, job: Job) {
+        match job {
+            Job::Cancel(id) => self.cancel(&id),
+            Job::CancelFor(path) => self.cancel_for(&path),
+            Job::Once(job) => self.schedule_once(job),
+            Job::Repeat(job) => self.schedule_repeat(job),
+            Job::Sys(job) => self.schedule_sys(job),
+            Job::Stop => {
+                self.once_jobs.clear();
+                self.repeat_jobs.clear();
+                self.sys_jobs.clear();
             }
+        }
+    }
 
-            thread::sleep(Duration::from_millis(cfg.frequency_millis));
-        });
+    // The earliest time a job is due
+    fn next_due(&self) -> Option<Instant> {
+        let once = self.once_jobs.iter().map(|j| j.send_at);
+        let repeat = self.repeat_jobs.iter().map(|j| j.send_at);
+        let sys = self.sys_jobs.iter().map(|j| j.send_at);
+        once.chain(repeat).chain(sys).min()
+    }
+
+    /// Send both the once and repeat jobs that are due
+    pub fn execute_jobs(&mut self) {
+        let now = self.clock.now();
+        let mut once_jobs = self.due_once_jobs(now);
+
+        let mut batches = Batches::default();
+        for job in once_jobs.iter_mut() {
+            batches.add(&job.receiver, &mut job.msg, job.sender.clone());
+        }
+        self.due_repeat_jobs(now, &mut batches);
 
-        tx
+        batches.send();
+        self.send_due_sys_jobs(now);
     }
 
     pub fn execute_once_jobs(&mut self) {
-        let (send, keep): (Vec<OnceJob>, Vec<OnceJob>) = self
-            .once_jobs
-            .drain(..)
-            .partition(|j| Instant::now() >= j.send_at);
+        let now = self.clock.now();
+        let mut once_jobs = self.due_once_jobs(now);
 
-        // send those messages where the 'send_at' time has been reached or elapsed
-        for job in send {
-            job.send();
+        let mut batches = Batches::default();
+        for job in once_jobs.iter_mut() {
+            batches.add(&job.receiver, &mut job.msg, job.sender.clone());
         }
 
+        batches.send();
+    }
+
+    pub fn execute_repeat_jobs(&mut self) {
+        let now = self.clock.now();
+
+        let mut batches = Batches::default();
+        self.due_repeat_jobs(now, &mut batches);
+
+        batches.send();
+    }
+
+    fn due_once_jobs(&mut self, now: Instant) -> Vec<OnceJob> {
+        let (send, keep): (Vec<OnceJob>, Vec<OnceJob>) =
+            self.once_jobs.drain(..).partition(|j| now >= j.send_at);
+
         // for those messages that are not to be sent yet, just put them back on the vec
-        for job in keep {
-            self.once_jobs.push(job);
+        self.once_jobs = keep;
+
+        // those messages where the 'send_at' time has been reached or elapsed
+        send
+    }
+
+    fn send_due_sys_jobs(&mut self, now: Instant) {
+        let (send, keep): (Vec<SysJob>, Vec<SysJob>) =
+            self.sys_jobs.drain(..).partition(|j| now >= j.send_at);
+        self.sys_jobs = keep;
+
+        for job in send {
+            job.send();
         }
     }
 
-    pub fn execute_repeat_jobs(&mut self) {
+    fn due_repeat_jobs<'a>(&'a mut self, now: Instant, batches: &mut Batches<'a>) {
         for job in self.repeat_jobs.iter_mut() {
-            if Instant::now() >= job.send_at {
-                job.send_at = Instant::now() + job.interval;
-                job.send();
+            if now >= job.send_at {
+                job.send_at = now + job.interval;
+
+                if self.coalesce_repeats && job.receiver.queued() > 0 {
+                    continue;
+                }
+
+                batches.add(&job.receiver, &mut job.msg, job.sender.clone());
             }
         }
     }
@@ -164,10 +351,25 @@ impl BasicTimer {
         if let Some(pos) = self.repeat_jobs.iter().position(|job| &job.id == id) {
             self.repeat_jobs.remove(pos);
         }
+
+        if let Some(pos) = self.sys_jobs.iter().position(|job| &job.id == id) {
+            self.sys_jobs.remove(pos);
+        }
+    }
+
+    pub fn cancel_for(&mut self, path: &ActorPath) {
+        self.once_jobs.retain(|job| job.receiver.path() != path);
+        self.repeat_jobs.retain(|job| job.receiver.path() != path);
+        self.sys_jobs.retain(|job| job.receiver.path() != path);
+    }
+
+    /// Number of jobs waiting to be sent
+    pub fn pending(&self) -> usize {
+        self.once_jobs.len() + self.repeat_jobs.len() + self.sys_jobs.len()
     }
 
     pub fn schedule_once(&mut self, job: OnceJob) {
-        if Instant::now() >= job.send_at {
+        if self.clock.now() >= job.send_at {
             job.send();
         } else {
             self.once_jobs.push(job);
@@ -175,21 +377,138 @@ impl BasicTimer {
     }
 
     pub fn schedule_repeat(&mut self, mut job: RepeatJob) {
-        if Instant::now() >= job.send_at {
+        if self.clock.now() >= job.send_at {
             job.send();
         }
         self.repeat_jobs.push(job);
     }
+
+    pub fn schedule_sys(&mut self, job: SysJob) {
+        if self.clock.now() >= job.send_at {
+            job.send();
+        } else {
+            self.sys_jobs.push(job);
+        }
+    }
+}
+
+/// A timer that only sends scheduled messages when advanced, for deterministic tests
+///
+/// Given to `SystemBuilder::timer`, the system schedules on this timer and
+/// uses its `ManualClock` as the system clock. `advance` then moves virtual
+/// time forward, sending the messages that fall due in the order they are
+/// due, on the calling thread. A repeat job is sent once for each interval
+/// that passes.
+///
+/// Cloned `ManualTimer`s share the same jobs and time.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::system::ManualTimer;
+/// # use std::time::Duration;
+///
+/// let timer = ManualTimer::new();
+/// let sys = SystemBuilder::new().timer(timer.clone()).create().unwrap();
+/// let inbox = Inbox::<u32>::new(&sys).unwrap();
+///
+/// let tick = Duration::from_secs(10);
+/// sys.schedule(tick, tick, inbox.sender_ref(), None, 1u32);
+///
+/// timer.advance(Duration::from_secs(60));
+/// let ticks = (0..6).filter_map(|_| inbox.receive(Duration::from_secs(1))).count();
+/// assert_eq!(ticks, 6);
+/// ```
+#[derive(Clone)]
+pub struct ManualTimer {
+    clock: ManualClock,
+    tx: TimerRef,
+    jobs: Arc<Mutex<(BasicTimer, mpsc::Receiver<Job>)>>,
+}
+
+impl ManualTimer {
+    pub fn new() -> Self {
+        let clock = ManualClock::new();
+        let (tx, rx) = mpsc::channel();
+        let timer = BasicTimer::new(Arc::new(clock.clone()), false);
+
+        ManualTimer {
+            clock,
+            tx,
+            jobs: Arc::new(Mutex::new((timer, rx))),
+        }
+    }
+
+    /// The timer's clock, used as the system clock
+    pub fn clock(&self) -> &ManualClock {
+        &self.clock
+    }
+
+    /// Number of jobs waiting to be sent, including those not yet received
+    pub fn pending(&self) -> usize {
+        let mut jobs = self.jobs.lock().unwrap();
+        let (timer, rx) = &mut *jobs;
+        while let Ok(job) = rx.try_recv() {
+            timer.receive(job);
+        }
+
+        timer.pending()
+    }
+
+    /// Move virtual time forward by `duration`, sending the messages that fall due
+    pub fn advance(&self, duration: Duration) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let (timer, rx) = &mut *jobs;
+        let until = self.clock.now() + duration;
+        let mut sent_at = None;
+
+        loop {
+            while let Ok(job) = rx.try_recv() {
+                timer.receive(job);
+            }
+
+            // a repeat job with a zero interval is sent once per advance
+            match timer.next_due() {
+                Some(due) if due <= until && sent_at != Some(due) => {
+                    let now = self.clock.now();
+                    if due > now {
+                        self.clock.advance(due - now);
+                    }
+                    timer.execute_jobs();
+                    sent_at = Some(due);
+                }
+                _ => break,
+            }
+        }
+
+        let now = self.clock.now();
+        self.clock.advance(until - now);
+    }
+}
+
+impl TimerFactory for ManualTimer {
+    fn start(&self, _: &str, _: &Config, _: ClockRef) -> io::Result<TimerRef> {
+        Ok(self.tx.clone())
+    }
+}
+
+impl Default for ManualTimer {
+    fn default() -> Self {
+        ManualTimer::new()
+    }
 }
 
 struct BasicTimerConfig {
     frequency_millis: u64,
+    coalesce_repeats: bool,
 }
 
-impl<'a> From<&'a Config> for BasicTimerConfig {
+impl From<&Config> for BasicTimerConfig {
     fn from(config: &Config) -> Self {
         BasicTimerConfig {
             frequency_millis: config.get_int("scheduler.frequency_millis").unwrap() as u64,
+            coalesce_repeats: config.get_bool("scheduler.coalesce_repeats").unwrap(),
         }
     }
 }
@@ -0,0 +1,66 @@
+use std::collections::BTreeSet;
+
+use crate::actor::{ActorReference, BasicActorRef};
+
+/// An actor and its descendants at the time of `ActorSystem::tree_snapshot`
+///
+/// Children are ordered by name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActorTreeNode {
+    pub path: String,
+    pub name: String,
+    pub children: Vec<ActorTreeNode>,
+    pub children_count: usize,
+
+    /// Number of user messages waiting in the actor's mailbox
+    pub mailbox_depth: usize,
+}
+
+impl ActorTreeNode {
+    pub(crate) fn new(actor: &BasicActorRef) -> Self {
+        let mut children: Vec<_> = actor.children().map(|c| ActorTreeNode::new(&c)).collect();
+        children.sort_by(|a, b| a.name.cmp(&b.name));
+
+        ActorTreeNode {
+            path: actor.path().to_string(),
+            name: actor.name().to_string(),
+            children_count: children.len(),
+            children,
+            mailbox_depth: actor.queued(),
+        }
+    }
+
+    /// Returns this node and all its descendants, depth first
+    pub fn iter(&self) -> impl Iterator<Item = &ActorTreeNode> + '_ {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    /// Compare with an earlier snapshot, see `ActorSystem::tree_diff`
+    pub fn diff(&self, prev: &ActorTreeNode) -> ActorTreeDiff {
+        let now: BTreeSet<_> = self.iter().map(|n| n.path.as_str()).collect();
+        let before: BTreeSet<_> = prev.iter().map(|n| n.path.as_str()).collect();
+
+        ActorTreeDiff {
+            added: now.difference(&before).map(|p| p.to_string()).collect(),
+            removed: before.difference(&now).map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+/// The actors created and stopped between two snapshots, by path in path order
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ActorTreeDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl ActorTreeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
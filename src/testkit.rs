@@ -0,0 +1,164 @@
+use std::{fmt, time::Duration};
+
+use config::Config;
+
+use crate::{
+    actor::{ActorRef, CreateError, Inbox, Sender},
+    load_config,
+    system::{ActorSystem, ManualTimer, SystemBuilder, SystemError},
+    Message,
+};
+
+/// An actor system for tests, run on virtual time
+///
+/// The system schedules on a `ManualTimer`, so scheduled messages are sent
+/// when the test calls `advance` rather than after real time passes.
+///
+/// # Examples
+///
+/// ```
+/// # use riker::actors::*;
+/// # use riker::testkit::TestKit;
+/// # use std::time::Duration;
+///
+/// let kit = TestKit::new().unwrap();
+/// let probe = kit.probe::<String>().unwrap();
+///
+/// kit.system().schedule_once(
+///     Duration::from_secs(60),
+///     probe.actor_ref(),
+///     None,
+///     "later".to_string(),
+/// );
+///
+/// probe.expect_no_msg(Duration::from_millis(100));
+/// kit.advance(Duration::from_secs(60));
+/// probe.expect_msg("later".to_string());
+/// ```
+pub struct TestKit {
+    sys: ActorSystem,
+    timer: ManualTimer,
+}
+
+impl TestKit {
+    pub fn new() -> Result<Self, SystemError> {
+        TestKit::with_config(load_config())
+    }
+
+    pub fn with_config(cfg: Config) -> Result<Self, SystemError> {
+        let timer = ManualTimer::new();
+        let sys = SystemBuilder::new()
+            .name("testkit")
+            .cfg(cfg)
+            .timer(timer.clone())
+            .create()?;
+
+        Ok(TestKit { sys, timer })
+    }
+
+    pub fn system(&self) -> &ActorSystem {
+        &self.sys
+    }
+
+    /// Create a `TestProbe` in this kit's system
+    pub fn probe<Msg: Message>(&self) -> Result<TestProbe<Msg>, CreateError> {
+        TestProbe::new(&self.sys)
+    }
+
+    /// Move virtual time forward by `duration`, see `ManualTimer::advance`
+    pub fn advance(&self, duration: Duration) {
+        self.timer.advance(duration);
+    }
+
+    /// Scale `duration` by `testkit.time_factor`
+    pub fn dilated(&self, duration: Duration) -> Duration {
+        dilated(&self.sys, duration)
+    }
+}
+
+/// A test actor that queues the messages it receives, to assert on them
+///
+/// Expectations wait in real time, scaled by `testkit.time_factor`,
+/// and panic if they aren't met.
+pub struct TestProbe<Msg: Message> {
+    inbox: Inbox<Msg>,
+    sys: ActorSystem,
+}
+
+impl<Msg: Message> TestProbe<Msg> {
+    pub fn new(sys: &ActorSystem) -> Result<Self, CreateError> {
+        Ok(TestProbe {
+            inbox: Inbox::new(sys)?,
+            sys: sys.clone(),
+        })
+    }
+
+    /// Returns the probe's `ActorRef`, to send messages to or use as a sender
+    pub fn actor_ref(&self) -> ActorRef<Msg> {
+        self.inbox.sender_ref()
+    }
+
+    /// Receive the next message within `testkit.expect_timeout_millis`
+    pub fn receive_msg(&self) -> (Msg, Sender) {
+        let timeout = self
+            .sys
+            .config()
+            .get_int("testkit.expect_timeout_millis")
+            .unwrap();
+        self.receive_msg_within(Duration::from_millis(timeout as u64))
+    }
+
+    /// Receive the next message within `within`
+    pub fn receive_msg_within(&self, within: Duration) -> (Msg, Sender) {
+        let within = dilated(&self.sys, within);
+        match self.inbox.receive_with_sender(within) {
+            Some(msg) => msg,
+            None => panic!("TestProbe: no message received within {:?}", within),
+        }
+    }
+
+    /// Expect the next message to be `expected`, within `testkit.expect_timeout_millis`
+    pub fn expect_msg(&self, expected: Msg) -> Sender
+    where
+        Msg: PartialEq,
+    {
+        let (msg, sender) = self.receive_msg();
+        assert_msg(msg, expected);
+        sender
+    }
+
+    /// Expect the next message to be `expected`, within `within`
+    pub fn expect_msg_within(&self, within: Duration, expected: Msg) -> Sender
+    where
+        Msg: PartialEq,
+    {
+        let (msg, sender) = self.receive_msg_within(within);
+        assert_msg(msg, expected);
+        sender
+    }
+
+    /// Expect no message to be received within `within`
+    pub fn expect_no_msg(&self, within: Duration) {
+        let within = dilated(&self.sys, within);
+        if let Some((msg, _)) = self.inbox.receive_with_sender(within) {
+            panic!("TestProbe: expected no message, received {:?}", msg);
+        }
+    }
+}
+
+impl<Msg: Message> fmt::Debug for TestProbe<Msg> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TestProbe[{:?}]", self.actor_ref())
+    }
+}
+
+fn assert_msg<Msg: Message + PartialEq>(msg: Msg, expected: Msg) {
+    if msg != expected {
+        panic!("TestProbe: expected {:?}, received {:?}", expected, msg);
+    }
+}
+
+fn dilated(sys: &ActorSystem, duration: Duration) -> Duration {
+    let factor = sys.config().get_float("testkit.time_factor").unwrap();
+    duration.mul_f64(factor)
+}
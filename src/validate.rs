@@ -1,12 +1,47 @@
 use regex::Regex;
 use std::fmt;
 
+/// Check `name` against the actor name grammar
+///
+/// A name is one or more segments separated by `.`, e.g. `orders.eu-west.worker-1`,
+/// and each segment is one or more unicode letters or digits, `_` or `-`:
+///
+/// ```text
+/// name    = segment *( "." segment )
+/// segment = 1*( alphanumeric / "_" / "-" )
+/// ```
+///
+/// So empty segments, e.g. `.`, `..` or `a..b`, are invalid, leaving `..`
+/// free to select an actor's parent. Actor system names follow the same grammar.
 pub fn validate_name(name: &str) -> Result<(), InvalidName> {
-    let rgx = Regex::new(r"^[a-zA-Z0-9_-]+$").unwrap();
-    if !rgx.is_match(name) {
-        Err(InvalidName { name: name.into() })
-    } else {
+    let valid_segment = |seg: &str| {
+        !seg.is_empty()
+            && seg
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    };
+
+    if name.split('.').all(valid_segment) {
         Ok(())
+    } else {
+        Err(InvalidName { name: name.into() })
+    }
+}
+
+/// Checks names of actors created under `/user`, see `SystemBuilder::name_validator`
+///
+/// A validator can only further restrict names, it's checked once the name
+/// follows the actor name grammar. Implemented for `Fn(&str) -> bool`.
+pub trait NameValidator: Send + Sync + 'static {
+    fn is_valid(&self, name: &str) -> bool;
+}
+
+impl<F> NameValidator for F
+where
+    F: Fn(&str) -> bool + Send + Sync + 'static,
+{
+    fn is_valid(&self, name: &str) -> bool {
+        self(name)
     }
 }
 
@@ -17,7 +52,7 @@ pub struct InvalidName {
 impl fmt::Display for InvalidName {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(&format!(
-            "\"{}\". Invalid name. Must contain only a-Z, 0-9, _, or -",
+            "\"{}\". Invalid name. Must be segments of letters, digits, _ or -, separated by .",
             self.name
         ))
     }
@@ -30,7 +65,7 @@ impl fmt::Debug for InvalidName {
 }
 
 pub fn validate_path(path: &str) -> Result<(), InvalidPath> {
-    let rgx = Regex::new(r"^[a-zA-Z0-9/*._-]+$").unwrap();
+    let rgx = Regex::new(r"^[\w/*.-]+$").unwrap();
     if !rgx.is_match(path) {
         Err(InvalidPath { path: path.into() })
     } else {
@@ -45,7 +80,7 @@ pub struct InvalidPath {
 impl fmt::Display for InvalidPath {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(&format!(
-            "\"{}\". Invalid path. Must contain only a-Z, 0-9, /, _, .., - or *",
+            "\"{}\". Invalid path. Must contain only letters, digits, /, _, .., - or *",
             self.path
         ))
     }
@@ -1,6 +1,12 @@
 #[macro_use]
 extern crate riker_testkit;
 
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Barrier,
+};
+use std::time::Duration;
+
 use riker::actors::*;
 
 use riker_testkit::probe::channel::{probe, ChannelProbe};
@@ -78,6 +84,49 @@ fn actor_create() {
     assert!(sys.actor_of::<Counter>("!").is_err());
 }
 
+#[test]
+fn actor_create_name_grammar() {
+    let sys = ActorSystem::new().unwrap();
+
+    assert!(sys.actor_of::<Counter>("orders.eu-west.worker_1").is_ok());
+    assert!(sys.actor_of::<Counter>("zähler").is_ok());
+    assert!(sys.actor_of::<Counter>("計数器.1").is_ok());
+
+    assert!(sys.actor_of::<Counter>("").is_err());
+    assert!(sys.actor_of::<Counter>(".").is_err());
+    assert!(sys.actor_of::<Counter>("..").is_err());
+    assert!(sys.actor_of::<Counter>(".a").is_err());
+    assert!(sys.actor_of::<Counter>("a.").is_err());
+    assert!(sys.actor_of::<Counter>("a..b").is_err());
+    assert!(sys.actor_of::<Counter>("a b").is_err());
+
+    // unicode names can be selected
+    assert_eq!(sys.select("/user/zähler").unwrap().resolve().len(), 1);
+}
+
+#[test]
+fn actor_create_name_validator() {
+    let sys = SystemBuilder::new()
+        .name("name_validator")
+        .name_validator(|name: &str| name.is_ascii() && !name.contains('_'))
+        .create()
+        .unwrap();
+
+    assert!(sys.actor_of::<Counter>("orders.worker-1").is_ok());
+
+    match sys.actor_of::<Counter>("zähler") {
+        Err(CreateError::InvalidName(name)) => assert_eq!(name, "zähler"),
+        _ => panic!("the validator should reject a non ASCII name"),
+    }
+    assert!(sys.actor_of::<Counter>("worker_1").is_err());
+
+    // the validator only restricts the grammar
+    assert!(sys.actor_of::<Counter>("a..b").is_err());
+
+    // system actors aren't checked by the validator
+    assert!(sys.sys_actor_of::<Counter>("sys_counter").is_ok());
+}
+
 #[test]
 fn actor_tell() {
     let sys = ActorSystem::new().unwrap();
@@ -172,3 +221,1667 @@ fn actor_stop() {
     system.stop(&parent);
     p_assert_eq!(listen, ());
 }
+
+#[test]
+fn actor_children_order() {
+    let sys = ActorSystem::new().unwrap();
+
+    for name in &["c", "a", "d", "b"] {
+        sys.actor_of::<Child>(name).unwrap();
+    }
+    let names = |children: Box<dyn Iterator<Item = BasicActorRef> + '_>| -> Vec<String> {
+        children.map(|child| child.name().to_string()).collect()
+    };
+
+    let user = sys.user_root();
+    assert_eq!(names(user.children()), vec!["c", "a", "d", "b"]);
+    assert_eq!(names(user.children_sorted()), vec!["a", "b", "c", "d"]);
+}
+
+#[derive(Clone, Debug)]
+pub struct CountProbe(ChannelProbe<(), u32>);
+
+// Blocks the actor in `recv` until the test has waited on the barrier twice
+#[derive(Clone, Debug)]
+pub struct Block(Arc<Barrier>);
+
+#[actor(CountProbe, Add, Block)]
+#[derive(Default)]
+struct GracefulStop {
+    probe: Option<CountProbe>,
+    count: u32,
+}
+
+impl Actor for GracefulStop {
+    type Msg = GracefulStopMsg;
+
+    fn post_stop(&mut self) {
+        // report how many messages were processed before stopping
+        self.probe.as_ref().unwrap().0.event(self.count);
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<CountProbe> for GracefulStop {
+    type Msg = GracefulStopMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: CountProbe, _sender: Sender) {
+        self.probe = Some(msg);
+    }
+}
+
+impl Receive<Add> for GracefulStop {
+    type Msg = GracefulStopMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, _msg: Add, _sender: Sender) {
+        self.count += 1;
+    }
+}
+
+impl Receive<Block> for GracefulStop {
+    type Msg = GracefulStopMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: Block, _sender: Sender) {
+        msg.0.wait();
+        msg.0.wait();
+    }
+}
+
+#[test]
+fn actor_stop_gracefully() {
+    let sys = ActorSystem::new().unwrap();
+
+    let actor = sys.actor_of::<GracefulStop>("graceful").unwrap();
+
+    let (probe, listen) = probe();
+    actor.tell(CountProbe(probe), None);
+
+    for _ in 0..1_000 {
+        actor.tell(Add, None);
+    }
+
+    // the poison pill is processed after the messages already queued
+    sys.stop_gracefully(&actor);
+    p_assert_eq!(listen, 1_000);
+}
+
+#[test]
+fn actor_stop_gracefully_while_busy() {
+    let sys = ActorSystem::new().unwrap();
+
+    let actor = sys.actor_of::<GracefulStop>("graceful-busy").unwrap();
+
+    let (probe, listen) = probe();
+    actor.tell(CountProbe(probe), None);
+
+    let barrier = Arc::new(Barrier::new(2));
+    actor.tell(Block(barrier.clone()), None);
+    barrier.wait();
+
+    // the pill is sent while the actor is in `recv`,
+    // so the message sent after it is never processed
+    sys.stop_gracefully(&actor);
+    actor.tell(Add, None);
+    barrier.wait();
+    p_assert_eq!(listen, 0);
+
+    let mut dead_letters = 0;
+    for _ in 0..100 {
+        let recent = futures::executor::block_on(sys.recent_dead_letters()).unwrap();
+        dead_letters = recent
+            .iter()
+            .filter(|dl| dl.recipient.path() == "/user/graceful-busy")
+            .count();
+        if dead_letters > 0 {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(dead_letters, 1);
+}
+
+#[derive(Default)]
+struct Echo;
+
+impl Actor for Echo {
+    type Msg = String;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        let _ = sender
+            .unwrap()
+            .try_tell(format!("echo {}", msg), Some(ctx.myself().into()));
+    }
+}
+
+#[test]
+fn actor_inbox_receive() {
+    let sys = ActorSystem::new().unwrap();
+
+    let echo = sys.actor_of::<Echo>("echo").unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+
+    echo.tell("a".to_string(), Some(inbox.sender_ref().into()));
+    let msg = inbox.receive(std::time::Duration::from_secs(1));
+    assert_eq!(msg, Some("echo a".to_string()));
+
+    echo.tell("b".to_string(), Some(inbox.sender_ref().into()));
+    let (msg, sender) = futures::executor::block_on(inbox.receive_async());
+    assert_eq!(msg, "echo b");
+    assert_eq!(sender.unwrap().path(), echo.path());
+
+    // nothing else is received
+    assert_eq!(inbox.receive(std::time::Duration::from_millis(100)), None);
+}
+
+#[test]
+fn actor_try_send() {
+    let sys = ActorSystem::new().unwrap();
+
+    let echo = sys.actor_of::<Echo>("echo").unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+
+    assert!(echo
+        .try_send("a".to_string(), Some(inbox.sender_ref().into()))
+        .is_ok());
+    let msg = inbox.receive(std::time::Duration::from_secs(1));
+    assert_eq!(msg, Some("echo a".to_string()));
+
+    sys.stop(&echo);
+
+    // once terminated the message is returned instead of sent to dead letters
+    let mut result = Ok(());
+    for _ in 0..100 {
+        result = echo.try_send("b".to_string(), Some(inbox.sender_ref().into()));
+        if result.is_err() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    match result {
+        Err(SendError::Terminated(msg)) => assert_eq!(msg, "b"),
+        _ => panic!("expected the message to be returned"),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Get;
+
+#[actor(Add, Respondable<Get, u32>)]
+#[derive(Default)]
+struct Tally {
+    count: u32,
+}
+
+impl Actor for Tally {
+    type Msg = TallyMsg;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<Add> for Tally {
+    type Msg = TallyMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, _msg: Add, _sender: Sender) {
+        self.count += 1;
+    }
+}
+
+impl Receive<Respondable<Get, u32>> for Tally {
+    type Msg = TallyMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: Respondable<Get, u32>, _sender: Sender) {
+        let _ = msg.reply(self.count);
+    }
+}
+
+#[test]
+fn actor_respondable_reply() {
+    let sys = ActorSystem::new().unwrap();
+
+    let tally = sys.actor_of::<Tally>("tally").unwrap();
+    tally.tell(Add, None);
+    tally.tell(Add, None);
+
+    let (req, reply) = Respondable::new(Get);
+    tally.send_msg(TallyMsg::from(req.clone()), None);
+    assert_eq!(futures::executor::block_on(reply), Ok(2));
+
+    // only the first reply is delivered
+    assert_eq!(req.reply(3), Err(3));
+}
+
+struct Fetcher {
+    out: ActorRef<String>,
+}
+
+impl ActorFactoryArgs<ActorRef<String>> for Fetcher {
+    fn create_args(out: ActorRef<String>) -> Self {
+        Fetcher { out }
+    }
+}
+
+impl Actor for Fetcher {
+    type Msg = String;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        if msg == "fetch" {
+            // simulate slow I/O without holding up an executor thread
+            let io = ctx
+                .run_blocking(|| std::thread::sleep(std::time::Duration::from_millis(200)))
+                .unwrap();
+            ctx.continue_with(async move {
+                io.await;
+                "fetched".to_string()
+            })
+            .unwrap();
+        } else {
+            self.out.tell(msg, None);
+        }
+    }
+}
+
+#[test]
+fn actor_continue_with() {
+    let sys = ActorSystem::new().unwrap();
+
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let fetcher = sys
+        .actor_of_args::<Fetcher, _>("fetcher", inbox.sender_ref())
+        .unwrap();
+
+    fetcher.tell("fetch".to_string(), None);
+    fetcher.tell("next".to_string(), None);
+
+    // the continuation is processed before the message queued behind it
+    let timeout = std::time::Duration::from_secs(1);
+    assert_eq!(inbox.receive(timeout), Some("fetched".to_string()));
+    assert_eq!(inbox.receive(timeout), Some("next".to_string()));
+}
+
+#[derive(Default)]
+struct ThreadName;
+
+impl Actor for ThreadName {
+    type Msg = ();
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, sender: Sender) {
+        let name = std::thread::current()
+            .name()
+            .unwrap_or_default()
+            .to_string();
+        sender.unwrap().try_tell(name, None).unwrap();
+    }
+}
+
+#[test]
+fn actor_with_dispatcher() {
+    let mut cfg = riker::load_config();
+    cfg.set("dispatchers.pinned.pool_size", 1).unwrap();
+    let sys = ActorSystem::with_config("dispatchers", cfg).unwrap();
+
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    let props = Props::new::<ThreadName>().with_dispatcher("pinned");
+    let pinned = sys.actor_of_props("pinned", props).unwrap();
+    pinned.tell((), Some(inbox.sender_ref().into()));
+    let name = inbox.receive(timeout).unwrap();
+    assert!(name.starts_with("dispatchers-pinned-thread-"));
+
+    let default = sys.actor_of::<ThreadName>("default").unwrap();
+    default.tell((), Some(inbox.sender_ref().into()));
+    let name = inbox.receive(timeout).unwrap();
+    assert!(name.starts_with("dispatchers-pool-thread-"));
+
+    let props = Props::new::<ThreadName>().with_dispatcher("unknown");
+    match sys.actor_of_props("unknown", props) {
+        Err(CreateError::UnknownDispatcher(name)) => assert_eq!(name, "unknown"),
+        _ => panic!("expected an unknown dispatcher error"),
+    }
+
+    // the failed creation doesn't keep the name
+    let props = Props::new::<ThreadName>().with_dispatcher("pinned");
+    assert!(sys.actor_of_props("unknown", props).is_ok());
+}
+
+const FAIR_ACTORS: usize = 1000;
+const FAIR_MSGS: usize = 100;
+
+#[derive(Default)]
+struct Progress {
+    counts: Vec<std::sync::atomic::AtomicUsize>,
+    // the fewest messages any actor had processed when the first finished
+    min_at_first_done: std::sync::Mutex<Option<usize>>,
+    done: std::sync::atomic::AtomicUsize,
+}
+
+// Processes `FAIR_MSGS` messages by telling itself
+struct Chatty {
+    index: usize,
+    progress: std::sync::Arc<Progress>,
+}
+
+impl ActorFactoryArgs<(usize, std::sync::Arc<Progress>)> for Chatty {
+    fn create_args((index, progress): (usize, std::sync::Arc<Progress>)) -> Self {
+        Chatty { index, progress }
+    }
+}
+
+impl Actor for Chatty {
+    type Msg = ();
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, _: Self::Msg, _: Sender) {
+        use std::sync::atomic::Ordering;
+
+        let count = self.progress.counts[self.index].fetch_add(1, Ordering::SeqCst) + 1;
+        if count < FAIR_MSGS {
+            ctx.myself().tell((), None);
+            return;
+        }
+
+        let mut min = self.progress.min_at_first_done.lock().unwrap();
+        if min.is_none() {
+            let counts = self.progress.counts.iter();
+            *min = counts.map(|c| c.load(Ordering::SeqCst)).min();
+        }
+        self.progress.done.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// Starts every actor in one message, so that all are scheduled before any runs
+struct Starter(Vec<ActorRef<()>>);
+
+impl ActorFactoryArgs<Vec<ActorRef<()>>> for Starter {
+    fn create_args(actors: Vec<ActorRef<()>>) -> Self {
+        Starter(actors)
+    }
+}
+
+impl Actor for Starter {
+    type Msg = ();
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {
+        for actor in &self.0 {
+            actor.tell((), None);
+        }
+    }
+}
+
+fn min_progress_at_first_done(fairness: &str) -> usize {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let mut cfg = riker::load_config();
+    cfg.set("dispatcher.fairness", fairness).unwrap();
+    cfg.set("dispatchers.single.pool_size", 1).unwrap();
+    cfg.set("mailbox.msg_process_limit", 10).unwrap();
+    let sys = ActorSystem::with_config("fairness", cfg).unwrap();
+
+    let progress = std::sync::Arc::new(Progress {
+        counts: (0..FAIR_ACTORS).map(|_| AtomicUsize::new(0)).collect(),
+        ..Default::default()
+    });
+
+    let actors = (0..FAIR_ACTORS)
+        .map(|i| {
+            let props =
+                Props::new_args::<Chatty, _>((i, progress.clone())).with_dispatcher("single");
+            sys.actor_of_props(&format!("chatty-{}", i), props).unwrap()
+        })
+        .collect();
+    let props = Props::new_args::<Starter, _>(actors).with_dispatcher("single");
+    sys.actor_of_props("starter", props).unwrap().tell((), None);
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while progress.done.load(Ordering::SeqCst) < FAIR_ACTORS {
+        assert!(std::time::Instant::now() < deadline, "actors didn't finish");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    let min = progress.min_at_first_done.lock().unwrap();
+    min.unwrap()
+}
+
+#[test]
+fn actor_dispatcher_fairness() {
+    // taking turns, the other actors are close behind the first to finish
+    assert!(min_progress_at_first_done("round_robin") >= FAIR_MSGS / 2);
+
+    // a greedy actor keeps the only thread until it is done
+    assert!(min_progress_at_first_done("greedy") < FAIR_MSGS / 2);
+}
+
+struct Prioritized {
+    out: ActorRef<String>,
+}
+
+impl ActorFactoryArgs<ActorRef<String>> for Prioritized {
+    fn create_args(out: ActorRef<String>) -> Self {
+        Prioritized { out }
+    }
+}
+
+impl Actor for Prioritized {
+    type Msg = (u8, String);
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        if msg.1 == "block" {
+            // let the remaining messages queue up
+            std::thread::sleep(std::time::Duration::from_millis(200));
+        }
+        self.out.tell(msg.1, None);
+    }
+}
+
+fn priority(msg: &(u8, String)) -> u8 {
+    msg.0
+}
+
+#[test]
+fn actor_with_priority() {
+    let sys = ActorSystem::new().unwrap();
+
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let props = Props::new_args::<Prioritized, _>(inbox.sender_ref());
+
+    // a preallocated mailbox grows past its capacity
+    let prioritized = [
+        ("prioritized", props.clone().with_priority(priority)),
+        ("preallocated", props.with_priority_capacity(priority, 2)),
+    ];
+    for (name, props) in prioritized {
+        let actor = sys.actor_of_props(name, props).unwrap();
+
+        actor.tell((2, "block".to_string()), None);
+        actor.tell((0, "data 1".to_string()), None);
+        actor.tell((1, "control".to_string()), None);
+        actor.tell((0, "data 2".to_string()), None);
+
+        let timeout = std::time::Duration::from_secs(1);
+        for expected in &["block", "control", "data 1", "data 2"] {
+            assert_eq!(inbox.receive(timeout), Some(expected.to_string()));
+        }
+    }
+}
+
+#[test]
+fn actor_with_priority_stop_gracefully() {
+    let sys = ActorSystem::new().unwrap();
+
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let props = Props::new_args::<Prioritized, _>(inbox.sender_ref()).with_priority(priority);
+    let actor = sys.actor_of_props("prioritized-graceful", props).unwrap();
+
+    actor.tell((2, "block".to_string()), None);
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    // the pill lets one more message through, the highest priority one,
+    // even though it was sent after the pill
+    actor.tell((0, "data".to_string()), None);
+    sys.stop_gracefully(&actor);
+    actor.tell((1, "control".to_string()), None);
+
+    let timeout = std::time::Duration::from_secs(1);
+    assert_eq!(inbox.receive(timeout), Some("block".to_string()));
+    assert_eq!(inbox.receive(timeout), Some("control".to_string()));
+    assert_eq!(inbox.receive(timeout), None);
+}
+
+struct Batched {
+    out: ActorRef<usize>,
+}
+
+impl ActorFactoryArgs<ActorRef<usize>> for Batched {
+    fn create_args(out: ActorRef<usize>) -> Self {
+        Batched { out }
+    }
+}
+
+impl Actor for Batched {
+    type Msg = u32;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {
+        panic!("batched actors receive in recv_batch");
+    }
+
+    fn receives_batches(&self) -> bool {
+        true
+    }
+
+    fn recv_batch(&mut self, _ctx: &Context<Self::Msg>, msgs: Vec<Self::Msg>) {
+        // let the remaining messages queue up behind the first batch
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        self.out.tell(msgs.len(), None);
+    }
+}
+
+#[test]
+fn actor_recv_batch() {
+    let sys = ActorSystem::new().unwrap();
+
+    let inbox = Inbox::<usize>::new(&sys).unwrap();
+    let batched = sys
+        .actor_of_args::<Batched, _>("batched", inbox.sender_ref())
+        .unwrap();
+
+    for i in 0..100u32 {
+        batched.tell(i, None);
+    }
+
+    let mut batches = 0;
+    let mut received = 0;
+    while received < 100 {
+        received += inbox.receive(std::time::Duration::from_secs(1)).unwrap();
+        batches += 1;
+    }
+
+    assert_eq!(received, 100);
+    assert!(batches <= 2);
+}
+
+// Reports the size of each batch, taking `per_msg` for each message
+struct SlowBatched {
+    out: ActorRef<usize>,
+    per_msg: std::time::Duration,
+}
+
+impl ActorFactoryArgs<(ActorRef<usize>, u64)> for SlowBatched {
+    fn create_args((out, per_msg): (ActorRef<usize>, u64)) -> Self {
+        SlowBatched {
+            out,
+            per_msg: std::time::Duration::from_millis(per_msg),
+        }
+    }
+}
+
+impl Actor for SlowBatched {
+    type Msg = u32;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {}
+
+    fn receives_batches(&self) -> bool {
+        true
+    }
+
+    fn recv_batch(&mut self, _ctx: &Context<Self::Msg>, msgs: Vec<Self::Msg>) {
+        std::thread::sleep(self.per_msg * msgs.len() as u32);
+        self.out.tell(msgs.len(), None);
+    }
+}
+
+// Returns the size of each batch until `n` messages are received
+fn batches(inbox: &Inbox<usize>, n: usize) -> Vec<usize> {
+    let mut batches = Vec::new();
+    while batches.iter().sum::<usize>() < n {
+        batches.push(inbox.receive(std::time::Duration::from_secs(2)).unwrap());
+    }
+    batches
+}
+
+#[test]
+fn actor_fixed_msg_process_limit() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<usize>::new(&sys).unwrap();
+
+    let props = Props::new_args::<SlowBatched, _>((inbox.sender_ref(), 20))
+        .with_msg_process_limit(MsgProcessLimit::Fixed(3));
+    let actor = sys.actor_of_props("fixed", props).unwrap();
+
+    for i in 0..10u32 {
+        actor.tell(i, None);
+    }
+    assert!(batches(&inbox, 10).iter().all(|&batch| batch <= 3));
+}
+
+#[test]
+fn actor_recv_batch_stop_gracefully() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<usize>::new(&sys).unwrap();
+
+    let props = Props::new_args::<SlowBatched, _>((inbox.sender_ref(), 20))
+        .with_msg_process_limit(MsgProcessLimit::Fixed(3));
+    let actor = sys.actor_of_props("batch-graceful", props).unwrap();
+
+    for i in 0..10u32 {
+        actor.tell(i, None);
+    }
+    sys.stop_gracefully(&actor);
+    for i in 10..15u32 {
+        actor.tell(i, None);
+    }
+
+    // the batches stop at the poison pill
+    let batches = batches(&inbox, 10);
+    assert!(batches.iter().all(|&batch| batch <= 3));
+    assert_eq!(batches.iter().sum::<usize>(), 10);
+    assert_eq!(inbox.receive(Duration::from_millis(200)), None);
+}
+
+#[test]
+fn actor_adaptive_msg_process_limit() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<usize>::new(&sys).unwrap();
+
+    // messages taking 5ms fit two to a 10ms budget
+    let limit = MsgProcessLimit::Adaptive {
+        max: 100,
+        budget: std::time::Duration::from_millis(10),
+    };
+    let props =
+        Props::new_args::<SlowBatched, _>((inbox.sender_ref(), 5)).with_msg_process_limit(limit);
+    let actor = sys.actor_of_props("adaptive", props).unwrap();
+
+    actor.tell(0u32, None);
+    assert_eq!(batches(&inbox, 1), vec![1]);
+
+    for i in 0..20u32 {
+        actor.tell(i, None);
+    }
+    let batches = batches(&inbox, 20);
+    assert!(batches.len() >= 10, "batches: {:?}", batches);
+    assert!(
+        batches.iter().all(|&batch| batch <= 2),
+        "batches: {:?}",
+        batches
+    );
+}
+
+#[test]
+fn actor_lockfree_mailbox() {
+    let mut cfg = riker::load_config();
+    cfg.set("mailbox.queue", "lockfree").unwrap();
+    let sys = ActorSystem::with_config("lockfree", cfg).unwrap();
+
+    let inbox = Inbox::<usize>::new(&sys).unwrap();
+    let batched = sys
+        .actor_of_args::<Batched, _>("batched", inbox.sender_ref())
+        .unwrap();
+
+    // many producers send to the same mailbox
+    let senders: Vec<_> = (0..4)
+        .map(|_| {
+            let batched = batched.clone();
+            std::thread::spawn(move || {
+                for i in 0..250u32 {
+                    batched.tell(i, None);
+                }
+            })
+        })
+        .collect();
+    for sender in senders {
+        sender.join().unwrap();
+    }
+
+    let mut received = 0;
+    while received < 1000 {
+        received += inbox.receive(std::time::Duration::from_secs(1)).unwrap();
+    }
+    assert_eq!(received, 1000);
+
+    // messages sent once the actor has stopped go to dead letters
+    sys.stop(&batched);
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    batched.tell(0u32, None);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(inbox.receive(std::time::Duration::from_millis(100)), None);
+}
+
+// Counts the messages it receives
+struct Tallying(Arc<AtomicUsize>);
+
+impl ActorFactoryArgs<Arc<AtomicUsize>> for Tallying {
+    fn create_args(count: Arc<AtomicUsize>) -> Self {
+        Tallying(count)
+    }
+}
+
+impl Actor for Tallying {
+    type Msg = u32;
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// Counts the dead letters
+struct DeadLetterTally(Arc<AtomicUsize>);
+
+impl ActorFactoryArgs<Arc<AtomicUsize>> for DeadLetterTally {
+    fn create_args(count: Arc<AtomicUsize>) -> Self {
+        DeadLetterTally(count)
+    }
+}
+
+impl Actor for DeadLetterTally {
+    type Msg = DeadLetter;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        ctx.system.dead_letters().subscribe(All, ctx.myself());
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn actor_lockfree_mailbox_stopped_while_sending() {
+    let mut cfg = riker::load_config();
+    cfg.set("mailbox.queue", "lockfree").unwrap();
+    let sys = ActorSystem::with_config("lockfree-stopped", cfg).unwrap();
+
+    let dead_letters = Arc::new(AtomicUsize::new(0));
+    let tally = sys
+        .actor_of_args::<DeadLetterTally, _>("dead-letters", dead_letters.clone())
+        .unwrap();
+    futures::executor::block_on(tally.started()).unwrap();
+
+    let processed = Arc::new(AtomicUsize::new(0));
+    let receiver = sys
+        .actor_of_args::<Tallying, _>("receiver", processed.clone())
+        .unwrap();
+
+    // the actor stops while many producers send to it
+    let senders: Vec<_> = (0..4)
+        .map(|_| {
+            let receiver = receiver.clone();
+            std::thread::spawn(move || {
+                for i in 0..2_000u32 {
+                    receiver.tell(i, None);
+                }
+            })
+        })
+        .collect();
+    std::thread::sleep(Duration::from_millis(1));
+    sys.stop(&receiver);
+    for sender in senders {
+        sender.join().unwrap();
+    }
+
+    // each message is either processed or a dead letter
+    let total = || processed.load(Ordering::Relaxed) + dead_letters.load(Ordering::Relaxed);
+    for _ in 0..500 {
+        if total() == 8_000 {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(total(), 8_000);
+}
+
+#[test]
+fn actor_of_at_path() {
+    let sys = ActorSystem::new().unwrap();
+
+    let workers = sys.actor_of::<Counter>("workers").unwrap();
+    let pool = sys
+        .actor_of_at::<Counter>("/user/workers", "pool-1")
+        .unwrap();
+    let worker = sys
+        .actor_of_at::<Counter>("/user/workers/pool-1", "worker-9")
+        .unwrap();
+
+    assert_eq!(worker.path().to_string(), "/user/workers/pool-1/worker-9");
+    assert_eq!(worker.parent().path(), pool.path());
+    assert!(workers.has_children());
+
+    match sys.actor_of_at::<Counter>("/user/missing", "worker") {
+        Err(CreateError::ParentNotFound(path)) => assert_eq!(path, "/user/missing"),
+        _ => panic!("expected the parent to be missing"),
+    }
+}
+
+#[derive(Default)]
+struct Spawner;
+
+impl Actor for Spawner {
+    type Msg = ();
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        for _ in 0..3 {
+            ctx.spawn_anon(Props::new::<Counter>()).unwrap();
+        }
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {}
+}
+
+#[test]
+fn actor_of_anon() {
+    let sys = ActorSystem::new().unwrap();
+
+    let a = sys.actor_of_anon::<Counter>().unwrap();
+    let b = sys.actor_of_anon::<Counter>().unwrap();
+
+    assert!(a.name().starts_with("a-"));
+    assert_ne!(a.name(), b.name());
+    assert_eq!(a.parent().path().to_string(), "/user");
+
+    let spawner = sys.actor_of::<Spawner>("spawner").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    let children: Vec<_> = spawner.children().collect();
+    assert_eq!(children.len(), 3);
+    assert!(children.iter().all(|c| c.name().starts_with("a-")));
+}
+
+// Signals when the future holding it is dropped
+struct DropGuard(std::sync::mpsc::Sender<()>);
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        let _ = self.0.send(());
+    }
+}
+
+type Dropped = std::sync::Arc<std::sync::Mutex<std::sync::mpsc::Sender<()>>>;
+
+struct Piper {
+    out: ActorRef<u32>,
+    dropped: Dropped,
+}
+
+impl ActorFactoryArgs<(ActorRef<u32>, Dropped)> for Piper {
+    fn create_args((out, dropped): (ActorRef<u32>, Dropped)) -> Self {
+        Piper { out, dropped }
+    }
+}
+
+impl Actor for Piper {
+    type Msg = u32;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        ctx.pipe_to_self(async { 21u32 }, |n| n * 2).unwrap();
+
+        let guard = DropGuard(self.dropped.lock().unwrap().clone());
+        ctx.spawn(async move {
+            let _guard = guard;
+            futures::future::pending::<()>().await;
+        })
+        .unwrap();
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        self.out.tell(msg, None);
+    }
+}
+
+#[test]
+fn actor_spawn_pipe_to_self() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<u32>::new(&sys).unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let dropped = std::sync::Arc::new(std::sync::Mutex::new(tx));
+
+    let piper = sys
+        .actor_of_args::<Piper, _>("piper", (inbox.sender_ref(), dropped))
+        .unwrap();
+
+    assert_eq!(inbox.receive(std::time::Duration::from_secs(1)), Some(42));
+    assert!(rx.try_recv().is_err());
+
+    // the pending future is aborted once the actor stops
+    sys.stop(&piper);
+    assert!(rx.recv_timeout(std::time::Duration::from_secs(1)).is_ok());
+}
+
+struct Idle {
+    out: ActorRef<String>,
+}
+
+impl ActorFactoryArgs<ActorRef<String>> for Idle {
+    fn create_args(out: ActorRef<String>) -> Self {
+        Idle { out }
+    }
+}
+
+impl Actor for Idle {
+    type Msg = String;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        ctx.set_receive_timeout(std::time::Duration::from_millis(300));
+    }
+
+    fn sys_recv(&mut self, _: &Context<Self::Msg>, msg: SystemMsg, _: Sender) {
+        if let SystemMsg::ReceiveTimeout(_) = msg {
+            self.out.tell("timeout".to_string(), None);
+        }
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        if msg == "cancel" {
+            ctx.cancel_receive_timeout();
+        }
+    }
+}
+
+#[test]
+fn actor_receive_timeout() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let idle = sys
+        .actor_of_args::<Idle, _>("idle", inbox.sender_ref())
+        .unwrap();
+
+    // messages received within the window hold off the timeout
+    for _ in 0..10 {
+        idle.tell("busy".to_string(), None);
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    assert_eq!(inbox.receive(std::time::Duration::from_millis(0)), None);
+
+    // once idle the timeout is received, and again for each idle window
+    let timeout = Some("timeout".to_string());
+    assert_eq!(inbox.receive(std::time::Duration::from_secs(1)), timeout);
+    assert_eq!(inbox.receive(std::time::Duration::from_secs(1)), timeout);
+
+    idle.tell("cancel".to_string(), None);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    while inbox.receive(std::time::Duration::from_millis(0)).is_some() {}
+    assert_eq!(inbox.receive(std::time::Duration::from_millis(800)), None);
+}
+
+#[test]
+fn actor_identify() {
+    let sys = ActorSystem::new().unwrap();
+    let counter = sys.actor_of::<Counter>("counter").unwrap();
+
+    for _ in 0..10 {
+        counter.tell(Add, None);
+    }
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    let info = futures::executor::block_on(counter.identify()).unwrap();
+    assert_eq!(info.path, *counter.path());
+    assert_eq!(info.msg_processed, 10);
+    assert_eq!(info.mailbox_len, 0);
+    assert_eq!(info.restarts, 0);
+    assert!(info.uptime >= std::time::Duration::from_millis(200));
+
+    // a stopped actor doesn't answer
+    sys.stop(&counter);
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    assert!(futures::executor::block_on(counter.identify()).is_err());
+}
+
+// refs hash by path and system id only, which can't change
+#[allow(clippy::mutable_key_type)]
+#[test]
+fn actor_ref_eq_across_systems() {
+    use std::collections::HashSet;
+
+    let sys_a = ActorSystem::new().unwrap();
+    let sys_b = ActorSystem::new().unwrap();
+
+    let a = sys_a.actor_of::<Counter>("counter").unwrap();
+    let b = sys_b.actor_of::<Counter>("counter").unwrap();
+    assert_eq!(a.path(), b.path());
+
+    assert_eq!(a, a.clone());
+    assert_ne!(a, b);
+    assert_ne!(
+        BasicActorRef::from(a.clone()),
+        BasicActorRef::from(b.clone())
+    );
+
+    let refs: HashSet<_> = vec![a.clone(), b.clone(), a.clone()].into_iter().collect();
+    assert_eq!(refs.len(), 2);
+
+    let basic: HashSet<BasicActorRef> = vec![a.into(), b.into()].into_iter().collect();
+    assert_eq!(basic.len(), 2);
+}
+
+#[test]
+fn actor_path_segments() {
+    let root = ActorPath::new("/");
+    let user = ActorPath::new("/user");
+    let path = ActorPath::new("/user/a/b");
+
+    assert_eq!(path.segments().collect::<Vec<_>>(), vec!["user", "a", "b"]);
+    assert_eq!(root.segments().count(), 0);
+
+    assert_eq!(path.parent(), Some(ActorPath::new("/user/a")));
+    assert_eq!(user.parent(), Some(root.clone()));
+    assert_eq!(root.parent(), None);
+
+    assert_eq!(root.child("user"), user);
+    assert_eq!(user.child("a").child("b"), path);
+
+    assert!(path.is_descendant_of(&user));
+    assert!(path.is_descendant_of(&root));
+    assert!(!user.is_descendant_of(&user));
+    assert!(!ActorPath::new("/username").is_descendant_of(&user));
+    assert!(!root.is_descendant_of(&root));
+
+    // created actors are registered under their parent's path
+    let sys = ActorSystem::new().unwrap();
+    let actor = sys.actor_of::<Counter>("counter").unwrap();
+    assert_eq!(*actor.path(), sys.user_root().path().child("counter"));
+}
+
+type Gate = std::sync::Arc<std::sync::Mutex<std::sync::mpsc::Receiver<()>>>;
+
+// Processes a message each time the gate is opened
+struct Gated {
+    gate: Gate,
+}
+
+impl ActorFactoryArgs<Gate> for Gated {
+    fn create_args(gate: Gate) -> Self {
+        Gated { gate }
+    }
+}
+
+impl Actor for Gated {
+    type Msg = u32;
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {
+        let _ = self.gate.lock().unwrap().recv();
+    }
+}
+
+#[test]
+fn actor_sink_backpressure() {
+    use futures::{FutureExt, SinkExt};
+
+    let sys = ActorSystem::new().unwrap();
+    let (open, gate) = std::sync::mpsc::channel();
+    let gated = sys
+        .actor_of_args::<Gated, _>("gated", std::sync::Arc::new(std::sync::Mutex::new(gate)))
+        .unwrap();
+
+    let mut sink = gated.sink(2);
+    futures::executor::block_on(sink.send(1)).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    // 1 is being processed, 2 and 3 fill the mailbox
+    futures::executor::block_on(sink.send(2)).unwrap();
+    futures::executor::block_on(sink.send(3)).unwrap();
+    assert!(sink.send(4).now_or_never().is_none());
+
+    // the sink is ready once the actor takes the next message
+    open.send(()).unwrap();
+    futures::executor::block_on(sink.send(4)).unwrap();
+    drop(open);
+}
+
+#[test]
+fn actor_stream_to_actor() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<StreamMsg<u32, String>>::new(&sys).unwrap();
+
+    let items = vec![Ok(1), Err("failed".to_string()), Ok(3)];
+    let streamer =
+        StreamActor::start(&sys, futures::stream::iter(items), inbox.sender_ref(), 1).unwrap();
+
+    let timeout = std::time::Duration::from_secs(1);
+    assert!(matches!(inbox.receive(timeout), Some(StreamMsg::Item(1))));
+    match inbox.receive(timeout) {
+        Some(StreamMsg::Error(e)) => assert_eq!(e, "failed"),
+        other => panic!("expected an error, received {:?}", other),
+    }
+
+    // nothing is forwarded after an error and the StreamActor stops
+    assert!(inbox
+        .receive(std::time::Duration::from_millis(200))
+        .is_none());
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    let path = streamer.path().to_string();
+    assert!(!sys.tree_snapshot().iter().any(|n| n.path == path));
+}
+
+#[test]
+fn any_message_take() {
+    // clones share a one time message, the first take wins
+    let mut msg = AnyMessage::new("hello".to_string(), true);
+    let mut copy = msg.clone();
+    assert_eq!(copy.take::<String>().unwrap(), "hello");
+    assert!(matches!(
+        msg.take::<String>(),
+        Err(riker::DowncastAnyMessageError::Taken { .. })
+    ));
+
+    // a repeated message is copied by each take
+    let mut msg = AnyMessage::new(7u32, false);
+    assert_eq!(msg.take::<u32>().unwrap(), 7);
+    assert_eq!(msg.clone().take::<u32>().unwrap(), 7);
+
+    match msg.take::<String>() {
+        Err(riker::DowncastAnyMessageError::WrongType {
+            expected,
+            actual,
+            actual_name,
+            ..
+        }) => {
+            assert_eq!(expected, std::any::TypeId::of::<String>());
+            assert_eq!(actual, std::any::TypeId::of::<u32>());
+            assert_eq!(actual_name, "u32");
+        }
+        other => panic!("expected WrongType, found {:?}", other),
+    }
+
+    // one time messages don't need to be Clone
+    struct Handle(u32);
+    let mut msg = AnyMessage::once(Handle(3));
+    assert_eq!(msg.take::<Handle>().unwrap().0, 3);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Seen(u32);
+
+// Counts messages in an extension, a `0` fails the actor
+struct Extended {
+    out: ActorRef<u32>,
+}
+
+impl ActorFactoryArgs<ActorRef<u32>> for Extended {
+    fn create_args(out: ActorRef<u32>) -> Self {
+        Extended { out }
+    }
+}
+
+impl Actor for Extended {
+    type Msg = u32;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        if !ctx.extensions().contains::<Seen>() {
+            ctx.extensions().insert(Seen(0));
+        }
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        if msg == 0 {
+            panic!("// TEST PANIC // TEST PANIC // TEST PANIC //");
+        }
+        let seen = ctx.extensions().with(|seen: &mut Seen| {
+            seen.0 += msg;
+            seen.0
+        });
+        self.out.tell(seen.unwrap(), None);
+    }
+}
+
+#[test]
+fn actor_extensions() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<u32>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    let actor = sys
+        .actor_of_args::<Extended, _>("extended", inbox.sender_ref())
+        .unwrap();
+    actor.tell(1u32, None);
+    assert_eq!(inbox.receive(timeout), Some(1));
+
+    // extensions are kept when the actor restarts
+    actor.tell(0u32, None);
+    actor.tell(2u32, None);
+    assert_eq!(inbox.receive(timeout), Some(3));
+
+    let ext = Extensions::default();
+    assert_eq!(ext.insert(Seen(1)), None);
+    assert_eq!(ext.insert(Seen(2)), Some(Seen(1)));
+    assert_eq!(ext.get::<Seen>(), Some(Seen(2)));
+    assert_eq!(ext.remove::<Seen>(), Some(Seen(2)));
+    assert!(!ext.contains::<Seen>());
+}
+
+type Log = std::sync::Arc<std::sync::Mutex<Vec<String>>>;
+
+// Records when it's entered and left
+struct Traced(&'static str, Log);
+
+impl Interceptor<String> for Traced {
+    fn around_receive(
+        &self,
+        ctx: &Context<String>,
+        msg: String,
+        sender: Sender,
+        next: Next<String>,
+    ) {
+        self.1.lock().unwrap().push(format!("{} {}", self.0, msg));
+        next.run(ctx, msg, sender);
+        self.1.lock().unwrap().push(format!("{} done", self.0));
+    }
+}
+
+// Drops "secret", upper cases everything else
+struct Guard;
+
+impl Interceptor<String> for Guard {
+    fn around_receive(
+        &self,
+        ctx: &Context<String>,
+        msg: String,
+        sender: Sender,
+        next: Next<String>,
+    ) {
+        if msg != "secret" {
+            next.run(ctx, msg.to_uppercase(), sender);
+        }
+    }
+}
+
+struct Relay(ActorRef<String>);
+
+impl ActorFactoryArgs<ActorRef<String>> for Relay {
+    fn create_args(out: ActorRef<String>) -> Self {
+        Relay(out)
+    }
+}
+
+impl Actor for Relay {
+    type Msg = String;
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        self.0.tell(msg, None);
+    }
+}
+
+#[test]
+fn actor_interceptors() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+    let log = Log::default();
+
+    let props = Props::new_args::<Relay, _>(inbox.sender_ref())
+        .with_interceptor(Traced("outer", log.clone()))
+        .with_interceptor(Guard)
+        .with_interceptor(Traced("inner", log.clone()));
+    let actor = sys.actor_of_props("relay", props).unwrap();
+
+    actor.tell("secret".to_string(), None);
+    actor.tell("hello".to_string(), None);
+    assert_eq!(inbox.receive(timeout), Some("HELLO".to_string()));
+    assert_eq!(inbox.receive(std::time::Duration::from_millis(100)), None);
+
+    // the first interceptor added sees the original message first and completes last
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "outer secret",
+            "outer done",
+            "outer hello",
+            "inner HELLO",
+            "inner done",
+            "outer done"
+        ]
+    );
+}
+
+type Lifecycles = std::sync::Arc<std::sync::Mutex<Vec<&'static str>>>;
+
+struct Entity {
+    out: ActorRef<u32>,
+    lifecycles: Lifecycles,
+    count: u32,
+}
+
+impl ActorFactoryArgs<(ActorRef<u32>, Lifecycles)> for Entity {
+    fn create_args((out, lifecycles): (ActorRef<u32>, Lifecycles)) -> Self {
+        Entity {
+            out,
+            lifecycles,
+            count: 0,
+        }
+    }
+}
+
+impl Actor for Entity {
+    type Msg = u32;
+
+    fn pre_start(&mut self, _: &Context<Self::Msg>) {
+        self.lifecycles.lock().unwrap().push("pre_start");
+    }
+
+    fn post_stop(&mut self) {
+        self.lifecycles.lock().unwrap().push("post_stop");
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        self.count += msg;
+        self.out.tell(self.count, None);
+    }
+}
+
+#[test]
+fn actor_auto_passivate() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<u32>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+    let lifecycles = Lifecycles::default();
+
+    let props = Props::new_args::<Entity, _>((inbox.sender_ref(), lifecycles.clone()))
+        .auto_passivate(std::time::Duration::from_millis(100));
+    let entity = sys.actor_of_props("entity", props).unwrap();
+
+    entity.tell(1u32, None);
+    assert_eq!(inbox.receive(timeout), Some(1));
+
+    std::thread::sleep(std::time::Duration::from_millis(400));
+    assert_eq!(*lifecycles.lock().unwrap(), vec!["pre_start", "post_stop"]);
+
+    // the next message is received by a new instance at the same path
+    entity.tell(2u32, None);
+    assert_eq!(inbox.receive(timeout), Some(2));
+    assert_eq!(
+        *lifecycles.lock().unwrap(),
+        vec!["pre_start", "post_stop", "pre_start"]
+    );
+
+    // the actor's stats outlive the instance
+    let info = futures::executor::block_on(entity.identify()).unwrap();
+    assert_eq!(info.msg_processed, 2);
+}
+
+// Replies with its request doubled, ignoring `0`
+#[derive(Default)]
+struct Doubler;
+
+impl Actor for Doubler {
+    type Msg = u32;
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        if msg > 0 {
+            sender.unwrap().try_tell(msg * 2, None).unwrap();
+        }
+    }
+}
+
+struct Asking {
+    doubler: ActorRef<u32>,
+    out: ActorRef<u32>,
+}
+
+impl ActorFactoryArgs<(ActorRef<u32>, ActorRef<u32>)> for Asking {
+    fn create_args((doubler, out): (ActorRef<u32>, ActorRef<u32>)) -> Self {
+        Asking { doubler, out }
+    }
+}
+
+impl Actor for Asking {
+    type Msg = u32;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let doubled = ctx.ask::<u32, _>(&self.doubler, 21u32).unwrap();
+        ctx.continue_with(async move { doubled.await.unwrap() })
+            .unwrap();
+
+        // an unanswered ask stops its temporary actor when dropped
+        drop(ctx.ask::<u32, _>(&self.doubler, 0u32).unwrap());
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        self.out.tell(msg, None);
+    }
+}
+
+#[test]
+fn actor_ask() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<u32>::new(&sys).unwrap();
+
+    let doubler = sys.actor_of::<Doubler>("doubler").unwrap();
+    sys.actor_of_args::<Asking, _>("asking", (doubler, inbox.sender_ref()))
+        .unwrap();
+    assert_eq!(inbox.receive(std::time::Duration::from_secs(1)), Some(42));
+
+    // both temporary actors have stopped, leaving the inbox
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(sys.temp_root().children().count(), 1);
+}
+
+// Asks the doubler `count` times, then sends the sum of the replies to `out`
+struct AskingMany {
+    doubler: ActorRef<u32>,
+    out: ActorRef<u32>,
+    count: u32,
+}
+
+impl ActorFactoryArgs<(ActorRef<u32>, ActorRef<u32>, u32)> for AskingMany {
+    fn create_args((doubler, out, count): (ActorRef<u32>, ActorRef<u32>, u32)) -> Self {
+        AskingMany {
+            doubler,
+            out,
+            count,
+        }
+    }
+}
+
+impl Actor for AskingMany {
+    type Msg = u32;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let asks: Vec<_> = (0..self.count)
+            .map(|_| ctx.ask::<u32, _>(&self.doubler, 1u32).unwrap())
+            .collect();
+        ctx.continue_with(async move {
+            let replies = futures::future::join_all(asks).await;
+            replies.into_iter().map(Result::unwrap).sum::<u32>()
+        })
+        .unwrap();
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        self.out.tell(msg, None);
+    }
+}
+
+#[test]
+fn actor_many_asks() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<u32>::new(&sys).unwrap();
+
+    let doubler = sys.actor_of::<Doubler>("doubler").unwrap();
+    sys.actor_of_args::<AskingMany, _>("asking-many", (doubler, inbox.sender_ref(), 300))
+        .unwrap();
+    assert_eq!(inbox.receive(Duration::from_secs(5)), Some(600));
+
+    // the timer isn't held up by the jobs of the temporary actors
+    let scheduled = std::time::Instant::now();
+    sys.schedule_once(Duration::from_millis(100), inbox.sender_ref(), None, 1u32);
+    assert_eq!(inbox.receive(Duration::from_secs(2)), Some(1));
+    assert!(scheduled.elapsed() < Duration::from_millis(500));
+}
+
+#[derive(Default)]
+struct Unresponsive;
+
+impl Actor for Unresponsive {
+    type Msg = u32;
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {}
+}
+
+struct AskingUnresponsive {
+    target: ActorRef<u32>,
+    out: ActorRef<String>,
+}
+
+impl ActorFactoryArgs<(ActorRef<u32>, ActorRef<String>)> for AskingUnresponsive {
+    fn create_args((target, out): (ActorRef<u32>, ActorRef<String>)) -> Self {
+        AskingUnresponsive { target, out }
+    }
+}
+
+impl Actor for AskingUnresponsive {
+    type Msg = String;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let reply = ctx.ask::<u32, _>(&self.target, 1u32).unwrap();
+        ctx.pipe_to_self(reply, |res| format!("{:?}", res)).unwrap();
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        self.out.tell(msg, None);
+    }
+}
+
+#[test]
+fn actor_ask_timeout() {
+    let mut cfg = riker::load_config();
+    cfg.set("temp.ttl_millis", 200).unwrap();
+    let sys = ActorSystem::with_config("ask-timeout", cfg).unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+
+    let target = sys.actor_of::<Unresponsive>("unresponsive").unwrap();
+    sys.actor_of_args::<AskingUnresponsive, _>("asking", (target, inbox.sender_ref()))
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(sys.temp_actor_count(), 2);
+
+    // the temporary actor stops, failing the ask
+    assert_eq!(
+        inbox.receive(std::time::Duration::from_secs(1)),
+        Some("Err(Timeout)".to_string())
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(sys.temp_actor_count(), 1);
+}
+
+// Forwards requests to a `Doubler`, which replies to the original sender
+#[actor(u32, String)]
+struct Router {
+    doubler: ActorRef<u32>,
+}
+
+impl ActorFactoryArgs<ActorRef<u32>> for Router {
+    fn create_args(doubler: ActorRef<u32>) -> Self {
+        Router { doubler }
+    }
+}
+
+impl Actor for Router {
+    type Msg = RouterMsg;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<u32> for Router {
+    type Msg = RouterMsg;
+
+    fn receive(&mut self, ctx: &Context<Self::Msg>, msg: u32, _: Sender) {
+        ctx.forward(&self.doubler, msg);
+    }
+}
+
+impl Receive<String> for Router {
+    type Msg = RouterMsg;
+
+    fn receive(&mut self, _: &Context<Self::Msg>, msg: String, sender: Sender) {
+        let original = riker::Envelope { sender, msg };
+        let n: u32 = original.msg.parse().unwrap();
+        self.doubler.forward(n, &original);
+    }
+}
+
+#[test]
+fn actor_forward() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<u32>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    let doubler = sys.actor_of::<Doubler>("doubler").unwrap();
+    let router = sys.actor_of_args::<Router, _>("router", doubler).unwrap();
+
+    router.tell(2u32, Some(inbox.sender_ref().into()));
+    assert_eq!(inbox.receive(timeout), Some(4));
+
+    router.tell("3".to_string(), Some(inbox.sender_ref().into()));
+    assert_eq!(inbox.receive(timeout), Some(6));
+}
+
+// Replies with each message, prefixed by the number of times it started
+struct Starts {
+    started: u32,
+    out: ActorRef<String>,
+}
+
+impl ActorFactoryArgs<ActorRef<String>> for Starts {
+    fn create_args(out: ActorRef<String>) -> Self {
+        Starts { started: 0, out }
+    }
+}
+
+impl Actor for Starts {
+    type Msg = String;
+
+    fn pre_start(&mut self, _ctx: &Context<Self::Msg>) {
+        self.started += 1;
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        if msg == "fail" {
+            panic!("// TEST PANIC // TEST PANIC // TEST PANIC //");
+        }
+        self.out.tell(format!("{} {}", self.started, msg), None);
+    }
+}
+
+#[test]
+fn actor_init_msg() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    let props =
+        Props::new_args::<Starts, _>(inbox.sender_ref()).with_init_msg("config".to_string());
+    let actor = sys.actor_of_props("init-msg", props).unwrap();
+    actor.tell("hello".to_string(), None);
+
+    assert_eq!(inbox.receive(timeout), Some("1 config".to_string()));
+    assert_eq!(inbox.receive(timeout), Some("1 hello".to_string()));
+
+    // a restarted actor doesn't receive it again
+    actor.tell("fail".to_string(), None);
+    actor.tell("again".to_string(), None);
+    assert_eq!(inbox.receive(timeout), Some("1 again".to_string()));
+}
+
+// Takes a while to start, optionally failing
+struct SlowStart {
+    fail: bool,
+}
+
+impl ActorFactoryArgs<bool> for SlowStart {
+    fn create_args(fail: bool) -> Self {
+        SlowStart { fail }
+    }
+}
+
+impl Actor for SlowStart {
+    type Msg = ();
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        if self.fail {
+            panic!("// TEST PANIC // TEST PANIC // TEST PANIC //");
+        }
+        ctx.actor_of::<Child>("child").unwrap();
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {}
+}
+
+#[test]
+fn actor_started() {
+    let sys = ActorSystem::new().unwrap();
+
+    let actor = sys.actor_of_args::<SlowStart, _>("slow", false).unwrap();
+    futures::executor::block_on(actor.started()).unwrap();
+    assert_eq!(sys.select("/user/slow/child").unwrap().resolve().len(), 1);
+
+    // a started actor completes at once
+    futures::executor::block_on(actor.started()).unwrap();
+
+    let failing = sys.actor_of_args::<SlowStart, _>("failing", true).unwrap();
+    let started = futures::executor::block_on(failing.started());
+    assert!(matches!(started, Err(CreateError::Panicked)));
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "admin")]
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use futures::executor::block_on;
+use riker::actors::*;
+use riker::admin::AdminServer;
+
+#[derive(Default)]
+struct Worker;
+
+impl Actor for Worker {
+    type Msg = u32;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {}
+}
+
+// Returns the status line and the body
+fn request(addr: SocketAddr, method: &str, path: &str) -> (String, serde_json::Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+    write!(stream, "{} {} HTTP/1.1\r\nHost: test\r\n\r\n", method, path).unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_at(response.find("\r\n\r\n").unwrap());
+    let status = head.lines().next().unwrap().to_string();
+    (status, serde_json::from_str(body.trim()).unwrap())
+}
+
+#[test]
+fn admin_endpoints() {
+    let sys = ActorSystem::new().unwrap();
+    let worker = sys.actor_of::<Worker>("worker").unwrap();
+    block_on(worker.started()).unwrap();
+    worker.tell(1u32, None);
+
+    let addr = block_on(AdminServer::start(&sys, "127.0.0.1:0".parse().unwrap()).unwrap()).unwrap();
+
+    let (status, tree) = request(addr, "GET", "/tree");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    let user = tree["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|node| node["name"] == "user")
+        .unwrap();
+    assert_eq!(user["children"][0]["path"], "/user/worker");
+
+    let (status, stats) = request(addr, "GET", "/actor/user/worker/stats");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_eq!(stats["path"], "/user/worker");
+    assert_eq!(stats["restarts"], 0);
+
+    let (status, _) = request(addr, "GET", "/actor/user/missing/stats");
+    assert_eq!(status, "HTTP/1.1 404 Not Found");
+
+    let (status, dead_letters) = request(addr, "GET", "/deadletters");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert!(dead_letters.is_array());
+
+    let (status, _) = request(addr, "POST", "/actor/user/worker/restart");
+    assert_eq!(status, "HTTP/1.1 202 Accepted");
+    std::thread::sleep(Duration::from_millis(100));
+    let (_, stats) = request(addr, "GET", "/actor/user/worker/stats");
+    assert_eq!(stats["restarts"], 1);
+
+    let (status, _) = request(addr, "POST", "/tree");
+    assert_eq!(status, "HTTP/1.1 405 Method Not Allowed");
+}
@@ -35,13 +35,8 @@ impl Actor for Subscriber {
 
     fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
         let sub = Box::new(ctx.myself());
-        self.chan.tell(
-            Subscribe {
-                actor: sub,
-                topic: self.topic.clone(),
-            },
-            None,
-        );
+        self.chan
+            .tell(Subscribe::new(self.topic.clone(), sub), None);
     }
 
     fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
@@ -151,6 +146,353 @@ fn channel_publish_subscribe_all() {
     p_assert_eq!(listen, ());
 }
 
+// *** Wildcard subscription test ***
+#[derive(Clone, Debug)]
+pub struct Order(String);
+
+struct OrderSubscriber {
+    chan: ChannelRef<Order>,
+    topic: Topic,
+    probe: ChannelProbe<(), String>,
+}
+
+impl ActorFactoryArgs<(ChannelRef<Order>, Topic, ChannelProbe<(), String>)> for OrderSubscriber {
+    fn create_args(
+        (chan, topic, probe): (ChannelRef<Order>, Topic, ChannelProbe<(), String>),
+    ) -> Self {
+        OrderSubscriber { chan, topic, probe }
+    }
+}
+
+impl Actor for OrderSubscriber {
+    type Msg = Order;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.chan.tell(
+            Subscribe::new(self.topic.clone(), Box::new(ctx.myself())),
+            None,
+        );
+        self.probe.event("subscribed".to_string());
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        self.probe.event(msg.0);
+    }
+}
+
+#[test]
+fn channel_wildcard_subscribe() {
+    let sys = ActorSystem::new().unwrap();
+    let chan: ChannelRef<Order> = channel("orders", &sys).unwrap();
+
+    let (created, created_listen) = probe();
+    sys.actor_of_args::<OrderSubscriber, _>(
+        "created",
+        (chan.clone(), "orders/*/created".into(), created),
+    )
+    .unwrap();
+
+    let (all_orders, all_orders_listen) = probe();
+    sys.actor_of_args::<OrderSubscriber, _>(
+        "all-orders",
+        (chan.clone(), "orders/#".into(), all_orders),
+    )
+    .unwrap();
+
+    created_listen.recv();
+    all_orders_listen.recv();
+
+    for topic in &[
+        "orders/1/created",
+        "orders/1/shipped",
+        "orders/1/2/created",
+        "orders",
+        "invoices/1/created",
+        "orders/2/created",
+    ] {
+        chan.tell(
+            Publish {
+                msg: Order(topic.to_string()),
+                topic: (*topic).into(),
+            },
+            None,
+        );
+    }
+
+    p_assert_eq!(created_listen, "orders/1/created".to_string());
+    p_assert_eq!(created_listen, "orders/2/created".to_string());
+
+    p_assert_eq!(all_orders_listen, "orders/1/created".to_string());
+    p_assert_eq!(all_orders_listen, "orders/1/shipped".to_string());
+    p_assert_eq!(all_orders_listen, "orders/1/2/created".to_string());
+    p_assert_eq!(all_orders_listen, "orders".to_string());
+    p_assert_eq!(all_orders_listen, "orders/2/created".to_string());
+}
+
+// *** Subscriber priority test ***
+type Notified = std::sync::Arc<std::sync::Mutex<Vec<String>>>;
+
+struct PrioritySubscriber {
+    chan: ChannelRef<SomeMessage>,
+    priority: Option<i32>,
+    notified: Notified,
+}
+
+impl ActorFactoryArgs<(ChannelRef<SomeMessage>, Option<i32>, Notified)> for PrioritySubscriber {
+    fn create_args(
+        (chan, priority, notified): (ChannelRef<SomeMessage>, Option<i32>, Notified),
+    ) -> Self {
+        PrioritySubscriber {
+            chan,
+            priority,
+            notified,
+        }
+    }
+}
+
+impl Actor for PrioritySubscriber {
+    type Msg = SomeMessage;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let mut sub = Subscribe::new("my-topic", Box::new(ctx.myself()));
+        if let Some(priority) = self.priority {
+            sub = sub.priority(priority);
+        }
+        self.chan.tell(sub, None);
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {
+        self.notified
+            .lock()
+            .unwrap()
+            .push(ctx.myself().name().to_string());
+    }
+}
+
+#[test]
+fn channel_subscriber_priority() {
+    // a single dispatcher thread runs actors in the order they were notified
+    let mut cfg = riker::load_config();
+    cfg.set("dispatcher.pool_size", 1).unwrap();
+    let sys = ActorSystem::with_config("priority", cfg).unwrap();
+
+    let chan: ChannelRef<SomeMessage> = channel("my-chan", &sys).unwrap();
+    let notified = Notified::default();
+
+    for (name, priority) in [("low", Some(-1)), ("default", None), ("high", Some(10))] {
+        sys.actor_of_args::<PrioritySubscriber, _>(
+            name,
+            (chan.clone(), priority, notified.clone()),
+        )
+        .unwrap();
+    }
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    chan.tell(
+        Publish {
+            msg: SomeMessage,
+            topic: "my-topic".into(),
+        },
+        None,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    assert_eq!(*notified.lock().unwrap(), vec!["high", "default", "low"]);
+}
+
+// *** Subscribe with response test ***
+#[actor(SubscribedResponse, SomeMessage)]
+struct AckSubscriber {
+    probe: TestProbe,
+    chan: ChannelRef<SomeMessage>,
+}
+
+impl ActorFactoryArgs<(ChannelRef<SomeMessage>, TestProbe)> for AckSubscriber {
+    fn create_args((chan, probe): (ChannelRef<SomeMessage>, TestProbe)) -> Self {
+        AckSubscriber { probe, chan }
+    }
+}
+
+impl Actor for AckSubscriber {
+    type Msg = AckSubscriberMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.chan.tell(
+            SubscribeWithResponse::new("my-topic", Box::new(ctx.myself()), Box::new(ctx.myself())),
+            None,
+        );
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<SubscribedResponse> for AckSubscriber {
+    type Msg = AckSubscriberMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, _msg: SubscribedResponse, _sender: Sender) {
+        self.probe.0.event(());
+    }
+}
+
+impl Receive<SomeMessage> for AckSubscriber {
+    type Msg = AckSubscriberMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, _msg: SomeMessage, _sender: Sender) {
+        self.probe.0.event(());
+    }
+}
+
+#[test]
+fn channel_subscribe_with_response() {
+    let sys = ActorSystem::new().unwrap();
+
+    let chan: ChannelRef<SomeMessage> = channel("my-chan", &sys).unwrap();
+
+    let (probe, listen) = probe();
+    sys.actor_of_args::<AckSubscriber, _>("ack-sub", (chan.clone(), TestProbe(probe)))
+        .unwrap();
+
+    // wait for the subscription to be confirmed before publishing
+    p_assert_eq!(listen, ());
+
+    chan.tell(
+        Publish {
+            msg: SomeMessage,
+            topic: "my-topic".into(),
+        },
+        None,
+    );
+
+    p_assert_eq!(listen, ());
+}
+
+#[test]
+fn channel_retained_message() {
+    let sys = ActorSystem::new().unwrap();
+
+    let chan: ChannelRef<SomeMessage> = retained_channel("my-chan", &sys).unwrap();
+
+    // publish before there are any subscribers
+    chan.tell(
+        Publish {
+            msg: SomeMessage,
+            topic: "my-topic".into(),
+        },
+        None,
+    );
+
+    let (probe, listen) = probe();
+    sys.actor_of_args::<AckSubscriber, _>("ack-sub", (chan, TestProbe(probe)))
+        .unwrap();
+
+    // the retained message is received, followed by the subscription response
+    p_assert_eq!(listen, ());
+    p_assert_eq!(listen, ());
+}
+
+// *** Publish with reply test ***
+#[derive(Clone, Debug)]
+pub struct Answer;
+
+struct Responder {
+    chan: ChannelRef<SomeMessage>,
+}
+
+impl ActorFactoryArgs<ChannelRef<SomeMessage>> for Responder {
+    fn create_args(chan: ChannelRef<SomeMessage>) -> Self {
+        Responder { chan }
+    }
+}
+
+impl Actor for Responder {
+    type Msg = SomeMessage;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.chan
+            .tell(Subscribe::new("ask", Box::new(ctx.myself())), None);
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, _msg: Self::Msg, sender: Sender) {
+        let _ = sender.unwrap().try_tell(Answer, Some(ctx.myself().into()));
+    }
+}
+
+struct Collector(TestProbe);
+
+impl ActorFactoryArgs<TestProbe> for Collector {
+    fn create_args(probe: TestProbe) -> Self {
+        Collector(probe)
+    }
+}
+
+impl Actor for Collector {
+    type Msg = Answer;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {
+        self.0 .0.event(());
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CountProbe(ChannelProbe<(), usize>);
+
+struct Counter(CountProbe);
+
+impl ActorFactoryArgs<CountProbe> for Counter {
+    fn create_args(probe: CountProbe) -> Self {
+        Counter(probe)
+    }
+}
+
+impl Actor for Counter {
+    type Msg = Published;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        self.0 .0.event(msg.subscribers);
+    }
+}
+
+#[test]
+fn channel_publish_with_reply() {
+    let sys = ActorSystem::new().unwrap();
+
+    let chan: ChannelRef<SomeMessage> = channel("my-chan", &sys).unwrap();
+    sys.actor_of_args::<Responder, _>("responder-a", chan.clone())
+        .unwrap();
+    sys.actor_of_args::<Responder, _>("responder-b", chan.clone())
+        .unwrap();
+
+    let (reply_probe, listen) = probe();
+    let collector = sys
+        .actor_of_args::<Collector, _>("collector", TestProbe(reply_probe))
+        .unwrap();
+
+    let (count_probe, count_listen) = probe();
+    let counter = sys
+        .actor_of_args::<Counter, _>("counter", CountProbe(count_probe))
+        .unwrap();
+
+    // wait for the responders to subscribe
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    chan.tell(
+        PublishWithReply {
+            topic: "ask".into(),
+            msg: SomeMessage,
+            reply_to: collector.into(),
+        },
+        Some(counter.into()),
+    );
+
+    // the publisher is told how many replies to expect
+    p_assert_eq!(count_listen, 2);
+
+    p_assert_eq!(listen, ());
+    p_assert_eq!(listen, ());
+}
+
 #[derive(Clone, Debug)]
 pub struct Panic;
 
@@ -183,11 +525,6 @@ impl Receive<SomeMessage> for DumbActor {
     }
 }
 
-// We must wrap SystemEvent in a type defined in this test crate
-// so we can implement traits on it
-#[derive(Clone, Debug)]
-struct SysEvent(SystemEvent);
-
 // *** Event stream test ***
 #[actor(TestProbe, SystemEvent)]
 #[derive(Default)]
@@ -201,13 +538,7 @@ impl Actor for EventSubscriber {
     fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
         // subscribe
         let sub = Box::new(ctx.myself());
-        ctx.system.sys_events().tell(
-            Subscribe {
-                actor: sub,
-                topic: "*".into(),
-            },
-            None,
-        );
+        ctx.system.sys_events().tell(Subscribe::new("*", sub), None);
     }
 
     fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
@@ -250,6 +581,12 @@ impl Receive<SystemEvent> for EventSubscriber {
                     self.probe.as_ref().unwrap().0.event(())
                 }
             }
+            SystemEvent::ActorFailed(failed) => {
+                if failed.actor.path() == "/user/dumb-actor" && failed.reason.is_some() {
+                    self.probe.as_ref().unwrap().0.event(())
+                }
+            }
+            SystemEvent::OverloadChanged(_) | SystemEvent::HealthChanged(_) => {}
         }
     }
 }
@@ -274,7 +611,8 @@ fn channel_system_events() {
 
     // Force restart of actor
     dumb.tell(Panic, None);
-    // ActorRestarted event was received
+    // ActorFailed and ActorRestarted events were received
+    p_assert_eq!(listen, ());
     p_assert_eq!(listen, ());
 
     // Terminate actor
@@ -283,6 +621,58 @@ fn channel_system_events() {
     p_assert_eq!(listen, ());
 }
 
+// Forwards the path of each failed actor, and "other" for other events
+struct FailedForwarder(ActorRef<String>);
+
+impl ActorFactoryArgs<ActorRef<String>> for FailedForwarder {
+    fn create_args(out: ActorRef<String>) -> Self {
+        FailedForwarder(out)
+    }
+}
+
+impl Actor for FailedForwarder {
+    type Msg = ();
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {}
+
+    fn sys_recv(&mut self, _: &Context<Self::Msg>, msg: SystemMsg, _: Sender) {
+        match msg {
+            SystemMsg::Event(SystemEvent::ActorFailed(failed)) => {
+                self.0.tell(failed.actor.path().to_string(), None)
+            }
+            SystemMsg::Event(_) => self.0.tell("other".to_string(), None),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn channel_actor_failed_events() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let subscribed = Inbox::<SubscribedResponse>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    let forwarder = sys
+        .actor_of_args::<FailedForwarder, _>("failed-forwarder", inbox.sender_ref())
+        .unwrap();
+    sys.sys_events().tell(
+        SubscribeWithResponse::new(
+            riker::system::SystemEventType::ActorFailed,
+            Box::new(BasicActorRef::from(forwarder)),
+            Box::new(subscribed.sender_ref()),
+        ),
+        None,
+    );
+    assert!(subscribed.receive(timeout).is_some());
+
+    // only the failure is received, not the creation or the restart
+    let dumb = sys.actor_of::<DumbActor>("failing").unwrap();
+    dumb.tell(Panic, None);
+    assert_eq!(inbox.receive(timeout), Some("/user/failing".to_string()));
+    assert_eq!(inbox.receive(std::time::Duration::from_millis(300)), None);
+}
+
 // *** Dead letters test ***
 #[actor(TestProbe, DeadLetter)]
 #[derive(Default)]
@@ -296,13 +686,9 @@ impl Actor for DeadLetterSub {
     fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
         // subscribe to dead_letters
         let sub = Box::new(ctx.myself());
-        ctx.system.dead_letters().tell(
-            Subscribe {
-                actor: sub,
-                topic: "*".into(),
-            },
-            None,
-        );
+        ctx.system
+            .dead_letters()
+            .tell(Subscribe::new("*", sub), None);
     }
 
     fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
@@ -347,3 +733,278 @@ fn channel_dead_letters() {
 
     p_assert_eq!(listen, ());
 }
+
+// *** Dead letters replay test ***
+struct ReplayActor {
+    probe: TestProbe,
+}
+
+impl ActorFactoryArgs<TestProbe> for ReplayActor {
+    fn create_args(probe: TestProbe) -> Self {
+        ReplayActor { probe }
+    }
+}
+
+impl Actor for ReplayActor {
+    type Msg = SomeMessage;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {
+        self.probe.0.event(());
+    }
+}
+
+#[test]
+fn channel_dead_letters_replay() {
+    let sys = ActorSystem::new().unwrap();
+
+    let (probe, listen) = probe();
+    let actor = sys
+        .actor_of_args::<ReplayActor, _>("replay", TestProbe(probe.clone()))
+        .unwrap();
+
+    // stop the actor and send it a message, which is dead lettered
+    sys.stop(&actor);
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    actor.tell(SomeMessage, None);
+
+    // recreate the actor and replay its dead letters
+    let _actor = sys
+        .actor_of_args::<ReplayActor, _>("replay", TestProbe(probe))
+        .unwrap();
+    assert_eq!(sys.dead_letters_replay("/user/replay"), 1);
+    p_assert_eq!(listen, ());
+
+    // the dead letters are only replayed once
+    assert_eq!(sys.dead_letters_replay("/user/replay"), 0);
+}
+
+#[test]
+fn channel_recent_dead_letters() {
+    let mut cfg = riker::load_config();
+    cfg.set("deadletters.capacity", 2).unwrap();
+    let sys = ActorSystem::with_config("recent-dead-letters", cfg).unwrap();
+
+    let dumb = sys.actor_of::<DumbActor>("dumb-actor").unwrap();
+    sys.stop(&dumb);
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    for _ in 0..3 {
+        dumb.tell(SomeMessage, None);
+    }
+    std::thread::sleep(std::time::Duration::from_millis(500));
+
+    // only the most recent dead letters are kept
+    let recent = futures::executor::block_on(sys.recent_dead_letters()).unwrap();
+    assert_eq!(recent.len(), 2);
+    assert!(recent
+        .iter()
+        .all(|dl| dl.recipient.path() == "/user/dumb-actor"));
+}
+
+#[derive(Debug)]
+struct Frame(Vec<u8>);
+
+// Forwards each frame to `fwd`
+struct FrameSubscriber {
+    chan: ChannelRef<ArcMessage<Frame>>,
+    fwd: ActorRef<ArcMessage<Frame>>,
+}
+
+impl ActorFactoryArgs<(ChannelRef<ArcMessage<Frame>>, ActorRef<ArcMessage<Frame>>)>
+    for FrameSubscriber
+{
+    fn create_args(
+        (chan, fwd): (ChannelRef<ArcMessage<Frame>>, ActorRef<ArcMessage<Frame>>),
+    ) -> Self {
+        FrameSubscriber { chan, fwd }
+    }
+}
+
+impl Actor for FrameSubscriber {
+    type Msg = ArcMessage<Frame>;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.chan
+            .tell(Subscribe::new("frames", Box::new(ctx.myself())), None);
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        self.fwd.tell(msg, None);
+    }
+}
+
+#[test]
+fn channel_publish_arc_message() {
+    let sys = ActorSystem::new().unwrap();
+    let chan: ChannelRef<ArcMessage<Frame>> = channel("frames", &sys).unwrap();
+    let inbox = Inbox::<ArcMessage<Frame>>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    for i in 0..3 {
+        sys.actor_of_args::<FrameSubscriber, _>(
+            &format!("sub-{}", i),
+            (chan.clone(), inbox.sender_ref()),
+        )
+        .unwrap();
+    }
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    let frame = ArcMessage::new(Frame(vec![7; 1024 * 1024]));
+    chan.tell(
+        Publish {
+            msg: frame.clone(),
+            topic: "frames".into(),
+        },
+        None,
+    );
+
+    // every subscriber receives the same allocation
+    for _ in 0..3 {
+        let received = inbox.receive(timeout).unwrap();
+        assert!(ArcMessage::ptr_eq(&received, &frame));
+        assert_eq!(received.0.len(), 1024 * 1024);
+    }
+}
+
+#[test]
+fn channel_ref_methods() {
+    let sys = ActorSystem::new().unwrap();
+    let chan: ChannelRef<String> = channel("methods", &sys).unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_millis(300);
+
+    chan.subscribe("a", inbox.sender_ref());
+    chan.subscribe("b", inbox.sender_ref());
+    chan.publish("a", "first".to_string());
+    assert_eq!(inbox.receive(timeout), Some("first".to_string()));
+
+    chan.unsubscribe("a", inbox.sender_ref());
+    chan.publish("a", "unsubscribed".to_string());
+    chan.publish("b", "second".to_string());
+    assert_eq!(inbox.receive(timeout), Some("second".to_string()));
+
+    chan.unsubscribe_all(inbox.sender_ref());
+    chan.publish("b", "unsubscribed".to_string());
+    assert_eq!(inbox.receive(timeout), None);
+}
+
+// Forwards what it receives, tagged with its tag
+struct Tagged(&'static str, ActorRef<String>);
+
+impl ActorFactoryArgs<(&'static str, ActorRef<String>)> for Tagged {
+    fn create_args((tag, out): (&'static str, ActorRef<String>)) -> Self {
+        Tagged(tag, out)
+    }
+}
+
+impl Actor for Tagged {
+    type Msg = String;
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, _: Sender) {
+        self.1.tell(format!("{} {}", self.0, msg), None);
+    }
+}
+
+#[test]
+fn channel_durable_subscription() {
+    let sys = ActorSystem::new().unwrap();
+    let chan: ChannelRef<String> = channel("durable-channel", &sys).unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_millis(300);
+
+    let durable = sys
+        .actor_of_args::<Tagged, _>("durable", ("durable", inbox.sender_ref()))
+        .unwrap();
+    let transient = sys
+        .actor_of_args::<Tagged, _>("transient", ("transient", inbox.sender_ref()))
+        .unwrap();
+    chan.tell(
+        Subscribe::new("topic", Box::new(durable.clone())).durable(),
+        None,
+    );
+    chan.subscribe("topic", transient.clone());
+
+    // both actors are stopped and created again at their paths
+    sys.stop(&durable);
+    sys.stop(&transient);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    sys.actor_of_args::<Tagged, _>("durable", ("recreated", inbox.sender_ref()))
+        .unwrap();
+    sys.actor_of_args::<Tagged, _>("transient", ("recreated", inbox.sender_ref()))
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    // only the durable subscription reaches the new actor
+    chan.publish("topic", "msg".to_string());
+    assert_eq!(inbox.receive(timeout), Some("recreated msg".to_string()));
+    assert_eq!(inbox.receive(timeout), None);
+}
+
+#[test]
+fn channel_stopped_subscriber_removed() {
+    let sys = ActorSystem::new().unwrap();
+    let chan: ChannelRef<String> = channel("stopped-channel", &sys).unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let published = Inbox::<Published>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    let durable = sys
+        .actor_of_args::<Tagged, _>("durable", ("durable", inbox.sender_ref()))
+        .unwrap();
+    let transient = sys
+        .actor_of_args::<Tagged, _>("transient", ("transient", inbox.sender_ref()))
+        .unwrap();
+    chan.tell(
+        Subscribe::new("topic", Box::new(durable.clone())).durable(),
+        None,
+    );
+    chan.subscribe("topic", transient.clone());
+    sys.stop(&durable);
+    sys.stop(&transient);
+
+    // only the durable subscription is kept once the channel sees them stop
+    let subscribers = || {
+        let publish = PublishWithReply {
+            topic: "topic".into(),
+            msg: "msg".to_string(),
+            reply_to: inbox.sender_ref().into(),
+        };
+        chan.tell(publish, Some(published.sender_ref().into()));
+        published.receive(timeout).unwrap().subscribers
+    };
+    let mut remaining = subscribers();
+    for _ in 0..100 {
+        if remaining == 1 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        remaining = subscribers();
+    }
+    assert_eq!(remaining, 1);
+}
+
+#[test]
+fn channel_publish_batch() {
+    let sys = ActorSystem::new().unwrap();
+    let chan: ChannelRef<String> = retained_channel("batches", &sys).unwrap();
+    let exact = Inbox::<String>::new(&sys).unwrap();
+    let pattern = Inbox::<String>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_millis(300);
+
+    chan.subscribe("telemetry/cpu", exact.sender_ref());
+    chan.subscribe("telemetry/#", pattern.sender_ref());
+    let msgs: Vec<_> = (0..3).map(|i| format!("sample {}", i)).collect();
+    chan.publish_batch("telemetry/cpu", msgs.clone());
+
+    // each subscriber receives the batch in order
+    for inbox in &[&exact, &pattern] {
+        let received: Vec<_> = (0..3).map(|_| inbox.receive(timeout).unwrap()).collect();
+        assert_eq!(received, msgs);
+    }
+
+    // the last message of the batch is retained
+    let late = Inbox::<String>::new(&sys).unwrap();
+    chan.subscribe("telemetry/cpu", late.sender_ref());
+    assert_eq!(late.receive(timeout), Some("sample 2".to_string()));
+    assert_eq!(late.receive(timeout), None);
+}
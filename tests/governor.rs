@@ -0,0 +1,211 @@
+#[macro_use]
+extern crate riker_testkit;
+
+use riker::actors::*;
+use riker::load_config;
+
+use riker_testkit::probe::channel::{probe, ChannelProbe};
+use riker_testkit::probe::{Probe, ProbeReceive};
+
+fn governed_system(policy: &str) -> ActorSystem {
+    let mut cfg = load_config();
+    cfg.set("governor.enabled", true).unwrap();
+    cfg.set("governor.max_msgs_per_sec", 50).unwrap();
+    cfg.set("governor.resume_msgs_per_sec", 10).unwrap();
+    cfg.set("governor.policy", policy).unwrap();
+    cfg.set("governor.delay_millis", 50).unwrap();
+
+    ActorSystem::with_config("governor", cfg).unwrap()
+}
+
+#[derive(Clone, Debug)]
+pub struct Add;
+
+#[derive(Clone, Debug)]
+pub struct TestProbe(ChannelProbe<(), ()>);
+
+#[derive(Clone, Debug)]
+pub struct CountProbe(ChannelProbe<(), u32>);
+
+#[actor(CountProbe, Add)]
+#[derive(Default)]
+struct Counter {
+    probe: Option<CountProbe>,
+    count: u32,
+}
+
+impl Actor for Counter {
+    type Msg = CounterMsg;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<CountProbe> for Counter {
+    type Msg = CounterMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: CountProbe, _sender: Sender) {
+        self.probe = Some(msg);
+    }
+}
+
+impl Receive<Add> for Counter {
+    type Msg = CounterMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, _msg: Add, _sender: Sender) {
+        self.count += 1;
+        if self.count == 100 {
+            self.probe.as_ref().unwrap().0.event(self.count);
+        }
+    }
+}
+
+// *** Load shedding test ***
+#[actor(TestProbe, DeadLetter)]
+#[derive(Default)]
+struct OverloadSub {
+    probe: Option<TestProbe>,
+}
+
+impl Actor for OverloadSub {
+    type Msg = OverloadSubMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let sub = Box::new(ctx.myself());
+        ctx.system
+            .dead_letters()
+            .tell(Subscribe::new("*", sub), None);
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender)
+    }
+}
+
+impl Receive<TestProbe> for OverloadSub {
+    type Msg = OverloadSubMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: TestProbe, _sender: Sender) {
+        msg.0.event(());
+        self.probe = Some(msg);
+    }
+}
+
+impl Receive<DeadLetter> for OverloadSub {
+    type Msg = OverloadSubMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: DeadLetter, _sender: Sender) {
+        if msg.reason == DeadLetterReason::Overloaded {
+            self.probe.as_ref().unwrap().0.event(());
+        }
+    }
+}
+
+#[test]
+fn governor_sheds_when_overloaded() {
+    let sys = governed_system("shed");
+    let sub = sys.actor_of::<OverloadSub>("overload-sub").unwrap();
+
+    let (probe, listen) = probe();
+    sub.tell(TestProbe(probe), None);
+
+    // wait for the probe to arrive at the actor before overloading the system
+    listen.recv();
+
+    let counter = sys.actor_of::<Counter>("counter").unwrap();
+    for _ in 0..100 {
+        counter.tell(Add, None);
+    }
+
+    p_assert_eq!(listen, ());
+}
+
+#[test]
+fn governor_try_send_returns_shed_messages() {
+    let sys = governed_system("shed");
+    let counter = sys.actor_of::<Counter>("counter").unwrap();
+
+    for _ in 0..100 {
+        let _ = counter.try_send(Add.into(), None);
+    }
+
+    // the shed message is returned to the caller
+    match counter.try_send(Add.into(), None) {
+        Err(SendError::Overloaded(_)) => {}
+        _ => panic!("expected the message to be shed"),
+    }
+}
+
+// *** Delay policy test ***
+#[actor(TestProbe, SystemEvent)]
+#[derive(Default)]
+struct OverloadEventSub {
+    probe: Option<TestProbe>,
+}
+
+impl Actor for OverloadEventSub {
+    type Msg = OverloadEventSubMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let sub = Box::new(ctx.myself());
+        ctx.system
+            .sys_events()
+            .tell(Subscribe::new(SysTopic::OverloadChanged, sub), None);
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+
+    fn sys_recv(&mut self, ctx: &Context<Self::Msg>, msg: SystemMsg, sender: Sender) {
+        if let SystemMsg::Event(evt) = msg {
+            self.receive(ctx, evt, sender);
+        }
+    }
+}
+
+impl Receive<TestProbe> for OverloadEventSub {
+    type Msg = OverloadEventSubMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: TestProbe, _sender: Sender) {
+        msg.0.event(());
+        self.probe = Some(msg);
+    }
+}
+
+impl Receive<SystemEvent> for OverloadEventSub {
+    type Msg = OverloadEventSubMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: SystemEvent, _sender: Sender) {
+        if let SystemEvent::OverloadChanged(evt) = msg {
+            if evt.overloaded {
+                self.probe.as_ref().unwrap().0.event(());
+            }
+        }
+    }
+}
+
+#[test]
+fn governor_delays_when_overloaded() {
+    let sys = governed_system("delay");
+    let sub = sys.actor_of::<OverloadEventSub>("overload-sub").unwrap();
+
+    let (event_probe, listen) = probe();
+    sub.tell(TestProbe(event_probe), None);
+    listen.recv();
+
+    let counter = sys.actor_of::<Counter>("counter").unwrap();
+    let (count_probe, count_listen) = probe();
+    counter.tell(CountProbe(count_probe), None);
+
+    for _ in 0..100 {
+        counter.tell(Add, None);
+    }
+
+    // the system became overloaded...
+    p_assert_eq!(listen, ());
+
+    // ...but every delayed message was eventually delivered
+    p_assert_eq!(count_listen, 100);
+}
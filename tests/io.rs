@@ -0,0 +1,194 @@
+use riker::actors::*;
+use riker::io::{
+    SendTo, TcpConnectionActor, TcpEvent, TcpListenerActor, TcpMsg, UdpActor, UdpEvent,
+};
+
+use std::time::Duration;
+
+fn next(inbox: &Inbox<TcpEvent>) -> TcpEvent {
+    inbox.receive(Duration::from_secs(3)).expect("no TcpEvent")
+}
+
+#[test]
+fn tcp_listener_connection() {
+    let sys = ActorSystem::new().unwrap();
+    let server = Inbox::<TcpEvent>::new(&sys).unwrap();
+    let client = Inbox::<TcpEvent>::new(&sys).unwrap();
+
+    let addr = "127.0.0.1:0".parse().unwrap();
+    TcpListenerActor::bind(&sys, "listener", addr, Box::new(server.sender_ref())).unwrap();
+    let addr = match next(&server) {
+        TcpEvent::Bound(addr) => addr,
+        other => panic!("expected Bound, received {:?}", other),
+    };
+
+    let conn =
+        TcpConnectionActor::connect(&sys, "client", addr, Box::new(client.sender_ref())).unwrap();
+    match next(&client) {
+        TcpEvent::Connected { peer, .. } => assert_eq!(peer, addr),
+        other => panic!("expected Connected, received {:?}", other),
+    }
+    let accepted = match next(&server) {
+        TcpEvent::Connected { conn, .. } => conn,
+        other => panic!("expected Connected, received {:?}", other),
+    };
+
+    // the server echoes what the client writes
+    conn.tell(TcpMsg::Write(b"ping".to_vec()), None);
+    match next(&server) {
+        TcpEvent::Received { bytes, .. } => assert_eq!(bytes, b"ping"),
+        other => panic!("expected Received, received {:?}", other),
+    }
+
+    accepted.tell(TcpMsg::Write(b"pong".to_vec()), None);
+    match next(&client) {
+        TcpEvent::Received { bytes, .. } => assert_eq!(bytes, b"pong"),
+        other => panic!("expected Received, received {:?}", other),
+    }
+
+    // closing one side closes both
+    conn.tell(TcpMsg::Close, None);
+    assert!(matches!(next(&client), TcpEvent::Closed { .. }));
+    assert!(matches!(next(&server), TcpEvent::Closed { .. }));
+}
+
+#[test]
+fn tcp_connect_failed() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<TcpEvent>::new(&sys).unwrap();
+
+    // bind a free port, then release it so nothing is listening
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    TcpConnectionActor::connect(&sys, "client", addr, Box::new(inbox.sender_ref())).unwrap();
+    match next(&inbox) {
+        TcpEvent::Failed { addr: failed, .. } => assert_eq!(failed, addr),
+        other => panic!("expected Failed, received {:?}", other),
+    }
+}
+
+#[test]
+fn udp_send_to() {
+    let sys = ActorSystem::new().unwrap();
+    let a_events = Inbox::<UdpEvent>::new(&sys).unwrap();
+    let b_events = Inbox::<UdpEvent>::new(&sys).unwrap();
+
+    let bound = |inbox: &Inbox<UdpEvent>| match inbox.receive(Duration::from_secs(3)) {
+        Some(UdpEvent::Bound(addr)) => addr,
+        other => panic!("expected Bound, received {:?}", other),
+    };
+
+    let addr = "127.0.0.1:0".parse().unwrap();
+    let a = UdpActor::bind(&sys, "a", addr, Box::new(a_events.sender_ref())).unwrap();
+    let a_addr = bound(&a_events);
+    let b = UdpActor::bind(&sys, "b", addr, Box::new(b_events.sender_ref())).unwrap();
+    let b_addr = bound(&b_events);
+
+    a.tell(
+        SendTo {
+            peer: b_addr,
+            bytes: b"beacon".to_vec(),
+        },
+        None,
+    );
+    match b_events.receive(Duration::from_secs(3)) {
+        Some(UdpEvent::Datagram { peer, bytes }) => {
+            assert_eq!(peer, a_addr);
+            assert_eq!(bytes, b"beacon");
+        }
+        other => panic!("expected Datagram, received {:?}", other),
+    }
+
+    // the socket is closed once the actor stops
+    sys.stop(&b);
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(std::net::UdpSocket::bind(b_addr).is_ok());
+}
+
+// An address that was free a moment ago
+fn free_addr() -> std::net::SocketAddr {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+fn restart<Msg: Message>(sys: &ActorSystem, actor: &ActorRef<Msg>) {
+    sys.restart_path(&actor.path().to_string()).unwrap();
+    while futures::executor::block_on(actor.identify())
+        .unwrap()
+        .restarts
+        == 0
+    {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn tcp_connection_restarted() {
+    let sys = ActorSystem::new().unwrap();
+    let server = Inbox::<TcpEvent>::new(&sys).unwrap();
+    let client = Inbox::<TcpEvent>::new(&sys).unwrap();
+
+    let addr = "127.0.0.1:0".parse().unwrap();
+    TcpListenerActor::bind(&sys, "listener", addr, Box::new(server.sender_ref())).unwrap();
+    let addr = match next(&server) {
+        TcpEvent::Bound(addr) => addr,
+        other => panic!("expected Bound, received {:?}", other),
+    };
+
+    let conn =
+        TcpConnectionActor::connect(&sys, "client", addr, Box::new(client.sender_ref())).unwrap();
+    assert!(matches!(next(&client), TcpEvent::Connected { .. }));
+    assert!(matches!(next(&server), TcpEvent::Connected { .. }));
+
+    // the restarted actor writes to the same connection
+    restart(&sys, &conn);
+    conn.tell(TcpMsg::Write(b"ping".to_vec()), None);
+    match next(&server) {
+        TcpEvent::Received { bytes, .. } => assert_eq!(bytes, b"ping"),
+        other => panic!("expected Received, received {:?}", other),
+    }
+}
+
+#[test]
+fn tcp_listener_restarted() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<TcpEvent>::new(&sys).unwrap();
+
+    let addr = free_addr();
+    let listener =
+        TcpListenerActor::bind(&sys, "listener", addr, Box::new(inbox.sender_ref())).unwrap();
+    assert!(matches!(next(&inbox), TcpEvent::Bound(bound) if bound == addr));
+
+    // the old listener is closed before the address is bound again
+    restart(&sys, &listener);
+    match next(&inbox) {
+        TcpEvent::Bound(bound) => assert_eq!(bound, addr),
+        other => panic!("expected Bound, received {:?}", other),
+    }
+}
+
+#[test]
+fn udp_restarted() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<UdpEvent>::new(&sys).unwrap();
+
+    let addr = std::net::UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let udp = UdpActor::bind(&sys, "udp", addr, Box::new(inbox.sender_ref())).unwrap();
+    let timeout = Duration::from_secs(3);
+    assert!(matches!(inbox.receive(timeout), Some(UdpEvent::Bound(bound)) if bound == addr));
+
+    // the old socket is closed before the address is bound again
+    restart(&sys, &udp);
+    match inbox.receive(timeout) {
+        Some(UdpEvent::Bound(bound)) => assert_eq!(bound, addr),
+        other => panic!("expected Bound, received {:?}", other),
+    }
+}
@@ -1,7 +1,14 @@
+#[macro_use]
+extern crate riker_testkit;
+
 use futures::executor::block_on;
 
 use riker::actors::*;
-use slog::{o, Fuse, Logger};
+use riker::system::LogEntry;
+use slog::{o, Fuse, Level, Logger};
+
+use riker_testkit::probe::channel::{probe, ChannelProbe};
+use riker_testkit::probe::{Probe, ProbeReceive};
 
 mod common {
     use std::{fmt, result};
@@ -59,3 +66,222 @@ fn logging_stdlog() {
     let _sys = ActorSystem::new().unwrap();
     log::info!("system exists");
 }
+
+#[derive(Clone, Debug)]
+pub struct TestProbe(ChannelProbe<(), String>);
+
+#[actor(TestProbe, LogRecord)]
+#[derive(Default)]
+struct LogSub {
+    probe: Option<TestProbe>,
+}
+
+impl Actor for LogSub {
+    type Msg = LogSubMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        ctx.system
+            .log_channel()
+            .tell(Subscribe::new(All, Box::new(ctx.myself())), None);
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<TestProbe> for LogSub {
+    type Msg = LogSubMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: TestProbe, _sender: Sender) {
+        msg.0.event(String::new());
+        self.probe = Some(msg);
+    }
+}
+
+impl Receive<LogRecord> for LogSub {
+    type Msg = LogSubMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: LogRecord, _sender: Sender) {
+        if let LogEvent::DeadLetter(_) = msg.event {
+            self.probe
+                .as_ref()
+                .unwrap()
+                .0
+                .event(msg.actor.path().to_string());
+        }
+    }
+}
+
+#[derive(Default)]
+struct DumbActor;
+
+impl Actor for DumbActor {
+    type Msg = ();
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {}
+}
+
+#[derive(Default)]
+struct DbActor;
+
+impl Actor for DbActor {
+    type Msg = ();
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        ctx.actor_of::<DumbActor>("conn").unwrap();
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {}
+}
+
+fn dead_letter(sys: &ActorSystem, recipient: BasicActorRef) {
+    sys.dead_letters().tell(
+        Publish {
+            topic: "dead_letter".into(),
+            msg: DeadLetter {
+                msg: "test".to_string(),
+                sender: None,
+                recipient,
+                reason: DeadLetterReason::Terminated,
+                payload: None,
+            },
+        },
+        None,
+    );
+}
+
+#[test]
+fn logging_channel_filter() {
+    let sys = ActorSystem::new().unwrap();
+
+    // dead letter records are published at info level
+    sys.set_log_filter("/user/*", Level::Warning);
+    sys.set_log_filter("/user/db/*", Level::Debug);
+
+    let sub = sys.actor_of::<LogSub>("log-sub").unwrap();
+    let (probe, listen) = probe();
+    sub.tell(TestProbe(probe), None);
+    listen.recv();
+
+    let other = sys.actor_of::<DumbActor>("other").unwrap();
+    let db = sys.actor_of::<DbActor>("db").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    let conn = db.children().next().unwrap();
+
+    dead_letter(&sys, other.into());
+    dead_letter(&sys, conn);
+
+    // only the record for the actor matching the more specific filter is published
+    p_assert_eq!(listen, "/user/db/conn".to_string());
+}
+
+// Keeps the messages of the records it's passed
+#[derive(Clone, Default)]
+struct CaptureDrain(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+impl slog::Drain for CaptureDrain {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(&self, record: &slog::Record, _: &slog::OwnedKVList) -> Result<(), slog::Never> {
+        self.0.lock().unwrap().push(record.msg().to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn dead_letter_logger_suppress_sample() {
+    let mut cfg = riker::load_config();
+    cfg.set("deadletters.suppress", vec!["/user/db/*"]).unwrap();
+    cfg.set("deadletters.log_every", 2).unwrap();
+
+    let drain = CaptureDrain::default();
+    let sys = SystemBuilder::new()
+        .cfg(cfg)
+        .log(Logger::root(drain.clone(), o!()))
+        .create()
+        .unwrap();
+
+    let other = sys.actor_of::<DumbActor>("other").unwrap();
+    let db = sys.actor_of::<DbActor>("db").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    let conn = db.children().next().unwrap();
+
+    dead_letter(&sys, conn);
+    for _ in 0..4 {
+        dead_letter(&sys, other.clone().into());
+    }
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    // the suppressed dead letter isn't logged, one in two of the others are
+    let logged: Vec<_> = drain
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|msg| msg.starts_with("DeadLetter"))
+        .cloned()
+        .collect();
+    assert_eq!(logged.len(), 2);
+    assert!(logged.iter().all(|msg| msg.contains("/user/other")));
+}
+
+// Keeps the messages of the log entries it's published
+struct EntrySub(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+impl ActorFactoryArgs<std::sync::Arc<std::sync::Mutex<Vec<String>>>> for EntrySub {
+    fn create_args(entries: std::sync::Arc<std::sync::Mutex<Vec<String>>>) -> Self {
+        EntrySub(entries)
+    }
+}
+
+impl Actor for EntrySub {
+    type Msg = LogEntry;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        ctx.system
+            .logger()
+            .entries()
+            .tell(Subscribe::new("log.entries", Box::new(ctx.myself())), None);
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, entry: LogEntry, _: Sender) {
+        self.0.lock().unwrap().push(entry.msg);
+    }
+}
+
+#[test]
+fn logger_ref_level_filter_entries() {
+    let drain = CaptureDrain::default();
+    let sys = SystemBuilder::new()
+        .log(Logger::root(drain.clone(), o!()))
+        .create()
+        .unwrap();
+
+    let entries = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    sys.actor_of_args::<EntrySub, _>("entries", entries.clone())
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    let logger = sys.logger();
+    logger.set_level(Level::Warning);
+    assert_eq!(logger.level(), Level::Warning);
+    slog::info!(sys.log(), "test: below the level");
+    slog::warn!(sys.log(), "test: at the level");
+
+    logger.add_filter("log*r");
+    slog::warn!(sys.log(), "test: filtered module");
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    let test_lines = |lines: &[String]| -> Vec<String> {
+        lines
+            .iter()
+            .filter(|msg| msg.starts_with("test:"))
+            .cloned()
+            .collect()
+    };
+    let expected = vec!["test: at the level".to_string()];
+    assert_eq!(test_lines(&drain.0.lock().unwrap()), expected);
+    assert_eq!(test_lines(&entries.lock().unwrap()), expected);
+}
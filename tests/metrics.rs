@@ -0,0 +1,280 @@
+#![cfg(feature = "metrics")]
+
+#[macro_use]
+extern crate riker_testkit;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Barrier, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+use riker::actors::*;
+
+use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+use riker_testkit::probe::channel::{probe, ChannelProbe};
+use riker_testkit::probe::{Probe, ProbeReceive};
+
+#[derive(Clone, Debug)]
+pub struct Add;
+
+#[derive(Clone, Debug)]
+pub struct TestProbe(ChannelProbe<(), ()>);
+
+#[actor(TestProbe, Add)]
+#[derive(Default)]
+struct Counter {
+    probe: Option<TestProbe>,
+    count: u32,
+}
+
+impl Actor for Counter {
+    type Msg = CounterMsg;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<TestProbe> for Counter {
+    type Msg = CounterMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: TestProbe, _sender: Sender) {
+        self.probe = Some(msg)
+    }
+}
+
+impl Receive<Add> for Counter {
+    type Msg = CounterMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, _msg: Add, _sender: Sender) {
+        self.count += 1;
+        if self.count == 10 {
+            self.probe.as_ref().unwrap().0.event(())
+        }
+    }
+}
+
+// The recorder is global and a snapshot resets the values it takes,
+// so the values are summed across the snapshots of every test
+struct Recorded {
+    snapshotter: Snapshotter,
+    totals: Mutex<HashMap<(String, Option<String>), f64>>,
+    serial: Mutex<()>,
+}
+
+fn recorded() -> &'static Recorded {
+    static RECORDED: OnceLock<Recorded> = OnceLock::new();
+    RECORDED.get_or_init(|| {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        recorder.install().unwrap();
+        Recorded {
+            snapshotter,
+            totals: Mutex::new(HashMap::new()),
+            serial: Mutex::new(()),
+        }
+    })
+}
+
+// The actors of every test share the guardians' labels, so the tests
+// run one at a time
+fn serial() -> MutexGuard<'static, ()> {
+    let serial = &recorded().serial;
+    serial.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// The value of a counter or gauge, or the number of histogram samples,
+// for the `guardian` or channel `path` label
+fn value(name: &str, label: Option<&str>) -> f64 {
+    let recorded = recorded();
+    let mut totals = recorded.totals.lock().unwrap();
+    for (key, _, _, value) in recorded.snapshotter.snapshot().into_vec() {
+        let label = key
+            .key()
+            .labels()
+            .find(|l| l.key() == "guardian" || l.key() == "path")
+            .map(|l| l.value().to_string());
+        let value = match value {
+            DebugValue::Counter(v) => v as f64,
+            DebugValue::Gauge(v) => v.0,
+            DebugValue::Histogram(v) => v.len() as f64,
+        };
+        *totals
+            .entry((key.key().name().to_string(), label))
+            .or_default() += value;
+    }
+    totals
+        .get(&(name.to_string(), label.map(String::from)))
+        .copied()
+        .unwrap_or(0.0)
+}
+
+// The change in a value since `delta` was called
+fn delta(name: &'static str, label: Option<&'static str>) -> impl Fn() -> f64 {
+    let start = value(name, label);
+    move || value(name, label) - start
+}
+
+// Waits up to 2 seconds for `cond` to hold
+fn eventually(cond: impl Fn() -> bool) -> bool {
+    for _ in 0..200 {
+        if cond() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    cond()
+}
+
+#[test]
+fn metrics_messages_processed() {
+    let _serial = serial();
+    let processed = delta("riker_messages_processed_total", Some("user"));
+
+    let sys = ActorSystem::new().unwrap();
+    let actor = sys.actor_of::<Counter>("counter").unwrap();
+
+    let (probe, listen) = probe();
+    actor.tell(TestProbe(probe), None);
+
+    for _ in 0..10 {
+        actor.tell(Add, None);
+    }
+
+    p_assert_eq!(listen, ());
+
+    // the `TestProbe` and the 10 `Add` messages
+    assert!(eventually(|| processed() == 11.0));
+}
+
+// Blocks in `recv` on "block" until the test has waited on the barrier twice
+struct Gauged {
+    barrier: Arc<Barrier>,
+}
+
+impl ActorFactoryArgs<Arc<Barrier>> for Gauged {
+    fn create_args(barrier: Arc<Barrier>) -> Self {
+        Gauged { barrier }
+    }
+}
+
+impl Actor for Gauged {
+    type Msg = String;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        match msg.as_str() {
+            "block" => {
+                self.barrier.wait();
+                self.barrier.wait();
+            }
+            "continue" => ctx.continue_with(async { "resumed".to_string() }).unwrap(),
+            "panic" => panic!("// TEST PANIC // TEST PANIC // TEST PANIC //"),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn metrics_mailbox_size() {
+    let _serial = serial();
+    let size = delta("riker_mailbox_size", Some("user"));
+    let processed = delta("riker_messages_processed_total", Some("user"));
+
+    let sys = ActorSystem::new().unwrap();
+    let barrier = Arc::new(Barrier::new(2));
+    let actor = sys
+        .actor_of_args::<Gauged, _>("gauged", barrier.clone())
+        .unwrap();
+
+    actor.tell("block".to_string(), None);
+    barrier.wait();
+    for _ in 0..4 {
+        actor.tell("queued".to_string(), None);
+    }
+
+    // the blocking message is counted until it has been handled
+    assert_eq!(size(), 5.0);
+    barrier.wait();
+
+    // the resumed continuation is handled ahead of "done",
+    // it was never in the mailbox and isn't counted
+    actor.tell("continue".to_string(), None);
+    actor.tell("done".to_string(), None);
+    assert!(eventually(|| processed() == 7.0 && size() == 0.0));
+}
+
+#[test]
+fn metrics_actor_restarts() {
+    let _serial = serial();
+    let restarts = delta("riker_actor_restarts_total", Some("user"));
+
+    let sys = ActorSystem::new().unwrap();
+    let barrier = Arc::new(Barrier::new(2));
+    let actor = sys
+        .actor_of_args::<Gauged, _>("restarted", barrier)
+        .unwrap();
+
+    actor.tell("panic".to_string(), None);
+    actor.tell("panic".to_string(), None);
+
+    assert!(eventually(|| restarts() == 2.0));
+}
+
+#[test]
+fn metrics_dead_letters() {
+    let _serial = serial();
+    let dead_letters = delta("riker_dead_letters_total", Some("user"));
+    let size = delta("riker_mailbox_size", Some("user"));
+
+    let sys = ActorSystem::new().unwrap();
+    let barrier = Arc::new(Barrier::new(2));
+    let actor = sys
+        .actor_of_args::<Gauged, _>("stopped", barrier.clone())
+        .unwrap();
+
+    // the messages sent after the poison pill are flushed to dead letters
+    actor.tell("block".to_string(), None);
+    barrier.wait();
+    sys.stop_gracefully(&actor);
+    for _ in 0..3 {
+        actor.tell("lost".to_string(), None);
+    }
+    barrier.wait();
+
+    assert!(eventually(|| dead_letters() == 3.0));
+
+    // and they're no longer counted in the mailbox
+    assert_eq!(size(), 0.0);
+}
+
+#[test]
+fn metrics_executor_queue_lag() {
+    let _serial = serial();
+
+    let sys = ActorSystem::new().unwrap();
+    let actor = sys.actor_of::<Counter>("lagged").unwrap();
+
+    let (probe, listen) = probe();
+    actor.tell(TestProbe(probe), None);
+    for _ in 0..10 {
+        actor.tell(Add, None);
+    }
+    p_assert_eq!(listen, ());
+
+    // each run of the mailbox records a sample
+    let samples = || value("riker_executor_queue_lag_seconds", None);
+    assert!(eventually(|| samples() > 0.0));
+}
+
+#[test]
+fn metrics_channel_published() {
+    let _serial = serial();
+
+    let sys = ActorSystem::new().unwrap();
+    let chan: ChannelRef<String> = channel("published", &sys).unwrap();
+
+    chan.publish("topic", "one".to_string());
+    chan.publish_batch("topic", vec!["two".to_string(), "three".to_string()]);
+
+    let published = || value("riker_channel_published_total", Some("/user/published"));
+    assert!(eventually(|| published() == 3.0));
+}
@@ -0,0 +1,151 @@
+use riker::actors::*;
+use riker::pattern::{Gathered, Rate, ScatterGather, ThrottlePolicy, Throttler};
+use riker::testkit::TestKit;
+
+use std::time::Duration;
+
+#[test]
+fn throttler_buffers_excess() {
+    let kit = TestKit::new().unwrap();
+    let probe = kit.probe::<u32>().unwrap();
+
+    let rate = Rate::new(2, Duration::from_secs(1));
+    let throttler = kit
+        .system()
+        .actor_of_args::<Throttler<u32>, _>(
+            "throttler",
+            (probe.actor_ref(), rate, ThrottlePolicy::Buffer(10)),
+        )
+        .unwrap();
+
+    for i in 0..5u32 {
+        throttler.tell(i, None);
+    }
+
+    // two messages each period, in the order they were sent
+    probe.expect_msg(0);
+    probe.expect_msg(1);
+    probe.expect_no_msg(Duration::from_millis(100));
+
+    kit.advance(Duration::from_secs(1));
+    probe.expect_msg(2);
+    probe.expect_msg(3);
+    probe.expect_no_msg(Duration::from_millis(100));
+
+    kit.advance(Duration::from_secs(1));
+    probe.expect_msg(4);
+    probe.expect_no_msg(Duration::from_millis(100));
+}
+
+#[test]
+fn throttler_sheds_excess() {
+    let kit = TestKit::new().unwrap();
+    let probe = kit.probe::<u32>().unwrap();
+
+    let rate = Rate::new(2, Duration::from_secs(1));
+    let throttler = kit
+        .system()
+        .actor_of_args::<Throttler<u32>, _>(
+            "throttler",
+            (probe.actor_ref(), rate, ThrottlePolicy::Shed),
+        )
+        .unwrap();
+
+    for i in 0..5u32 {
+        throttler.tell(i, None);
+    }
+
+    probe.expect_msg(0);
+    probe.expect_msg(1);
+
+    // the rest are shed once the throttler has taken them
+    let shed = || {
+        let recent = futures::executor::block_on(kit.system().recent_dead_letters()).unwrap();
+        recent
+            .into_iter()
+            .filter(|dl| dl.reason == DeadLetterReason::Throttled)
+            .collect::<Vec<_>>()
+    };
+    let deadline = std::time::Instant::now() + kit.dilated(Duration::from_secs(3));
+    while shed().len() < 3 && std::time::Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let shed = shed();
+    assert_eq!(shed.len(), 3);
+    assert!(shed
+        .iter()
+        .all(|dl| dl.recipient.path() == probe.actor_ref().path()));
+
+    // and aren't forwarded in the next period
+    kit.advance(Duration::from_secs(1));
+    probe.expect_no_msg(Duration::from_millis(100));
+
+    // a new period forwards again
+    throttler.tell(5u32, None);
+    probe.expect_msg(5);
+}
+
+#[derive(Default)]
+struct Worker;
+
+impl Actor for Worker {
+    type Msg = u32;
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        // odd requests are left unanswered
+        if msg % 2 == 0 {
+            sender.unwrap().try_tell(msg * 10, None).unwrap();
+        }
+    }
+}
+
+#[test]
+fn scatter_gather_selection() {
+    let kit = TestKit::new().unwrap();
+    let probe = kit.probe::<Gathered<u32>>().unwrap();
+
+    let sys = kit.system();
+    for i in 0..3 {
+        sys.actor_of::<Worker>(&format!("worker-{}", i)).unwrap();
+    }
+
+    let sel = sys.select("/user/*").unwrap();
+    ScatterGather::selection(2u32, &sel)
+        .run(sys, Box::new(probe.actor_ref()))
+        .unwrap();
+
+    let (gathered, _) = probe.receive_msg();
+    assert_eq!(gathered.replies, vec![20, 20, 20]);
+    assert!(!gathered.timed_out);
+
+    // fewer replies than recipients
+    ScatterGather::selection(4u32, &sel)
+        .count(1)
+        .run(sys, Box::new(probe.actor_ref()))
+        .unwrap();
+
+    let (gathered, _) = probe.receive_msg();
+    assert_eq!(gathered.replies, vec![40]);
+    assert!(!gathered.timed_out);
+}
+
+#[test]
+fn scatter_gather_timeout() {
+    let kit = TestKit::new().unwrap();
+    let probe = kit.probe::<Gathered<u32>>().unwrap();
+
+    let sys = kit.system();
+    let worker = sys.actor_of::<Worker>("worker").unwrap();
+
+    ScatterGather::new(1u32, vec![worker.into()])
+        .timeout(Duration::from_secs(10))
+        .run(sys, Box::new(probe.actor_ref()))
+        .unwrap();
+
+    probe.expect_no_msg(Duration::from_millis(100));
+
+    kit.advance(Duration::from_secs(10));
+    let (gathered, _) = probe.receive_msg();
+    assert!(gathered.replies.is_empty());
+    assert!(gathered.timed_out);
+}
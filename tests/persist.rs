@@ -0,0 +1,72 @@
+#![cfg(feature = "persist")]
+
+use std::time::Duration;
+
+use futures::executor::block_on;
+use riker::actors::*;
+use riker::system::{FileStore, PersistentScheduler, ScheduleStore};
+
+// Forwards its reminders
+struct Reminders(ActorRef<String>);
+
+impl ActorFactoryArgs<ActorRef<String>> for Reminders {
+    fn create_args(inbox: ActorRef<String>) -> Self {
+        Reminders(inbox)
+    }
+}
+
+impl Actor for Reminders {
+    type Msg = String;
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        self.0.tell(msg, None);
+    }
+}
+
+#[test]
+fn persistent_schedule_restored() {
+    let dir = std::env::temp_dir().join(format!("riker-persist-{}", std::process::id()));
+
+    // the first run schedules, then stops before the reminders are due
+    let sys = ActorSystem::new().unwrap();
+    let scheduler = PersistentScheduler::start(&sys, FileStore::new(&dir).unwrap()).unwrap();
+    scheduler
+        .schedule_once(
+            Duration::from_millis(300),
+            "/user/reminders",
+            "once".to_string(),
+        )
+        .unwrap();
+    let cancelled = scheduler
+        .schedule_once(
+            Duration::from_millis(300),
+            "/user/reminders",
+            "cancelled".to_string(),
+        )
+        .unwrap();
+    scheduler.cancel(cancelled).unwrap();
+    block_on(sys.shutdown()).unwrap();
+
+    assert_eq!(FileStore::new(&dir).unwrap().load().unwrap().len(), 1);
+
+    // the second run restores the reminder
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    sys.actor_of_args::<Reminders, _>("reminders", inbox.sender_ref())
+        .unwrap();
+
+    let scheduler = PersistentScheduler::start(&sys, FileStore::new(&dir).unwrap()).unwrap();
+    assert_eq!(scheduler.restore::<u32>().unwrap(), 0);
+    assert_eq!(scheduler.restore::<String>().unwrap(), 1);
+
+    assert_eq!(
+        inbox.receive(Duration::from_secs(2)),
+        Some("once".to_string())
+    );
+    assert_eq!(inbox.receive(Duration::from_millis(500)), None);
+
+    // sent once schedules are removed from the store
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(FileStore::new(&dir).unwrap().load().unwrap().is_empty());
+    std::fs::remove_dir_all(dir).unwrap();
+}
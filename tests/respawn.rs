@@ -0,0 +1,104 @@
+#[macro_use]
+extern crate riker_testkit;
+
+use riker::actors::*;
+
+use riker_testkit::probe::channel::{probe, ChannelProbe};
+use riker_testkit::probe::{Probe, ProbeReceive};
+
+#[derive(Clone, Debug)]
+pub struct TestProbe(ChannelProbe<(), String>);
+
+#[derive(Default)]
+struct Worker;
+
+impl Actor for Worker {
+    type Msg = TestProbe;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        msg.0.event(ctx.myself().path().to_string());
+    }
+}
+
+#[actor(TestProbe, RespawnRequest)]
+#[derive(Default)]
+struct Manager {
+    probe: Option<TestProbe>,
+}
+
+impl Actor for Manager {
+    type Msg = ManagerMsg;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<TestProbe> for Manager {
+    type Msg = ManagerMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: TestProbe, _sender: Sender) {
+        msg.0.event(String::new());
+        self.probe = Some(msg);
+    }
+}
+
+impl Receive<RespawnRequest> for Manager {
+    type Msg = ManagerMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: RespawnRequest, _sender: Sender) {
+        self.probe
+            .as_ref()
+            .unwrap()
+            .0
+            .event(format!("{} {}", msg.path, msg.dead_letters));
+    }
+}
+
+fn stop_and_wait(sys: &ActorSystem, actor: &ActorRef<TestProbe>) {
+    sys.stop(actor);
+    std::thread::sleep(std::time::Duration::from_millis(200));
+}
+
+#[test]
+fn respawn_recreate() {
+    let sys = ActorSystem::new().unwrap();
+
+    sys.add_respawn_policy(
+        RespawnPolicy::new("/user/worker", 2).recreate("worker", Props::new::<Worker>()),
+    );
+
+    let worker = sys.actor_of::<Worker>("worker").unwrap();
+    stop_and_wait(&sys, &worker);
+
+    let (probe, listen) = probe();
+    worker.tell(TestProbe(probe.clone()), None);
+    worker.tell(TestProbe(probe.clone()), None);
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    // the policy recreated the worker at the same path
+    let sel = sys.select("worker").unwrap();
+    sel.try_tell(TestProbe(probe), None);
+    p_assert_eq!(listen, "/user/worker".to_string());
+}
+
+#[test]
+fn respawn_notify() {
+    let sys = ActorSystem::new().unwrap();
+
+    let manager = sys.actor_of::<Manager>("manager").unwrap();
+    let (probe, listen) = probe();
+    manager.tell(TestProbe(probe.clone()), None);
+    listen.recv();
+
+    sys.add_respawn_policy(RespawnPolicy::new("/user/worker", 3).notify(manager));
+
+    let worker = sys.actor_of::<Worker>("worker").unwrap();
+    stop_and_wait(&sys, &worker);
+
+    for _ in 0..3 {
+        worker.tell(TestProbe(probe.clone()), None);
+    }
+
+    p_assert_eq!(listen, "/user/worker 3".to_string());
+}
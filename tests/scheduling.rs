@@ -2,11 +2,16 @@
 extern crate riker_testkit;
 
 use riker::actors::*;
+use riker::system::{BasicTimer, ClockRef, Job, ManualClock, ManualTimer, TimerFactory};
 
 use riker_testkit::probe::channel::{probe, ChannelProbe};
 use riker_testkit::probe::{Probe, ProbeReceive};
 
 use chrono::{Duration as CDuration, Utc};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    mpsc, Arc,
+};
 use std::time::Duration;
 
 #[derive(Clone, Debug)]
@@ -134,3 +139,332 @@ fn schedule_repeat() {
 
     p_assert_eq!(listen, ());
 }
+
+#[test]
+fn schedule_with_manual_clock() {
+    let clock = ManualClock::new();
+    let sys = SystemBuilder::new().clock(clock.clone()).create().unwrap();
+
+    let actor = sys.actor_of::<ScheduleOnce>("schedule-once").unwrap();
+
+    let (probe, listen) = probe();
+
+    let schedule_at = sys.clock().wall() + CDuration::hours(1);
+    sys.schedule_at_time(schedule_at, actor, None, TestProbe(probe));
+
+    // the probe is only sent once the clock has moved past the scheduled time
+    std::thread::sleep(Duration::from_millis(200));
+    clock.advance(Duration::from_secs(60 * 60));
+
+    // the actor then schedules `SomeMessage` 200ms (of clock time) later
+    std::thread::sleep(Duration::from_millis(200));
+    clock.advance(Duration::from_secs(1));
+    p_assert_eq!(listen, ());
+
+    assert_eq!(sys.uptime(), 60 * 60 + 1);
+}
+
+// *** Coalesce repeat test ***
+#[derive(Clone, Debug)]
+pub struct CountProbe(ChannelProbe<(), u32>);
+
+#[actor(CountProbe, SomeMessage)]
+#[derive(Default)]
+struct SlowReceiver {
+    count: u32,
+}
+
+impl Actor for SlowReceiver {
+    type Msg = SlowReceiverMsg;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<CountProbe> for SlowReceiver {
+    type Msg = SlowReceiverMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: CountProbe, _sender: Sender) {
+        msg.0.event(self.count);
+    }
+}
+
+impl Receive<SomeMessage> for SlowReceiver {
+    type Msg = SlowReceiverMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, _msg: SomeMessage, _sender: Sender) {
+        // the first message keeps the receiver busy
+        if self.count == 0 {
+            std::thread::sleep(Duration::from_millis(500));
+        }
+        self.count += 1;
+    }
+}
+
+#[test]
+fn schedule_repeat_coalesced() {
+    let mut cfg = riker::load_config();
+    cfg.set("scheduler.coalesce_repeats", true).unwrap();
+    let sys = ActorSystem::with_config("coalesce", cfg).unwrap();
+
+    let actor = sys.actor_of::<SlowReceiver>("slow").unwrap();
+
+    let id = sys.schedule(
+        Duration::from_millis(0),
+        Duration::from_millis(10),
+        actor.clone(),
+        None,
+        SomeMessage,
+    );
+    std::thread::sleep(Duration::from_millis(500));
+    sys.cancel_schedule(id);
+
+    // while the receiver was busy at most one message was waiting
+    let (probe, listen) = probe();
+    actor.tell(CountProbe(probe), None);
+    assert!(listen.recv() <= 3);
+}
+
+#[test]
+fn schedule_with_manual_timer() {
+    let timer = ManualTimer::new();
+    let sys = SystemBuilder::new().timer(timer.clone()).create().unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    let none = |inbox: &Inbox<String>| inbox.receive(Duration::from_millis(100));
+
+    let tick = Duration::from_secs(10);
+    let repeat = sys.schedule(tick, tick, inbox.sender_ref(), None, "tick".to_string());
+    sys.schedule_once(
+        Duration::from_secs(15),
+        inbox.sender_ref(),
+        None,
+        "once".to_string(),
+    );
+
+    // nothing is sent until virtual time moves
+    assert_eq!(none(&inbox), None);
+
+    // messages are sent in the order they are due
+    timer.advance(Duration::from_secs(30));
+    let msgs: Vec<_> = (0..4)
+        .map(|_| inbox.receive(Duration::from_secs(1)).unwrap())
+        .collect();
+    assert_eq!(msgs, vec!["tick", "once", "tick", "tick"]);
+    assert_eq!(none(&inbox), None);
+
+    sys.cancel_schedule(repeat);
+    timer.advance(Duration::from_secs(60));
+    assert_eq!(none(&inbox), None);
+    assert_eq!(sys.uptime(), 90);
+}
+
+#[test]
+fn schedule_with_timer_factory() {
+    let scheduled = Arc::new(AtomicUsize::new(0));
+    let count = scheduled.clone();
+
+    // a timer that counts the jobs it receives, sending them with a `BasicTimer`
+    let factory = move |name: &str, cfg: &config::Config, clock: ClockRef| {
+        let timer = BasicTimer::start(name, cfg, clock)?;
+        let count = count.clone();
+        let (tx, rx) = mpsc::channel::<Job>();
+        std::thread::spawn(move || {
+            for job in rx {
+                if let Job::Once(_) = job {
+                    count.fetch_add(1, Ordering::Relaxed);
+                }
+                let _ = timer.send(job);
+            }
+        });
+        Ok(tx)
+    };
+
+    let sys = SystemBuilder::new()
+        .timer_factory(factory)
+        .create()
+        .unwrap();
+    let inbox = Inbox::<u32>::new(&sys).unwrap();
+
+    for i in 0..3u32 {
+        sys.schedule_once(Duration::from_millis(10), inbox.sender_ref(), None, i);
+    }
+
+    for _ in 0..3 {
+        assert!(inbox.receive(Duration::from_secs(1)).is_some());
+    }
+    assert_eq!(scheduled.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn schedule_fn() {
+    let timer = ManualTimer::new();
+    let sys = SystemBuilder::new().timer(timer.clone()).create().unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+
+    // each tick sends a new message
+    let mut ticks = 0;
+    let tick = Duration::from_secs(10);
+    let id = sys.schedule_fn(tick, tick, inbox.sender_ref(), None, move || {
+        ticks += 1;
+        format!("tick {}", ticks)
+    });
+
+    timer.advance(Duration::from_secs(30));
+    let msgs: Vec<_> = (0..3)
+        .map(|_| inbox.receive(Duration::from_secs(1)).unwrap())
+        .collect();
+    assert_eq!(msgs, vec!["tick 1", "tick 2", "tick 3"]);
+
+    sys.cancel_schedule(id);
+    timer.advance(Duration::from_secs(30));
+    assert_eq!(inbox.receive(Duration::from_millis(100)), None);
+}
+
+// Notifies itself, forwarding the notifications
+struct Notified(ActorRef<String>);
+
+impl ActorFactoryArgs<ActorRef<String>> for Notified {
+    fn create_args(inbox: ActorRef<String>) -> Self {
+        Notified(inbox)
+    }
+}
+
+impl Actor for Notified {
+    type Msg = String;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        ctx.notify_interval("tick".to_string(), Duration::from_secs(10));
+        ctx.notify_later("later".to_string(), Duration::from_secs(15));
+        let cancelled = ctx.notify_later("cancelled".to_string(), Duration::from_secs(5));
+        ctx.cancel_schedule(cancelled);
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        self.0.tell(msg, None);
+    }
+}
+
+// Counts the jobs matching `counted`, sending all jobs to the manual timer
+fn counting_timer(
+    timer: &ManualTimer,
+    counted: fn(&Job) -> bool,
+) -> (ActorSystem, Arc<AtomicUsize>) {
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let (manual, counter) = (timer.clone(), count.clone());
+    let factory = move |name: &str, cfg: &config::Config, clock: ClockRef| {
+        let timer = manual.start(name, cfg, clock)?;
+        let counter = counter.clone();
+        let (tx, rx) = mpsc::channel::<Job>();
+        std::thread::spawn(move || {
+            for job in rx {
+                if counted(&job) {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+                let _ = timer.send(job);
+            }
+        });
+        Ok(tx)
+    };
+    let sys = SystemBuilder::new()
+        .clock(timer.clock().clone())
+        .timer_factory(factory)
+        .create()
+        .unwrap();
+    (sys, count)
+}
+
+#[test]
+fn schedule_notify_self() {
+    let timer = ManualTimer::new();
+    let (sys, cancelled) = counting_timer(&timer, |job| {
+        matches!(job, Job::Cancel(_) | Job::CancelFor(_))
+    });
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+
+    let actor = sys
+        .actor_of_args::<Notified, _>("notified", inbox.sender_ref())
+        .unwrap();
+    futures::executor::block_on(actor.started()).unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+
+    timer.advance(Duration::from_secs(30));
+    let msgs: Vec<_> = (0..4)
+        .map(|_| inbox.receive(Duration::from_secs(1)).unwrap())
+        .collect();
+    assert_eq!(msgs, vec!["tick", "later", "tick", "tick"]);
+    assert_eq!(cancelled.load(Ordering::Relaxed), 1);
+
+    // the remaining schedules are cancelled together when the actor stops
+    sys.stop(&actor);
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(cancelled.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn schedule_cancelled_on_termination() {
+    let timer = ManualTimer::new();
+    let sys = SystemBuilder::new().timer(timer.clone()).create().unwrap();
+    let inbox = Inbox::<String>::new(&sys).unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+
+    // the jobs of the system actors
+    let system = timer.pending();
+
+    let actor = sys
+        .actor_of_args::<Notified, _>("notified", inbox.sender_ref())
+        .unwrap();
+    futures::executor::block_on(actor.started()).unwrap();
+    let tick = Duration::from_secs(10);
+    sys.schedule(tick, tick, actor.clone(), None, "repeat".to_string());
+    sys.schedule_once(tick, inbox.sender_ref(), None, "inbox".to_string());
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(timer.pending(), system + 4);
+
+    // only the schedules to the terminated actor are cancelled
+    sys.stop(&actor);
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(timer.pending(), system + 1);
+
+    sys.cancel_for(inbox.sender_ref().path());
+    assert_eq!(timer.pending(), system);
+}
+
+#[test]
+fn schedule_cancel_for_only_scheduled() {
+    let timer = ManualTimer::new();
+    let (sys, cancelled) = counting_timer(&timer, |job| matches!(job, Job::CancelFor(_)));
+
+    let unscheduled = sys.actor_of::<SlowReceiver>("unscheduled").unwrap();
+    let scheduled = sys.actor_of::<SlowReceiver>("scheduled").unwrap();
+    sys.schedule_once(
+        Duration::from_secs(10),
+        scheduled.clone(),
+        None,
+        SomeMessage,
+    );
+
+    // the timer isn't asked to cancel the jobs of an actor that has none
+    sys.stop(&unscheduled);
+    while sys
+        .user_root()
+        .children()
+        .any(|child| child.name() == "unscheduled")
+    {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    // jobs are counted in order, so any sent on termination precede this
+    sys.cancel_for(unscheduled.path());
+    while cancelled.load(Ordering::Relaxed) == 0 {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(cancelled.load(Ordering::Relaxed), 1);
+
+    sys.stop(&scheduled);
+    while cancelled.load(Ordering::Relaxed) == 1 {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(cancelled.load(Ordering::Relaxed), 2);
+}
@@ -1,6 +1,7 @@
 #[macro_use]
 extern crate riker_testkit;
 
+use futures::executor::block_on;
 use riker::actors::*;
 
 use riker_testkit::probe::channel::{probe, ChannelProbe};
@@ -61,11 +62,11 @@ fn select_child() {
 fn select_child_of_child() {
     let sys = ActorSystem::new().unwrap();
 
-    sys.actor_of::<SelectTest>("select-actor").unwrap();
+    let actor = sys.actor_of::<SelectTest>("select-actor").unwrap();
 
-    // delay to allow 'select-actor' pre_start to create 'child_a' and 'child_b'
+    // wait for 'select-actor' pre_start to create 'child_a' and 'child_b'
     // Direct messaging on the actor_ref doesn't have this same issue
-    std::thread::sleep(std::time::Duration::from_millis(500));
+    block_on(actor.started()).unwrap();
 
     let (probe, listen) = probe();
 
@@ -81,11 +82,11 @@ fn select_child_of_child() {
 fn select_all_children_of_child() {
     let sys = ActorSystem::new().unwrap();
 
-    sys.actor_of::<SelectTest>("select-actor").unwrap();
+    let actor = sys.actor_of::<SelectTest>("select-actor").unwrap();
 
-    // delay to allow 'select-actor' pre_start to create 'child_a' and 'child_b'
+    // wait for 'select-actor' pre_start to create 'child_a' and 'child_b'
     // Direct messaging on the actor_ref doesn't have this same issue
-    std::thread::sleep(std::time::Duration::from_millis(500));
+    block_on(actor.started()).unwrap();
 
     let (probe, listen) = probe();
 
@@ -227,3 +228,127 @@ fn select_paths() {
 
 //     p_assert_eq!(listen, ());
 // }
+
+#[test]
+fn select_cached_invalidation() {
+    let sys = ActorSystem::new().unwrap();
+
+    let child_a = sys.actor_of::<Child>("child_a").unwrap();
+    let sel = sys.select_cached("*").unwrap();
+    assert_eq!(sel.resolve().len(), 1);
+
+    // the cache is invalidated once the creation is published
+    sys.actor_of::<Child>("child_b").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    assert_eq!(sel.resolve().len(), 2);
+
+    // and once the termination is published
+    sys.stop(&child_a);
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    assert_eq!(sel.resolve().len(), 1);
+
+    let (probe, listen) = probe();
+    sel.try_tell(TestProbe(probe), None);
+    p_assert_eq!(listen, ());
+}
+
+#[test]
+fn select_cached_covered_paths() {
+    let sys = ActorSystem::new().unwrap();
+
+    sys.actor_of::<Child>("scoped").unwrap();
+    let sel = sys.select_cached("/user/scoped").unwrap();
+    let all = sys.select_cached("*").unwrap();
+    assert_eq!(sel.resolve().len(), 1);
+    assert_eq!(all.resolve().len(), 1);
+    assert!(!sel.is_stale());
+
+    // an actor outside the selection doesn't invalidate it
+    sys.actor_of::<Child>("unrelated").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    assert!(!sel.is_stale());
+    assert!(all.is_stale());
+    assert_eq!(all.resolve().len(), 2);
+
+    // the selected actor does
+    sys.stop(sel.resolve().pop().unwrap());
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    assert!(sel.is_stale());
+    assert!(sel.resolve().is_empty());
+    assert!(!sel.is_stale());
+}
+
+#[derive(Debug)]
+struct Payload(Vec<u8>);
+
+// Replies to the sender with the payload it received, "echo" has two children
+#[derive(Default)]
+struct Echo;
+
+impl Actor for Echo {
+    type Msg = ArcMessage<Payload>;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        if ctx.myself().name() == "echo" {
+            ctx.actor_of::<Echo>("echo_a").unwrap();
+            ctx.actor_of::<Echo>("echo_b").unwrap();
+        }
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        sender.unwrap().try_tell(msg, None).unwrap();
+    }
+}
+
+#[test]
+fn select_arc_message() {
+    let sys = ActorSystem::new().unwrap();
+    sys.actor_of::<Echo>("echo").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    let inbox = Inbox::<ArcMessage<Payload>>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    let payload = ArcMessage::new(Payload(vec![1; 1024 * 1024]));
+    let sel = sys.select("/user/echo/*").unwrap();
+    sel.try_tell(payload.clone(), Some(inbox.sender_ref().into()));
+    for _ in 0..2 {
+        let received = inbox.receive(timeout).unwrap();
+        assert!(ArcMessage::ptr_eq(&received, &payload));
+        assert_eq!(received.0.len(), 1024 * 1024);
+    }
+}
+
+#[test]
+fn select_refs_glob() {
+    let sys = ActorSystem::new().unwrap();
+    sys.actor_of::<Echo>("echo").unwrap();
+    sys.actor_of::<Child>("child_a").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    let paths = |glob: &str| -> Vec<String> {
+        sys.select_refs(glob)
+            .iter()
+            .map(|actor| actor.path().to_string())
+            .collect()
+    };
+
+    assert_eq!(paths("/user/echo"), vec!["/user/echo"]);
+    assert_eq!(
+        paths("/user/echo/*"),
+        vec!["/user/echo/echo_a", "/user/echo/echo_b"]
+    );
+    assert_eq!(
+        paths("/user/*_a"),
+        vec!["/user/child_a", "/user/echo/echo_a"]
+    );
+    assert_eq!(paths("/user/*").len(), 4);
+    assert!(paths("/user/missing").is_empty());
+    assert!(paths("/user").is_empty());
+
+    // terminated actors are removed from the index
+    let child = sys.select_refs("/user/child_a").pop().unwrap();
+    sys.stop(&child);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert!(paths("/user/child_a").is_empty());
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "serde")]
+
+use riker::actors::*;
+
+use riker::system::ActorCreated;
+use serde_json::json;
+
+#[derive(Default)]
+struct Dumb;
+
+impl Actor for Dumb {
+    type Msg = String;
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {}
+}
+
+#[test]
+fn serde_system_event() {
+    let sys = ActorSystem::new().unwrap();
+    let dumb = sys.actor_of::<Dumb>("dumb").unwrap();
+
+    let evt = SystemEvent::ActorCreated(ActorCreated {
+        actor: dumb.clone().into(),
+    });
+    assert_eq!(
+        serde_json::to_value(&evt).unwrap(),
+        json!({ "ActorCreated": { "actor": "/user/dumb" } })
+    );
+
+    let envelope = riker::Envelope {
+        sender: Some(dumb.into()),
+        msg: "hello".to_string(),
+    };
+    assert_eq!(
+        serde_json::to_value(&envelope).unwrap(),
+        json!({ "sender": "/user/dumb", "msg": "hello" })
+    );
+}
+
+#[test]
+fn serde_topic_and_path() {
+    let topic: Topic = "orders/*".into();
+    let json = serde_json::to_string(&topic).unwrap();
+    assert_eq!(json, "\"orders/*\"");
+    assert_eq!(serde_json::from_str::<Topic>(&json).unwrap(), topic);
+
+    let path = ActorPath::new("/user/orders");
+    let json = serde_json::to_string(&path).unwrap();
+    assert_eq!(json, "\"/user/orders\"");
+    assert_eq!(serde_json::from_str::<ActorPath>(&json).unwrap(), path);
+}
@@ -0,0 +1,67 @@
+// In its own test binary, so that only the threads of this test's systems run
+use futures::executor::block_on;
+use riker::actors::*;
+use riker::io::{UdpActor, UdpEvent};
+
+use std::time::{Duration, Instant};
+
+fn riker_threads() -> Vec<String> {
+    std::fs::read_dir("/proc/self/task")
+        .unwrap()
+        .filter_map(|task| std::fs::read_to_string(task.ok()?.path().join("comm")).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| name.starts_with("stopping-"))
+        .collect()
+}
+
+#[derive(Default)]
+struct Worker;
+
+impl Actor for Worker {
+    type Msg = ();
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        if ctx.myself().name() == "worker" {
+            ctx.actor_of::<Worker>("child").unwrap();
+        }
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {}
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn shutdown_stops_threads() {
+    let sys = ActorSystem::with_name("stopping").unwrap();
+    let worker = sys.actor_of::<Worker>("worker").unwrap();
+    sys.schedule_once(Duration::from_secs(60), worker.clone(), None, ());
+
+    let inbox = Inbox::<UdpEvent>::new(&sys).unwrap();
+    let addr = "127.0.0.1:0".parse().unwrap();
+    UdpActor::bind(&sys, "udp", addr, Box::new(inbox.sender_ref())).unwrap();
+
+    // the threads are spawned as they're needed, names are cut to 15 bytes
+    let expected = ["stopping-timer", "stopping-pool-t", "stopping-io"];
+    let started = || {
+        let threads = riker_threads();
+        expected
+            .iter()
+            .all(|name| threads.iter().any(|t| t == name))
+    };
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !started() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(started(), "{:?}", riker_threads());
+
+    block_on(sys.shutdown()).unwrap();
+    drop(worker);
+    drop(inbox);
+    drop(sys);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !riker_threads().is_empty() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert_eq!(riker_threads(), Vec::<String>::new());
+}
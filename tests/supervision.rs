@@ -1,7 +1,12 @@
 #[macro_use]
 extern crate riker_testkit;
 
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
 use riker::actors::*;
+use riker::system::ActorCreated;
 
 use riker_testkit::probe::channel::{probe, ChannelProbe};
 use riker_testkit::probe::{Probe, ProbeReceive};
@@ -218,3 +223,485 @@ fn supervision_escalate_failed_actor() {
     p_assert_eq!(listen, ());
     sys.print_tree();
 }
+
+// Test supervised futures
+#[derive(Clone, Debug)]
+pub struct RunTask(TaskFailure);
+
+struct TaskActor {
+    probe: ChannelProbe<(), String>,
+}
+
+impl ActorFactoryArgs<ChannelProbe<(), String>> for TaskActor {
+    fn create_args(probe: ChannelProbe<(), String>) -> Self {
+        TaskActor { probe }
+    }
+}
+
+impl Actor for TaskActor {
+    type Msg = RunTask;
+
+    fn pre_start(&mut self, _ctx: &Context<Self::Msg>) {
+        self.probe.event("started".to_string());
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        match msg.0 {
+            TaskFailure::Notify => ctx
+                .run_supervised(async { Err::<(), _>("task error") }, msg.0)
+                .unwrap(),
+            TaskFailure::Fail => ctx
+                .run_supervised(
+                    async {
+                        panic!("// TEST PANIC // TEST PANIC // TEST PANIC //") as Result<(), ()>
+                    },
+                    msg.0,
+                )
+                .unwrap(),
+        }
+    }
+
+    fn sys_recv(&mut self, _ctx: &Context<Self::Msg>, msg: SystemMsg, _sender: Sender) {
+        if let SystemMsg::TaskFailed(failed) = msg {
+            self.probe.event(failed.error);
+        }
+    }
+}
+
+#[test]
+fn supervision_run_supervised() {
+    let sys = ActorSystem::new().unwrap();
+
+    let (probe, listen) = probe::<String>();
+    let actor = sys
+        .actor_of_args::<TaskActor, _>("task-actor", probe)
+        .unwrap();
+    p_assert_eq!(listen, "started".to_string());
+
+    // the error is reported to the actor, which keeps running
+    actor.tell(RunTask(TaskFailure::Notify), None);
+    p_assert_eq!(listen, "\"task error\"".to_string());
+
+    // the panic is reported and the actor is then restarted by its supervisor
+    actor.tell(RunTask(TaskFailure::Fail), None);
+    p_assert_eq!(
+        listen,
+        "// TEST PANIC // TEST PANIC // TEST PANIC //".to_string()
+    );
+    p_assert_eq!(listen, "started".to_string());
+}
+
+// Test OneForAll and RestForOne Strategies
+type Started = std::sync::Arc<std::sync::Mutex<Vec<String>>>;
+
+struct GroupMember {
+    probe: TestProbe,
+    started: Started,
+}
+
+impl ActorFactoryArgs<(TestProbe, Started)> for GroupMember {
+    fn create_args((probe, started): (TestProbe, Started)) -> Self {
+        GroupMember { probe, started }
+    }
+}
+
+impl Actor for GroupMember {
+    type Msg = Panic;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.started
+            .lock()
+            .unwrap()
+            .push(ctx.myself().name().to_string());
+        self.probe.0.event(());
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {
+        panic!("// TEST PANIC // TEST PANIC // TEST PANIC //");
+    }
+}
+
+struct GroupSup {
+    rest_for_one: bool,
+    probe: TestProbe,
+    started: Started,
+    member_b: Option<ActorRef<Panic>>,
+}
+
+impl ActorFactoryArgs<(bool, TestProbe, Started)> for GroupSup {
+    fn create_args((rest_for_one, probe, started): (bool, TestProbe, Started)) -> Self {
+        GroupSup {
+            rest_for_one,
+            probe,
+            started,
+            member_b: None,
+        }
+    }
+}
+
+impl Actor for GroupSup {
+    type Msg = Panic;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        for name in &["a", "b", "c"] {
+            let member = ctx
+                .actor_of_args::<GroupMember, _>(name, (self.probe.clone(), self.started.clone()))
+                .unwrap();
+
+            if *name == "b" {
+                self.member_b = Some(member);
+            }
+        }
+    }
+
+    fn supervisor_strategy(&self) -> Strategy {
+        if self.rest_for_one {
+            Strategy::RestForOne
+        } else {
+            Strategy::OneForAll
+        }
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.member_b.as_ref().unwrap().tell(msg, sender);
+    }
+}
+
+fn restarted_members(rest_for_one: bool, expected: usize) -> Vec<String> {
+    let sys = ActorSystem::new().unwrap();
+    let started = Started::default();
+
+    let (probe, listen) = probe::<()>();
+    let sup = sys
+        .actor_of_args::<GroupSup, _>("group", (rest_for_one, TestProbe(probe), started.clone()))
+        .unwrap();
+
+    for _ in 0..3 {
+        listen.recv();
+    }
+
+    // fail member `b`
+    sup.tell(Panic, None);
+
+    for _ in 0..expected {
+        listen.recv();
+    }
+
+    // allow any unexpected restarts to happen
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    let mut restarted = started.lock().unwrap().split_off(3);
+    restarted.sort();
+    restarted
+}
+
+#[test]
+fn supervision_one_for_all() {
+    assert_eq!(restarted_members(false, 3), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn supervision_rest_for_one() {
+    assert_eq!(restarted_members(true, 2), vec!["b", "c"]);
+}
+
+// Test deciding the strategy from the failure
+#[derive(Clone, Debug)]
+pub struct Fail(&'static str);
+
+#[actor(TestProbe, Fail)]
+#[derive(Default)]
+struct Fallible;
+
+impl Actor for Fallible {
+    type Msg = FallibleMsg;
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<TestProbe> for Fallible {
+    type Msg = FallibleMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: TestProbe, _sender: Sender) {
+        msg.0.event(());
+    }
+}
+
+impl Receive<Fail> for Fallible {
+    type Msg = FallibleMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: Fail, _sender: Sender) {
+        panic!("{}", msg.0);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Decided(ChannelProbe<(), (String, usize)>);
+
+#[actor(Decided, Fail)]
+#[derive(Default)]
+struct DecidingSup {
+    child: Option<ActorRef<FallibleMsg>>,
+    probe: Option<Decided>,
+}
+
+impl Actor for DecidingSup {
+    type Msg = DecidingSupMsg;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        self.child = ctx.actor_of::<Fallible>("fallible").ok();
+    }
+
+    fn decide(&self, info: &FailureInfo) -> Strategy {
+        let panic_msg = info.panic_msg.clone().unwrap();
+        self.probe
+            .as_ref()
+            .unwrap()
+            .0
+            .event((panic_msg.clone(), info.restart_count));
+
+        if panic_msg == "fatal" {
+            Strategy::Stop
+        } else {
+            Strategy::Restart
+        }
+    }
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        self.receive(ctx, msg, sender);
+    }
+}
+
+impl Receive<Decided> for DecidingSup {
+    type Msg = DecidingSupMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: Decided, _sender: Sender) {
+        self.probe = Some(msg);
+    }
+}
+
+impl Receive<Fail> for DecidingSup {
+    type Msg = DecidingSupMsg;
+
+    fn receive(&mut self, _ctx: &Context<Self::Msg>, msg: Fail, sender: Sender) {
+        self.child.as_ref().unwrap().tell(msg, sender);
+    }
+}
+
+#[test]
+fn supervision_decide_from_failure() {
+    let sys = ActorSystem::new().unwrap();
+    let sup = sys.actor_of::<DecidingSup>("deciding").unwrap();
+
+    let (probe, listen) = probe();
+    sup.tell(Decided(probe), None);
+
+    // transient failures restart the child
+    sup.tell(Fail("transient"), None);
+    p_assert_eq!(listen, ("transient".to_string(), 0));
+
+    sup.tell(Fail("transient"), None);
+    p_assert_eq!(listen, ("transient".to_string(), 1));
+
+    // a fatal failure stops the child
+    sup.tell(Fail("fatal"), None);
+    p_assert_eq!(listen, ("fatal".to_string(), 2));
+
+    let mut stopped = false;
+    for _ in 0..100 {
+        if sys
+            .select("/user/deciding/fallible")
+            .unwrap()
+            .resolve()
+            .is_empty()
+        {
+            stopped = true;
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(stopped);
+}
+
+// Test restart hooks
+struct RestartHooks {
+    probe: ChannelProbe<(), String>,
+}
+
+impl ActorFactoryArgs<ChannelProbe<(), String>> for RestartHooks {
+    fn create_args(probe: ChannelProbe<(), String>) -> Self {
+        RestartHooks { probe }
+    }
+}
+
+impl Actor for RestartHooks {
+    type Msg = Fail;
+
+    fn pre_start(&mut self, _ctx: &Context<Self::Msg>) {
+        self.probe.event("pre_start".to_string());
+    }
+
+    fn pre_restart(&mut self, _ctx: &Context<Self::Msg>, reason: Option<FailureInfo>) {
+        let panic_msg = reason.and_then(|r| r.panic_msg).unwrap_or_default();
+        self.probe.event(format!("pre_restart {}", panic_msg));
+    }
+
+    fn post_restart(&mut self, _ctx: &Context<Self::Msg>) {
+        self.probe.event("post_restart".to_string());
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        panic!("{}", msg.0);
+    }
+}
+
+#[test]
+fn supervision_restart_hooks() {
+    let sys = ActorSystem::new().unwrap();
+
+    let (probe, listen) = probe();
+    let actor = sys
+        .actor_of_args::<RestartHooks, _>("restart-hooks", probe)
+        .unwrap();
+    p_assert_eq!(listen, "pre_start".to_string());
+
+    actor.tell(Fail("broken"), None);
+    p_assert_eq!(listen, "pre_restart broken".to_string());
+    p_assert_eq!(listen, "pre_start".to_string());
+    p_assert_eq!(listen, "post_restart".to_string());
+}
+
+// Keeps its count across restarts, a `0` fails the actor
+struct Tally {
+    count: u32,
+    out: ActorRef<u32>,
+}
+
+impl ActorFactoryArgs<ActorRef<u32>> for Tally {
+    fn create_args(out: ActorRef<u32>) -> Self {
+        Tally { count: 0, out }
+    }
+}
+
+impl Actor for Tally {
+    type Msg = u32;
+
+    fn export_state(&mut self) -> Option<StateBlob> {
+        Some(StateBlob::new(self.count))
+    }
+
+    fn import_state(&mut self, state: StateBlob) {
+        self.count = state.downcast().unwrap();
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        if msg == 0 {
+            panic!("// TEST PANIC // TEST PANIC // TEST PANIC //");
+        }
+        self.count += msg;
+        self.out.tell(self.count, None);
+    }
+}
+
+#[test]
+fn supervision_restart_state_handoff() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<u32>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    let tally = sys
+        .actor_of_args::<Tally, _>("tally", inbox.sender_ref())
+        .unwrap();
+    tally.tell(2u32, None);
+    assert_eq!(inbox.receive(timeout), Some(2));
+
+    // the restarted instance continues from the exported count
+    tally.tell(0u32, None);
+    tally.tell(3u32, None);
+    assert_eq!(inbox.receive(timeout), Some(5));
+}
+
+// Handles each system event slowly, building a backlog in its mailbox
+struct BusySup {
+    handled: Arc<AtomicUsize>,
+    out: ActorRef<usize>,
+}
+
+impl ActorFactoryArgs<(Arc<AtomicUsize>, ActorRef<usize>)> for BusySup {
+    fn create_args((handled, out): (Arc<AtomicUsize>, ActorRef<usize>)) -> Self {
+        BusySup { handled, out }
+    }
+}
+
+impl Actor for BusySup {
+    type Msg = ();
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        let args = (self.handled.clone(), self.out.clone());
+        ctx.actor_of_args::<Reporter, _>("child", args).unwrap();
+    }
+
+    fn sys_recv(&mut self, _ctx: &Context<Self::Msg>, msg: SystemMsg, _sender: Sender) {
+        if let SystemMsg::Event(_) = msg {
+            std::thread::sleep(Duration::from_millis(2));
+            self.handled.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {}
+}
+
+// Reports how many events its supervisor had handled each time it starts
+struct Reporter {
+    handled: Arc<AtomicUsize>,
+    out: ActorRef<usize>,
+}
+
+impl ActorFactoryArgs<(Arc<AtomicUsize>, ActorRef<usize>)> for Reporter {
+    fn create_args((handled, out): (Arc<AtomicUsize>, ActorRef<usize>)) -> Self {
+        Reporter { handled, out }
+    }
+}
+
+impl Actor for Reporter {
+    type Msg = Fail;
+
+    fn pre_start(&mut self, _ctx: &Context<Self::Msg>) {
+        self.out.tell(self.handled.load(Ordering::SeqCst), None);
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, msg: Self::Msg, _sender: Sender) {
+        panic!("{}", msg.0);
+    }
+}
+
+#[test]
+fn supervision_restart_latency_under_load() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<usize>::new(&sys).unwrap();
+    let timeout = Duration::from_secs(5);
+    let handled = Arc::new(AtomicUsize::new(0));
+
+    let sup = sys
+        .actor_of_args::<BusySup, _>("busy-sup", (handled.clone(), inbox.sender_ref()))
+        .unwrap();
+    assert_eq!(inbox.receive(timeout), Some(0));
+
+    // 200 events take the supervisor at least 400ms
+    let created = ActorCreated {
+        actor: sup.clone().into(),
+    };
+    for _ in 0..200 {
+        sup.sys_tell(SystemMsg::Event(SystemEvent::ActorCreated(created.clone())));
+    }
+
+    sys.select("/user/busy-sup/child")
+        .unwrap()
+        .try_tell(Fail("broken"), None);
+
+    // the failure is handled ahead of the queued events
+    let at_restart = inbox.receive(timeout).unwrap();
+    assert!(at_restart < 50, "restarted after {} events", at_restart);
+}
@@ -1,5 +1,12 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
 use futures::executor::block_on;
+use futures::future::BoxFuture;
+use futures::task::{SpawnError, SpawnExt};
 use riker::actors::*;
+use riker::kernel::Dispatcher;
+use riker::system::{ConfigError, EventSink, SystemError, SystemExtension};
 
 #[test]
 fn system_create() {
@@ -52,6 +59,25 @@ fn system_shutdown() {
     block_on(sys.shutdown()).unwrap();
 }
 
+#[test]
+fn system_await_termination() {
+    let sys = ActorSystem::new().unwrap();
+
+    let _ = sys
+        .actor_of_args::<ShutdownTest, _>("test-actor-1", 1)
+        .unwrap();
+
+    let terminated = sys.await_termination();
+    assert!(!sys.is_terminated());
+
+    sys.stop(sys.user_root());
+    block_on(terminated).unwrap();
+    assert!(sys.is_terminated());
+
+    // completes immediately once terminated
+    block_on(sys.await_termination()).unwrap();
+}
+
 #[test]
 fn system_futures_exec() {
     let sys = ActorSystem::new().unwrap();
@@ -68,11 +94,12 @@ fn system_futures_panic() {
     let sys = ActorSystem::new().unwrap();
 
     for _ in 0..100 {
-        let _ = sys
-            .run(async move {
+        drop(
+            sys.run(async move {
                 panic!("// TEST PANIC // TEST PANIC // TEST PANIC //");
             })
-            .unwrap();
+            .unwrap(),
+        );
     }
 
     for i in 0..100 {
@@ -82,11 +109,37 @@ fn system_futures_panic() {
     }
 }
 
+#[test]
+fn system_run_blocking() {
+    let sys = ActorSystem::new().unwrap();
+
+    let name = block_on(
+        sys.run_blocking(|| std::thread::current().name().map(String::from))
+            .unwrap(),
+    );
+    assert!(name.unwrap().starts_with("riker-blocking-thread-"));
+
+    // blocking tasks don't hold up the actor executor
+    let blocked: Vec<_> = (0..4)
+        .map(|_| {
+            sys.run_blocking(|| std::thread::sleep(std::time::Duration::from_millis(500)))
+                .unwrap()
+        })
+        .collect();
+
+    let f = sys.run(async move { "not blocked" }).unwrap();
+    assert_eq!(block_on(f), "not blocked");
+
+    for f in blocked {
+        block_on(f);
+    }
+}
+
 #[test]
 fn system_load_app_config() {
     let sys = ActorSystem::new().unwrap();
 
-    assert_eq!(sys.config().get_int("app.some_setting").unwrap() as i64, 1);
+    assert_eq!(sys.config().get_int("app.some_setting").unwrap(), 1);
 }
 
 #[test]
@@ -97,3 +150,586 @@ fn system_builder() {
     let sys = SystemBuilder::new().name("my-sys").create().unwrap();
     block_on(sys.shutdown()).unwrap();
 }
+
+#[test]
+fn system_builder_host_port() {
+    let sys = SystemBuilder::new()
+        .host("node-1.example.com")
+        .port(2552)
+        .create()
+        .unwrap();
+
+    assert_eq!(&*sys.host(), "node-1.example.com");
+    assert_eq!(sys.port(), Some(2552));
+
+    let uri = sys.user_root().uri().clone();
+    assert_eq!(&*uri.host, "node-1.example.com");
+    assert_eq!(uri.port, Some(2552));
+    assert_eq!(format!("{:?}", uri), "node-1.example.com:2552:///user");
+
+    let sys = ActorSystem::new().unwrap();
+    assert_eq!(&*sys.host(), "localhost");
+    assert_eq!(sys.port(), None);
+}
+
+#[test]
+fn system_config_env_override() {
+    std::env::set_var("RIKER_MAILBOX__MSG_PROCESS_LIMIT", "500");
+    let cfg = riker::load_config();
+    assert_eq!(cfg.get_int("mailbox.msg_process_limit").unwrap(), 500);
+
+    let sys = ActorSystem::with_config("env", cfg).unwrap();
+    std::env::remove_var("RIKER_MAILBOX__MSG_PROCESS_LIMIT");
+    assert_eq!(
+        sys.config().get_int("mailbox.msg_process_limit").unwrap(),
+        500
+    );
+}
+
+#[test]
+fn system_invalid_config() {
+    let mut cfg = riker::load_config();
+    cfg.set("mailbox.msg_process_limit", 0).unwrap();
+    cfg.set("mailbox.queue", "unbounded").unwrap();
+
+    // every invalid key is reported
+    match ActorSystem::with_config("invalid", cfg) {
+        Err(SystemError::InvalidConfig(errors)) => {
+            let keys: Vec<_> = errors
+                .iter()
+                .map(|e| match e {
+                    ConfigError::Invalid { key, .. } => key.as_str(),
+                    ConfigError::Missing(key) => key.as_str(),
+                })
+                .collect();
+            assert_eq!(keys, vec!["mailbox.msg_process_limit", "mailbox.queue"]);
+        }
+        _ => panic!("expected the config to be invalid"),
+    }
+}
+
+#[test]
+fn system_minimal_config() {
+    // missing keys take their defaults
+    let sys = ActorSystem::with_config("minimal", config::Config::new()).unwrap();
+    assert_eq!(
+        sys.config().get_int("mailbox.msg_process_limit").unwrap(),
+        1000
+    );
+
+    let mut cfg = config::Config::new();
+    cfg.set("dispatcher.pool_size", "many").unwrap();
+    cfg.set("governor.policy", "drop").unwrap();
+    cfg.set("log.level", "loud").unwrap();
+
+    match ActorSystem::with_config("invalid", cfg) {
+        Err(SystemError::InvalidConfig(errors)) => {
+            let keys: Vec<_> = errors
+                .iter()
+                .map(|e| match e {
+                    ConfigError::Invalid { key, .. } => key.as_str(),
+                    ConfigError::Missing(key) => key.as_str(),
+                })
+                .collect();
+            assert_eq!(
+                keys,
+                vec!["dispatcher.pool_size", "governor.policy", "log.level"]
+            );
+        }
+        _ => panic!("expected the config to be invalid"),
+    }
+}
+
+#[test]
+fn system_timer_failed() {
+    use std::error::Error;
+    use std::io;
+
+    let factory = |_: &str, _: &config::Config, _: riker::system::ClockRef| -> io::Result<_> {
+        Err(io::Error::other("no threads"))
+    };
+
+    match SystemBuilder::new().timer_factory(factory).create() {
+        Err(err @ SystemError::Timer(_)) => {
+            let source = err.source().expect("the io error is the source");
+            assert_eq!(source.to_string(), "no threads");
+        }
+        _ => panic!("expected the timer to fail"),
+    }
+}
+
+#[test]
+fn system_tree_snapshot_diff() {
+    let sys = ActorSystem::new().unwrap();
+    let before = sys.tree_snapshot();
+
+    let top = sys.actor_of_args::<ShutdownTest, _>("top", 8).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+
+    let snapshot = sys.tree_snapshot();
+    assert_eq!(snapshot.path, "/");
+    let user = snapshot.children.iter().find(|n| n.name == "user").unwrap();
+    let node = user.children.iter().find(|n| n.name == "top").unwrap();
+    assert_eq!(node.path, "/user/top");
+    assert_eq!(node.children_count, 1);
+    assert_eq!(node.children[0].name, "test-actor-9");
+    assert_eq!(node.mailbox_depth, 0);
+
+    let diff = snapshot.diff(&before);
+    assert_eq!(
+        diff.added,
+        vec![
+            "/user/top",
+            "/user/top/test-actor-9",
+            "/user/top/test-actor-9/test-actor-10",
+        ]
+    );
+    assert!(diff.removed.is_empty());
+
+    sys.stop(&top);
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    let diff = sys.tree_diff(&snapshot);
+    assert!(diff.added.is_empty());
+    assert_eq!(diff.removed.len(), 3);
+    assert!(sys.tree_diff(&before).is_empty());
+}
+
+#[derive(Default)]
+struct Service;
+
+impl Actor for Service {
+    type Msg = ();
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        ctx.system.register("payment-service", ctx.myself());
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, _: Sender) {}
+}
+
+#[test]
+fn system_receptionist() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<Listing>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    sys.subscribe_listing("payment-service", inbox.sender_ref());
+    assert!(inbox.receive(timeout).unwrap().actors.is_empty());
+
+    let a = sys.actor_of::<Service>("a").unwrap();
+    let b = sys.actor_of::<Service>("b").unwrap();
+    inbox.receive(timeout).unwrap();
+    assert_eq!(inbox.receive(timeout).unwrap().actors.len(), 2);
+
+    let listing = block_on(sys.lookup("payment-service")).unwrap();
+    assert_eq!(listing.service, "payment-service");
+    assert_eq!(listing.actors.len(), 2);
+    assert!(block_on(sys.lookup("other")).unwrap().actors.is_empty());
+
+    // actors are deregistered when they terminate
+    sys.stop(&a);
+    let listing = inbox.receive(timeout).unwrap();
+    assert_eq!(listing.actors, vec![BasicActorRef::from(b.clone())]);
+
+    sys.deregister("payment-service", b);
+    assert!(inbox.receive(timeout).unwrap().actors.is_empty());
+}
+
+struct RecordingSink(std::sync::mpsc::Sender<String>);
+
+impl EventSink for RecordingSink {
+    fn system_event(&mut self, evt: &SystemEvent) {
+        if let SystemEvent::ActorCreated(created) = evt {
+            let _ = self.0.send(format!("created {}", created.actor.path()));
+        }
+    }
+
+    fn dead_letter(&mut self, dl: &DeadLetter) {
+        let _ = self.0.send(format!("dead letter {}", dl.recipient.path()));
+    }
+}
+
+#[test]
+fn system_event_sink() {
+    let sys = ActorSystem::new().unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let timeout = std::time::Duration::from_secs(1);
+
+    sys.add_event_sink("recorder", RecordingSink(tx)).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    let service = sys.actor_of::<Service>("service").unwrap();
+    assert_eq!(rx.recv_timeout(timeout).unwrap(), "created /user/service");
+
+    sys.stop(&service);
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    service.tell((), None);
+    assert_eq!(
+        rx.recv_timeout(timeout).unwrap(),
+        "dead letter /user/service"
+    );
+}
+
+#[derive(Default)]
+struct Echo;
+
+impl Actor for Echo {
+    type Msg = u32;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        if ctx.myself().name() == "parent" {
+            ctx.actor_of::<Echo>("child").unwrap();
+        }
+    }
+
+    fn recv(&mut self, _: &Context<Self::Msg>, msg: Self::Msg, sender: Sender) {
+        sender.unwrap().try_tell(msg, None).unwrap();
+    }
+}
+
+#[test]
+fn system_suspend_resume() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<u32>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_millis(300);
+
+    let parent = sys.actor_of::<Echo>("parent").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let child = sys.select("/user/parent/child").unwrap();
+
+    assert!(sys.suspend("/user/parent"));
+    assert!(!sys.suspend("/user/missing"));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    // messages are queued while the actor and its children are suspended
+    parent.tell(1u32, Some(inbox.sender_ref().into()));
+    child.try_tell(2u32, Some(inbox.sender_ref().into()));
+    assert_eq!(inbox.receive(timeout), None);
+
+    assert!(sys.resume("/user/parent"));
+    let mut received = vec![
+        inbox.receive(timeout).unwrap(),
+        inbox.receive(timeout).unwrap(),
+    ];
+    received.sort();
+    assert_eq!(received, vec![1, 2]);
+}
+
+// Reports each start
+struct Starts(ActorRef<()>);
+
+impl ActorFactoryArgs<ActorRef<()>> for Starts {
+    fn create_args(started: ActorRef<()>) -> Self {
+        Starts(started)
+    }
+}
+
+impl Actor for Starts {
+    type Msg = ();
+
+    fn pre_start(&mut self, _ctx: &Context<Self::Msg>) {
+        self.0.tell((), None);
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {}
+}
+
+#[test]
+fn system_stop_restart_path() {
+    let sys = ActorSystem::new().unwrap();
+    let inbox = Inbox::<()>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_millis(500);
+
+    let _actor = sys
+        .actor_of_args::<Starts, _>("managed", inbox.sender_ref())
+        .unwrap();
+    assert!(inbox.receive(timeout).is_some());
+
+    sys.restart_path("/user/managed").unwrap();
+    assert!(inbox.receive(timeout).is_some());
+
+    sys.stop_path("/user/managed").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert!(sys.list_actors("/user").is_empty());
+
+    let err = sys.stop_path("/user/managed").unwrap_err();
+    assert_eq!(err.0, "/user/managed");
+    assert!(sys.restart_path("/user/missing").is_err());
+
+    // the guardians can't be managed by path
+    assert!(sys.stop_path("/user").is_err());
+}
+
+// Reports the health set by the test
+struct Checked(Arc<AtomicBool>);
+
+impl ActorFactoryArgs<Arc<AtomicBool>> for Checked {
+    fn create_args(healthy: Arc<AtomicBool>) -> Self {
+        Checked(healthy)
+    }
+}
+
+impl Actor for Checked {
+    type Msg = ();
+
+    fn health(&self) -> Health {
+        if self.0.load(Ordering::SeqCst) {
+            Health::Healthy
+        } else {
+            Health::Unhealthy("disconnected".to_string())
+        }
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {}
+}
+
+// Forwards the health changes
+struct HealthEvents(ActorRef<Health>);
+
+impl ActorFactoryArgs<ActorRef<Health>> for HealthEvents {
+    fn create_args(changes: ActorRef<Health>) -> Self {
+        HealthEvents(changes)
+    }
+}
+
+impl Actor for HealthEvents {
+    type Msg = SystemEvent;
+
+    fn pre_start(&mut self, ctx: &Context<Self::Msg>) {
+        ctx.system.sys_events().tell(
+            Subscribe::new(SysTopic::HealthChanged, Box::new(ctx.myself())),
+            None,
+        );
+    }
+
+    fn sys_recv(&mut self, _ctx: &Context<Self::Msg>, msg: SystemMsg, _sender: Sender) {
+        if let SystemMsg::Event(SystemEvent::HealthChanged(changed)) = msg {
+            self.0.tell(changed.health, None);
+        }
+    }
+
+    fn recv(&mut self, _ctx: &Context<Self::Msg>, _msg: Self::Msg, _sender: Sender) {}
+}
+
+#[test]
+fn system_health_checks() {
+    let mut cfg = riker::load_config();
+    cfg.set("health.interval_millis", 50).unwrap();
+    let sys = ActorSystem::with_config("health", cfg).unwrap();
+    let inbox = Inbox::<Health>::new(&sys).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+
+    sys.actor_of_args::<HealthEvents, _>("events", inbox.sender_ref())
+        .unwrap();
+    let healthy = Arc::new(AtomicBool::new(true));
+    let actor = sys
+        .actor_of_args::<Checked, _>("checked", healthy.clone())
+        .unwrap();
+    sys.watch_health(actor.clone());
+    std::thread::sleep(std::time::Duration::from_millis(150));
+    assert!(block_on(sys.health_report()).unwrap().is_healthy());
+
+    healthy.store(false, Ordering::SeqCst);
+    assert_eq!(
+        inbox.receive(timeout),
+        Some(Health::Unhealthy("disconnected".to_string()))
+    );
+    let report = block_on(sys.health_report()).unwrap();
+    assert_eq!(report.actors.len(), 1);
+    assert_eq!(report.actors[0].actor.path(), "/user/checked");
+    assert!(!report.is_healthy());
+
+    healthy.store(true, Ordering::SeqCst);
+    assert_eq!(inbox.receive(timeout), Some(Health::Healthy));
+
+    // terminated actors are no longer watched
+    sys.stop(&actor);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert!(block_on(sys.health_report()).unwrap().actors.is_empty());
+}
+
+// Records its lifecycle hooks
+#[derive(Default)]
+struct Lifecycle {
+    events: std::sync::Mutex<Vec<String>>,
+}
+
+impl SystemExtension for Lifecycle {
+    fn create(sys: &ActorSystem) -> Self {
+        let lifecycle = Lifecycle::default();
+        lifecycle
+            .events
+            .lock()
+            .unwrap()
+            .push(format!("created {}", sys.name()));
+        lifecycle
+    }
+
+    fn started(&self, _: &ActorSystem) {
+        self.events.lock().unwrap().push("started".to_string());
+    }
+
+    fn before_shutdown(&self, sys: &ActorSystem) {
+        // actors are still running
+        assert!(!sys.is_terminated());
+        self.events
+            .lock()
+            .unwrap()
+            .push("before_shutdown".to_string());
+    }
+}
+
+#[test]
+fn system_extension() {
+    let sys = SystemBuilder::new()
+        .name("extended")
+        .extension::<Lifecycle>()
+        .create()
+        .unwrap();
+
+    // registering again returns the registered instance
+    let ext = sys.extension::<Lifecycle>().unwrap();
+    assert!(std::sync::Arc::ptr_eq(
+        &ext,
+        &sys.register_extension::<Lifecycle>()
+    ));
+
+    block_on(sys.shutdown()).unwrap();
+    assert_eq!(
+        *ext.events.lock().unwrap(),
+        vec!["created extended", "started", "before_shutdown"]
+    );
+}
+
+// Replies with the name of the thread it runs on
+#[derive(Default)]
+struct ThreadName;
+
+impl Actor for ThreadName {
+    type Msg = ();
+
+    fn recv(&mut self, _: &Context<Self::Msg>, _: Self::Msg, sender: Sender) {
+        let name = std::thread::current()
+            .name()
+            .unwrap_or_default()
+            .to_string();
+        sender.unwrap().try_tell(name, None).unwrap();
+    }
+}
+
+#[test]
+fn system_multiple_instances() {
+    let systems: Vec<_> = (0..4)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let name = format!("instance-{}", i);
+                let sys = if i % 2 == 0 {
+                    ActorSystem::with_name(&name).unwrap()
+                } else {
+                    ActorSystem::new_isolated(&name).unwrap()
+                };
+                let inbox = Inbox::<String>::new(&sys).unwrap();
+
+                // each system runs its actors on its own threads
+                let actor = sys.actor_of::<ThreadName>("thread-name").unwrap();
+                actor.tell((), Some(inbox.sender_ref().into()));
+                let thread = inbox.receive(std::time::Duration::from_secs(1)).unwrap();
+                assert!(thread.starts_with(&format!("{}-pool-thread-#", name)));
+
+                block_on(sys.shutdown()).unwrap();
+                name
+            })
+        })
+        .collect();
+
+    for (i, sys) in systems.into_iter().enumerate() {
+        assert_eq!(sys.join().unwrap(), format!("instance-{}", i));
+    }
+
+    // the global logger is still installed
+    log::info!("systems shut down");
+}
+
+// Fails to compile if a type loses `Send` or `Sync`
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn system_types_thread_safe() {
+    assert_send_sync::<ActorSystem>();
+    assert_send_sync::<SystemMsg>();
+    assert_send_sync::<riker::Envelope<String>>();
+    assert_send_sync::<BasicActorRef>();
+    assert_send_sync::<ActorRef<String>>();
+}
+
+#[test]
+fn system_list_actors() {
+    let sys = ActorSystem::new().unwrap();
+    let sys_actors = sys.actor_count();
+    assert!(sys.list_actors("/user").is_empty());
+
+    sys.actor_of_args::<ShutdownTest, _>("listed", 9).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    let paths: Vec<String> = sys
+        .list_actors("/user/")
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(paths, vec!["/user/listed", "/user/listed/test-actor-10"]);
+    assert_eq!(sys.list_actors("/user/listed/test-actor-10").len(), 1);
+    assert_eq!(sys.actor_count(), sys_actors + 2);
+    assert!(sys.list_actors("/").len() >= sys.list_actors("/system").len() + 2);
+}
+
+// Runs the actors on a thread pool, recording the dispatcher's calls
+struct Recording {
+    pool: futures::executor::ThreadPool,
+    tasks: Arc<AtomicUsize>,
+    shut_down: Arc<AtomicBool>,
+}
+
+impl Dispatcher for Recording {
+    fn execute(&self, task: BoxFuture<'static, ()>) -> Result<(), SpawnError> {
+        self.tasks.fetch_add(1, Ordering::SeqCst);
+        self.pool.spawn(task)
+    }
+
+    fn shutdown(&self) {
+        self.shut_down.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn system_custom_dispatcher() {
+    let tasks = Arc::new(AtomicUsize::new(0));
+    let shut_down = Arc::new(AtomicBool::new(false));
+    let dispatcher = Recording {
+        pool: futures::executor::ThreadPool::new().unwrap(),
+        tasks: tasks.clone(),
+        shut_down: shut_down.clone(),
+    };
+    let sys = SystemBuilder::new()
+        .dispatcher(dispatcher)
+        .create()
+        .unwrap();
+
+    let actor = sys
+        .actor_of_args::<ShutdownTest, _>("dispatched", 1)
+        .unwrap();
+    block_on(actor.started()).unwrap();
+    let before = tasks.load(Ordering::SeqCst);
+    actor.tell((), None);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert!(tasks.load(Ordering::SeqCst) > before);
+
+    // the dispatcher is shut down once the root has stopped
+    block_on(sys.shutdown()).unwrap();
+    for _ in 0..50 {
+        if shut_down.load(Ordering::SeqCst) {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert!(shut_down.load(Ordering::SeqCst));
+}
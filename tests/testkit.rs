@@ -0,0 +1,69 @@
+use riker::actors::*;
+use riker::testkit::{TestKit, TestProbe};
+
+use std::time::Duration;
+
+#[derive(Default)]
+struct Echo;
+
+impl Actor for Echo {
+    type Msg = (String, ActorRef<String>);
+
+    fn recv(&mut self, ctx: &Context<Self::Msg>, (msg, reply_to): Self::Msg, _: Sender) {
+        reply_to.tell(msg, Some(ctx.myself().into()));
+    }
+}
+
+#[test]
+fn testkit_probe_expect_msg() {
+    let kit = TestKit::new().unwrap();
+    let probe = kit.probe::<String>().unwrap();
+    let echo = kit.system().actor_of::<Echo>("echo").unwrap();
+
+    echo.tell(("hello".to_string(), probe.actor_ref()), None);
+    let sender = probe.expect_msg("hello".to_string());
+    assert_eq!(sender.unwrap().path(), echo.path());
+
+    probe.expect_no_msg(Duration::from_millis(100));
+}
+
+#[test]
+#[should_panic(expected = "expected \"hello\", received \"bye\"")]
+fn testkit_probe_unexpected_msg() {
+    let sys = ActorSystem::new().unwrap();
+    let probe = TestProbe::<String>::new(&sys).unwrap();
+
+    probe.actor_ref().tell("bye".to_string(), None);
+    probe.expect_msg("hello".to_string());
+}
+
+#[test]
+fn testkit_advance_virtual_time() {
+    let kit = TestKit::new().unwrap();
+    let probe = kit.probe::<u32>().unwrap();
+
+    let sys = kit.system();
+    sys.schedule_once(Duration::from_secs(60), probe.actor_ref(), None, 1u32);
+    sys.schedule_once(Duration::from_secs(120), probe.actor_ref(), None, 2u32);
+
+    probe.expect_no_msg(Duration::from_millis(100));
+
+    kit.advance(Duration::from_secs(60));
+    probe.expect_msg(1);
+    probe.expect_no_msg(Duration::from_millis(100));
+
+    kit.advance(Duration::from_secs(60));
+    probe.expect_msg(2);
+}
+
+#[test]
+fn testkit_time_factor() {
+    let mut cfg = riker::load_config();
+    cfg.set("testkit.time_factor", 2.5).unwrap();
+    let kit = TestKit::with_config(cfg).unwrap();
+
+    assert_eq!(
+        kit.dilated(Duration::from_millis(100)),
+        Duration::from_millis(250)
+    );
+}